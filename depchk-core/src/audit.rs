@@ -0,0 +1,395 @@
+use std::error::Error;
+use std::fmt;
+#[cfg(any(feature = "npm", feature = "cargo"))]
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use futures::{future, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::client::RegistryClient;
+#[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+use crate::npm::{read_package_lock, NPM_REGISTRY};
+#[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+use crate::npm_audit;
+#[cfg(feature = "cargo")]
+use crate::rustsec::AdvisoryDatabase;
+use crate::{ghsa, osv, Dependency};
+
+/// The advisory database vulnerabilities are looked up in.
+#[derive(Clone, Debug)]
+pub enum AdvisorySource {
+    /// The OSV database, at osv.dev.
+    Osv,
+    /// The GitHub Advisory Database, which can only be queried with a GitHub token.
+    Github { token: String },
+    /// The RustSec advisory database, which only covers crates.io and also lists
+    /// unmaintained crates. It is downloaded, and kept in the cache directory if
+    /// there is one.
+    #[cfg(feature = "cargo")]
+    RustSec { cache_dir: Option<PathBuf> },
+    /// The bulk advisory endpoint of the npm registry, which audits every package
+    /// of a package-lock.json file, transitive dependencies included. Dev
+    /// dependencies are only audited if `dev` is set. The lockfile is read from the
+    /// filesystem, so this source is not available in WASM.
+    #[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+    NpmRegistry { lockfile: PathBuf, dev: bool },
+}
+
+/// A dependency at the version that is looked up in advisory databases.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditedPackage {
+    name: String,
+    version: String,
+    ecosystem: String,
+}
+
+/// How severe a vulnerability is, in the buckets CVSS scores are rated in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    /// The rating of a CVSS score, or `None` for a score of 0, which CVSS rates as
+    /// no severity at all.
+    pub fn from_score(score: f64) -> Option<Self> {
+        match score {
+            score if score >= 9.0 => Some(Severity::Critical),
+            score if score >= 7.0 => Some(Severity::High),
+            score if score >= 4.0 => Some(Severity::Medium),
+            score if score > 0.0 => Some(Severity::Low),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    /// Reads a severity, in any case. GitHub and npm call medium severities
+    /// `moderate`, which is read as well.
+    fn from_str(severity: &str) -> Result<Self, Self::Err> {
+        match severity.to_ascii_lowercase().as_str() {
+            "low" => Ok(Severity::Low),
+            "medium" | "moderate" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            "critical" => Ok(Severity::Critical),
+            _ => Err(format!(
+                "unknown severity {}, expected low, medium, high or critical",
+                severity
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+/// A known vulnerability that affects the audited version of a dependency.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Vulnerability {
+    package: String,
+    version: String,
+
+    /// The identifier of the advisory, e.g. `GHSA-p6mc-m468-83gw` or `RUSTSEC-2021-0078`.
+    id: String,
+
+    /// Other identifiers of the same vulnerability, such as its CVE.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+
+    /// Where the advisory can be read, for the advisory databases that publish
+    /// their advisories on the web.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+
+    /// The severity of the vulnerability, which is missing if the advisory database
+    /// does not rate it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    severity: Option<Severity>,
+
+    /// The CVSS base score of the vulnerability, from 0 to 10.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cvss_score: Option<f64>,
+
+    /// The versions the vulnerability is fixed in, for each affected range.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fixed_versions: Vec<String>,
+
+    /// Set for advisories that are not vulnerabilities but still worth knowing
+    /// about, e.g. `unmaintained` or `unsound`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    informational: Option<String>,
+}
+
+impl AuditedPackage {
+    pub fn new(name: &str, version: &str, ecosystem: &str) -> Self {
+        AuditedPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            ecosystem: ecosystem.to_string(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn ecosystem(&self) -> &str {
+        &self.ecosystem
+    }
+}
+
+impl Vulnerability {
+    pub fn new(package: &AuditedPackage, id: &str) -> Self {
+        Vulnerability {
+            package: package.name.clone(),
+            version: package.version.clone(),
+            id: id.to_string(),
+            aliases: Vec::new(),
+            url: advisory_url(id),
+            summary: None,
+            severity: None,
+            cvss_score: None,
+            fixed_versions: Vec::new(),
+            informational: None,
+        }
+    }
+
+    pub fn with_aliases(mut self, aliases: Vec<String>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    pub fn with_summary(mut self, summary: &str) -> Self {
+        self.summary = Some(summary.to_string());
+        self
+    }
+
+    /// Sets the CVSS base score, and the severity it rates as unless the severity
+    /// was already set.
+    pub fn with_cvss_score(mut self, cvss_score: f64) -> Self {
+        self.cvss_score = Some(cvss_score);
+        self.severity = self.severity.or(Severity::from_score(cvss_score));
+        self
+    }
+
+    /// Sets the severity, which takes precedence over the rating of the CVSS score
+    /// since advisory databases may rate vulnerabilities differently.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = Some(severity);
+        self
+    }
+
+    pub fn with_fixed_versions(mut self, fixed_versions: Vec<String>) -> Self {
+        self.fixed_versions = fixed_versions;
+        self
+    }
+
+    pub fn with_informational(mut self, informational: &str) -> Self {
+        self.informational = Some(informational.to_string());
+        self
+    }
+
+    /// The name of the affected dependency.
+    pub fn package(&self) -> &str {
+        &self.package
+    }
+
+    /// The version of the dependency that was audited.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    pub fn cvss_score(&self) -> Option<f64> {
+        self.cvss_score
+    }
+
+    pub fn fixed_versions(&self) -> &[String] {
+        &self.fixed_versions
+    }
+
+    pub fn informational(&self) -> Option<&str> {
+        self.informational.as_deref()
+    }
+
+    /// Whether the vulnerability is at least as severe as `severity`. Vulnerabilities
+    /// whose severity is not known are, so that none go unreported, but informational
+    /// advisories are not since they are not vulnerabilities.
+    pub fn is_at_least(&self, severity: Severity) -> bool {
+        match self.severity {
+            Some(own) => own >= severity,
+            None => self.informational.is_none(),
+        }
+    }
+}
+
+/// Where the advisory or vulnerability with the identifier can be read, e.g. the
+/// National Vulnerability Database for CVEs. Returns `None` for the identifiers of
+/// databases that do not publish their advisories, such as the numbers of the npm
+/// registry.
+pub fn advisory_url(id: &str) -> Option<String> {
+    if id.starts_with("GHSA-") {
+        Some(format!("https://github.com/advisories/{}", id))
+    } else if id.starts_with("CVE-") {
+        Some(format!("https://nvd.nist.gov/vuln/detail/{}", id))
+    } else if id.starts_with("RUSTSEC-") {
+        Some(format!("https://rustsec.org/advisories/{}.html", id))
+    } else if id.contains('-') {
+        Some(format!("https://osv.dev/vulnerability/{}", id))
+    } else {
+        None
+    }
+}
+
+/// Works out which version of each dependency to audit, which is the version a
+/// fresh install resolves to, running at most `concurrency` lookups at the same
+/// time. Dependencies that advisory databases do not cover are left out.
+pub async fn audited_packages<'a, T: Dependency + 'a>(
+    client: &RegistryClient,
+    dependencies: impl IntoIterator<Item = &'a T>,
+    concurrency: usize,
+) -> Vec<AuditedPackage> {
+    futures::stream::iter(dependencies)
+        .map(|dependency| async move {
+            let ecosystem = dependency.get_ecosystem()?;
+            let version = dependency.satisfiable_version(client).await?;
+
+            Some(AuditedPackage::new(
+                dependency.get_name(),
+                &version.to_string(),
+                ecosystem,
+            ))
+        })
+        .buffered(concurrency.max(1))
+        .filter_map(future::ready)
+        .collect()
+        .await
+}
+
+/// Looks up the known vulnerabilities of the dependencies in the advisory database.
+/// The npm registry audits the packages of its lockfile instead.
+pub async fn audit_dependencies<'a, T: Dependency + 'a>(
+    client: &RegistryClient,
+    dependencies: impl IntoIterator<Item = &'a T>,
+    concurrency: usize,
+    source: &AdvisorySource,
+) -> Result<Vec<Vulnerability>, Box<dyn Error>> {
+    match source {
+        AdvisorySource::Osv => {
+            let packages = audited_packages(client, dependencies, concurrency).await;
+
+            osv::query_vulnerabilities(client.http(), &packages, concurrency).await
+        }
+        AdvisorySource::Github { token } => {
+            let packages = audited_packages(client, dependencies, concurrency).await;
+
+            ghsa::query_vulnerabilities(client.http(), &packages, token).await
+        }
+        #[cfg(feature = "cargo")]
+        AdvisorySource::RustSec { cache_dir } => {
+            let packages = audited_packages(client, dependencies, concurrency).await;
+            let database = AdvisoryDatabase::load(client, cache_dir.as_deref()).await?;
+
+            Ok(database.vulnerabilities(&packages))
+        }
+        #[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+        AdvisorySource::NpmRegistry { lockfile, dev } => {
+            let mut packages = read_package_lock(lockfile)?;
+
+            packages.retain(|package| *dev || !package.is_dev());
+
+            npm_audit::query_vulnerabilities(client.http(), NPM_REGISTRY, &packages).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_severity() {
+        assert_eq!(Severity::from_score(9.8), Some(Severity::Critical));
+        assert_eq!(Severity::from_score(7.0), Some(Severity::High));
+        assert_eq!(Severity::from_score(6.9), Some(Severity::Medium));
+        assert_eq!(Severity::from_score(0.1), Some(Severity::Low));
+        assert_eq!(Severity::from_score(0.0), None);
+
+        assert_eq!("MODERATE".parse(), Ok(Severity::Medium));
+        assert!("severe".parse::<Severity>().is_err());
+
+        let lodash = AuditedPackage::new("lodash", "4.17.15", "npm");
+        let rated = Vulnerability::new(&lodash, "GHSA-35jh-r3h4-6jhm")
+            .with_cvss_score(7.2)
+            .with_severity(Severity::Critical);
+        let unrated = Vulnerability::new(&lodash, "1094500");
+        let unmaintained = unrated.clone().with_informational("unmaintained");
+
+        assert_eq!(rated.severity(), Some(Severity::Critical));
+        assert!(rated.is_at_least(Severity::Critical));
+        assert!(unrated.is_at_least(Severity::Critical));
+        assert!(!unmaintained.is_at_least(Severity::Low));
+    }
+
+    #[test]
+    fn test_advisory_url() {
+        assert_eq!(
+            advisory_url("GHSA-35jh-r3h4-6jhm").as_deref(),
+            Some("https://github.com/advisories/GHSA-35jh-r3h4-6jhm")
+        );
+        assert_eq!(
+            advisory_url("CVE-2021-23337").as_deref(),
+            Some("https://nvd.nist.gov/vuln/detail/CVE-2021-23337")
+        );
+        assert_eq!(
+            advisory_url("RUSTSEC-2021-0078").as_deref(),
+            Some("https://rustsec.org/advisories/RUSTSEC-2021-0078.html")
+        );
+        assert_eq!(
+            advisory_url("PYSEC-2021-108").as_deref(),
+            Some("https://osv.dev/vulnerability/PYSEC-2021-108")
+        );
+        assert_eq!(advisory_url("1094500"), None);
+    }
+}
@@ -0,0 +1,91 @@
+use tokio::runtime::{Builder, Runtime};
+
+use crate::options::{self, CheckOptions};
+use crate::progress::ProgressReporter;
+use crate::{CheckReport, Dependency, DependencyFileParser, DirectResult, ProjectDependencies};
+
+/// Checks dependencies without an async runtime of its own, such as from build scripts
+/// or synchronous applications, by running the checks on a runtime it owns.
+///
+/// ```no_run
+/// # fn check() -> Result<(), depchk_core::DepchkError> {
+/// use depchk_core::blocking::Checker;
+/// use depchk_core::npm::PackageJson;
+/// use depchk_core::options::CheckOptions;
+///
+/// let checker = Checker::new(CheckOptions::new())?;
+/// let dependencies = checker.parse_file::<PackageJson>("package.json")?;
+///
+/// let report = checker.check(dependencies.dependencies());
+///
+/// println!("{} of {} dependencies outdated", report.outdated.len(), report.len());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Checker {
+    runtime: Runtime,
+    checker: options::Checker,
+}
+
+impl Checker {
+    pub fn new(options: CheckOptions) -> DirectResult<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        let checker = options.build()?;
+
+        Ok(Checker { runtime, checker })
+    }
+
+    /// The async checker the checks are made with.
+    pub fn inner(&self) -> &options::Checker {
+        &self.checker
+    }
+
+    /// Parses the dependency file, pointing the dependencies at the registries of the
+    /// options.
+    pub fn parse_file<P: DependencyFileParser>(
+        &self,
+        file_name: &str,
+    ) -> DirectResult<ProjectDependencies<P::Output>> {
+        let mut dependencies = P::parse_file(file_name)?;
+        self.checker.use_registries(&mut dependencies);
+
+        Ok(dependencies)
+    }
+
+    /// Checks every dependency, blocking until all of them are. See
+    /// [`options::Checker::check`].
+    pub fn check<T: Dependency>(&self, dependencies: &[T]) -> CheckReport {
+        self.check_with_progress(dependencies, &())
+    }
+
+    /// Checks every dependency like [`Checker::check`], telling the reporter about the
+    /// progress of the check as it goes.
+    pub fn check_with_progress<T: Dependency>(
+        &self,
+        dependencies: &[T],
+        progress: &dyn ProgressReporter,
+    ) -> CheckReport {
+        self.runtime
+            .block_on(self.checker.check(dependencies, progress))
+    }
+}
+
+#[cfg(all(test, feature = "npm"))]
+mod tests {
+    use super::*;
+    use crate::npm::NpmDependency;
+
+    #[test]
+    fn checks_block_until_every_dependency_is_checked() {
+        let checker = Checker::new(CheckOptions::new().offline()).unwrap();
+
+        let report = checker.check(&[
+            NpmDependency::new("axios", "^1.0.0"),
+            NpmDependency::new("left-pad", "^1.0.0"),
+        ]);
+
+        assert_eq!(report.len(), 2);
+        assert_eq!(report.skipped.len(), 2);
+        assert!(report.skipped.iter().all(|err| err.error().is_not_cached()));
+    }
+}
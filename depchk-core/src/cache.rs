@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::time::Instant;
+
+/// Where the latest versions resolved from registries are kept between lookups, keyed
+/// by registry and package name. Entries are only fresh for as long as the cache
+/// decides, after which the registry is asked again, unless the client is offline.
+///
+/// Embedders that need to control caching themselves, such as servers that share one
+/// cache between checks, can plug in their own, or keep a handle on one of the caches
+/// of this module to invalidate packages they know to have been published.
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use depchk_core::cache::{Cache, MemoryCache};
+/// use depchk_core::client::RegistryClient;
+///
+/// let cache = Arc::new(MemoryCache::new(Duration::from_secs(300)));
+/// let client = RegistryClient::default().with_cache(Arc::clone(&cache));
+///
+/// // A new version of axios was just published
+/// cache.invalidate("https://registry.npmjs.org", "axios").unwrap();
+/// ```
+pub trait Cache: Send + Sync {
+    /// Returns the cached latest version of the package, if there is an entry for it
+    /// that has not expired yet.
+    fn get(&self, registry: &str, name: &str) -> Option<String>;
+
+    /// Returns the cached latest version of the package regardless of how old the
+    /// entry is, for when querying the registry is not an option.
+    fn get_stale(&self, registry: &str, name: &str) -> Option<String>;
+
+    /// Stores the latest version of the package. Failing to write to the cache is not
+    /// fatal for a check, so errors are returned for the caller to decide whether to
+    /// ignore them.
+    fn set(&self, registry: &str, name: &str, version: &str) -> io::Result<()>;
+
+    /// Drops the entry of the package, so that it is looked up again.
+    fn invalidate(&self, registry: &str, name: &str) -> io::Result<()>;
+}
+
+/// An on-disk cache of registry responses. Every entry is stored as a
+/// small JSON file under `<dir>/<registry>/<package>.json`, and is only
+/// considered fresh while it is younger than the configured TTL.
+pub struct DiskCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// A cache of registry responses that lives as long as it does, such as for the
+/// lifetime of a server. Entries are only fresh while they are younger than the TTL.
+pub struct MemoryCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<(String, String), (Instant, String)>>,
+}
+
+/// The data stored in a single cache entry.
+#[derive(Serialize, Deserialize, Debug)]
+struct CacheEntry {
+    fetched_at: u64,
+    version: String,
+}
+
+impl DiskCache {
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        DiskCache { dir, ttl }
+    }
+
+    /// The platform cache directory for depchk, e.g. `~/.cache/depchk` on Linux.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("depchk"))
+    }
+
+    fn read(&self, registry: &str, name: &str) -> Option<CacheEntry> {
+        let file = fs::read_to_string(self.entry_path(registry, name)).ok()?;
+
+        serde_json::from_str(&file).ok()
+    }
+
+    fn entry_path(&self, registry: &str, name: &str) -> PathBuf {
+        self.dir
+            .join(encode(registry))
+            .join(format!("{}.json", encode(name)))
+    }
+}
+
+impl Cache for DiskCache {
+    fn get(&self, registry: &str, name: &str) -> Option<String> {
+        let entry = self.read(registry, name)?;
+
+        let age = now().saturating_sub(entry.fetched_at);
+
+        if age > self.ttl.as_secs() {
+            return None;
+        }
+
+        Some(entry.version)
+    }
+
+    fn get_stale(&self, registry: &str, name: &str) -> Option<String> {
+        self.read(registry, name).map(|entry| entry.version)
+    }
+
+    fn set(&self, registry: &str, name: &str, version: &str) -> io::Result<()> {
+        let path = self.entry_path(registry, name);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let entry = CacheEntry {
+            fetched_at: now(),
+            version: version.to_string(),
+        };
+
+        fs::write(path, serde_json::to_string(&entry)?)
+    }
+
+    fn invalidate(&self, registry: &str, name: &str) -> io::Result<()> {
+        match fs::remove_file(self.entry_path(registry, name)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl MemoryCache {
+    pub fn new(ttl: Duration) -> Self {
+        MemoryCache {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Cache for MemoryCache {
+    fn get(&self, registry: &str, name: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap();
+        let (fetched_at, version) = entries.get(&(registry.to_string(), name.to_string()))?;
+
+        (fetched_at.elapsed() <= self.ttl).then(|| version.clone())
+    }
+
+    fn get_stale(&self, registry: &str, name: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap();
+
+        entries
+            .get(&(registry.to_string(), name.to_string()))
+            .map(|(_, version)| version.clone())
+    }
+
+    fn set(&self, registry: &str, name: &str, version: &str) -> io::Result<()> {
+        self.entries.lock().unwrap().insert(
+            (registry.to_string(), name.to_string()),
+            (Instant::now(), version.to_string()),
+        );
+
+        Ok(())
+    }
+
+    fn invalidate(&self, registry: &str, name: &str) -> io::Result<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .remove(&(registry.to_string(), name.to_string()));
+
+        Ok(())
+    }
+}
+
+impl<T: Cache + ?Sized> Cache for Arc<T> {
+    fn get(&self, registry: &str, name: &str) -> Option<String> {
+        (**self).get(registry, name)
+    }
+
+    fn get_stale(&self, registry: &str, name: &str) -> Option<String> {
+        (**self).get_stale(registry, name)
+    }
+
+    fn set(&self, registry: &str, name: &str, version: &str) -> io::Result<()> {
+        (**self).set(registry, name, version)
+    }
+
+    fn invalidate(&self, registry: &str, name: &str) -> io::Result<()> {
+        (**self).invalidate(registry, name)
+    }
+}
+
+impl<T: Cache + ?Sized> Cache for Box<T> {
+    fn get(&self, registry: &str, name: &str) -> Option<String> {
+        (**self).get(registry, name)
+    }
+
+    fn get_stale(&self, registry: &str, name: &str) -> Option<String> {
+        (**self).get_stale(registry, name)
+    }
+
+    fn set(&self, registry: &str, name: &str, version: &str) -> io::Result<()> {
+        (**self).set(registry, name, version)
+    }
+
+    fn invalidate(&self, registry: &str, name: &str) -> io::Result<()> {
+        (**self).invalidate(registry, name)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Percent-encodes everything that is not safe to use in a file name,
+/// so that keys like `@types/node` map to a single, unique file.
+fn encode(key: &str) -> String {
+    key.bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(name: &str, ttl: Duration) -> DiskCache {
+        let dir =
+            std::env::temp_dir().join(format!("depchk-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        DiskCache::new(dir, ttl)
+    }
+
+    #[test]
+    fn entries_are_returned_while_fresh() {
+        let cache = cache("fresh", Duration::from_secs(60));
+
+        cache
+            .set("https://registry.npmjs.org", "@types/node", "18.0.0")
+            .unwrap();
+
+        assert_eq!(
+            cache.get("https://registry.npmjs.org", "@types/node"),
+            Some("18.0.0".to_string())
+        );
+        assert_eq!(cache.get("https://registry.npmjs.org", "axios"), None);
+    }
+
+    #[test]
+    fn expired_entries_are_ignored() {
+        let cache = cache("expired", Duration::from_secs(60));
+        let path = cache.entry_path("https://registry.npmjs.org", "axios");
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, r#"{"fetched_at":0,"version":"1.0.0"}"#).unwrap();
+
+        assert_eq!(cache.get("https://registry.npmjs.org", "axios"), None);
+        assert_eq!(
+            cache.get_stale("https://registry.npmjs.org", "axios"),
+            Some("1.0.0".to_string())
+        );
+    }
+
+    #[test]
+    fn entries_are_invalidated_per_package() {
+        let disk = cache("invalidated", Duration::from_secs(60));
+        let memory = MemoryCache::new(Duration::from_secs(60));
+
+        for cache in [&disk as &dyn Cache, &memory] {
+            cache
+                .set("https://registry.npmjs.org", "axios", "1.0.0")
+                .unwrap();
+            cache
+                .set("https://registry.npmjs.org", "react", "18.0.0")
+                .unwrap();
+
+            cache
+                .invalidate("https://registry.npmjs.org", "axios")
+                .unwrap();
+            cache
+                .invalidate("https://registry.npmjs.org", "lodash")
+                .unwrap();
+
+            assert_eq!(cache.get_stale("https://registry.npmjs.org", "axios"), None);
+            assert_eq!(
+                cache.get("https://registry.npmjs.org", "react"),
+                Some("18.0.0".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn memory_entries_expire() {
+        let cache = MemoryCache::new(Duration::ZERO);
+
+        cache
+            .set("https://registry.npmjs.org", "axios", "1.0.0")
+            .unwrap();
+
+        assert_eq!(cache.get("https://registry.npmjs.org", "axios"), None);
+        assert_eq!(
+            cache.get_stale("https://registry.npmjs.org", "axios"),
+            Some("1.0.0".to_string())
+        );
+    }
+
+    #[test]
+    fn keys_are_encoded_into_safe_file_names() {
+        assert_eq!(encode("@types/node"), "%40types%2Fnode");
+        assert_eq!(encode("lodash.merge"), "lodash.merge");
+    }
+}
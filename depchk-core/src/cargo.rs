@@ -0,0 +1,948 @@
+use crate::error::RegistryResponse;
+use crate::time::parse_rfc3339;
+use crate::transport::HttpClient;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    fix::ConstraintUpdate,
+    transitive::{LockfileGraph, TransitiveDependencies},
+    workspace::WorkspacePackage,
+    LockedChecksum, LockedVersions,
+};
+use crate::{
+    DeclaredDependency, DepchkError, Dependency, DependencyFileParser, DirectResult,
+    OptionalResult, ParseWarning, ProjectDependencies, Registry, SkipReason, Unavailability,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::process::Command;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+#[cfg(not(target_arch = "wasm32"))]
+use glob::glob;
+use reqwest::header::{AUTHORIZATION, USER_AGENT};
+use semver::{Comparator, Op, Version, VersionReq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The crates.io sparse index, which serves one small, cacheable file per crate
+/// and is the endpoint Cargo itself uses, as opposed to the rate-limited web API.
+const SPARSE_INDEX: &str = "https://index.crates.io";
+
+/// The git index of crates.io, which older Cargo.lock files name as the source of its
+/// crates.
+#[cfg(not(target_arch = "wasm32"))]
+const CRATES_IO_INDEX: &str = "https://github.com/rust-lang/crates.io-index";
+
+/// The crates.io web API, which is only used for data that the index does not
+/// carry, such as release dates, and only for dependencies that are outdated.
+const CRATES_IO_API: &str = "https://crates.io/api/v1/crates";
+
+/// crates.io asks every automated client to identify itself.
+const DEPCHK_USER_AGENT: &str = concat!(
+    "depchk/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/cryptaliagy/depchk)"
+);
+
+/// A struct representing a crate dependency from a Cargo.toml file.
+pub struct CargoDependency {
+    version: VersionReq,
+    raw_version: String,
+    name: String,
+    index_url: String,
+}
+
+pub type CargoToml = ProjectDependencies<CargoDependency>;
+
+/// A struct used to deserialize a Cargo.toml file into a format
+/// that can be more easily processed into the appropriate dependency.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct CargoTomlRaw {
+    #[serde(default)]
+    package: Option<CargoPackage>,
+
+    #[serde(default)]
+    dependencies: BTreeMap<String, DependencySpec>,
+
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, DependencySpec>,
+
+    #[serde(default)]
+    workspace: Option<CargoWorkspace>,
+}
+
+/// The `[package]` table of a Cargo.toml file.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CargoPackage {
+    name: String,
+}
+
+/// The `[workspace]` table of a Cargo.toml file.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CargoWorkspace {
+    #[serde(default)]
+    members: Vec<String>,
+
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// A dependency entry of a Cargo.toml file, which is either a bare
+/// version requirement or a table of options.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum DependencySpec {
+    Version(String),
+    Detailed {
+        #[serde(default)]
+        version: Option<String>,
+
+        #[serde(default)]
+        package: Option<String>,
+    },
+}
+
+/// The parts of a Cargo.lock file that list the locked packages.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Deserialize, Debug)]
+struct CargoLockRaw {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Deserialize, Debug)]
+struct LockedPackage {
+    name: String,
+    version: String,
+
+    /// Where the package comes from, which is not set for path dependencies and
+    /// workspace members.
+    source: Option<String>,
+
+    /// The SHA-256 checksum of the package, in hexadecimal, for registry packages.
+    checksum: Option<String>,
+
+    /// The packages this one depends on, as `name`, or as `name version` or
+    /// `name version (source)` when several versions of a package are locked.
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// A single line of a crate's file in the sparse index, which
+/// describes one published version of the crate.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IndexEntry {
+    vers: String,
+
+    #[serde(default)]
+    yanked: bool,
+
+    /// The SHA-256 checksum of the published crate file, in hexadecimal.
+    #[serde(default)]
+    cksum: Option<String>,
+}
+
+/// The list of published versions of a crate, as returned by the crates.io web API.
+#[derive(Serialize, Deserialize, Debug)]
+struct CrateVersions {
+    versions: Vec<CrateVersion>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CrateVersion {
+    num: String,
+    created_at: String,
+}
+
+/// The metadata of a crate, as returned by the crates.io web API.
+#[derive(Serialize, Deserialize, Debug)]
+struct CrateMetadata {
+    #[serde(rename = "crate")]
+    krate: CrateLinks,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CrateLinks {
+    homepage: Option<String>,
+    repository: Option<String>,
+}
+
+/// A single published version of a crate, as returned by the crates.io web API.
+#[derive(Serialize, Deserialize, Debug)]
+struct CrateVersionMetadata {
+    version: CrateVersionLicense,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CrateVersionLicense {
+    license: Option<String>,
+}
+
+impl CargoDependency {
+    /// Creates a new crate dependency from the given name
+    /// and Cargo version requirement string.
+    ///
+    /// ```
+    /// # use depchk_core::cargo::CargoDependency;
+    /// # use depchk_core::Dependency;
+    ///
+    /// let dependency = CargoDependency::new("serde", "1.0");
+    ///
+    /// assert!(dependency.is_satisfied_by("1.0.152"));
+    /// assert!(!dependency.is_satisfied_by("2.0.0"));
+    /// ```
+    pub fn new(name: &str, version: &str) -> Self {
+        CargoDependency::try_new(name, version).unwrap()
+    }
+
+    /// Attempts to create a new crate dependency from the given name and
+    /// version requirement string. However, if the requirement is not
+    /// parsable, returns None.
+    ///
+    /// ```
+    /// # use depchk_core::cargo::CargoDependency;
+    ///
+    /// assert!(CargoDependency::try_new("serde", ">=1.0, <1.1").is_some());
+    /// assert!(CargoDependency::try_new("serde", "^1.0 || ^2.0").is_none());
+    /// ```
+    pub fn try_new(name: &str, version: &str) -> Option<Self> {
+        let parsed = VersionReq::parse(version).ok()?;
+
+        Some(CargoDependency {
+            name: name.to_string(),
+            raw_version: version.to_string(),
+            version: parsed,
+            index_url: format!("{}/{}", SPARSE_INDEX, index_path(name)),
+        })
+    }
+
+    /// Creates the dependencies declared by a Cargo.toml dependency table. Entries
+    /// without a version requirement (path, git and workspace-inherited dependencies)
+    /// are not resolved from crates.io and are therefore skipped. Renamed dependencies
+    /// are looked up by the name of the crate they point to. Entries whose version
+    /// requirement cannot be parsed are skipped as well, with a warning.
+    pub fn from_specs(specs: BTreeMap<String, DependencySpec>) -> (Vec<Self>, Vec<ParseWarning>) {
+        let mut dependencies = Vec::new();
+        let mut warnings = Vec::new();
+
+        for (name, spec) in specs {
+            let (name, version) = match spec {
+                DependencySpec::Version(version) => (name, version),
+                DependencySpec::Detailed {
+                    version: Some(version),
+                    package,
+                } => (package.unwrap_or(name), version),
+                DependencySpec::Detailed { version: None, .. } => continue,
+            };
+
+            match CargoDependency::try_new(&name, &version) {
+                Some(dependency) => dependencies.push(dependency),
+                None => warnings.push(ParseWarning::new(
+                    &name,
+                    &version,
+                    SkipReason::InvalidConstraint,
+                )),
+            }
+        }
+
+        (dependencies, warnings)
+    }
+
+    /// Fetches every published version of this crate from the sparse index.
+    pub async fn fetch_index(&self, client: &HttpClient) -> DirectResult<Vec<IndexEntry>> {
+        fetch_index_file(client, &self.index_url, None).await
+    }
+}
+
+/// Fetches the index file of a crate from a sparse index, authenticated with the token
+/// of the index if it has one.
+async fn fetch_index_file(
+    client: &HttpClient,
+    url: &str,
+    token: Option<&str>,
+) -> DirectResult<Vec<IndexEntry>> {
+    let mut request = client.get(url).header(USER_AGENT, DEPCHK_USER_AGENT);
+
+    if let Some(token) = token {
+        request = request.header(AUTHORIZATION, token);
+    }
+
+    let body = client
+        .send(request)
+        .await?
+        .error_for_registry_status()
+        .await?
+        .text()
+        .await?;
+
+    let entries = body
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<IndexEntry>, _>>()?;
+
+    Ok(entries)
+}
+
+impl TryFrom<(&str, &str)> for CargoDependency {
+    type Error = DepchkError;
+
+    /// Creates a crate dependency from its name and version requirement, failing with a
+    /// [`DepchkError::Constraint`] error when the requirement cannot be parsed.
+    fn try_from((name, version): (&str, &str)) -> DirectResult<Self> {
+        CargoDependency::try_new(name, version).ok_or_else(|| DepchkError::Constraint {
+            constraint: version.to_string(),
+            message: format!("{} does not have a valid version requirement", name),
+        })
+    }
+}
+
+/// Crate dependencies are serialized as their name and version requirement, since
+/// they are always resolved from crates.io.
+impl Serialize for CargoDependency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DeclaredDependency {
+            name: self.name.clone(),
+            constraint: self.raw_version.clone(),
+            registry: None,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CargoDependency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let declared = DeclaredDependency::deserialize(deserializer)?;
+
+        CargoDependency::try_from((declared.name.as_str(), declared.constraint.as_str()))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl IndexEntry {
+    pub fn version(&self) -> &str {
+        &self.vers
+    }
+
+    pub fn is_yanked(&self) -> bool {
+        self.yanked
+    }
+}
+
+/// Picks the latest version that Cargo would consider for a new requirement,
+/// i.e. the highest version that is neither yanked nor a prerelease.
+pub fn latest_version(entries: &[IndexEntry]) -> Option<Version> {
+    entries
+        .iter()
+        .filter(|entry| !entry.yanked)
+        .filter_map(|entry| Version::parse(&entry.vers).ok())
+        .filter(|version| version.pre.is_empty())
+        .max()
+}
+
+/// The path of a crate's file in the index, which is sharded by the
+/// length and the first characters of the lowercased crate name.
+fn index_path(name: &str) -> String {
+    let name = name.to_lowercase();
+
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+/// The smallest version a comparator can match, used as a
+/// candidate when looking for versions two requirements share.
+fn lower_bound(comparator: &Comparator) -> Version {
+    let mut version = Version::new(
+        comparator.major,
+        comparator.minor.unwrap_or(0),
+        comparator.patch.unwrap_or(0),
+    );
+
+    if comparator.op == Op::Greater {
+        version.patch += 1;
+    }
+
+    version
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Dependency for CargoDependency {
+    async fn fetch_latest_version(&self, client: &HttpClient) -> DirectResult<String> {
+        let entries = self.fetch_index(client).await?;
+
+        let latest = latest_version(&entries)
+            .ok_or_else(|| format!("{} has no published stable version", self.name))?;
+
+        Ok(latest.to_string())
+    }
+
+    async fn fetch_release_dates(
+        &self,
+        client: &HttpClient,
+    ) -> DirectResult<HashMap<String, SystemTime>> {
+        let crate_versions: CrateVersions = client
+            .send(
+                client
+                    .get(format!("{}/{}/versions", CRATES_IO_API, self.name))
+                    .header(USER_AGENT, DEPCHK_USER_AGENT),
+            )
+            .await?
+            .error_for_registry_status()
+            .await?
+            .json()
+            .await?;
+
+        Ok(crate_versions
+            .versions
+            .into_iter()
+            .filter_map(|version| Some((version.num, parse_rfc3339(&version.created_at)?)))
+            .collect())
+    }
+
+    async fn fetch_homepage(&self, client: &HttpClient) -> DirectResult<Option<String>> {
+        let metadata: CrateMetadata = client
+            .send(
+                client
+                    .get(format!("{}/{}", CRATES_IO_API, self.name))
+                    .header(USER_AGENT, DEPCHK_USER_AGENT),
+            )
+            .await?
+            .error_for_registry_status()
+            .await?
+            .json()
+            .await?;
+
+        Ok(metadata.krate.homepage.or(metadata.krate.repository))
+    }
+
+    async fn fetch_repository(&self, client: &HttpClient) -> DirectResult<Option<String>> {
+        let metadata: CrateMetadata = client
+            .send(
+                client
+                    .get(format!("{}/{}", CRATES_IO_API, self.name))
+                    .header(USER_AGENT, DEPCHK_USER_AGENT),
+            )
+            .await?
+            .error_for_registry_status()
+            .await?
+            .json()
+            .await?;
+
+        Ok(metadata.krate.repository)
+    }
+
+    async fn fetch_license(
+        &self,
+        client: &HttpClient,
+        version: &str,
+    ) -> DirectResult<Option<String>> {
+        let metadata: CrateVersionMetadata = client
+            .send(
+                client
+                    .get(format!("{}/{}/{}", CRATES_IO_API, self.name, version))
+                    .header(USER_AGENT, DEPCHK_USER_AGENT),
+            )
+            .await?
+            .error_for_registry_status()
+            .await?
+            .json()
+            .await?;
+
+        Ok(metadata.version.license)
+    }
+
+    async fn fetch_versions(&self, client: &HttpClient) -> DirectResult<Vec<String>> {
+        let entries = self.fetch_index(client).await?;
+
+        Ok(entries
+            .iter()
+            .filter(|entry| !entry.is_yanked())
+            .map(|entry| entry.version().to_string())
+            .collect())
+    }
+
+    /// Yanked versions are still in the index, so they can be told apart from
+    /// versions that were removed altogether.
+    async fn fetch_availability(
+        &self,
+        client: &HttpClient,
+        version: &str,
+    ) -> OptionalResult<Unavailability> {
+        let entries = self.fetch_index(client).await?;
+
+        Ok(
+            match entries.iter().find(|entry| entry.version() == version) {
+                Some(entry) if entry.is_yanked() => Some(Unavailability::Yanked),
+                Some(_) => None,
+                None => Some(Unavailability::Unpublished),
+            },
+        )
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_constraint(&self) -> &str {
+        &self.raw_version
+    }
+
+    fn get_registry(&self) -> &str {
+        SPARSE_INDEX
+    }
+
+    fn get_ecosystem(&self) -> Option<&str> {
+        Some("crates.io")
+    }
+
+    fn try_is_satisfied_by(&self, version: &str) -> DirectResult<bool> {
+        let parsed = Version::parse(version).map_err(|err| DepchkError::InvalidVersion {
+            version: version.to_string(),
+            message: err.to_string(),
+        })?;
+
+        Ok(self.version.matches(&parsed))
+    }
+
+    /// Cargo requirements cannot be intersected directly, so instead the lower bound
+    /// of every comparator of either requirement is tried against both of them.
+    fn is_compatible_with(&self, other: &Self) -> bool {
+        if self.version.comparators.is_empty() || other.version.comparators.is_empty() {
+            return true;
+        }
+
+        self.version
+            .comparators
+            .iter()
+            .chain(other.version.comparators.iter())
+            .map(lower_bound)
+            .any(|candidate| self.version.matches(&candidate) && other.version.matches(&candidate))
+    }
+}
+
+impl From<CargoTomlRaw> for CargoToml {
+    fn from(value: CargoTomlRaw) -> Self {
+        let (dependencies, mut warnings) = CargoDependency::from_specs(value.dependencies);
+        let (dev_dependencies, dev_warnings) = CargoDependency::from_specs(value.dev_dependencies);
+
+        warnings.extend(dev_warnings);
+
+        CargoToml::new(dependencies, dev_dependencies).with_warnings(warnings)
+    }
+}
+
+#[async_trait(?Send)]
+impl DependencyFileParser for CargoToml {
+    type Output = CargoDependency;
+
+    fn parse_str(contents: &str) -> DirectResult<ProjectDependencies<Self::Output>> {
+        let raw: CargoTomlRaw = toml::from_str(contents)?;
+
+        Ok(CargoToml::from(raw))
+    }
+
+    /// Parses the Cargo.toml file of a workspace root along with the Cargo.toml of
+    /// every member matched by its `workspace.members` globs, skipping the paths
+    /// listed in `workspace.exclude`. The root is always returned first.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn parse_workspaces(
+        file_name: &str,
+    ) -> DirectResult<Vec<WorkspacePackage<Self::Output>>> {
+        let mut root = read_cargo_toml(file_name)?;
+        let root_dir = Path::new(file_name).parent().unwrap_or(Path::new("."));
+        let workspace = root.workspace.take();
+
+        let root_name = root
+            .package
+            .take()
+            .map(|package| package.name)
+            .unwrap_or_else(|| file_name.to_string());
+
+        let mut packages = vec![WorkspacePackage::new(&root_name, CargoToml::from(root))];
+
+        let Some(workspace) = workspace else {
+            return Ok(packages);
+        };
+
+        let excluded = workspace
+            .exclude
+            .iter()
+            .map(|path| root_dir.join(path))
+            .collect::<Vec<_>>();
+
+        for member in &workspace.members {
+            for member_dir in glob(&root_dir.join(member).to_string_lossy())? {
+                let member_dir = member_dir?;
+                let manifest = member_dir.join("Cargo.toml");
+
+                if excluded.contains(&member_dir) || !manifest.is_file() {
+                    continue;
+                }
+
+                let mut raw = read_cargo_toml(&manifest.to_string_lossy())?;
+                let name = raw
+                    .package
+                    .take()
+                    .map(|package| package.name)
+                    .unwrap_or_else(|| member_dir.to_string_lossy().to_string());
+
+                packages.push(WorkspacePackage::new(&name, CargoToml::from(raw)));
+            }
+        }
+
+        Ok(packages)
+    }
+
+    /// Reads the Cargo.lock file of the package, which is at the root of its
+    /// workspace, so it is looked for in every parent directory of the manifest.
+    /// Only packages that come from a registry are returned.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parse_lockfile(file_name: &str) -> OptionalResult<LockedVersions> {
+        let Some(raw) = read_cargo_lock(file_name)? else {
+            return Ok(None);
+        };
+
+        let mut locked = LockedVersions::new();
+
+        for package in raw.package {
+            if package
+                .source
+                .is_some_and(|source| is_registry_source(&source))
+            {
+                locked
+                    .entry(package.name)
+                    .or_default()
+                    .push(package.version);
+            }
+        }
+
+        Ok(Some(locked))
+    }
+
+    /// Reads the Cargo.lock file of the package, starting from the registry packages
+    /// that the workspace members depend on and are declared in the Cargo.toml file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parse_transitive(file_name: &str) -> OptionalResult<TransitiveDependencies<Self::Output>> {
+        let Some(raw) = read_cargo_lock(file_name)? else {
+            return Ok(None);
+        };
+
+        let declared = Self::parse_file(file_name)?;
+        let declared: HashSet<&str> = declared
+            .dependencies()
+            .iter()
+            .chain(declared.dev_dependencies())
+            .map(Dependency::get_name)
+            .collect();
+
+        let mut graph = LockfileGraph::new();
+
+        // Path packages are in the graph as well, so that the indices of the graph
+        // are the same as the ones of the lockfile
+        for package in &raw.package {
+            graph.add_package(&package.name, &package.version);
+        }
+
+        let resolve = |reference: &str| {
+            let mut parts = reference.split_whitespace();
+            let name = parts.next()?;
+            let version = parts.next();
+
+            raw.package.iter().position(|package| {
+                package.name == name && version.is_none_or(|version| package.version == version)
+            })
+        };
+
+        let is_registry = |index: usize| {
+            raw.package[index]
+                .source
+                .as_deref()
+                .is_some_and(is_registry_source)
+        };
+
+        let mut direct = Vec::new();
+
+        for (index, package) in raw.package.iter().enumerate() {
+            for dependency in package.dependencies.iter().filter_map(|d| resolve(d)) {
+                if !is_registry(dependency) {
+                    continue;
+                }
+
+                graph.add_dependency(index, dependency);
+
+                if package.source.is_none()
+                    && declared.contains(raw.package[dependency].name.as_str())
+                {
+                    direct.push(dependency);
+                }
+            }
+        }
+
+        direct.sort_unstable();
+        direct.dedup();
+
+        let mut transitive = TransitiveDependencies::new();
+
+        for (index, parents) in graph.transitive(&direct) {
+            let (name, version) = graph.package(index);
+
+            transitive.push(
+                CargoDependency::new(name, &format!("={}", version)),
+                version,
+                parents,
+            );
+        }
+
+        Ok(Some(transitive))
+    }
+
+    /// Reads the Cargo.lock file of the package, keeping only the packages that come
+    /// from a registry, like `parse_lockfile`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parse_lockfile_checksums(file_name: &str) -> OptionalResult<Vec<LockedChecksum>> {
+        let Some(raw) = read_cargo_lock(file_name)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            raw.package
+                .iter()
+                .filter(|package| package.source.as_deref().is_some_and(is_registry_source))
+                .filter_map(|package| {
+                    let checksum = package.checksum.as_deref()?;
+                    let source = package.source.as_deref()?;
+                    let locked = LockedChecksum::new(&package.name, &package.version, checksum);
+
+                    Some(match is_crates_io_source(source) {
+                        true => locked,
+                        false => locked.with_registry(source),
+                    })
+                })
+                .collect(),
+        ))
+    }
+
+    /// Fetches the index file of the crate from the sparse index of the registry, given
+    /// as the source of its Cargo.lock entry (`sparse+URL`). Registries that are not
+    /// Cargo sources, which crate checks ignore, are looked up on crates.io instead.
+    /// The checksums of git indexes cannot be looked up without cloning them.
+    async fn fetch_published_checksums(
+        client: &HttpClient,
+        registry: Option<&Registry>,
+        name: &str,
+    ) -> DirectResult<HashMap<String, String>> {
+        let (index, token) = match registry {
+            Some(registry) if registry.url().starts_with("registry+") => {
+                return Err(DepchkError::Other(format!(
+                    "the checksums of {} cannot be looked up in the git index {}",
+                    name,
+                    registry.url().trim_start_matches("registry+")
+                )));
+            }
+            Some(registry) => match registry.url().strip_prefix("sparse+") {
+                Some(index) => (index.trim_end_matches('/'), registry.token()),
+                None => (SPARSE_INDEX, None),
+            },
+            None => (SPARSE_INDEX, None),
+        };
+
+        let url = format!("{}/{}", index, index_path(name));
+        let entries = fetch_index_file(client, &url, token).await?;
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| Some((entry.vers, entry.cksum?)))
+            .collect())
+    }
+
+    /// Updates only the crates whose constraints were updated, along with the crates
+    /// they need, with `cargo update -p`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn sync_lockfile_command(file_name: &str, updates: &[ConstraintUpdate]) -> Option<Command> {
+        let has_lockfile = Path::new(file_name)
+            .ancestors()
+            .skip(1)
+            .any(|dir| dir.join("Cargo.lock").is_file());
+
+        if !has_lockfile || updates.is_empty() {
+            return None;
+        }
+
+        let mut command = Command::new("cargo");
+
+        command.args(["update", "--manifest-path", file_name]);
+
+        for update in updates {
+            command.args(["-p", update.name()]);
+        }
+
+        Some(command)
+    }
+}
+
+/// Reads the Cargo.lock file of the package, which is at the root of its workspace,
+/// so it is looked for in every parent directory of the manifest.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_cargo_lock(file_name: &str) -> OptionalResult<CargoLockRaw> {
+    let manifest = Path::new(file_name);
+    let lockfile = manifest
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|lockfile| lockfile.is_file());
+
+    match lockfile {
+        Some(lockfile) => Ok(Some(toml::from_str(&fs::read_to_string(lockfile)?)?)),
+        None => Ok(None),
+    }
+}
+
+/// Whether a package of a Cargo.lock file comes from a registry, rather than from
+/// a git repository.
+#[cfg(not(target_arch = "wasm32"))]
+fn is_registry_source(source: &str) -> bool {
+    source.starts_with("registry+") || source.starts_with("sparse+")
+}
+
+/// Whether a registry source of a Cargo.lock file is crates.io, through either its git
+/// index or its sparse one.
+#[cfg(not(target_arch = "wasm32"))]
+fn is_crates_io_source(source: &str) -> bool {
+    let index = source.trim_end_matches('/');
+
+    index == format!("registry+{}", CRATES_IO_INDEX) || index == format!("sparse+{}", SPARSE_INDEX)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_cargo_toml(file_name: &str) -> DirectResult<CargoTomlRaw> {
+    let file = fs::read_to_string(file_name)?;
+
+    Ok(toml::from_str(&file)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_paths_follow_the_sparse_index_layout() {
+        assert_eq!(index_path("a"), "1/a");
+        assert_eq!(index_path("cc"), "2/cc");
+        assert_eq!(index_path("syn"), "3/s/syn");
+        assert_eq!(index_path("Serde_JSON"), "se/rd/serde_json");
+    }
+
+    #[test]
+    fn latest_version_skips_yanked_and_prerelease_versions() {
+        let entries: Vec<IndexEntry> = [
+            r#"{"name":"foo","vers":"1.0.0","yanked":false}"#,
+            r#"{"name":"foo","vers":"1.2.0","yanked":false}"#,
+            r#"{"name":"foo","vers":"1.3.0","yanked":true}"#,
+            r#"{"name":"foo","vers":"2.0.0-beta.1","yanked":false}"#,
+        ]
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+        assert_eq!(latest_version(&entries), Some(Version::new(1, 2, 0)));
+    }
+
+    #[test]
+    fn manifest_dependencies_are_parsed_from_every_form() {
+        let manifest = CargoToml::parse_str(
+            r#"
+            [package]
+            name = "depchk"
+
+            [dependencies]
+            serde = "1.0"
+            tokio = { version = "1.25", features = ["full"] }
+            semver_crate = { package = "semver", version = "1" }
+            local = { path = "../local" }
+
+            [dev-dependencies]
+            pretty_assertions = "1"
+            "#,
+        )
+        .unwrap();
+
+        let names = manifest
+            .iter()
+            .map(|dependency| dependency.get_name())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["semver", "serde", "tokio", "pretty_assertions"]);
+    }
+
+    #[test]
+    fn requirements_are_compatible_when_they_share_a_version() {
+        let caret = CargoDependency::new("serde", "1.2");
+
+        assert!(caret.is_compatible_with(&CargoDependency::new("serde", "1.5")));
+        assert!(caret.is_compatible_with(&CargoDependency::new("serde", ">=1.0, <1.3")));
+        assert!(caret.is_compatible_with(&CargoDependency::new("serde", "*")));
+        assert!(!caret.is_compatible_with(&CargoDependency::new("serde", "2")));
+        assert!(!CargoDependency::new("rand", "0.7")
+            .is_compatible_with(&CargoDependency::new("rand", "0.8")));
+    }
+
+    #[test]
+    fn lockfiles_are_found_at_the_root_of_the_workspace() {
+        let root = std::env::temp_dir().join(format!("depchk-cargo-lock-{}", std::process::id()));
+        let member = root.join("member");
+
+        fs::create_dir_all(&member).unwrap();
+        fs::write(
+            root.join("Cargo.lock"),
+            r#"
+            version = 3
+
+            [[package]]
+            name = "log"
+            version = "0.4.10"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+
+            [[package]]
+            name = "log"
+            version = "0.3.9"
+            source = "sparse+https://index.crates.io/"
+
+            [[package]]
+            name = "member"
+            version = "0.1.0"
+
+            [[package]]
+            name = "forked"
+            version = "1.0.0"
+            source = "git+https://github.com/example/forked#0123456789abcdef"
+            "#,
+        )
+        .unwrap();
+
+        let locked =
+            CargoToml::parse_lockfile(&member.join("Cargo.toml").to_string_lossy()).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            locked,
+            Some(LockedVersions::from([(
+                "log".to_string(),
+                vec!["0.4.10".to_string(), "0.3.9".to_string()]
+            )]))
+        );
+    }
+}
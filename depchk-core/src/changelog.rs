@@ -0,0 +1,352 @@
+use reqwest::header::USER_AGENT;
+use reqwest::Client;
+use semver::Version;
+use serde::Deserialize;
+
+use crate::client::RegistryClient;
+use crate::maintenance::github_repository;
+use crate::{Dependency, DirectResult};
+
+const GITHUB_REPOS_API: &str = "https://api.github.com/repos";
+const GITHUB_RAW: &str = "https://raw.githubusercontent.com";
+
+/// The changelog files looked up when a repository publishes no GitHub releases.
+const CHANGELOG_FILES: [&str; 3] = ["CHANGELOG.md", "HISTORY.md", "CHANGES.md"];
+
+/// What changed in one version of a dependency, as its release notes or the entry of
+/// its changelog tell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReleaseNotes {
+    version: String,
+    url: Option<String>,
+    notes: String,
+}
+
+/// The settings of the changelog lookups.
+#[derive(Clone, Debug, Default)]
+pub struct ChangelogLookup {
+    github_token: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GitHubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    draft: bool,
+}
+
+impl ReleaseNotes {
+    pub fn new(version: &str, notes: &str) -> Self {
+        ReleaseNotes {
+            version: version.to_string(),
+            url: None,
+            notes: notes.to_string(),
+        }
+    }
+
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The page of the release, if the notes come from a GitHub release.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// The notes, as the Markdown they were written in.
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+}
+
+impl ChangelogLookup {
+    /// Looks up changelogs in GitHub repositories. Without a token, GitHub only answers
+    /// a few dozen lookups an hour.
+    pub fn new(github_token: Option<String>) -> Self {
+        ChangelogLookup { github_token }
+    }
+
+    /// Looks up what changed in the versions after `from`, up to and including `to`,
+    /// newest first. The notes come from the GitHub releases of the repository of the
+    /// dependency, or from its changelog file if it has no releases. Lookups that fail
+    /// return no notes, rather than failing the whole check.
+    pub async fn between<T: Dependency + ?Sized>(
+        &self,
+        client: &RegistryClient,
+        dependency: &T,
+        from: &str,
+        to: &str,
+    ) -> Vec<ReleaseNotes> {
+        let (Ok(from), Ok(to)) = (Version::parse(from), Version::parse(to)) else {
+            return Vec::new();
+        };
+
+        if client.is_offline() {
+            return Vec::new();
+        }
+
+        let repository = client.repository(dependency).await.ok().flatten();
+
+        let Some((owner, name)) = repository.as_deref().and_then(github_repository) else {
+            return Vec::new();
+        };
+
+        let http = client.http();
+        let releases = self
+            .fetch_releases(http, &owner, &name)
+            .await
+            .unwrap_or_default();
+
+        let mut notes = release_notes(&releases, dependency.get_name(), &from, &to);
+
+        if notes.is_empty() {
+            for file in CHANGELOG_FILES {
+                if let Ok(Some(changelog)) = fetch_changelog(http, &owner, &name, file).await {
+                    notes = changelog_entries(&changelog, &from, &to);
+                    break;
+                }
+            }
+        }
+
+        notes
+    }
+
+    async fn fetch_releases(
+        &self,
+        client: &Client,
+        owner: &str,
+        name: &str,
+    ) -> DirectResult<Vec<GitHubRelease>> {
+        let request = client
+            .get(format!("{}/{}/{}/releases", GITHUB_REPOS_API, owner, name))
+            .query(&[("per_page", "100")])
+            .header(USER_AGENT, "depchk");
+
+        let request = match &self.github_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+}
+
+/// The changelog file of the default branch of a repository, or `None` if it has none.
+async fn fetch_changelog(
+    client: &Client,
+    owner: &str,
+    name: &str,
+    file: &str,
+) -> DirectResult<Option<String>> {
+    let response = client
+        .get(format!("{}/{}/{}/HEAD/{}", GITHUB_RAW, owner, name, file))
+        .header(USER_AGENT, "depchk")
+        .send()
+        .await?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    Ok(Some(response.error_for_status()?.text().await?))
+}
+
+/// The notes of the releases between both versions, newest first.
+fn release_notes(
+    releases: &[GitHubRelease],
+    package: &str,
+    from: &Version,
+    to: &Version,
+) -> Vec<ReleaseNotes> {
+    let mut notes: Vec<_> = releases
+        .iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| Some((tag_version(&release.tag_name, package)?, release)))
+        .filter(|(version, _)| is_between(version, from, to))
+        .map(|(version, release)| {
+            ReleaseNotes::new(
+                &version.to_string(),
+                release.body.as_deref().unwrap_or_default().trim(),
+            )
+            .with_url(&release.html_url)
+        })
+        .collect();
+
+    notes.sort_by_cached_key(|notes| std::cmp::Reverse(Version::parse(&notes.version).ok()));
+    notes
+}
+
+/// Whether a version was released after `from`, up to and including `to`. Prereleases
+/// are left out unless `to` is one.
+fn is_between(version: &Version, from: &Version, to: &Version) -> bool {
+    version > from && version <= to && (version.pre.is_empty() || version == to)
+}
+
+/// The version a release tag is for, e.g. `v1.2.3`, `1.2.3`, or `axios@1.2.3` and
+/// `serde_json-v1.0.0` in repositories that release several packages. Tags that name
+/// another package than `package` are not for any of its versions.
+fn tag_version(tag: &str, package: &str) -> Option<Version> {
+    let start = tag.find(|c: char| c.is_ascii_digit())?;
+    let (prefix, version) = tag.split_at(start);
+
+    let prefix = prefix
+        .trim_end_matches('v')
+        .trim_end_matches(['@', '-', '_', '/']);
+
+    // Scoped npm packages are often tagged without their scope
+    let unscoped = package.rsplit('/').next().unwrap_or(package);
+
+    if !prefix.is_empty() && prefix != package && prefix != unscoped {
+        return None;
+    }
+
+    Version::parse(version).ok()
+}
+
+/// The entries of a Markdown changelog for the versions between both versions, newest
+/// first. An entry starts at a heading with a version in it, e.g. `## [1.2.3] - 2023-10-01`
+/// or `# v1.2.3`, and runs up to the next heading of the same level or above.
+fn changelog_entries(changelog: &str, from: &Version, to: &Version) -> Vec<ReleaseNotes> {
+    let mut entries = Vec::new();
+    let mut current: Option<(usize, Version, Vec<&str>)> = None;
+
+    for line in changelog.lines() {
+        let level = line.chars().take_while(|c| *c == '#').count();
+        let is_heading = level > 0 && line[level..].starts_with(' ');
+
+        if is_heading && current.as_ref().is_some_and(|(open, ..)| level <= *open) {
+            entries.extend(current.take());
+        }
+
+        if is_heading && current.is_none() {
+            if let Some(version) = heading_version(&line[level..]) {
+                current = Some((level, version, Vec::new()));
+                continue;
+            }
+        }
+
+        if let Some((_, _, lines)) = &mut current {
+            lines.push(line);
+        }
+    }
+
+    entries.extend(current);
+
+    let mut notes: Vec<_> = entries
+        .into_iter()
+        .filter(|(_, version, _)| is_between(version, from, to))
+        .map(|(_, version, lines)| ReleaseNotes::new(&version.to_string(), lines.join("\n").trim()))
+        .collect();
+
+    notes.sort_by_cached_key(|notes| std::cmp::Reverse(Version::parse(&notes.version).ok()));
+    notes
+}
+
+/// The first word of a changelog heading that is a version, e.g. `1.2.3` out of
+/// `[v1.2.3](https://...) (2023-10-01)`.
+fn heading_version(heading: &str) -> Option<Version> {
+    heading
+        .split(|c: char| c.is_whitespace() || matches!(c, '[' | ']' | '(' | ')'))
+        .map(|word| word.trim_start_matches('v'))
+        .find_map(|word| Version::parse(word).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_version() {
+        let version = Some(Version::new(1, 2, 3));
+
+        assert_eq!(tag_version("v1.2.3", "axios"), version);
+        assert_eq!(tag_version("1.2.3", "axios"), version);
+        assert_eq!(tag_version("axios@1.2.3", "axios"), version);
+        assert_eq!(tag_version("core@v1.2.3", "@babel/core"), version);
+        assert_eq!(tag_version("serde_json-v1.2.3", "serde_json"), version);
+
+        assert_eq!(tag_version("serde_derive-v1.2.3", "serde_json"), None);
+        assert_eq!(tag_version("nightly", "axios"), None);
+    }
+
+    #[test]
+    fn test_release_notes() {
+        let release = |tag: &str, draft: bool| GitHubRelease {
+            tag_name: tag.to_string(),
+            html_url: format!("https://github.com/axios/axios/releases/tag/{}", tag),
+            body: Some(format!("Notes of {}\n", tag)),
+            draft,
+        };
+
+        let releases = [
+            release("v1.6.2", false),
+            release("v1.7.0", true),
+            release("v1.6.1-beta.1", false),
+            release("v1.6.1", false),
+            release("v1.6.0", false),
+        ];
+
+        let notes = release_notes(
+            &releases,
+            "axios",
+            &Version::new(1, 6, 0),
+            &Version::new(1, 6, 2),
+        );
+
+        assert_eq!(
+            notes,
+            vec![
+                ReleaseNotes::new("1.6.2", "Notes of v1.6.2")
+                    .with_url("https://github.com/axios/axios/releases/tag/v1.6.2"),
+                ReleaseNotes::new("1.6.1", "Notes of v1.6.1")
+                    .with_url("https://github.com/axios/axios/releases/tag/v1.6.1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changelog_entries() {
+        let changelog = "# Changelog
+
+All notable changes to this project are documented here.
+
+## [Unreleased]
+
+- Work in progress
+
+## [2.1.0] - 2023-10-01
+
+### Added
+
+- A new option
+
+## [v2.0.0](https://github.com/owner/name/compare/v1.9.0...v2.0.0) (2023-09-01)
+
+### Removed
+
+- The deprecated API
+
+## 1.9.0
+
+- A fix
+";
+
+        let entries = changelog_entries(changelog, &Version::new(1, 9, 0), &Version::new(2, 1, 0));
+
+        assert_eq!(
+            entries,
+            vec![
+                ReleaseNotes::new("2.1.0", "### Added\n\n- A new option"),
+                ReleaseNotes::new("2.0.0", "### Removed\n\n- The deprecated API"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1365 @@
+use crate::cache::Cache;
+use crate::registry::PackageMetadata;
+use crate::{DepchkError, Dependency, DirectResult, Unavailability};
+
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fmt::Display;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use reqwest::{Client, ClientBuilder};
+use semver::Version;
+use tokio::sync::OnceCell;
+
+use crate::time::{self, Instant};
+use crate::transport::HttpClient;
+
+/// Versions resolved during the lifetime of a client, keyed by registry and package name.
+type ResolvedVersions = HashMap<(String, String), Arc<OnceCell<String>>>;
+
+/// Package metadata fetched during the lifetime of a client, keyed by registry and package
+/// name. Packages whose registry does not publish it as a single document have none.
+type FetchedMetadata = HashMap<(String, String), Arc<OnceCell<Option<Arc<PackageMetadata>>>>>;
+
+/// The prerelease policy of each package, by name.
+pub(crate) type PrereleasePolicies = Box<dyn Fn(&str) -> PrereleasePolicy + Send + Sync>;
+
+/// The client used to look up dependencies in their registries. Wraps the
+/// underlying HTTP client along with the optional response cache that is
+/// consulted before any request is made.
+///
+/// Every version resolved through a client is also remembered for as long
+/// as the client lives, so a package that is declared several times (for
+/// example as both a dependency and a dev dependency) is only looked up once.
+///
+/// The other lookups, such as published versions, release dates or licenses, only
+/// add detail to mismatches that were already found. They are never cached, and
+/// return nothing when running offline. Registries that publish the metadata of a
+/// package as a single document have it fetched once per client, however many of
+/// these lookups read it.
+pub struct RegistryClient {
+    client: HttpClient,
+    cache: Option<Box<dyn Cache>>,
+    retry_policy: RetryPolicy,
+    deadline: Option<tokio::time::Instant>,
+    offline: bool,
+    min_release_age: Option<Duration>,
+    prerelease_policies: Option<PrereleasePolicies>,
+    resolved: Mutex<ResolvedVersions>,
+    metadata: Mutex<FetchedMetadata>,
+    stats: Mutex<LookupStats>,
+}
+
+/// A registry that dependencies are resolved from instead of the default one of their
+/// ecosystem, such as a mirror or a private registry, along with the token it needs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Registry {
+    url: String,
+    token: Option<String>,
+    tag: Option<String>,
+}
+
+/// Whether prerelease versions, such as `2.0.0-beta.1`, can be reported as the latest
+/// version of a package.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PrereleasePolicy {
+    /// The latest version is the one the registry reports: the version tagged as latest
+    /// on npm, which may be a prerelease, and the newest version that is not one on
+    /// crates.io.
+    #[default]
+    Registry,
+    /// Prereleases are never the latest version. When the registry reports one, the
+    /// newest version before it that is not a prerelease is the latest instead.
+    Never,
+    /// Prereleases are only the latest version of packages whose constraint includes
+    /// one, such as `^2.0.0-beta.1`, which are already tracking them.
+    Constraint,
+    /// The newest published version is the latest, whether it is a prerelease or not.
+    Always,
+}
+
+/// Counters describing the lookups made through a [`RegistryClient`],
+/// used to make the cost of a check (and the effect of caching) visible.
+#[derive(Clone, Debug, Default)]
+pub struct LookupStats {
+    cache_hits: usize,
+    cache_misses: usize,
+    requests: BTreeMap<String, usize>,
+    network_time: Duration,
+    lookups: Vec<(String, Duration)>,
+}
+
+/// The error reported for lookups of packages that are not in the
+/// cache while the client is offline, whose latest version is unknown.
+#[derive(Debug)]
+pub struct NotCached;
+
+/// The error reported for lookups that had not resolved
+/// by the time the deadline of the client passed.
+#[derive(Debug)]
+pub struct DeadlineExceeded;
+
+/// How registry lookups that failed for a transient reason (server errors,
+/// rate limiting, timeouts or dropped connections) are retried. The delay
+/// before each retry grows exponentially and is randomized ("full jitter"),
+/// so that many failing lookups do not all retry at the same moment.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(retries: u32, base_delay: Duration) -> Self {
+        RetryPolicy {
+            retries,
+            base_delay,
+            max_delay: Duration::from_secs(10),
+        }
+    }
+
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        RetryPolicy::new(0, Duration::ZERO)
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// The delay before the given retry (starting at 0), which is picked at
+    /// random between zero and the exponential backoff for that attempt.
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+
+        let jitter = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+
+        backoff.mul_f64(jitter)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3, Duration::from_millis(250))
+    }
+}
+
+impl Registry {
+    pub fn new(url: &str) -> Self {
+        Registry {
+            url: url.trim_end_matches('/').to_string(),
+            token: None,
+            tag: None,
+        }
+    }
+
+    /// Authenticates the requests to the registry with a bearer token.
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Resolves the latest version of packages from a distribution tag other than
+    /// `latest`, such as `next`, for registries that have them.
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_string());
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+}
+
+impl RegistryClient {
+    /// Creates a registry client around a [`reqwest::Client`], or an [`HttpClient`] that
+    /// sends its requests through another transport.
+    pub fn new(client: impl Into<HttpClient>) -> Self {
+        RegistryClient {
+            client: client.into(),
+            cache: None,
+            retry_policy: RetryPolicy::default(),
+            deadline: None,
+            offline: false,
+            min_release_age: None,
+            prerelease_policies: None,
+            resolved: Mutex::new(HashMap::new()),
+            metadata: Mutex::new(HashMap::new()),
+            stats: Mutex::new(LookupStats::default()),
+        }
+    }
+
+    pub fn with_cache(mut self, cache: impl Cache + 'static) -> Self {
+        self.cache = Some(Box::new(cache));
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets a point in time after which every lookup that has not resolved
+    /// yet fails with [`DeadlineExceeded`] instead of waiting any longer.
+    /// Deadlines need the timer of a tokio runtime, so they are not available in WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_deadline(mut self, deadline: tokio::time::Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Answers every lookup from the cache, however old the cached entries are,
+    /// and never queries the registries. Lookups of packages that are not in
+    /// the cache fail with [`NotCached`].
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    /// Only reports versions published at least this long ago as the latest ones,
+    /// falling back to the newest version that is old enough when the latest is
+    /// too recent. Registries that do not publish release dates are not affected.
+    pub fn with_min_release_age(mut self, min_release_age: Duration) -> Self {
+        self.min_release_age = Some(min_release_age);
+        self
+    }
+
+    /// Decides whether prereleases can be the latest version of each package, by name,
+    /// instead of leaving it to the registry.
+    pub fn with_prerelease_policy(
+        mut self,
+        policy: impl Fn(&str) -> PrereleasePolicy + Send + Sync + 'static,
+    ) -> Self {
+        self.prerelease_policies = Some(Box::new(policy));
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    pub fn http(&self) -> &Client {
+        self.client.inner()
+    }
+
+    /// The client dependencies are looked up with, along with its transport.
+    pub fn transport(&self) -> &HttpClient {
+        &self.client
+    }
+
+    /// A snapshot of the statistics of every lookup made so far.
+    pub fn stats(&self) -> LookupStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Resolves the latest version of the dependency, answering from the
+    /// cache when a fresh entry exists and populating it otherwise. Concurrent
+    /// calls for the same package share a single lookup.
+    pub async fn latest_version<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<String> {
+        let resolved = self.resolved_cell(dependency.get_registry(), dependency.get_name());
+
+        let lookup = resolved.get_or_try_init(|| self.fetch_latest_version(dependency));
+
+        let version = match self.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, lookup)
+                .await
+                .map_err(|_| DeadlineExceeded)??,
+            None => lookup.await?,
+        };
+
+        Ok(version.clone())
+    }
+
+    /// The latest version of the dependency, if an earlier lookup already resolved it.
+    /// Unlike [`RegistryClient::latest_version`], this never queries the registry.
+    pub fn resolved_version<T: Dependency + ?Sized>(&self, dependency: &T) -> Option<String> {
+        self.resolved_cell(dependency.get_registry(), dependency.get_name())
+            .get()
+            .cloned()
+    }
+
+    /// Forgets the latest version of the dependency, both the one resolved during the
+    /// lifetime of the client and the one in its cache, so that the next lookup asks
+    /// the registry again.
+    pub fn invalidate<T: Dependency + ?Sized>(&self, dependency: &T) -> io::Result<()> {
+        let registry = dependency.get_registry();
+        let name = dependency.get_name();
+
+        self.resolved
+            .lock()
+            .unwrap()
+            .remove(&(registry.to_string(), name.to_string()));
+
+        match &self.cache {
+            Some(cache) => cache.invalidate(registry, name),
+            None => Ok(()),
+        }
+    }
+
+    /// Resolves the latest versions of as many of the dependencies as possible
+    /// without looking each one up on its own, from the versions resolved earlier,
+    /// the cache and the bulk lookup endpoint of the registry, if it has one.
+    /// Dependencies missing from the returned map still need to be resolved individually.
+    pub async fn latest_versions<T: Dependency>(
+        &self,
+        dependencies: &[T],
+    ) -> HashMap<String, String> {
+        let mut resolved = HashMap::new();
+        let mut unresolved = Vec::new();
+
+        for dependency in dependencies {
+            let registry = dependency.get_registry();
+            let name = dependency.get_name();
+
+            let resolved_cell = self.resolved_cell(registry, name);
+
+            let known = resolved_cell.get().cloned().or_else(|| {
+                if self.min_release_age.is_some() || self.prerelease_policies.is_some() {
+                    return None;
+                }
+
+                let version = self.cached(registry, name)?;
+                let _ = resolved_cell.set(version.clone());
+
+                Some(version)
+            });
+
+            match known {
+                Some(version) => {
+                    resolved.insert(name.to_string(), version);
+                }
+                None => unresolved.push(dependency),
+            }
+        }
+
+        // Bulk lookups report neither release dates nor every published version, so
+        // every version has to be checked against the minimum release age and the
+        // prerelease policy on its own
+        if unresolved.is_empty()
+            || self.offline
+            || self.min_release_age.is_some()
+            || self.prerelease_policies.is_some()
+        {
+            return resolved;
+        }
+
+        // A failed bulk lookup is not reported, since every dependency
+        // it should have resolved is retried on its own afterwards.
+        let lookup = T::fetch_latest_versions(&self.client, &unresolved);
+        let started = Instant::now();
+
+        let fetched = match self.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, lookup)
+                .await
+                .unwrap_or_default(),
+            None => lookup.await,
+        };
+
+        if fetched.is_some() {
+            self.record_request(unresolved[0].get_registry(), "(bulk)", started);
+        }
+
+        if let Some(Ok(fetched)) = fetched {
+            for dependency in unresolved {
+                let Some(version) = fetched.get(dependency.get_name()) else {
+                    continue;
+                };
+
+                self.stats.lock().unwrap().cache_misses += 1;
+
+                self.remember(dependency, version);
+                resolved.insert(dependency.get_name().to_string(), version.clone());
+            }
+        }
+
+        resolved
+    }
+
+    /// Lists every published version of the dependency.
+    pub async fn published_versions<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<Vec<String>> {
+        let lookup = async {
+            let versions = dependency.fetch_versions(&self.client).await?;
+
+            Ok((!versions.is_empty()).then_some(versions))
+        };
+
+        Ok(self.lookup(dependency, lookup).await?.unwrap_or_default())
+    }
+
+    /// Looks up whether the given version of the dependency can still be installed.
+    pub async fn availability<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+        version: &str,
+    ) -> DirectResult<Option<Unavailability>> {
+        let lookup = async {
+            let availability = dependency.fetch_availability(&self.client, version).await?;
+
+            // Unlike the other lookups, an available version is an answer of the registry
+            Ok(Some(availability))
+        };
+
+        Ok(self.lookup(dependency, lookup).await?.flatten())
+    }
+
+    /// Looks up the release dates of the published versions of the dependency.
+    pub async fn release_dates<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<HashMap<String, SystemTime>> {
+        if let Some(metadata) = self.metadata(dependency).await? {
+            return Ok(metadata.release_dates().clone());
+        }
+
+        let lookup = async {
+            let release_dates = dependency.fetch_release_dates(&self.client).await?;
+
+            Ok((!release_dates.is_empty()).then_some(release_dates))
+        };
+
+        Ok(self.lookup(dependency, lookup).await?.unwrap_or_default())
+    }
+
+    /// Looks up the homepage of the dependency.
+    pub async fn homepage<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<Option<String>> {
+        match self.metadata(dependency).await? {
+            Some(metadata) => Ok(metadata
+                .homepage()
+                .or(metadata.repository())
+                .map(str::to_string)),
+            None => {
+                self.lookup(dependency, dependency.fetch_homepage(&self.client))
+                    .await
+            }
+        }
+    }
+
+    /// Looks up the repository of the dependency.
+    pub async fn repository<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<Option<String>> {
+        match self.metadata(dependency).await? {
+            Some(metadata) => Ok(metadata.repository().map(str::to_string)),
+            None => {
+                self.lookup(dependency, dependency.fetch_repository(&self.client))
+                    .await
+            }
+        }
+    }
+
+    /// Looks up why the latest version of the dependency is deprecated, if it is.
+    pub async fn deprecation<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<Option<String>> {
+        match self.metadata(dependency).await? {
+            Some(metadata) => Ok(metadata.deprecation().map(str::to_string)),
+            None => {
+                self.lookup(dependency, dependency.fetch_deprecation(&self.client))
+                    .await
+            }
+        }
+    }
+
+    /// Looks up the license the given version of the dependency is published under.
+    pub async fn license<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+        version: &str,
+    ) -> DirectResult<Option<String>> {
+        match self.metadata(dependency).await? {
+            Some(metadata) => Ok(metadata.license(version).map(str::to_string)),
+            None => {
+                self.lookup(dependency, dependency.fetch_license(&self.client, version))
+                    .await
+            }
+        }
+    }
+
+    /// Looks up the published versions of the dependency that carry a provenance
+    /// attestation.
+    pub async fn attested_versions<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<Option<HashSet<String>>> {
+        match self.metadata(dependency).await? {
+            Some(metadata) => Ok(metadata.attested_versions().cloned()),
+            None => {
+                self.lookup(dependency, dependency.fetch_attested_versions(&self.client))
+                    .await
+            }
+        }
+    }
+
+    /// The metadata of the dependency, if its registry publishes it as a single document.
+    /// Concurrent calls for the same package share a single request, and the document
+    /// is kept for the lifetime of the client.
+    async fn metadata<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<Option<Arc<PackageMetadata>>> {
+        let fetched = self
+            .metadata
+            .lock()
+            .unwrap()
+            .entry((
+                dependency.get_registry().to_string(),
+                dependency.get_name().to_string(),
+            ))
+            .or_default()
+            .clone();
+
+        let metadata = fetched
+            .get_or_try_init(|| async {
+                let lookup = async {
+                    let metadata = dependency.fetch_metadata(&self.client).await?;
+
+                    Ok(metadata.map(Arc::new))
+                };
+
+                self.lookup(dependency, lookup).await
+            })
+            .await?;
+
+        Ok(metadata.clone())
+    }
+
+    /// Runs a lookup of the dependency other than its latest version within the
+    /// deadline of the client, or nothing when running offline. Registries that cannot
+    /// answer the lookup return `None` without making a request, so only the other
+    /// lookups are counted as requests.
+    async fn lookup<T, F, R>(&self, dependency: &T, lookup: F) -> DirectResult<Option<R>>
+    where
+        T: Dependency + ?Sized,
+        F: Future<Output = DirectResult<Option<R>>>,
+    {
+        self.lookup_in(dependency.get_registry(), dependency.get_name(), lookup)
+            .await
+    }
+
+    /// Like [`RegistryClient::lookup`], for lookups of a package in another service
+    /// than its registry, such as the repository it is hosted in, which are counted
+    /// as requests to that service.
+    pub(crate) async fn lookup_in<F, R>(
+        &self,
+        registry: &str,
+        name: &str,
+        lookup: F,
+    ) -> DirectResult<Option<R>>
+    where
+        F: Future<Output = DirectResult<Option<R>>>,
+    {
+        if self.offline {
+            return Ok(None);
+        }
+
+        let started = Instant::now();
+
+        let found = match self.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, lookup)
+                .await
+                .map_err(|_| DeadlineExceeded)?,
+            None => lookup.await,
+        };
+
+        if !matches!(found, Ok(None)) {
+            self.record_request(registry, name, started);
+        }
+
+        found
+    }
+
+    async fn fetch_latest_version<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<String> {
+        let version = self.fetch_newest_version(dependency).await?;
+        let version = self.permitted_version(dependency, version).await?;
+
+        self.eligible_version(dependency, version).await
+    }
+
+    /// The latest version of the dependency under its prerelease policy, out of the one
+    /// its registry reports. Running offline, the published versions are not known, so
+    /// the version the registry reported is kept.
+    async fn permitted_version<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+        latest: String,
+    ) -> DirectResult<String> {
+        let Some(policies) = &self.prerelease_policies else {
+            return Ok(latest);
+        };
+
+        let prereleases = match policies(dependency.get_name()) {
+            PrereleasePolicy::Registry => return Ok(latest),
+            PrereleasePolicy::Never => false,
+            PrereleasePolicy::Constraint => has_prerelease(dependency.get_constraint()),
+            PrereleasePolicy::Always => true,
+        };
+
+        let Ok(newest) = Version::parse(&latest) else {
+            return Ok(latest);
+        };
+
+        // Only prereleases need to be replaced when they are not permitted
+        if !prereleases && newest.pre.is_empty() {
+            return Ok(latest);
+        }
+
+        let versions = self.published_versions(dependency).await?;
+
+        // Either a newer prerelease than the version the registry reports, or the
+        // newest version before it that is not a prerelease
+        let permitted = versions
+            .iter()
+            .filter_map(|version| Some((Version::parse(version).ok()?, version)))
+            .filter(|(version, _)| match prereleases {
+                true => *version > newest,
+                false => version.pre.is_empty() && *version < newest,
+            })
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(permitted.map_or(latest, |(_, version)| version.clone()))
+    }
+
+    /// The newest version of the dependency that was published at least the minimum
+    /// release age ago, starting from its latest version. Versions without a release
+    /// date are taken to be old enough, and prereleases are only picked when the
+    /// latest version is one as well. When no version is old enough, the latest
+    /// version is kept.
+    async fn eligible_version<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+        latest: String,
+    ) -> DirectResult<String> {
+        let Some(min_release_age) = self.min_release_age else {
+            return Ok(latest);
+        };
+
+        let release_dates = self.release_dates(dependency).await?;
+        let cutoff = SystemTime::now()
+            .checked_sub(min_release_age)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+
+        if release_dates
+            .get(&latest)
+            .is_none_or(|released_at| *released_at <= cutoff)
+        {
+            return Ok(latest);
+        }
+
+        let Ok(newest) = Version::parse(&latest) else {
+            return Ok(latest);
+        };
+
+        let eligible = release_dates
+            .iter()
+            .filter(|(_, released_at)| **released_at <= cutoff)
+            .filter_map(|(version, _)| Some((Version::parse(version).ok()?, version)))
+            .filter(|(version, _)| *version < newest)
+            .filter(|(version, _)| version.pre.is_empty() || !newest.pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(eligible.map_or(latest, |(_, version)| version.clone()))
+    }
+
+    /// Looks up the latest version of the dependency, from the cache or its registry.
+    async fn fetch_newest_version<T: Dependency + ?Sized>(
+        &self,
+        dependency: &T,
+    ) -> DirectResult<String> {
+        let registry = dependency.get_registry();
+        let name = dependency.get_name();
+
+        if self.offline {
+            let cached = self
+                .cache
+                .as_ref()
+                .and_then(|cache| cache.get_stale(registry, name));
+
+            let mut stats = self.stats.lock().unwrap();
+
+            match cached {
+                Some(version) => {
+                    stats.cache_hits += 1;
+                    return Ok(version);
+                }
+                None => {
+                    stats.cache_misses += 1;
+                    return Err(NotCached.into());
+                }
+            }
+        }
+
+        if let Some(version) = self.cached(registry, name) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(registry, name, version = %version, "answered from the cache");
+
+            return Ok(version);
+        }
+
+        self.stats.lock().unwrap().cache_misses += 1;
+
+        let mut attempt = 0;
+
+        let version = loop {
+            let started = Instant::now();
+
+            match dependency.fetch_latest_version(&self.client).await {
+                Ok(version) => {
+                    self.record_request(registry, name, started);
+                    break version;
+                }
+                Err(err) if attempt >= self.retry_policy.retries || !is_transient(&err) => {
+                    self.record_request(registry, name, started);
+                    return Err(err);
+                }
+                Err(_err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(registry, name, attempt, error = %_err, "retrying lookup");
+
+                    self.record_request(registry, name, started)
+                }
+            }
+
+            time::sleep(self.retry_policy.delay(attempt)).await;
+            attempt += 1;
+        };
+
+        if let Some(cache) = &self.cache {
+            // The cache is only an optimization, so failing to write to it
+            // should never fail the check itself.
+            let _ = cache.set(registry, name, &version);
+        }
+
+        Ok(version)
+    }
+
+    fn record_request(&self, registry: &str, name: &str, started: Instant) {
+        self.stats
+            .lock()
+            .unwrap()
+            .record_request(registry, name, started.elapsed());
+    }
+
+    /// Reads a fresh entry from the cache, if there is one, counting it as a hit.
+    fn cached(&self, registry: &str, name: &str) -> Option<String> {
+        let version = self.cache.as_ref()?.get(registry, name)?;
+
+        self.stats.lock().unwrap().cache_hits += 1;
+
+        Some(version)
+    }
+
+    fn remember<T: Dependency + ?Sized>(&self, dependency: &T, version: &str) {
+        let registry = dependency.get_registry();
+        let name = dependency.get_name();
+
+        let _ = self.resolved_cell(registry, name).set(version.to_string());
+
+        if let Some(cache) = &self.cache {
+            let _ = cache.set(registry, name, version);
+        }
+    }
+
+    fn resolved_cell(&self, registry: &str, name: &str) -> Arc<OnceCell<String>> {
+        self.resolved
+            .lock()
+            .unwrap()
+            .entry((registry.to_string(), name.to_string()))
+            .or_default()
+            .clone()
+    }
+}
+
+/// Whether a constraint includes a prerelease, such as `^2.0.0-beta.1` or
+/// `>=1.0.0-rc.1, <2`.
+fn has_prerelease(constraint: &str) -> bool {
+    constraint
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | '|'))
+        .map(|part| part.trim_start_matches(['^', '~', '=', '<', '>', 'v']))
+        .any(|part| Version::parse(part).is_ok_and(|version| !version.pre.is_empty()))
+}
+
+impl Display for NotCached {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown, not in the offline cache")
+    }
+}
+
+impl Error for NotCached {}
+
+impl Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timed out before the deadline")
+    }
+}
+
+impl Error for DeadlineExceeded {}
+
+impl LookupStats {
+    /// The number of lookups that were answered from the cache.
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits
+    }
+
+    /// The number of lookups that could not be answered from the cache.
+    pub fn cache_misses(&self) -> usize {
+        self.cache_misses
+    }
+
+    /// The number of requests sent to each registry, retries included.
+    pub fn requests(&self) -> &BTreeMap<String, usize> {
+        &self.requests
+    }
+
+    /// The time spent waiting on all requests combined. Since requests run
+    /// concurrently, this is usually longer than the check itself took.
+    pub fn network_time(&self) -> Duration {
+        self.network_time
+    }
+
+    /// The packages whose requests took the longest, slowest first.
+    pub fn slowest(&self, count: usize) -> Vec<(&str, Duration)> {
+        let mut lookups = self
+            .lookups
+            .iter()
+            .map(|(name, duration)| (name.as_str(), *duration))
+            .collect::<Vec<_>>();
+
+        lookups.sort_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+        lookups.truncate(count);
+
+        lookups
+    }
+
+    fn record_request(&mut self, registry: &str, name: &str, duration: Duration) {
+        *self.requests.entry(registry.to_string()).or_default() += 1;
+        self.network_time += duration;
+        self.lookups.push((name.to_string(), duration));
+    }
+}
+
+/// Creates a builder for the HTTP client of a [`RegistryClient`], tuned for
+/// sending many small requests to the same few registry hosts. HTTP/2 is
+/// negotiated whenever the registry supports it, so that concurrent lookups
+/// are multiplexed over a handful of pooled connections instead of each
+/// opening their own, and those connections are kept alive between lookups.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn http_client_builder() -> ClientBuilder {
+    Client::builder()
+        .http2_adaptive_window(true)
+        .http2_keep_alive_interval(Duration::from_secs(15))
+        .http2_keep_alive_while_idle(true)
+        .tcp_keepalive(Duration::from_secs(60))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(8)
+}
+
+/// Creates a builder for the HTTP client of a [`RegistryClient`]. In WASM, requests are
+/// made with the fetch API of the browser, which manages connections by itself.
+#[cfg(target_arch = "wasm32")]
+pub fn http_client_builder() -> ClientBuilder {
+    Client::builder()
+}
+
+/// Whether the error (or any of its sources) is likely to go away by itself,
+/// making the request that caused it worth retrying.
+fn is_transient(err: &DepchkError) -> bool {
+    let retryable_status = err.status().is_some_and(|status| {
+        status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+    });
+
+    let mut current: Option<&(dyn Error + 'static)> = match err {
+        DepchkError::Network(err) if err.is_timeout() || is_connect(err) => return true,
+        DepchkError::Network(err) => Some(err),
+        DepchkError::Io(err) => Some(err),
+        _ => None,
+    };
+
+    while let Some(err) = current {
+        if let Some(err) = err.downcast_ref::<io::Error>() {
+            if matches!(
+                err.kind(),
+                io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::TimedOut
+                    | io::ErrorKind::UnexpectedEof
+            ) {
+                return true;
+            }
+        }
+
+        current = err.source();
+    }
+
+    retryable_status
+}
+
+/// Whether the request failed because no connection to the registry could be made.
+/// Browsers do not tell connection failures apart from other failed fetches.
+#[cfg(not(target_arch = "wasm32"))]
+fn is_connect(err: &reqwest::Error) -> bool {
+    err.is_connect()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn is_connect(_err: &reqwest::Error) -> bool {
+    false
+}
+
+impl Default for RegistryClient {
+    fn default() -> Self {
+        RegistryClient::new(Client::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use crate::cache::MemoryCache;
+
+    struct CountingDependency {
+        name: String,
+        lookups: Arc<AtomicUsize>,
+        failures: usize,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Dependency for CountingDependency {
+        fn get_name(&self) -> &str {
+            &self.name
+        }
+
+        fn get_constraint(&self) -> &str {
+            "*"
+        }
+
+        fn get_registry(&self) -> &str {
+            "counting"
+        }
+
+        fn try_is_satisfied_by(&self, _version: &str) -> DirectResult<bool> {
+            Ok(true)
+        }
+
+        fn is_compatible_with(&self, _other: &Self) -> bool {
+            true
+        }
+
+        async fn fetch_latest_version(&self, _client: &HttpClient) -> DirectResult<String> {
+            let attempt = self.lookups.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+
+            if attempt < self.failures {
+                return Err(io::Error::from(io::ErrorKind::ConnectionReset).into());
+            }
+
+            Ok("1.0.0".to_string())
+        }
+    }
+
+    /// A dependency whose registry publishes its metadata as a single document,
+    /// counting how many times it was fetched.
+    struct DocumentedDependency {
+        fetches: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Dependency for DocumentedDependency {
+        fn get_name(&self) -> &str {
+            "documented"
+        }
+
+        fn get_constraint(&self) -> &str {
+            "*"
+        }
+
+        fn get_registry(&self) -> &str {
+            "documented"
+        }
+
+        fn try_is_satisfied_by(&self, _version: &str) -> DirectResult<bool> {
+            Ok(true)
+        }
+
+        fn is_compatible_with(&self, _other: &Self) -> bool {
+            true
+        }
+
+        async fn fetch_latest_version(&self, _client: &HttpClient) -> DirectResult<String> {
+            Ok("1.0.0".to_string())
+        }
+
+        async fn fetch_metadata(
+            &self,
+            _client: &HttpClient,
+        ) -> DirectResult<Option<PackageMetadata>> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Some(
+                PackageMetadata::default()
+                    .with_repository("https://github.com/example/documented")
+                    .with_deprecation("use something else")
+                    .with_license("1.0.0", "MIT")
+                    .with_release_date("1.0.0", SystemTime::UNIX_EPOCH),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn package_metadata_is_fetched_once_per_package() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let dependency = DocumentedDependency {
+            fetches: fetches.clone(),
+        };
+
+        let client = RegistryClient::default();
+
+        let (homepage, deprecation, license, release_dates) = tokio::join!(
+            client.homepage(&dependency),
+            client.deprecation(&dependency),
+            client.license(&dependency, "1.0.0"),
+            client.release_dates(&dependency),
+        );
+
+        assert_eq!(
+            homepage.unwrap().as_deref(),
+            Some("https://github.com/example/documented")
+        );
+        assert_eq!(deprecation.unwrap().as_deref(), Some("use something else"));
+        assert_eq!(license.unwrap().as_deref(), Some("MIT"));
+        assert!(release_dates.unwrap().contains_key("1.0.0"));
+
+        assert!(client.repository(&dependency).await.unwrap().is_some());
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(client.stats().requests().get("documented"), Some(&1));
+    }
+
+    /// A dependency whose latest version, `2.0.0`, was released a day ago.
+    struct FreshDependency;
+
+    #[async_trait]
+    impl Dependency for FreshDependency {
+        fn get_name(&self) -> &str {
+            "fresh"
+        }
+
+        fn get_constraint(&self) -> &str {
+            "^1.0.0"
+        }
+
+        fn get_registry(&self) -> &str {
+            "fresh"
+        }
+
+        fn try_is_satisfied_by(&self, _version: &str) -> DirectResult<bool> {
+            Ok(true)
+        }
+
+        fn is_compatible_with(&self, _other: &Self) -> bool {
+            true
+        }
+
+        async fn fetch_latest_version(&self, _client: &HttpClient) -> DirectResult<String> {
+            Ok("2.0.0".to_string())
+        }
+
+        async fn fetch_release_dates(
+            &self,
+            _client: &HttpClient,
+        ) -> DirectResult<HashMap<String, SystemTime>> {
+            let days_ago = |days: u64| SystemTime::now() - Duration::from_secs(days * 86_400);
+
+            Ok(HashMap::from([
+                ("1.0.0".to_string(), days_ago(100)),
+                ("1.1.0".to_string(), days_ago(30)),
+                ("2.0.0-rc.1".to_string(), days_ago(10)),
+                ("2.0.0".to_string(), days_ago(1)),
+            ]))
+        }
+    }
+
+    #[tokio::test]
+    async fn versions_younger_than_the_minimum_release_age_are_not_the_latest() {
+        let latest = |days: u64| async move {
+            RegistryClient::default()
+                .with_min_release_age(Duration::from_secs(days * 86_400))
+                .latest_version(&FreshDependency)
+                .await
+                .unwrap()
+        };
+
+        assert_eq!(latest(0).await, "2.0.0");
+        assert_eq!(latest(7).await, "1.1.0");
+        assert_eq!(latest(60).await, "1.0.0");
+        assert_eq!(latest(365).await, "2.0.0");
+    }
+
+    /// A dependency whose registry reports a prerelease, `3.0.0-beta.1`, as its latest
+    /// version.
+    struct PrereleaseDependency {
+        constraint: &'static str,
+    }
+
+    #[async_trait]
+    impl Dependency for PrereleaseDependency {
+        fn get_name(&self) -> &str {
+            "prerelease"
+        }
+
+        fn get_constraint(&self) -> &str {
+            self.constraint
+        }
+
+        fn get_registry(&self) -> &str {
+            "prerelease"
+        }
+
+        fn try_is_satisfied_by(&self, _version: &str) -> DirectResult<bool> {
+            Ok(true)
+        }
+
+        fn is_compatible_with(&self, _other: &Self) -> bool {
+            true
+        }
+
+        async fn fetch_latest_version(&self, _client: &HttpClient) -> DirectResult<String> {
+            Ok("3.0.0-beta.1".to_string())
+        }
+
+        async fn fetch_versions(&self, _client: &HttpClient) -> DirectResult<Vec<String>> {
+            Ok(["2.4.0", "2.5.0", "3.0.0-beta.1", "3.0.0-beta.2"]
+                .map(String::from)
+                .to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn prereleases_are_only_the_latest_version_if_the_policy_permits_it() {
+        let latest = |policy: PrereleasePolicy, constraint: &'static str| async move {
+            RegistryClient::default()
+                .with_prerelease_policy(move |_| policy)
+                .latest_version(&PrereleaseDependency { constraint })
+                .await
+                .unwrap()
+        };
+
+        assert_eq!(
+            latest(PrereleasePolicy::Registry, "^2.4.0").await,
+            "3.0.0-beta.1"
+        );
+        assert_eq!(latest(PrereleasePolicy::Never, "^2.4.0").await, "2.5.0");
+        assert_eq!(
+            latest(PrereleasePolicy::Always, "^2.4.0").await,
+            "3.0.0-beta.2"
+        );
+        assert_eq!(
+            latest(PrereleasePolicy::Constraint, "^2.4.0").await,
+            "2.5.0"
+        );
+        assert_eq!(
+            latest(PrereleasePolicy::Constraint, "^3.0.0-beta.1").await,
+            "3.0.0-beta.2"
+        );
+    }
+
+    #[tokio::test]
+    async fn identical_packages_are_only_looked_up_once() {
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let dependency = |name: &str| CountingDependency {
+            name: name.to_string(),
+            lookups: lookups.clone(),
+            failures: 0,
+            delay: Duration::ZERO,
+        };
+
+        let client = RegistryClient::default();
+        let (react, react_again, axios) = (
+            dependency("react"),
+            dependency("react"),
+            dependency("axios"),
+        );
+
+        let results = futures::join!(
+            client.latest_version(&react),
+            client.latest_version(&react_again),
+            client.latest_version(&axios),
+        );
+
+        assert_eq!(results.0.unwrap(), "1.0.0");
+        assert_eq!(results.1.unwrap(), "1.0.0");
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+
+        client.latest_version(&dependency("react")).await.unwrap();
+
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+        assert_eq!(client.resolved_version(&react), Some("1.0.0".to_string()));
+        assert_eq!(client.resolved_version(&dependency("vue")), None);
+    }
+
+    #[tokio::test]
+    async fn invalidated_packages_are_looked_up_again() {
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let dependency = CountingDependency {
+            name: "react".to_string(),
+            lookups: lookups.clone(),
+            failures: 0,
+            delay: Duration::ZERO,
+        };
+
+        let cache = Arc::new(MemoryCache::new(Duration::from_secs(60)));
+        let client = RegistryClient::default().with_cache(Arc::clone(&cache));
+
+        client.latest_version(&dependency).await.unwrap();
+        client.latest_version(&dependency).await.unwrap();
+
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+
+        client.invalidate(&dependency).unwrap();
+
+        assert_eq!(client.resolved_version(&dependency), None);
+        assert_eq!(cache.get_stale("counting", "react"), None);
+
+        client.latest_version(&dependency).await.unwrap();
+
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried() {
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let dependency = CountingDependency {
+            name: "axios".to_string(),
+            lookups: lookups.clone(),
+            failures: 2,
+            delay: Duration::ZERO,
+        };
+
+        let client = RegistryClient::default()
+            .with_retry_policy(RetryPolicy::new(2, Duration::from_millis(1)));
+
+        assert_eq!(client.latest_version(&dependency).await.unwrap(), "1.0.0");
+        assert_eq!(lookups.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_give_up_after_the_configured_attempts() {
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let dependency = CountingDependency {
+            name: "axios".to_string(),
+            lookups: lookups.clone(),
+            failures: 5,
+            delay: Duration::ZERO,
+        };
+
+        let client = RegistryClient::default()
+            .with_retry_policy(RetryPolicy::new(1, Duration::from_millis(1)));
+
+        assert!(client.latest_version(&dependency).await.is_err());
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn retry_delays_are_capped() {
+        let policy =
+            RetryPolicy::new(10, Duration::from_secs(1)).with_max_delay(Duration::from_secs(4));
+
+        for attempt in 0..10 {
+            assert!(policy.delay(attempt) <= Duration::from_secs(4));
+        }
+    }
+
+    #[tokio::test]
+    async fn lookups_past_the_deadline_time_out() {
+        let dependency = CountingDependency {
+            name: "axios".to_string(),
+            lookups: Arc::new(AtomicUsize::new(0)),
+            failures: 0,
+            delay: Duration::from_secs(5),
+        };
+
+        let client = RegistryClient::default()
+            .with_deadline(tokio::time::Instant::now() + Duration::from_millis(10));
+
+        let err = client.latest_version(&dependency).await.unwrap_err();
+
+        assert!(matches!(err, DepchkError::DeadlineExceeded(_)));
+    }
+
+    #[tokio::test]
+    async fn offline_clients_never_query_the_registry() {
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let dependency = CountingDependency {
+            name: "axios".to_string(),
+            lookups: lookups.clone(),
+            failures: 0,
+            delay: Duration::ZERO,
+        };
+
+        let client = RegistryClient::default().offline();
+
+        let err = client.latest_version(&dependency).await.unwrap_err();
+
+        assert!(matches!(err, DepchkError::NotCached(_)));
+        assert_eq!(lookups.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn lookups_are_counted_in_the_stats() {
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let dependency = CountingDependency {
+            name: "axios".to_string(),
+            lookups: lookups.clone(),
+            failures: 1,
+            delay: Duration::ZERO,
+        };
+
+        let client = RegistryClient::default()
+            .with_retry_policy(RetryPolicy::new(1, Duration::from_millis(1)));
+
+        client.latest_version(&dependency).await.unwrap();
+
+        let stats = client.stats();
+
+        assert_eq!(stats.cache_hits(), 0);
+        assert_eq!(stats.cache_misses(), 1);
+        assert_eq!(stats.requests()["counting"], 2);
+        assert_eq!(stats.slowest(5).len(), 2);
+    }
+}
@@ -0,0 +1,124 @@
+/// The base score of a CVSS v3 vector, e.g.
+/// `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`, which is how OSV and RustSec
+/// publish the severity of advisories. Returns `None` for vectors of other CVSS
+/// versions, or that are missing a base metric.
+pub fn base_score(vector: &str) -> Option<f64> {
+    if !matches!(vector.split('/').next()?, "CVSS:3.0" | "CVSS:3.1") {
+        return None;
+    }
+
+    let metric = |name: &str| {
+        vector
+            .split('/')
+            .filter_map(|metric| metric.split_once(':'))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    };
+
+    let scope_changed = match metric("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+
+    let attack_vector: f64 = match metric("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+
+    let attack_complexity = match metric("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+
+    let privileges_required = match (metric("PR")?, scope_changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+
+    let user_interaction = match metric("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+
+    let impact = |name: &str| match metric(name)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+
+    let impact_subscore: f64 =
+        1.0 - (1.0 - impact("C")?) * (1.0 - impact("I")?) * (1.0 - impact("A")?);
+
+    let impact = match scope_changed {
+        false => 6.42 * impact_subscore,
+        true => 7.52 * (impact_subscore - 0.029) - 3.25 * (impact_subscore - 0.02).powi(15),
+    };
+
+    let exploitability =
+        8.22 * attack_vector * attack_complexity * privileges_required * user_interaction;
+
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    Some(match scope_changed {
+        false => round_up((impact + exploitability).min(10.0)),
+        true => round_up((1.08 * (impact + exploitability)).min(10.0)),
+    })
+}
+
+/// Rounds up to one decimal, as the CVSS v3.1 specification defines it, which
+/// avoids floating point errors rounding e.g. 4.000001 up to 4.1.
+fn round_up(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as u64;
+
+    match scaled % 10_000 {
+        0 => scaled as f64 / 100_000.0,
+        _ => (scaled / 10_000 + 1) as f64 / 10.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_score() {
+        assert_eq!(
+            base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            base_score("CVSS:3.1/AV:N/AC:L/PR:H/UI:N/S:U/C:H/I:H/A:H"),
+            Some(7.2)
+        );
+        assert_eq!(
+            base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:L/A:N"),
+            Some(5.3)
+        );
+        assert_eq!(
+            base_score("CVSS:3.0/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"),
+            Some(6.1)
+        );
+        assert_eq!(
+            base_score("CVSS:3.1/AV:L/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N"),
+            Some(0.0)
+        );
+        assert_eq!(
+            base_score("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"),
+            None
+        );
+        assert_eq!(base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H"), None);
+    }
+}
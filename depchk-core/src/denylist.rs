@@ -0,0 +1,253 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use futures::StreamExt;
+#[cfg(feature = "npm")]
+use node_semver::{Range, Version};
+#[cfg(not(feature = "npm"))]
+use semver::{Version, VersionReq as Range};
+use serde::{Deserialize, Serialize};
+
+use crate::client::RegistryClient;
+use crate::{Dependency, LockedVersions};
+
+/// Packages that must not be used, whatever their registry says about them, such
+/// as the "do not use" lists security teams keep. Without `versions`, every version
+/// of the package is denied. Version ranges are written like npm ranges, whichever
+/// the ecosystem of the project is, or like Cargo requirements when depchk-core is
+/// built without the `npm` feature.
+///
+/// ```toml
+/// [[deny]]
+/// name = "event-stream"
+/// versions = ["3.3.6"]
+/// reason = "Compromised release that steals cryptocurrency wallets"
+///
+/// [[deny]]
+/// name = "request"
+/// reason = "Deprecated, use undici instead"
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DenyList {
+    entries: Vec<DenyEntry>,
+}
+
+#[derive(Clone, Debug)]
+struct DenyEntry {
+    name: String,
+    versions: Vec<Range>,
+    reason: String,
+}
+
+#[derive(Deserialize)]
+struct DenyListRaw {
+    #[serde(default)]
+    deny: Vec<DenyEntryRaw>,
+}
+
+#[derive(Deserialize)]
+struct DenyEntryRaw {
+    name: String,
+
+    #[serde(default)]
+    versions: Vec<String>,
+
+    reason: String,
+}
+
+/// A dependency that the deny list does not permit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeniedDependency {
+    name: String,
+
+    /// The version that was denied, or `None` if the version could not be worked out
+    /// and every version of the package is denied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+
+    reason: String,
+}
+
+impl DenyList {
+    /// Reads the deny list from a TOML file.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let raw: DenyListRaw = toml::from_str(&fs::read_to_string(path)?)?;
+        let mut entries = Vec::with_capacity(raw.deny.len());
+
+        for entry in raw.deny {
+            let versions = entry
+                .versions
+                .iter()
+                .map(|range| {
+                    range.parse().map_err(|_| {
+                        format!(
+                            "invalid version range {} of {} in the deny list",
+                            range, entry.name
+                        )
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+
+            entries.push(DenyEntry {
+                name: entry.name,
+                versions,
+                reason: entry.reason,
+            });
+        }
+
+        Ok(DenyList { entries })
+    }
+
+    /// Why the version of the package is denied, if it is. A version that is not
+    /// known, or not a valid version, is only denied by the entries that deny every
+    /// version of the package.
+    pub fn reason(&self, name: &str, version: Option<&str>) -> Option<&str> {
+        let version = version.and_then(|version| version.parse::<Version>().ok());
+
+        self.entries
+            .iter()
+            .filter(|entry| entry.name == name)
+            .find(|entry| {
+                entry.versions.is_empty()
+                    || version.as_ref().is_some_and(|version| {
+                        entry.versions.iter().any(|range| allows(range, version))
+                    })
+            })
+            .map(|entry| entry.reason.as_str())
+    }
+
+    /// Finds the dependencies that the deny list does not permit, running at most
+    /// `concurrency` lookups at the same time. The versions checked are the ones in
+    /// the lockfile if there is one, or else the one a fresh install resolves to.
+    pub async fn find_denied<'a, T: Dependency + 'a>(
+        &self,
+        client: &RegistryClient,
+        dependencies: impl IntoIterator<Item = &'a T>,
+        locked: Option<&LockedVersions>,
+        concurrency: usize,
+    ) -> Vec<DeniedDependency> {
+        let denied = |name: &str, version: Option<String>| {
+            self.reason(name, version.as_deref())
+                .map(|reason| DeniedDependency {
+                    name: name.to_string(),
+                    version,
+                    reason: reason.to_string(),
+                })
+        };
+
+        // Dependencies that are not on the list are never looked up
+        let listed = dependencies
+            .into_iter()
+            .filter(|dependency| {
+                self.entries
+                    .iter()
+                    .any(|entry| entry.name == dependency.get_name())
+            })
+            .collect::<Vec<_>>();
+
+        let mut found: Vec<_> = match locked {
+            Some(locked) => listed
+                .into_iter()
+                .flat_map(|dependency| {
+                    let name = dependency.get_name();
+                    let versions = locked.get(name).cloned().unwrap_or_default();
+
+                    match versions.is_empty() {
+                        true => vec![denied(name, None)],
+                        false => versions
+                            .into_iter()
+                            .map(|version| denied(name, Some(version)))
+                            .collect(),
+                    }
+                })
+                .flatten()
+                .collect(),
+            None => {
+                let lookups = listed.into_iter().map(|dependency| async move {
+                    let version = dependency.satisfiable_version(client).await;
+
+                    denied(
+                        dependency.get_name(),
+                        version.map(|version| version.to_string()),
+                    )
+                });
+
+                futures::stream::iter(lookups)
+                    .buffer_unordered(concurrency.max(1))
+                    .filter_map(futures::future::ready)
+                    .collect()
+                    .await
+            }
+        };
+
+        found.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        found.dedup();
+        found
+    }
+}
+
+impl DeniedDependency {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+/// Whether the range of an entry includes the version.
+#[cfg(feature = "npm")]
+fn allows(range: &Range, version: &Version) -> bool {
+    range.satisfies(version)
+}
+
+#[cfg(not(feature = "npm"))]
+fn allows(range: &Range, version: &Version) -> bool {
+    range.matches(version)
+}
+
+#[cfg(all(test, feature = "npm"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reason() {
+        let deny_list = DenyList {
+            entries: vec![
+                DenyEntry {
+                    name: "event-stream".to_string(),
+                    versions: vec!["3.3.6".parse().unwrap(), ">=4.0.0 <4.0.2".parse().unwrap()],
+                    reason: "compromised".to_string(),
+                },
+                DenyEntry {
+                    name: "request".to_string(),
+                    versions: vec![],
+                    reason: "deprecated".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            deny_list.reason("event-stream", Some("3.3.6")),
+            Some("compromised")
+        );
+        assert_eq!(
+            deny_list.reason("event-stream", Some("4.0.1")),
+            Some("compromised")
+        );
+        assert_eq!(deny_list.reason("event-stream", Some("3.3.5")), None);
+        assert_eq!(deny_list.reason("event-stream", None), None);
+        assert_eq!(
+            deny_list.reason("request", Some("2.88.2")),
+            Some("deprecated")
+        );
+        assert_eq!(deny_list.reason("request", None), Some("deprecated"));
+        assert_eq!(deny_list.reason("axios", Some("1.0.0")), None);
+    }
+}
@@ -0,0 +1,21 @@
+/// Encodes bytes in standard base64, with padding.
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | (*byte as u32) << (16 - 8 * index)
+        });
+
+        for index in 0..4 {
+            encoded.push(match index <= chunk.len() {
+                true => ALPHABET[(group >> (18 - 6 * index) & 0x3f) as usize] as char,
+                false => '=',
+            });
+        }
+    }
+
+    encoded
+}
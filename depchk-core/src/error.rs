@@ -0,0 +1,182 @@
+use std::error::Error;
+use std::io;
+
+use reqwest::StatusCode;
+
+use crate::client::{DeadlineExceeded, NotCached};
+use crate::DependencyError;
+
+/// The longest registry response body kept in a [`DepchkError::Registry`] error.
+#[cfg(any(feature = "npm", feature = "cargo"))]
+const MAX_BODY_LENGTH: usize = 1024;
+
+/// The ways checking dependencies can fail, so that library consumers can tell the
+/// causes of failures apart instead of only printing them.
+#[derive(Debug, thiserror::Error)]
+pub enum DepchkError {
+    /// A dependency file, lockfile or registry response could not be parsed.
+    #[error(transparent)]
+    Parse(Box<dyn Error + Send + Sync>),
+
+    /// A file could not be read.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// A request could not be sent, or its response could not be received.
+    #[error(transparent)]
+    Network(#[from] reqwest::Error),
+
+    /// The registry answered a request with an error status.
+    #[error("{url} answered with {status}")]
+    Registry {
+        url: String,
+        status: StatusCode,
+        body: String,
+    },
+
+    /// A version constraint could not be parsed or worked with.
+    #[error("invalid constraint {constraint}: {message}")]
+    Constraint { constraint: String, message: String },
+
+    /// The registry published a version that could not be parsed.
+    #[error("invalid version {version}: {message}")]
+    InvalidVersion { version: String, message: String },
+
+    /// The package is not in the cache while the client is offline.
+    #[error(transparent)]
+    NotCached(#[from] NotCached),
+
+    /// The lookup had not resolved by the time the deadline of the client passed.
+    #[error(transparent)]
+    DeadlineExceeded(#[from] DeadlineExceeded),
+
+    /// Checking a single dependency failed.
+    #[error(transparent)]
+    Dependency(#[from] DependencyError),
+
+    /// Any other failure, described by its message.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl DepchkError {
+    /// The status the registry answered with, if it answered with an error status.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            DepchkError::Registry { status, .. } => Some(*status),
+            DepchkError::Network(err) => err.status(),
+            DepchkError::Dependency(err) => err.error().status(),
+            _ => None,
+        }
+    }
+
+    /// Whether the package is unknown because it is not in the offline cache.
+    pub fn is_not_cached(&self) -> bool {
+        match self {
+            DepchkError::NotCached(_) => true,
+            DepchkError::Dependency(err) => err.error().is_not_cached(),
+            _ => false,
+        }
+    }
+}
+
+impl From<serde_json::Error> for DepchkError {
+    fn from(err: serde_json::Error) -> Self {
+        DepchkError::Parse(Box::new(err))
+    }
+}
+
+impl From<toml::de::Error> for DepchkError {
+    fn from(err: toml::de::Error) -> Self {
+        DepchkError::Parse(Box::new(err))
+    }
+}
+
+impl From<glob::PatternError> for DepchkError {
+    fn from(err: glob::PatternError) -> Self {
+        DepchkError::Parse(Box::new(err))
+    }
+}
+
+impl From<glob::GlobError> for DepchkError {
+    fn from(err: glob::GlobError) -> Self {
+        DepchkError::Io(err.into())
+    }
+}
+
+impl From<String> for DepchkError {
+    fn from(message: String) -> Self {
+        DepchkError::Other(message)
+    }
+}
+
+impl From<&str> for DepchkError {
+    fn from(message: &str) -> Self {
+        DepchkError::Other(message.to_string())
+    }
+}
+
+/// Turns responses with an error status into [`DepchkError::Registry`] errors, like
+/// [`reqwest::Response::error_for_status`] but keeping the start of the body, which registries
+/// explain the error in.
+#[cfg(any(feature = "npm", feature = "cargo"))]
+pub(crate) trait RegistryResponse: Sized {
+    async fn error_for_registry_status(self) -> Result<Self, DepchkError>;
+}
+
+#[cfg(any(feature = "npm", feature = "cargo"))]
+impl RegistryResponse for reqwest::Response {
+    async fn error_for_registry_status(self) -> Result<Self, DepchkError> {
+        let status = self.status();
+
+        if !status.is_client_error() && !status.is_server_error() {
+            return Ok(self);
+        }
+
+        let url = self.url().to_string();
+        let mut body = self.text().await.unwrap_or_default();
+
+        if body.len() > MAX_BODY_LENGTH {
+            let end = (0..=MAX_BODY_LENGTH)
+                .rev()
+                .find(|&end| body.is_char_boundary(end))
+                .unwrap_or_default();
+
+            body.truncate(end);
+        }
+
+        Err(DepchkError::Registry { url, status, body })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_errors() {
+        let registry = DepchkError::from(DependencyError {
+            name: "axios".to_string(),
+            source: Box::new(DepchkError::Registry {
+                url: "https://registry.npmjs.org/axios".to_string(),
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                body: String::new(),
+            }),
+        });
+
+        assert_eq!(
+            registry.to_string(),
+            "axios: https://registry.npmjs.org/axios answered with 503 Service Unavailable"
+        );
+        assert_eq!(registry.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!registry.is_not_cached());
+
+        let not_cached = DepchkError::from(DependencyError {
+            name: "axios".to_string(),
+            source: Box::new(NotCached.into()),
+        });
+
+        assert!(not_cached.is_not_cached());
+        assert_eq!(not_cached.status(), None);
+    }
+}
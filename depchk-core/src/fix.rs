@@ -0,0 +1,404 @@
+use std::fmt::Write;
+#[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+use std::ops::Range;
+
+/// A new constraint to write to the dependency file for one of its dependencies.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintUpdate {
+    name: String,
+    dev: bool,
+    constraint: String,
+}
+
+impl ConstraintUpdate {
+    pub fn new(name: &str, dev: bool, constraint: &str) -> Self {
+        ConstraintUpdate {
+            name: name.to_string(),
+            dev,
+            constraint: constraint.to_string(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the dependency is a dev dependency.
+    pub fn is_dev(&self) -> bool {
+        self.dev
+    }
+
+    /// The constraint that replaces the declared one.
+    pub fn constraint(&self) -> &str {
+        &self.constraint
+    }
+}
+
+/// How an updated constraint is written.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RangeStyle {
+    /// The way the constraint it replaces is written, with the same operator and as
+    /// many version components.
+    #[default]
+    Keep,
+    /// A caret range of the full version, e.g. `^2.0.1`.
+    Caret,
+    /// A tilde range of the full version, e.g. `~2.0.1`.
+    Tilde,
+    /// The full version alone, e.g. `2.0.1`, which npm reads as that version only.
+    Exact,
+}
+
+/// The operators a constraint that is a single version may start with, longest
+/// first so that `>=` is not read as `>`.
+const OPERATORS: [&str; 5] = [">=", "^", "~", "=", ""];
+
+/// The constraint that allows `version`, written in the given style. Keeping the
+/// style of `constraint` keeps its operator and as many version components, e.g.
+/// `^1.2` becomes `^2.0` for version 2.0.1. Returns `None` for constraints that are
+/// not a single version with an optional operator, such as `^16 || ^17` or git URLs,
+/// which are left for people to update whatever the style.
+pub fn updated_constraint(constraint: &str, version: &str, style: RangeStyle) -> Option<String> {
+    let constraint = constraint.trim();
+
+    let (operator, declared) = OPERATORS
+        .iter()
+        .find_map(|operator| Some((*operator, constraint.strip_prefix(operator)?)))?;
+
+    // Prerelease and build metadata are dropped, the new version has its own
+    let core = declared.trim_start().split(['-', '+']).next()?;
+    let components = core.split('.').count();
+
+    let is_version = components <= 3
+        && core.split('.').all(|component| {
+            !component.is_empty() && component.chars().all(|c| c.is_ascii_digit())
+        });
+
+    if !is_version {
+        return None;
+    }
+
+    match style {
+        RangeStyle::Keep => {}
+        RangeStyle::Caret => return Some(format!("^{}", version)),
+        RangeStyle::Tilde => return Some(format!("~{}", version)),
+        RangeStyle::Exact => return Some(version.to_string()),
+    }
+
+    let version = match components {
+        3 => version.to_string(),
+        _ => version
+            .split(['-', '+'])
+            .next()?
+            .split('.')
+            .take(components)
+            .collect::<Vec<_>>()
+            .join("."),
+    };
+
+    Some(format!("{}{}", operator, version))
+}
+
+/// How many unchanged lines surround the changed ones in a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// A unified diff of the changes to the dependency file at `path`, as `git apply`
+/// and `patch -p1` read it, or an empty string if nothing changed. Updating
+/// constraints never adds or removes lines, so the lines are compared one by one.
+pub fn unified_diff(path: &str, before: &str, after: &str) -> String {
+    let before: Vec<_> = before.lines().collect();
+    let after: Vec<_> = after.lines().collect();
+
+    let changed: Vec<_> = (0..before.len().max(after.len()))
+        .filter(|&line| before.get(line) != after.get(line))
+        .collect();
+
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    let mut diff = format!("--- a/{0}\n+++ b/{0}\n", path);
+    let mut index = 0;
+
+    while index < changed.len() {
+        // Changes that are close enough to share their context go in the same hunk
+        let mut last = index;
+
+        while changed
+            .get(last + 1)
+            .is_some_and(|&next| next - changed[last] <= 2 * DIFF_CONTEXT + 1)
+        {
+            last += 1;
+        }
+
+        let start = changed[index].saturating_sub(DIFF_CONTEXT);
+        let end = (changed[last] + DIFF_CONTEXT + 1).min(before.len().max(after.len()));
+        let count = |lines: &[&str]| end.min(lines.len()).saturating_sub(start);
+
+        let _ = writeln!(
+            diff,
+            "@@ -{},{} +{},{} @@",
+            start + 1,
+            count(&before),
+            start + 1,
+            count(&after)
+        );
+
+        let mut line = start;
+
+        while line < end {
+            if !changed.contains(&line) {
+                let _ = writeln!(diff, " {}", before[line]);
+                line += 1;
+                continue;
+            }
+
+            let run_end = (line..end)
+                .find(|line| !changed.contains(line))
+                .unwrap_or(end);
+
+            for removed in before.get(line..run_end.min(before.len())).unwrap_or(&[]) {
+                let _ = writeln!(diff, "-{}", removed);
+            }
+
+            for added in after.get(line..run_end.min(after.len())).unwrap_or(&[]) {
+                let _ = writeln!(diff, "+{}", added);
+            }
+
+            line = run_end;
+        }
+
+        index = last + 1;
+    }
+
+    diff
+}
+
+/// The byte range of the string at the path of object keys in a JSON document,
+/// quotes included, which lets a value be replaced without re-serializing the
+/// document and losing its formatting. Returns `None` if there is no string at
+/// the path, or the document is not valid JSON.
+#[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+pub(crate) fn json_string_span(json: &str, path: &[&str]) -> Option<Range<usize>> {
+    let bytes = json.as_bytes();
+    let mut position = skip_whitespace(bytes, 0);
+
+    for key in path {
+        if bytes.get(position) != Some(&b'{') {
+            return None;
+        }
+
+        position = skip_whitespace(bytes, position + 1);
+
+        loop {
+            let key_end = string_end(bytes, position)?;
+            let found: String = serde_json::from_str(&json[position..key_end]).ok()?;
+
+            position = skip_whitespace(bytes, key_end);
+
+            if bytes.get(position) != Some(&b':') {
+                return None;
+            }
+
+            position = skip_whitespace(bytes, position + 1);
+
+            if found == *key {
+                break;
+            }
+
+            position = skip_whitespace(bytes, value_end(bytes, position)?);
+
+            match bytes.get(position) {
+                Some(b',') => position = skip_whitespace(bytes, position + 1),
+                _ => return None,
+            }
+        }
+    }
+
+    Some(position..string_end(bytes, position)?)
+}
+
+#[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+fn skip_whitespace(bytes: &[u8], mut position: usize) -> usize {
+    while bytes.get(position).is_some_and(u8::is_ascii_whitespace) {
+        position += 1;
+    }
+
+    position
+}
+
+/// The position right after the string that starts at `position`.
+#[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+fn string_end(bytes: &[u8], position: usize) -> Option<usize> {
+    if bytes.get(position) != Some(&b'"') {
+        return None;
+    }
+
+    let mut position = position + 1;
+
+    loop {
+        match bytes.get(position)? {
+            b'\\' => position += 2,
+            b'"' => return Some(position + 1),
+            _ => position += 1,
+        }
+    }
+}
+
+/// The position right after the value that starts at `position`.
+#[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+fn value_end(bytes: &[u8], position: usize) -> Option<usize> {
+    match bytes.get(position)? {
+        b'"' => string_end(bytes, position),
+        b'{' | b'[' => {
+            let mut depth = 0;
+            let mut position = position;
+
+            loop {
+                match bytes.get(position)? {
+                    b'"' => {
+                        position = string_end(bytes, position)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+
+                        if depth == 0 {
+                            return Some(position + 1);
+                        }
+                    }
+                    _ => {}
+                }
+
+                position += 1;
+            }
+        }
+        _ => {
+            let mut position = position;
+
+            while bytes.get(position).is_some_and(|byte| {
+                !matches!(byte, b',' | b'}' | b']') && !byte.is_ascii_whitespace()
+            }) {
+                position += 1;
+            }
+
+            Some(position)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_updated_constraint() {
+        assert_eq!(
+            updated_constraint("^1.2.3", "2.0.1", RangeStyle::Keep).as_deref(),
+            Some("^2.0.1")
+        );
+        assert_eq!(
+            updated_constraint("~4.17", "5.1.0", RangeStyle::Keep).as_deref(),
+            Some("~5.1")
+        );
+        assert_eq!(
+            updated_constraint(">= 1", "2.0.0", RangeStyle::Keep).as_deref(),
+            Some(">=2")
+        );
+        assert_eq!(
+            updated_constraint("1.0.0", "1.2.0", RangeStyle::Keep).as_deref(),
+            Some("1.2.0")
+        );
+        assert_eq!(
+            updated_constraint("=1.0.0-beta.1", "1.0.0", RangeStyle::Keep).as_deref(),
+            Some("=1.0.0")
+        );
+        assert_eq!(
+            updated_constraint("^16 || ^17", "18.2.0", RangeStyle::Keep),
+            None
+        );
+        assert_eq!(updated_constraint("1.x", "2.0.0", RangeStyle::Keep), None);
+        assert_eq!(
+            updated_constraint("latest", "2.0.0", RangeStyle::Keep),
+            None
+        );
+        assert_eq!(
+            updated_constraint("github:user/repo#v1.0.0", "2.0.0", RangeStyle::Keep),
+            None
+        );
+    }
+
+    #[test]
+    fn test_updated_constraint_style() {
+        let updated = |constraint, style| updated_constraint(constraint, "2.0.1", style);
+
+        assert_eq!(
+            updated("~1.2", RangeStyle::Caret).as_deref(),
+            Some("^2.0.1")
+        );
+        assert_eq!(
+            updated(">=1.0.0", RangeStyle::Tilde).as_deref(),
+            Some("~2.0.1")
+        );
+        assert_eq!(
+            updated("^1.2.3", RangeStyle::Exact).as_deref(),
+            Some("2.0.1")
+        );
+        assert_eq!(
+            updated("1.0.0", RangeStyle::Caret).as_deref(),
+            Some("^2.0.1")
+        );
+        assert_eq!(updated("^1 || ^2", RangeStyle::Caret), None);
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let before = "{\n  \"name\": \"app\",\n  \"version\": \"1.0.0\",\n  \"private\": true,\n  \"license\": \"MIT\",\n  \"dependencies\": {\n    \"axios\": \"^0.21.0\",\n    \"react\": \"^17.0.2\"\n  }\n}\n";
+        let after = before
+            .replace("^0.21.0", "^1.6.2")
+            .replace("^17.0.2", "^18.2.0");
+
+        assert_eq!(
+            unified_diff("package.json", before, &after),
+            concat!(
+                "--- a/package.json\n",
+                "+++ b/package.json\n",
+                "@@ -4,7 +4,7 @@\n",
+                "   \"private\": true,\n",
+                "   \"license\": \"MIT\",\n",
+                "   \"dependencies\": {\n",
+                "-    \"axios\": \"^0.21.0\",\n",
+                "-    \"react\": \"^17.0.2\"\n",
+                "+    \"axios\": \"^1.6.2\",\n",
+                "+    \"react\": \"^18.2.0\"\n",
+                "   }\n",
+                " }\n",
+            )
+        );
+        assert_eq!(unified_diff("package.json", before, before), "");
+    }
+
+    #[test]
+    #[cfg(feature = "npm")]
+    fn test_json_string_span() {
+        let json = r#"{
+  "name": "app",
+  "scripts": { "test": "jest \"src\"", "nested": [1, {"a": "}"}] },
+  "dependencies": {
+    "react": "^17.0.2",
+    "lodash"  :  "~4.17.0"
+  }
+}"#;
+
+        let span = json_string_span(json, &["dependencies", "lodash"]).unwrap();
+        assert_eq!(&json[span], r#""~4.17.0""#);
+
+        let span = json_string_span(json, &["dependencies", "react"]).unwrap();
+        assert_eq!(&json[span], r#""^17.0.2""#);
+
+        assert_eq!(json_string_span(json, &["devDependencies", "react"]), None);
+        assert_eq!(json_string_span(json, &["scripts"]), None);
+        assert_eq!(json_string_span("not json", &["name"]), None);
+    }
+}
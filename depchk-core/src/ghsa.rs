@@ -0,0 +1,248 @@
+use std::error::Error;
+
+use reqwest::header::USER_AGENT;
+use reqwest::Client;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::audit::{AuditedPackage, Vulnerability};
+
+const GITHUB_GRAPHQL_API: &str = "https://api.github.com/graphql";
+
+/// How many packages are looked up in a single GraphQL query, each as a field of
+/// its own, which keeps queries well within GitHub's limits.
+const BATCH_SIZE: usize = 50;
+
+/// The fields that are queried for every vulnerability of a package.
+const VULNERABILITY_FIELDS: &str = "nodes { advisory { ghsaId summary severity cvss { score } identifiers { type value } } vulnerableVersionRange firstPatchedVersion { identifier } }";
+
+#[derive(Serialize, Debug)]
+struct GraphQlRequest {
+    query: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct GraphQlResponse {
+    data: Option<Value>,
+
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GraphQlError {
+    message: String,
+}
+
+/// The vulnerabilities of a single package.
+#[derive(Deserialize, Debug)]
+struct VulnerabilityConnection {
+    nodes: Vec<SecurityVulnerability>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SecurityVulnerability {
+    advisory: Advisory,
+    vulnerable_version_range: String,
+    first_patched_version: Option<PatchedVersion>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Advisory {
+    ghsa_id: String,
+    summary: String,
+
+    /// One of `LOW`, `MODERATE`, `HIGH` or `CRITICAL`.
+    severity: Option<String>,
+
+    cvss: Option<Cvss>,
+    identifiers: Vec<Identifier>,
+}
+
+/// The CVSS score of an advisory, which is 0 for advisories that were not scored.
+#[derive(Deserialize, Debug)]
+struct Cvss {
+    score: f64,
+}
+
+#[derive(Deserialize, Debug)]
+struct Identifier {
+    value: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct PatchedVersion {
+    identifier: String,
+}
+
+/// The name of the ecosystem in the GitHub Advisory Database, from its name in OSV,
+/// which is what [`crate::Dependency::get_ecosystem`] returns.
+fn github_ecosystem(ecosystem: &str) -> Option<&'static str> {
+    match ecosystem {
+        "npm" => Some("NPM"),
+        "crates.io" => Some("RUST"),
+        "PyPI" => Some("PIP"),
+        _ => None,
+    }
+}
+
+/// Whether the version is in a range as GitHub writes them, e.g. `>= 1.0.0, < 1.2.3`.
+/// Ranges that cannot be read are assumed to include the version, so that no
+/// vulnerability goes unreported.
+fn is_in_range(version: &str, range: &str) -> bool {
+    match (Version::parse(version), VersionReq::parse(range)) {
+        (Ok(version), Ok(range)) => range.matches(&version),
+        _ => true,
+    }
+}
+
+impl SecurityVulnerability {
+    fn affecting(&self, package: &AuditedPackage) -> Vulnerability {
+        let aliases = self
+            .advisory
+            .identifiers
+            .iter()
+            .map(|identifier| identifier.value.clone())
+            .filter(|identifier| *identifier != self.advisory.ghsa_id)
+            .collect();
+
+        let mut vulnerability = Vulnerability::new(package, &self.advisory.ghsa_id)
+            .with_aliases(aliases)
+            .with_summary(&self.advisory.summary)
+            .with_fixed_versions(
+                self.first_patched_version
+                    .iter()
+                    .map(|version| version.identifier.clone())
+                    .collect(),
+            );
+
+        if let Some(cvss) = self.advisory.cvss.as_ref().filter(|cvss| cvss.score > 0.0) {
+            vulnerability = vulnerability.with_cvss_score(cvss.score);
+        }
+
+        match self
+            .advisory
+            .severity
+            .as_deref()
+            .and_then(|severity| severity.parse().ok())
+        {
+            Some(severity) => vulnerability.with_severity(severity),
+            None => vulnerability,
+        }
+    }
+}
+
+/// Looks up the vulnerabilities that affect the packages in the GitHub Advisory
+/// Database, which requires a GitHub token. Packages of ecosystems it does not
+/// cover are skipped.
+pub async fn query_vulnerabilities(
+    client: &Client,
+    packages: &[AuditedPackage],
+    token: &str,
+) -> Result<Vec<Vulnerability>, Box<dyn Error>> {
+    let packages: Vec<_> = packages
+        .iter()
+        .filter_map(|package| Some((package, github_ecosystem(package.ecosystem())?)))
+        .collect();
+
+    let mut vulnerabilities = Vec::new();
+
+    for batch in packages.chunks(BATCH_SIZE) {
+        let fields: Vec<_> = batch
+            .iter()
+            .enumerate()
+            .map(|(index, (package, ecosystem))| {
+                // GraphQL strings are quoted and escaped the same way as JSON strings
+                Ok::<_, serde_json::Error>(format!(
+                    "p{}: securityVulnerabilities(ecosystem: {}, package: {}, first: 100) {{ {} }}",
+                    index,
+                    ecosystem,
+                    serde_json::to_string(package.name())?,
+                    VULNERABILITY_FIELDS
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let response: GraphQlResponse = client
+            .post(GITHUB_GRAPHQL_API)
+            .bearer_auth(token)
+            .header(USER_AGENT, "depchk")
+            .json(&GraphQlRequest {
+                query: format!("query {{ {} }}", fields.join(" ")),
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.errors.first() {
+            return Err(format!("GitHub Advisory Database: {}", error.message).into());
+        }
+
+        let data = response.data.unwrap_or_default();
+
+        for (index, (package, _)) in batch.iter().enumerate() {
+            let connection: VulnerabilityConnection =
+                serde_json::from_value(data[format!("p{}", index)].clone())?;
+
+            vulnerabilities.extend(
+                connection
+                    .nodes
+                    .iter()
+                    .filter(|node| is_in_range(package.version(), &node.vulnerable_version_range))
+                    .map(|node| node.affecting(package)),
+            );
+        }
+    }
+
+    Ok(vulnerabilities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::Severity;
+
+    #[test]
+    fn test_is_in_range() {
+        assert!(is_in_range("4.17.15", "< 4.17.21"));
+        assert!(is_in_range("1.1.0", ">= 1.0.0, < 1.2.3"));
+        assert!(!is_in_range("1.2.3", ">= 1.0.0, < 1.2.3"));
+        assert!(!is_in_range("0.4.0", "<= 0.3.1"));
+        assert!(is_in_range("1.0.0", "not a range"));
+    }
+
+    #[test]
+    fn vulnerabilities_list_their_other_identifiers_as_aliases() {
+        let vulnerability: SecurityVulnerability = serde_json::from_str(
+            r#"{
+                "advisory": {
+                    "ghsaId": "GHSA-35jh-r3h4-6jhm",
+                    "summary": "Command Injection in lodash",
+                    "severity": "HIGH",
+                    "cvss": { "score": 7.2 },
+                    "identifiers": [
+                        { "type": "GHSA", "value": "GHSA-35jh-r3h4-6jhm" },
+                        { "type": "CVE", "value": "CVE-2021-23337" }
+                    ]
+                },
+                "vulnerableVersionRange": "< 4.17.21",
+                "firstPatchedVersion": { "identifier": "4.17.21" }
+            }"#,
+        )
+        .unwrap();
+
+        let vulnerability =
+            vulnerability.affecting(&AuditedPackage::new("lodash", "4.17.15", "npm"));
+
+        assert_eq!(vulnerability.id(), "GHSA-35jh-r3h4-6jhm");
+        assert_eq!(vulnerability.aliases(), ["CVE-2021-23337"]);
+        assert_eq!(vulnerability.fixed_versions(), ["4.17.21"]);
+        assert_eq!(vulnerability.cvss_score(), Some(7.2));
+        assert_eq!(vulnerability.severity(), Some(Severity::High));
+    }
+}
@@ -0,0 +1,118 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use glob::Pattern;
+use serde::Deserialize;
+
+/// Groups of related packages that are updated together, like Dependabot and
+/// Renovate group them: fixes and pull requests bump every outdated package of a
+/// group at once, and reports can list them together. Packages are matched by
+/// name with glob patterns, and belong to the first group that matches them.
+///
+/// ```toml
+/// [[group]]
+/// name = "babel"
+/// patterns = ["@babel/*"]
+///
+/// [[group]]
+/// name = "react"
+/// patterns = ["react", "react-dom"]
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UpdateGroups {
+    groups: Vec<UpdateGroup>,
+}
+
+#[derive(Clone, Debug)]
+struct UpdateGroup {
+    name: String,
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Deserialize)]
+struct UpdateGroupsRaw {
+    #[serde(default)]
+    group: Vec<UpdateGroupRaw>,
+}
+
+#[derive(Deserialize)]
+struct UpdateGroupRaw {
+    name: String,
+    patterns: Vec<String>,
+}
+
+impl UpdateGroups {
+    /// Reads the groups from a TOML file.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    fn parse(toml: &str) -> Result<Self, Box<dyn Error>> {
+        let raw: UpdateGroupsRaw = toml::from_str(toml)?;
+        let mut groups = Vec::with_capacity(raw.group.len());
+
+        for group in raw.group {
+            let patterns = group
+                .patterns
+                .iter()
+                .map(|pattern| {
+                    Pattern::new(pattern).map_err(|err| {
+                        format!(
+                            "invalid pattern {} of the {} group: {}",
+                            pattern, group.name, err
+                        )
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+
+            groups.push(UpdateGroup {
+                name: group.name,
+                patterns,
+            });
+        }
+
+        Ok(UpdateGroups { groups })
+    }
+
+    /// The name of the group the package belongs to, if any.
+    pub fn group_of(&self, name: &str) -> Option<&str> {
+        self.groups
+            .iter()
+            .find(|group| group.patterns.iter().any(|pattern| pattern.matches(name)))
+            .map(|group| group.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_of() {
+        let groups = UpdateGroups::parse(
+            r#"
+            [[group]]
+            name = "babel"
+            patterns = ["@babel/*"]
+
+            [[group]]
+            name = "lint"
+            patterns = ["eslint*", "@typescript-eslint/*", "prettier"]
+
+            [[group]]
+            name = "react"
+            patterns = ["react", "react-dom"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(groups.group_of("@babel/core"), Some("babel"));
+        assert_eq!(groups.group_of("eslint-plugin-react"), Some("lint"));
+        assert_eq!(groups.group_of("@typescript-eslint/parser"), Some("lint"));
+        assert_eq!(groups.group_of("react-dom"), Some("react"));
+        assert_eq!(groups.group_of("react-router"), None);
+
+        assert!(UpdateGroups::parse("[[group]]\nname = \"bad\"\npatterns = [\"[\"]").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use glob::Pattern;
+use serde::Deserialize;
+
+use crate::time::parse_date;
+
+/// A package that is left out of the checks, by name or by glob pattern. An ignore can
+/// expire at the end of its `until` date, after which the package is checked again, so
+/// that ignored dependencies cannot drift out of date unnoticed forever.
+///
+/// ```toml
+/// ignore = [
+///     "@types/*",
+///     { name = "webpack", until = "2025-09-01", reason = "Waiting on the loaders" },
+/// ]
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "IgnoreRaw")]
+pub struct Ignore {
+    pattern: Pattern,
+    until: Option<String>,
+    expires_at: Option<SystemTime>,
+    reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IgnoreRaw {
+    Name(String),
+    Entry {
+        name: String,
+        #[serde(default)]
+        until: Option<String>,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+}
+
+impl Ignore {
+    /// Ignores the packages whose name matches the pattern, for good.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let pattern = Pattern::new(pattern)
+            .map_err(|err| format!("invalid ignore pattern {}: {}", pattern, err))?;
+
+        Ok(Ignore {
+            pattern,
+            until: None,
+            expires_at: None,
+            reason: None,
+        })
+    }
+
+    /// Only ignores the packages up to the end of the date, e.g. `2025-09-01`.
+    pub fn with_until(mut self, until: &str) -> Result<Self, String> {
+        let date = parse_date(until).ok_or_else(|| {
+            format!(
+                "invalid date {} of the ignore of {}, dates are written like 2025-09-01",
+                until, self.pattern
+            )
+        })?;
+
+        self.until = Some(until.to_string());
+        self.expires_at = Some(date + Duration::from_secs(86_400));
+
+        Ok(self)
+    }
+
+    pub fn with_reason(mut self, reason: &str) -> Self {
+        self.reason = Some(reason.to_string());
+        self
+    }
+
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// The last day the packages are ignored on, if the ignore expires.
+    pub fn until(&self) -> Option<&str> {
+        self.until.as_deref()
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// Whether the ignore has expired by the given time.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Whether the package is ignored, which expired ignores never do.
+    pub fn ignores(&self, name: &str, now: SystemTime) -> bool {
+        !self.is_expired(now) && self.pattern.matches(name)
+    }
+}
+
+impl FromStr for Ignore {
+    type Err = String;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Ignore::new(pattern)
+    }
+}
+
+impl TryFrom<IgnoreRaw> for Ignore {
+    type Error = String;
+
+    fn try_from(raw: IgnoreRaw) -> Result<Self, Self::Error> {
+        match raw {
+            IgnoreRaw::Name(name) => Ignore::new(&name),
+            IgnoreRaw::Entry {
+                name,
+                until,
+                reason,
+            } => {
+                let mut ignore = Ignore::new(&name)?;
+
+                if let Some(until) = until {
+                    ignore = ignore.with_until(&until)?;
+                }
+
+                if let Some(reason) = reason {
+                    ignore = ignore.with_reason(&reason);
+                }
+
+                Ok(ignore)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Config {
+        ignore: Vec<Ignore>,
+    }
+
+    #[test]
+    fn test_ignores() {
+        let config: Config = toml::from_str(
+            r#"
+            ignore = [
+                "@types/*",
+                { name = "webpack", until = "2025-09-01", reason = "Waiting on the loaders" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        // 2025-09-01 at noon, then the day after
+        let before = UNIX_EPOCH + Duration::from_secs(1_756_728_000);
+        let after = before + Duration::from_secs(86_400);
+
+        let [types, webpack] = &config.ignore[..] else {
+            panic!("expected two ignores, got {:?}", config.ignore);
+        };
+
+        assert!(types.ignores("@types/node", after));
+        assert!(!types.ignores("typescript", before));
+
+        assert_eq!(webpack.until(), Some("2025-09-01"));
+        assert_eq!(webpack.reason(), Some("Waiting on the loaders"));
+        assert!(webpack.ignores("webpack", before));
+        assert!(!webpack.ignores("webpack", after));
+        assert!(webpack.is_expired(after));
+
+        assert!(toml::from_str::<Config>(r#"ignore = [{ name = "a", until = "soon" }]"#).is_err());
+    }
+}
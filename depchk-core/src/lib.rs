@@ -0,0 +1,1857 @@
+pub mod audit;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+#[cfg(feature = "cargo")]
+pub mod cargo;
+pub mod changelog;
+pub mod client;
+mod cvss;
+pub mod denylist;
+mod encoding;
+mod error;
+pub mod fix;
+mod ghsa;
+pub mod groups;
+pub mod ignore;
+pub mod license;
+pub mod maintenance;
+#[cfg(feature = "npm")]
+pub mod npm;
+#[cfg(all(feature = "npm", not(target_arch = "wasm32")))]
+mod npm_audit;
+pub mod options;
+mod osv;
+pub mod progress;
+pub mod pull_request;
+pub mod registry;
+pub mod risk;
+#[cfg(feature = "cargo")]
+mod rustsec;
+mod time;
+pub mod transitive;
+pub mod transport;
+pub mod typosquat;
+pub mod workspace;
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::Display;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+use std::io::Read;
+#[cfg(not(target_arch = "wasm32"))]
+use std::process::Command;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use client::{NotCached, Registry, RegistryClient};
+pub use error::DepchkError;
+#[cfg(not(target_arch = "wasm32"))]
+use fix::ConstraintUpdate;
+use futures::future::{self, Either};
+use futures::{Future, Stream, StreamExt};
+use maintenance::MaintenanceFlag;
+use progress::ProgressReporter;
+use registry::PackageMetadata;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use transitive::TransitiveDependencies;
+use transport::HttpClient;
+use workspace::VersionSkew;
+#[cfg(not(target_arch = "wasm32"))]
+use workspace::WorkspacePackage;
+
+/// The length of an average Gregorian year, which libyears are measured in.
+const SECONDS_PER_YEAR: f64 = 365.2425 * 86_400.0;
+
+type DirectResult<T> = Result<T, DepchkError>;
+type OptionalResult<T> = DirectResult<Option<T>>;
+
+pub type DependencyMismatchResult = DirectResult<VersionMismatch>;
+
+/// The versions a lockfile locks each package to, keyed by name. A package can be
+/// locked to several versions when different dependents need incompatible ones.
+pub type LockedVersions = HashMap<String, Vec<String>>;
+type DependencyCheckResult = OptionalResult<VersionMismatch>;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait Dependency: Send + Sync {
+    fn get_name(&self) -> &str;
+
+    fn get_constraint(&self) -> &str;
+
+    /// An identifier for the registry this dependency is resolved from,
+    /// used to tell apart identically named packages of different registries.
+    fn get_registry(&self) -> &str;
+
+    /// Resolves this dependency from another registry than the default one of its
+    /// ecosystem, such as a mirror or a private registry. Ecosystems whose registry
+    /// cannot be changed ignore it.
+    fn use_registry(&mut self, _registry: &Registry) {}
+
+    /// The name of the ecosystem of this dependency in advisory databases, such as
+    /// OSV, or `None` if they do not cover it.
+    fn get_ecosystem(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether the version satisfies the constraint. Fails with
+    /// [`DepchkError::InvalidVersion`] if the version cannot be parsed, which happens
+    /// when a registry serves a malformed version.
+    fn try_is_satisfied_by(&self, version: &str) -> DirectResult<bool>;
+
+    /// Whether the version satisfies the constraint. Versions that cannot be parsed
+    /// satisfy no constraint.
+    fn is_satisfied_by(&self, version: &str) -> bool {
+        self.try_is_satisfied_by(version).unwrap_or(false)
+    }
+
+    /// Whether there is at least one version that satisfies
+    /// both this dependency's constraint and the other one's.
+    fn is_compatible_with(&self, other: &Self) -> bool
+    where
+        Self: Sized;
+
+    /// Requests the latest version of this dependency from its registry.
+    async fn fetch_latest_version(&self, client: &HttpClient) -> DirectResult<String>;
+
+    /// Requests the latest versions of many dependencies in as few requests as
+    /// possible, for registries that expose a bulk lookup endpoint. The returned
+    /// map is keyed by dependency name. Registries without such an endpoint
+    /// return `None`, and each dependency is then looked up on its own.
+    async fn fetch_latest_versions(
+        _client: &HttpClient,
+        _dependencies: &[&Self],
+    ) -> Option<DirectResult<HashMap<String, String>>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Requests every published version of this dependency from its registry.
+    /// Registries that cannot list versions return an empty list.
+    async fn fetch_versions(&self, _client: &HttpClient) -> DirectResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Requests everything the registry publishes about this dependency besides its
+    /// versions at once, for registries that publish it as a single document. A
+    /// [`RegistryClient`] fetches it once and answers the lookups of release dates,
+    /// links, deprecations, licenses and attestations out of it. Other registries
+    /// return `None`, and each of those is requested on its own.
+    async fn fetch_metadata(&self, _client: &HttpClient) -> OptionalResult<PackageMetadata> {
+        Ok(None)
+    }
+
+    /// Requests the release dates of the published versions of this dependency from
+    /// its registry, keyed by version. Registries that do not publish release dates
+    /// return an empty map.
+    async fn fetch_release_dates(
+        &self,
+        _client: &HttpClient,
+    ) -> DirectResult<HashMap<String, SystemTime>> {
+        Ok(HashMap::new())
+    }
+
+    /// Requests the SPDX license expression the given version of this dependency is
+    /// published under. Registries without package metadata return `None`.
+    async fn fetch_license(&self, _client: &HttpClient, _version: &str) -> OptionalResult<String> {
+        Ok(None)
+    }
+
+    /// Requests the published versions of this dependency that carry a provenance
+    /// attestation, which links a release to the source and build it came from.
+    /// Registries without provenance attestations return `None`.
+    async fn fetch_attested_versions(
+        &self,
+        _client: &HttpClient,
+    ) -> OptionalResult<HashSet<String>> {
+        Ok(None)
+    }
+
+    /// Requests whether the given version of this dependency can still be installed from
+    /// its registry, returning why not if it cannot. Versions that are no longer listed
+    /// are taken to be unpublished, and registries that cannot list versions return `None`.
+    async fn fetch_availability(
+        &self,
+        client: &HttpClient,
+        version: &str,
+    ) -> OptionalResult<Unavailability> {
+        let versions = self.fetch_versions(client).await?;
+
+        let is_listed = versions.is_empty() || versions.iter().any(|listed| listed == version);
+
+        Ok((!is_listed).then_some(Unavailability::Unpublished))
+    }
+
+    /// Requests the URL of the homepage of this dependency from its registry, falling
+    /// back to its repository. Registries without package metadata return `None`.
+    async fn fetch_homepage(&self, _client: &HttpClient) -> OptionalResult<String> {
+        Ok(None)
+    }
+
+    /// Requests the URL of the repository of this dependency from its registry.
+    /// Registries without package metadata return `None`.
+    async fn fetch_repository(&self, _client: &HttpClient) -> OptionalResult<String> {
+        Ok(None)
+    }
+
+    /// Requests the deprecation message of the latest version of this dependency
+    /// from its registry. Returns `None` if it is not deprecated, or the registry
+    /// has no deprecations.
+    async fn fetch_deprecation(&self, _client: &HttpClient) -> OptionalResult<String> {
+        Ok(None)
+    }
+
+    /// Resolves the latest version of this dependency through the client
+    /// and reports a mismatch if it does not satisfy the constraint.
+    async fn check_version(&self, client: &RegistryClient) -> DependencyCheckResult {
+        let version = client.latest_version(self).await?;
+
+        self.compare_version(version)
+    }
+
+    /// The highest published version that satisfies the constraint, which is what
+    /// a fresh install resolves to. When the published versions cannot be listed,
+    /// this is the lowest version the constraint allows instead.
+    async fn satisfiable_version(&self, client: &RegistryClient) -> Option<Version> {
+        let versions = client.published_versions(self).await.unwrap_or_default();
+
+        versions
+            .iter()
+            .filter(|version| self.is_satisfied_by(version))
+            .filter_map(|version| Version::parse(version).ok())
+            .max()
+            .or_else(|| constraint_floor(self.get_constraint()))
+    }
+
+    /// Works out how big of an update the mismatch is, by comparing the latest
+    /// version with the highest published version that satisfies the constraint.
+    /// When the published versions cannot be listed, the lowest version the
+    /// constraint allows is compared instead. The time between the releases of
+    /// both versions is recorded as well, if the registry publishes release dates.
+    async fn classify_mismatch(
+        &self,
+        client: &RegistryClient,
+        mismatch: VersionMismatch,
+    ) -> VersionMismatch {
+        let current = self.satisfiable_version(client).await;
+
+        let update_kind = match (&current, Version::parse(&mismatch.version)) {
+            (Some(current), Ok(latest)) => UpdateKind::between(current, &latest),
+            _ => None,
+        };
+
+        let release_dates = client.release_dates(self).await.unwrap_or_default();
+
+        let libyears = current
+            .as_ref()
+            .and_then(|current| release_dates.get(&current.to_string()))
+            .zip(release_dates.get(&mismatch.version))
+            .map(|(current, latest)| {
+                latest
+                    .duration_since(*current)
+                    .unwrap_or_default()
+                    .as_secs_f64()
+                    / SECONDS_PER_YEAR
+            });
+
+        VersionMismatch {
+            satisfiable_version: current.as_ref().map(Version::to_string),
+            update_kind,
+            libyears,
+            released_at: release_dates.get(&mismatch.version).copied(),
+            ..mismatch
+        }
+    }
+
+    /// Reports a mismatch if the given latest version does not satisfy the constraint.
+    fn compare_version(&self, version: String) -> DependencyCheckResult {
+        if self.try_is_satisfied_by(&version)? {
+            return Ok(None);
+        }
+
+        Ok(Some(VersionMismatch {
+            name: self.get_name().to_string(),
+            constraint: self.get_constraint().to_string(),
+            version,
+            satisfiable_version: None,
+            update_kind: None,
+            libyears: None,
+            released_at: None,
+            flags: Vec::new(),
+            group: None,
+            workspace: None,
+        }))
+    }
+}
+
+#[async_trait(?Send)]
+pub trait DependencyFileParser {
+    type Output: Dependency;
+
+    /// Parses the contents of a dependency file, such as one fetched from an API or
+    /// read from a git blob, without touching the filesystem.
+    fn parse_str(contents: &str) -> DirectResult<ProjectDependencies<Self::Output>>;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(file = file_name))
+    )]
+    fn parse_file(file_name: &str) -> DirectResult<ProjectDependencies<Self::Output>> {
+        let dependencies = Self::parse_str(&fs::read_to_string(file_name)?)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            dependencies = dependencies.len(),
+            warnings = dependencies.warnings().len(),
+            "parsed dependency file"
+        );
+
+        Ok(dependencies)
+    }
+
+    /// Parses a dependency file read until its end from the reader, such as stdin.
+    fn parse_reader(mut reader: impl Read) -> DirectResult<ProjectDependencies<Self::Output>> {
+        let mut contents = String::new();
+        reader.read_to_string(&mut contents)?;
+
+        Self::parse_str(&contents)
+    }
+
+    /// Parses the dependency file of a workspace root along with the dependency files
+    /// of every package of the workspace, root first. Ecosystems without a notion of
+    /// workspaces only return the root package.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn parse_workspaces(
+        file_name: &str,
+    ) -> DirectResult<Vec<WorkspacePackage<Self::Output>>> {
+        Ok(vec![WorkspacePackage::new(
+            file_name,
+            Self::parse_file(file_name)?,
+        )])
+    }
+
+    /// Reads the versions the lockfile next to the dependency file locks packages to.
+    /// Returns `None` when there is no lockfile, or the ecosystem does not have any.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parse_lockfile(_file_name: &str) -> OptionalResult<LockedVersions> {
+        Ok(None)
+    }
+
+    /// Reads the packages the lockfile next to the dependency file installs only because
+    /// the declared dependencies depend on them, along with the declared dependencies
+    /// that pull each of them in. Returns `None` when there is no lockfile, or it does
+    /// not record what the locked packages depend on.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parse_transitive(_file_name: &str) -> OptionalResult<TransitiveDependencies<Self::Output>> {
+        Ok(None)
+    }
+
+    /// Reads the checksums the lockfile next to the dependency file records for the
+    /// packages it locks. Returns `None` when there is no lockfile, or the ecosystem
+    /// does not have any.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parse_lockfile_checksums(_file_name: &str) -> OptionalResult<Vec<LockedChecksum>> {
+        Ok(None)
+    }
+
+    /// Requests the checksums the registry publishes for the versions of a package,
+    /// keyed by version, in the same format as the lockfile records them. Packages are
+    /// looked up on the default registry of the ecosystem unless another one is given.
+    async fn fetch_published_checksums(
+        _client: &HttpClient,
+        _registry: Option<&Registry>,
+        _name: &str,
+    ) -> DirectResult<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
+
+    /// The contents of the dependency file with the constraints of the dependencies
+    /// replaced by the updated ones. The rest of the file is kept exactly as it was
+    /// written, formatting included. The file itself is left untouched.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_constraints(_file_name: &str, _updates: &[ConstraintUpdate]) -> DirectResult<String> {
+        Err("updating the dependency file is not supported for this package manager".into())
+    }
+
+    /// The package manager command that brings the lockfile next to the dependency file
+    /// in line with the updated constraints, installing as little as the package manager
+    /// allows. Returns `None` when there is no lockfile to sync.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn sync_lockfile_command(_file_name: &str, _updates: &[ConstraintUpdate]) -> Option<Command> {
+        None
+    }
+}
+
+/// The dependencies a project declares. Projects can be serialized, for instance to
+/// be cached or sent to another process, as long as their dependencies can be.
+#[derive(Serialize, Deserialize)]
+pub struct ProjectDependencies<T: Dependency> {
+    dependencies: Vec<T>,
+    dev_dependencies: Vec<T>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<ParseWarning>,
+}
+
+/// An entry of a dependency file that was left out of the parsed dependencies, because
+/// it cannot be checked against a registry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseWarning {
+    name: String,
+    constraint: String,
+    reason: SkipReason,
+}
+
+/// Why an entry of a dependency file was left out of the parsed dependencies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The constraint is not a valid version range.
+    InvalidConstraint,
+    /// The dependency is not resolved from a registry, but from a path, a git
+    /// repository, a URL or another package of the workspace.
+    UnsupportedSpecifier,
+    /// The dependency is declared again later in the same section, and only its last
+    /// declaration is kept.
+    Duplicate,
+}
+
+/// How dependencies are serialized: as the name and constraint they are declared with,
+/// along with the registry they are resolved from when it is not the default one.
+#[cfg(any(feature = "npm", feature = "cargo"))]
+#[derive(Serialize, Deserialize)]
+pub(crate) struct DeclaredDependency {
+    pub(crate) name: String,
+    pub(crate) constraint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) registry: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VersionMismatch {
+    name: String,
+    constraint: String,
+    version: String,
+
+    /// The highest published version allowed by the constraint, or the lowest
+    /// allowed one if the published versions are not known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    satisfiable_version: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    update_kind: Option<UpdateKind>,
+
+    /// How far behind the latest release the highest version allowed by the
+    /// constraint is, in years.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    libyears: Option<f64>,
+
+    /// When the latest version was released, if the registry publishes release dates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    released_at: Option<SystemTime>,
+
+    /// The signs that the dependency is no longer maintained, if its maintenance was checked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    flags: Vec<MaintenanceFlag>,
+
+    /// The group of related packages the dependency is updated along with, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+
+    /// The workspace package that declares the dependency, if the packages of the
+    /// workspace were checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    workspace: Option<String>,
+}
+
+/// How big of an update a mismatch is, by the most significant
+/// version segment that changes. Ordered from smallest to biggest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateKind {
+    Patch,
+    Minor,
+    Major,
+}
+
+/// Why a locked version of a dependency can no longer be installed from its registry.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unavailability {
+    /// The version was yanked, which crates.io does to versions that should no
+    /// longer be depended on. Existing lockfiles can still install it.
+    Yanked,
+    /// The version was unpublished, or removed from the registry altogether.
+    Unpublished,
+}
+
+/// A dependency whose locked version can no longer be installed from its registry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnavailableVersion {
+    name: String,
+    version: String,
+    reason: Unavailability,
+}
+
+/// A dependency whose latest version carries no provenance attestation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingProvenance {
+    name: String,
+    version: String,
+
+    /// The version a fresh install resolves to, if it carries the provenance the
+    /// latest version lost.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    attested_version: Option<String>,
+}
+
+/// The checksum a lockfile records for a locked package. Checksums are given either
+/// as Subresource Integrity strings, e.g. `sha512-<base64>`, several of which may be
+/// separated by spaces, or as a bare digest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockedChecksum {
+    name: String,
+    version: String,
+    checksum: String,
+    registry: Option<String>,
+}
+
+/// A locked package whose checksum differs from the one its registry publishes, which
+/// means either the lockfile or the registry it was installed from was tampered with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityMismatch {
+    name: String,
+    version: String,
+    locked: String,
+
+    /// The checksum the registry publishes.
+    published: String,
+}
+
+/// A dependency whose constraint allows its latest version.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpToDate {
+    name: String,
+    constraint: String,
+    latest_version: String,
+}
+
+/// The outcome of checking dependencies against their registries, which accounts
+/// for every one of them, not only the outdated ones.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub up_to_date: Vec<UpToDate>,
+    pub outdated: Vec<VersionMismatch>,
+
+    /// Dependencies that were not checked, because they were not cached while
+    /// running offline.
+    pub skipped: Vec<DependencyError>,
+
+    pub errored: Vec<DependencyError>,
+
+    /// Whether the check was cancelled before every dependency was checked, in
+    /// which case the report only accounts for the ones that were.
+    pub cancelled: bool,
+}
+
+/// The outcome of checking a single dependency, see [`check_stream`].
+#[derive(Debug)]
+pub enum CheckOutcome {
+    UpToDate(UpToDate),
+    Outdated(VersionMismatch),
+
+    /// Not checked, because the dependency was not cached while running offline.
+    Skipped(DependencyError),
+
+    Errored(DependencyError),
+}
+
+/// An error that occurred while checking a single dependency,
+/// tagged with the name of the dependency it belongs to.
+#[derive(Debug)]
+pub struct DependencyError {
+    name: String,
+    source: Box<DepchkError>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Mismatches {
+    pub dependencies: Vec<VersionMismatch>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dev_dependencies: Option<Vec<VersionMismatch>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_skew: Option<Vec<VersionSkew>>,
+
+    /// Dependencies whose latest version could not be determined
+    /// because they were not cached while running offline.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unknown: Option<Vec<String>>,
+}
+
+impl VersionMismatch {
+    pub fn new(name: &str, constraint: &str, version: &str) -> Self {
+        VersionMismatch {
+            name: name.to_string(),
+            constraint: constraint.to_string(),
+            version: version.to_string(),
+            satisfiable_version: None,
+            update_kind: None,
+            libyears: None,
+            released_at: None,
+            flags: Vec::new(),
+            group: None,
+            workspace: None,
+        }
+    }
+
+    pub fn with_satisfiable_version(mut self, satisfiable_version: &str) -> Self {
+        self.satisfiable_version = Some(satisfiable_version.to_string());
+        self
+    }
+
+    pub fn with_update_kind(mut self, update_kind: UpdateKind) -> Self {
+        self.update_kind = Some(update_kind);
+        self
+    }
+
+    /// The name, constraint and latest version of the dependency, in that order.
+    pub fn destruct(&self) -> (&str, &str, &str) {
+        (&self.name, &self.constraint, &self.version)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The version constraint the dependency is declared with.
+    pub fn constraint(&self) -> &str {
+        &self.constraint
+    }
+
+    /// The latest version of the dependency, which its constraint does not allow.
+    pub fn latest_version(&self) -> &str {
+        &self.version
+    }
+
+    /// The version that is compared against the latest one, if it could be worked out.
+    /// See [`Dependency::classify_mismatch`].
+    pub fn satisfiable_version(&self) -> Option<&str> {
+        self.satisfiable_version.as_deref()
+    }
+
+    /// How big of an update this is, if it could be worked out.
+    pub fn update_kind(&self) -> Option<UpdateKind> {
+        self.update_kind
+    }
+
+    /// How many years passed between the release of the highest version allowed by
+    /// the constraint and the release of the latest version, if both are known.
+    pub fn libyears(&self) -> Option<f64> {
+        self.libyears
+    }
+
+    pub fn with_libyears(mut self, libyears: f64) -> Self {
+        self.libyears = Some(libyears);
+        self
+    }
+
+    /// When the latest version was released, if it is known.
+    pub fn released_at(&self) -> Option<SystemTime> {
+        self.released_at
+    }
+
+    pub fn with_released_at(mut self, released_at: SystemTime) -> Self {
+        self.released_at = Some(released_at);
+        self
+    }
+
+    /// How many whole days passed between the release of the latest version and `now`,
+    /// which is how long the dependency has been behind it, if the release date is known.
+    pub fn days_behind(&self, now: SystemTime) -> Option<u64> {
+        let behind = now.duration_since(self.released_at?).unwrap_or_default();
+
+        Some(behind.as_secs() / 86_400)
+    }
+
+    pub fn flags(&self) -> &[MaintenanceFlag] {
+        &self.flags
+    }
+
+    pub fn with_flags(mut self, flags: Vec<MaintenanceFlag>) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// The group of related packages the dependency is updated along with, if it
+    /// belongs to one. See [`groups::UpdateGroups`].
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+
+    /// The name of the workspace package that declares the dependency, if it is known.
+    /// See [`workspace::WorkspacePackage`].
+    pub fn workspace(&self) -> Option<&str> {
+        self.workspace.as_deref()
+    }
+
+    pub fn with_workspace(mut self, workspace: &str) -> Self {
+        self.workspace = Some(workspace.to_string());
+        self
+    }
+}
+
+impl UpdateKind {
+    /// The kind of update from the current version to the latest one, or `None`
+    /// if the latest version is not newer.
+    ///
+    /// ```
+    /// use depchk_core::UpdateKind;
+    /// use semver::Version;
+    ///
+    /// let current = Version::parse("1.4.2").unwrap();
+    ///
+    /// assert_eq!(UpdateKind::between(&current, &Version::parse("2.0.0").unwrap()), Some(UpdateKind::Major));
+    /// assert_eq!(UpdateKind::between(&current, &Version::parse("1.5.0").unwrap()), Some(UpdateKind::Minor));
+    /// assert_eq!(UpdateKind::between(&current, &Version::parse("1.4.3").unwrap()), Some(UpdateKind::Patch));
+    /// assert_eq!(UpdateKind::between(&current, &Version::parse("1.4.2").unwrap()), None);
+    /// ```
+    pub fn between(current: &Version, latest: &Version) -> Option<UpdateKind> {
+        if latest <= current {
+            None
+        } else if latest.major != current.major {
+            Some(UpdateKind::Major)
+        } else if latest.minor != current.minor {
+            Some(UpdateKind::Minor)
+        } else {
+            Some(UpdateKind::Patch)
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UpdateKind::Patch => "patch",
+            UpdateKind::Minor => "minor",
+            UpdateKind::Major => "major",
+        }
+    }
+}
+
+impl Display for UpdateKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The lowest version a constraint allows, read from the first version that appears
+/// in it, with missing segments filled in with zeros. For example, `^1.2` gives 1.2.0.
+fn constraint_floor(constraint: &str) -> Option<Version> {
+    let start = constraint.find(|c: char| c.is_ascii_digit())?;
+
+    let segments: Vec<u64> = constraint[start..]
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()?
+        .split('.')
+        .map_while(|segment| segment.parse().ok())
+        .take(3)
+        .collect();
+
+    match segments[..] {
+        [major] => Some(Version::new(major, 0, 0)),
+        [major, minor] => Some(Version::new(major, minor, 0)),
+        [major, minor, patch] => Some(Version::new(major, minor, patch)),
+        _ => None,
+    }
+}
+
+impl Unavailability {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unavailability::Yanked => "yanked",
+            Unavailability::Unpublished => "unpublished",
+        }
+    }
+}
+
+impl UnavailableVersion {
+    pub fn new(name: &str, version: &str, reason: Unavailability) -> Self {
+        UnavailableVersion {
+            name: name.to_string(),
+            version: version.to_string(),
+            reason,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The locked version that can no longer be installed.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn reason(&self) -> Unavailability {
+        self.reason
+    }
+}
+
+impl MissingProvenance {
+    pub fn new(name: &str, version: &str) -> Self {
+        MissingProvenance {
+            name: name.to_string(),
+            version: version.to_string(),
+            attested_version: None,
+        }
+    }
+
+    pub fn with_attested_version(mut self, attested_version: &str) -> Self {
+        self.attested_version = Some(attested_version.to_string());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The latest version, which has no provenance attestation.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn attested_version(&self) -> Option<&str> {
+        self.attested_version.as_deref()
+    }
+
+    /// Whether upgrading would lose the provenance of the version in use, which is
+    /// more suspicious than a package that never had any.
+    pub fn is_lost(&self) -> bool {
+        self.attested_version.is_some()
+    }
+
+    /// Whether the provenance was `lost` by the latest version, or is `missing` altogether.
+    pub fn status(&self) -> &'static str {
+        match self.is_lost() {
+            true => "lost",
+            false => "missing",
+        }
+    }
+}
+
+impl LockedChecksum {
+    pub fn new(name: &str, version: &str, checksum: &str) -> Self {
+        LockedChecksum {
+            name: name.to_string(),
+            version: version.to_string(),
+            checksum: checksum.to_string(),
+            registry: None,
+        }
+    }
+
+    /// Sets the registry the lockfile resolved the package from, when it is not the
+    /// default one of its ecosystem.
+    pub fn with_registry(mut self, registry: &str) -> Self {
+        self.registry = Some(registry.to_string());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn checksum(&self) -> &str {
+        &self.checksum
+    }
+
+    pub fn registry(&self) -> Option<&str> {
+        self.registry.as_deref()
+    }
+}
+
+impl IntegrityMismatch {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The checksum the lockfile records.
+    pub fn locked(&self) -> &str {
+        &self.locked
+    }
+
+    pub fn published(&self) -> &str {
+        &self.published
+    }
+}
+
+impl UpToDate {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn constraint(&self) -> &str {
+        &self.constraint
+    }
+
+    pub fn latest_version(&self) -> &str {
+        &self.latest_version
+    }
+}
+
+impl CheckReport {
+    /// The number of dependencies that were checked, or skipped.
+    pub fn len(&self) -> usize {
+        self.up_to_date.len() + self.outdated.len() + self.skipped.len() + self.errored.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds the outcome of checking another dependency to the report.
+    pub fn add(&mut self, outcome: CheckOutcome) {
+        match outcome {
+            CheckOutcome::UpToDate(up_to_date) => self.up_to_date.push(up_to_date),
+            CheckOutcome::Outdated(mismatch) => self.outdated.push(mismatch),
+            CheckOutcome::Skipped(err) => self.skipped.push(err),
+            CheckOutcome::Errored(err) => self.errored.push(err),
+        }
+    }
+}
+
+impl CheckOutcome {
+    /// The name of the dependency that was checked.
+    pub fn name(&self) -> &str {
+        match self {
+            CheckOutcome::UpToDate(up_to_date) => up_to_date.name(),
+            CheckOutcome::Outdated(mismatch) => mismatch.name(),
+            CheckOutcome::Skipped(err) | CheckOutcome::Errored(err) => err.name(),
+        }
+    }
+}
+
+impl DependencyError {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The error checking the dependency failed with.
+    pub fn error(&self) -> &DepchkError {
+        &self.source
+    }
+}
+
+impl ParseWarning {
+    pub fn new(name: &str, constraint: &str, reason: SkipReason) -> Self {
+        ParseWarning {
+            name: name.to_string(),
+            constraint: constraint.to_string(),
+            reason,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn constraint(&self) -> &str {
+        &self.constraint
+    }
+
+    pub fn reason(&self) -> SkipReason {
+        self.reason
+    }
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.reason {
+            SkipReason::InvalidConstraint => write!(
+                f,
+                "{}: {} is not a valid version constraint",
+                self.name, self.constraint
+            ),
+            SkipReason::UnsupportedSpecifier => write!(
+                f,
+                "{}: {} is not resolved from a registry",
+                self.name, self.constraint
+            ),
+            SkipReason::Duplicate => write!(
+                f,
+                "{}: {} is declared again later, which takes precedence",
+                self.name, self.constraint
+            ),
+        }
+    }
+}
+
+impl Display for DependencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.source)
+    }
+}
+
+impl Error for DependencyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+impl<T: Dependency> ProjectDependencies<T> {
+    pub fn new(deps: Vec<T>, dev_deps: Vec<T>) -> Self {
+        ProjectDependencies {
+            dependencies: deps,
+            dev_dependencies: dev_deps,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Records the entries of the dependency file that were left out while parsing it.
+    pub fn with_warnings(mut self, warnings: Vec<ParseWarning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Creates the dependencies of a project from the names and constraints of its
+    /// dependencies and dev dependencies, failing on the first constraint that cannot
+    /// be parsed.
+    ///
+    #[cfg_attr(feature = "npm", doc = "```")]
+    #[cfg_attr(not(feature = "npm"), doc = "```ignore")]
+    /// # use depchk_core::npm::PackageJson;
+    /// let project = PackageJson::from_pairs(
+    ///     vec![("axios", "^1.6.0"), ("left-pad", "1.3.0")],
+    ///     vec![("jest", "^29.0.0")],
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(project.len(), 3);
+    /// assert!(PackageJson::from_pairs(vec![("axios", "not a range")], vec![]).is_err());
+    /// ```
+    pub fn from_pairs<N: AsRef<str>, C: AsRef<str>>(
+        dependencies: impl IntoIterator<Item = (N, C)>,
+        dev_dependencies: impl IntoIterator<Item = (N, C)>,
+    ) -> DirectResult<Self>
+    where
+        T: for<'a> TryFrom<(&'a str, &'a str), Error = DepchkError>,
+    {
+        let parse = |pairs: Vec<(N, C)>| {
+            pairs
+                .iter()
+                .map(|(name, constraint)| T::try_from((name.as_ref(), constraint.as_ref())))
+                .collect::<DirectResult<Vec<_>>>()
+        };
+
+        Ok(ProjectDependencies::new(
+            parse(dependencies.into_iter().collect())?,
+            parse(dev_dependencies.into_iter().collect())?,
+        ))
+    }
+
+    pub fn dependencies(&self) -> &[T] {
+        &self.dependencies
+    }
+
+    pub fn dev_dependencies(&self) -> &[T] {
+        &self.dev_dependencies
+    }
+
+    /// The entries of the dependency file that were left out while parsing it, because
+    /// they cannot be checked against a registry.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    /// Iterates over every dependency, regular dependencies first
+    /// and dev dependencies after.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.dependencies.iter().chain(self.dev_dependencies.iter())
+    }
+
+    /// The number of dependencies, dev dependencies included.
+    pub fn len(&self) -> usize {
+        self.dependencies.len() + self.dev_dependencies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.dependencies
+            .iter_mut()
+            .chain(self.dev_dependencies.iter_mut())
+    }
+
+    /// Only keeps the dependencies, dev dependencies included, that the predicate
+    /// returns true for.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&T) -> bool) {
+        self.dependencies.retain(&mut predicate);
+        self.dev_dependencies.retain(predicate);
+    }
+
+    /// Resolves the dependencies from the registries they are mapped to by name, see
+    /// [`Dependency::use_registry`]. Dependencies mapped to none keep their registry.
+    pub fn use_registries(&mut self, registry_of: impl Fn(&str) -> Option<Registry>) {
+        for dependency in self.iter_mut() {
+            if let Some(registry) = registry_of(dependency.get_name()) {
+                dependency.use_registry(&registry);
+            }
+        }
+    }
+
+    pub async fn check_dependencies(
+        &self,
+        client: &RegistryClient,
+        concurrency: usize,
+    ) -> CheckReport {
+        check_dependencies(client, &self.dependencies, concurrency).await
+    }
+
+    pub async fn check_dev_dependencies(
+        &self,
+        client: &RegistryClient,
+        concurrency: usize,
+    ) -> CheckReport {
+        check_dependencies(client, &self.dev_dependencies, concurrency).await
+    }
+
+    /// Checks the dependencies, yielding the outcome of each one as soon as it is
+    /// known. See [`check_stream`].
+    pub fn check_stream<'a>(
+        &'a self,
+        client: &'a RegistryClient,
+        concurrency: usize,
+    ) -> impl Stream<Item = CheckOutcome> + 'a {
+        check_stream(client, &self.dependencies, concurrency)
+    }
+
+    /// Checks the dev dependencies, yielding the outcome of each one as soon as it
+    /// is known. See [`check_stream`].
+    pub fn check_dev_stream<'a>(
+        &'a self,
+        client: &'a RegistryClient,
+        concurrency: usize,
+    ) -> impl Stream<Item = CheckOutcome> + 'a {
+        check_stream(client, &self.dev_dependencies, concurrency)
+    }
+
+    pub fn stream_dependencies<'a>(
+        &'a self,
+        client: &'a RegistryClient,
+        concurrency: usize,
+    ) -> impl Stream<Item = DependencyMismatchResult> + 'a {
+        stream_dependencies(client, &self.dependencies, concurrency)
+    }
+
+    pub fn stream_dev_dependencies<'a>(
+        &'a self,
+        client: &'a RegistryClient,
+        concurrency: usize,
+    ) -> impl Stream<Item = DependencyMismatchResult> + 'a {
+        stream_dependencies(client, &self.dev_dependencies, concurrency)
+    }
+}
+
+impl<'a, T: Dependency> IntoIterator for &'a ProjectDependencies<T> {
+    type Item = &'a T;
+    type IntoIter = std::iter::Chain<std::slice::Iter<'a, T>, std::slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.dependencies.iter().chain(self.dev_dependencies.iter())
+    }
+}
+
+impl<T: Dependency> From<Vec<T>> for ProjectDependencies<T> {
+    fn from(value: Vec<T>) -> Self {
+        ProjectDependencies::new(value, Vec::new())
+    }
+}
+
+/// Checks every dependency against its registry, running at most `concurrency`
+/// lookups at the same time. Every dependency ends up in the report, in the order
+/// in which their lookups resolved, and each failed check is wrapped in a
+/// [`DependencyError`] so that it can still be traced back to its dependency.
+pub async fn check_dependencies<T: Dependency>(
+    client: &RegistryClient,
+    dependencies: &[T],
+    concurrency: usize,
+) -> CheckReport {
+    check_dependencies_with_progress(client, dependencies, concurrency, &()).await
+}
+
+/// Checks every dependency like [`check_dependencies`], telling the reporter about
+/// the progress of the check as it goes.
+pub async fn check_dependencies_with_progress<T: Dependency>(
+    client: &RegistryClient,
+    dependencies: &[T],
+    concurrency: usize,
+    progress: &dyn ProgressReporter,
+) -> CheckReport {
+    check_stream_with_progress(client, dependencies, concurrency, progress)
+        .fold(CheckReport::default(), |mut report, outcome| {
+            report.add(outcome);
+            future::ready(report)
+        })
+        .await
+}
+
+/// Checks every dependency like [`check_dependencies_with_progress`] until the
+/// `cancelled` future resolves, such as the `cancelled()` future of a cancellation
+/// token or a shutdown signal. The lookups still in flight are then dropped, and
+/// the report of the dependencies checked so far is returned, marked as cancelled.
+///
+/// Dropping the future of any check function cancels it as well, but loses the
+/// results collected so far.
+pub async fn check_dependencies_until<T: Dependency>(
+    client: &RegistryClient,
+    dependencies: &[T],
+    concurrency: usize,
+    progress: &dyn ProgressReporter,
+    cancelled: impl Future<Output = ()>,
+) -> CheckReport {
+    let outcomes = check_stream_with_progress(client, dependencies, concurrency, progress);
+
+    futures::pin_mut!(outcomes, cancelled);
+
+    let mut report = CheckReport::default();
+
+    loop {
+        match future::select(cancelled.as_mut(), outcomes.next()).await {
+            Either::Left(_) => {
+                report.cancelled = true;
+                break;
+            }
+            Either::Right((Some(outcome), _)) => report.add(outcome),
+            Either::Right((None, _)) => break,
+        }
+    }
+
+    report
+}
+
+/// The streaming counterpart of [`check_dependencies`], which yields every
+/// mismatch or error as soon as the registry lookup behind it resolves.
+/// Up to date dependencies are left out.
+pub fn stream_dependencies<'a, T: Dependency>(
+    client: &'a RegistryClient,
+    dependencies: &'a [T],
+    concurrency: usize,
+) -> impl Stream<Item = DependencyMismatchResult> + 'a {
+    check_stream(client, dependencies, concurrency).filter_map(|outcome| async move {
+        match outcome {
+            CheckOutcome::UpToDate(_) => None,
+            CheckOutcome::Outdated(mismatch) => Some(Ok(mismatch)),
+            CheckOutcome::Skipped(err) | CheckOutcome::Errored(err) => Some(Err(err.into())),
+        }
+    })
+}
+
+/// Checks every dependency, yielding the outcome of each one as soon as the registry
+/// lookup behind it resolves, so that results can be processed without waiting for
+/// the whole check. Dependencies are first resolved in bulk where the registry
+/// supports it, and only the remaining ones are looked up one by one.
+pub fn check_stream<'a, T: Dependency>(
+    client: &'a RegistryClient,
+    dependencies: &'a [T],
+    concurrency: usize,
+) -> impl Stream<Item = CheckOutcome> + 'a {
+    check_stream_with_progress(client, dependencies, concurrency, &())
+}
+
+/// Checks every dependency like [`check_stream`], telling the reporter about the
+/// progress of the check as it goes.
+pub fn check_stream_with_progress<'a, T: Dependency>(
+    client: &'a RegistryClient,
+    dependencies: &'a [T],
+    concurrency: usize,
+    progress: &'a dyn ProgressReporter,
+) -> impl Stream<Item = CheckOutcome> + 'a {
+    let resolved = async move {
+        progress.checking(dependencies.len());
+        client.latest_versions(dependencies).await
+    };
+
+    futures::stream::once(resolved).flat_map(move |resolved| {
+        futures::stream::iter(dependencies)
+            .map(move |dependency| {
+                let version = resolved.get(dependency.get_name()).cloned();
+
+                async move {
+                    progress.started(dependency.get_name());
+
+                    let outcome = check_resolved(client, dependency, version).await;
+
+                    match &outcome {
+                        CheckOutcome::Skipped(err) | CheckOutcome::Errored(err) => {
+                            progress.failed(err)
+                        }
+                        outcome => progress.completed(outcome),
+                    }
+
+                    outcome
+                }
+            })
+            .buffer_unordered(concurrency.max(1))
+    })
+}
+
+/// Finds the dependencies whose locked versions have been yanked or unpublished,
+/// running at most `concurrency` lookups at the same time. Dependencies that are
+/// not in the lockfile are skipped, as are the ones whose lookup failed.
+pub async fn find_unavailable_versions<'a, T: Dependency + 'a>(
+    client: &RegistryClient,
+    dependencies: impl IntoIterator<Item = &'a T>,
+    locked: &LockedVersions,
+    concurrency: usize,
+) -> Vec<UnavailableVersion> {
+    let lookups = dependencies.into_iter().flat_map(|dependency| {
+        let versions = locked.get(dependency.get_name()).into_iter().flatten();
+
+        versions.map(move |version| async move {
+            let reason = client.availability(dependency, version).await.ok()??;
+
+            Some(UnavailableVersion::new(
+                dependency.get_name(),
+                version,
+                reason,
+            ))
+        })
+    });
+
+    let mut unavailable: Vec<_> = futures::stream::iter(lookups)
+        .buffer_unordered(concurrency.max(1))
+        .filter_map(futures::future::ready)
+        .collect()
+        .await;
+
+    unavailable.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    unavailable.dedup();
+    unavailable
+}
+
+/// Finds the dependencies whose latest version carries no provenance attestation,
+/// running at most `concurrency` lookups at the same time, and whether the version
+/// a fresh install resolves to had one. Dependencies of registries without provenance
+/// attestations are skipped, as are the ones whose lookup failed.
+pub async fn find_missing_provenance<'a, T: Dependency + 'a>(
+    client: &RegistryClient,
+    dependencies: impl IntoIterator<Item = &'a T>,
+    concurrency: usize,
+) -> Vec<MissingProvenance> {
+    let lookups = dependencies.into_iter().map(|dependency| async move {
+        let attested = client.attested_versions(dependency).await.ok()??;
+        let latest = client.latest_version(dependency).await.ok()?;
+
+        if attested.contains(&latest) {
+            return None;
+        }
+
+        let missing = MissingProvenance::new(dependency.get_name(), &latest);
+
+        let attested_version = dependency
+            .satisfiable_version(client)
+            .await
+            .map(|version| version.to_string())
+            .filter(|version| attested.contains(version));
+
+        Some(match attested_version {
+            Some(version) => missing.with_attested_version(&version),
+            None => missing,
+        })
+    });
+
+    let mut missing: Vec<_> = futures::stream::iter(lookups)
+        .buffer_unordered(concurrency.max(1))
+        .filter_map(futures::future::ready)
+        .collect()
+        .await;
+
+    missing.sort_by(|a, b| a.name.cmp(&b.name));
+    missing.dedup();
+    missing
+}
+
+/// Compares the checksums the lockfile records against the ones the registry publishes,
+/// looking each package up once per registry, with at most `concurrency` lookups at the
+/// same time. Packages are looked up on the registry `registry_of` gives for them, or
+/// else on the default one of their ecosystem. Locked versions the registry does not
+/// publish cannot be compared, so they are errors rather than mismatches. Errors are
+/// wrapped in a [`DependencyError`], like those of [`check_dependencies`].
+pub async fn verify_checksums<P: DependencyFileParser>(
+    client: &RegistryClient,
+    locked: &[LockedChecksum],
+    registry_of: impl Fn(&LockedChecksum) -> Option<Registry>,
+    concurrency: usize,
+) -> Vec<DirectResult<IntegrityMismatch>> {
+    let mut lookups: Vec<(&str, Option<Registry>, Vec<&LockedChecksum>)> = Vec::new();
+
+    for package in locked {
+        let registry = registry_of(package);
+
+        match lookups
+            .iter_mut()
+            .find(|(name, other, _)| *name == package.name && *other == registry)
+        {
+            Some((_, _, packages)) => packages.push(package),
+            None => lookups.push((&package.name, registry, vec![package])),
+        }
+    }
+
+    let lookups = lookups
+        .into_iter()
+        .map(|(name, registry, packages)| async move {
+            let published = match client.is_offline() {
+                true => Err(NotCached.into()),
+                false => {
+                    P::fetch_published_checksums(client.transport(), registry.as_ref(), name).await
+                }
+            };
+
+            let error = |source: DepchkError| {
+                Err(DepchkError::from(DependencyError {
+                    name: name.to_string(),
+                    source: Box::new(source),
+                }))
+            };
+
+            let results: Vec<_> = match published {
+                Ok(published) => packages
+                    .into_iter()
+                    .filter_map(|package| {
+                        let Some(checksum) = published.get(&package.version) else {
+                            return Some(error(DepchkError::Other(format!(
+                                "version {} is not published by {}",
+                                package.version,
+                                registry
+                                    .as_ref()
+                                    .map_or("the default registry", |registry| registry.url())
+                            ))));
+                        };
+
+                        if checksums_match(&package.checksum, checksum) {
+                            return None;
+                        }
+
+                        Some(Ok(IntegrityMismatch {
+                            name: package.name.clone(),
+                            version: package.version.clone(),
+                            locked: package.checksum.clone(),
+                            published: checksum.clone(),
+                        }))
+                    })
+                    .collect(),
+                Err(err) => vec![error(err)],
+            };
+
+            futures::stream::iter(results)
+        });
+
+    futures::stream::iter(lookups)
+        .buffer_unordered(concurrency.max(1))
+        .flatten()
+        .collect()
+        .await
+}
+
+/// Whether two checksums agree. Both are read as lists of `<algorithm>-<digest>`
+/// hashes, or bare digests, and they agree when the digests of every algorithm they
+/// have in common do. Checksums with no algorithm in common cannot be told apart,
+/// so they are taken to agree.
+fn checksums_match(locked: &str, published: &str) -> bool {
+    let parse = |checksum: &str| -> Vec<(String, String)> {
+        checksum
+            .split_whitespace()
+            .map(|hash| match hash.split_once('-') {
+                Some((algorithm, digest)) => (algorithm.to_ascii_lowercase(), digest.to_string()),
+                None => (String::new(), hash.to_ascii_lowercase()),
+            })
+            .collect()
+    };
+
+    let published = parse(published);
+
+    parse(locked).iter().all(|(algorithm, digest)| {
+        published
+            .iter()
+            .filter(|(other, _)| other == algorithm)
+            .all(|(_, other)| other == digest)
+    })
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "check",
+        level = "debug",
+        skip_all,
+        fields(name = dependency.get_name(), constraint = dependency.get_constraint())
+    )
+)]
+async fn check_resolved<T: Dependency>(
+    client: &RegistryClient,
+    dependency: &T,
+    version: Option<String>,
+) -> CheckOutcome {
+    let version = match version {
+        Some(version) => Ok(version),
+        None => client.latest_version(dependency).await,
+    };
+
+    let version = match version {
+        Ok(version) => version,
+        Err(source) => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %source, "could not resolve the latest version");
+
+            let err = DependencyError {
+                name: dependency.get_name().to_string(),
+                source: Box::new(source),
+            };
+
+            return match err.error().is_not_cached() {
+                true => CheckOutcome::Skipped(err),
+                false => CheckOutcome::Errored(err),
+            };
+        }
+    };
+
+    let compared = dependency.compare_version(version.clone());
+
+    #[cfg(feature = "tracing")]
+    match &compared {
+        Ok(Some(_)) => tracing::debug!(latest = %version, "outdated"),
+        Ok(None) => tracing::debug!(latest = %version, "up to date"),
+        Err(err) => tracing::warn!(latest = %version, error = %err, "could not compare versions"),
+    }
+
+    match compared {
+        Ok(Some(mismatch)) => {
+            CheckOutcome::Outdated(dependency.classify_mismatch(client, mismatch).await)
+        }
+        Ok(None) => CheckOutcome::UpToDate(UpToDate {
+            name: dependency.get_name().to_string(),
+            constraint: dependency.get_constraint().to_string(),
+            latest_version: version,
+        }),
+        Err(source) => CheckOutcome::Errored(DependencyError {
+            name: dependency.get_name().to_string(),
+            source: Box::new(source),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubDependency {
+        name: String,
+        latest: Result<String, String>,
+        bulk: bool,
+    }
+
+    impl StubDependency {
+        fn new(name: &str, latest: Result<&str, &str>) -> Self {
+            StubDependency {
+                name: name.to_string(),
+                latest: latest.map(str::to_string).map_err(str::to_string),
+                bulk: false,
+            }
+        }
+
+        fn bulk(name: &str, latest: &str) -> Self {
+            StubDependency {
+                bulk: true,
+                ..StubDependency::new(name, Ok(latest))
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Dependency for StubDependency {
+        fn get_name(&self) -> &str {
+            &self.name
+        }
+
+        fn get_constraint(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn try_is_satisfied_by(&self, version: &str) -> DirectResult<bool> {
+            Ok(version == self.get_constraint())
+        }
+
+        fn is_compatible_with(&self, other: &Self) -> bool {
+            self.get_constraint() == other.get_constraint()
+        }
+
+        fn get_registry(&self) -> &str {
+            "stub"
+        }
+
+        async fn fetch_latest_version(&self, _client: &HttpClient) -> DirectResult<String> {
+            if self.bulk {
+                return Err("bulk dependencies must not be looked up individually".into());
+            }
+
+            Ok(self.latest.clone()?)
+        }
+
+        async fn fetch_latest_versions(
+            _client: &HttpClient,
+            dependencies: &[&Self],
+        ) -> Option<DirectResult<HashMap<String, String>>> {
+            let resolved = dependencies
+                .iter()
+                .filter(|dependency| dependency.bulk)
+                .map(|dependency| (dependency.name.clone(), dependency.latest.clone().unwrap()))
+                .collect::<HashMap<_, _>>();
+
+            if resolved.is_empty() {
+                return None;
+            }
+
+            Some(Ok(resolved))
+        }
+    }
+
+    #[tokio::test]
+    async fn check_dependencies_reports_every_dependency() {
+        let dependencies = vec![
+            StubDependency::new("current", Ok("1.0.0")),
+            StubDependency::new("outdated", Ok("2.0.0")),
+            StubDependency::new("broken", Err("registry unavailable")),
+        ];
+
+        let report = check_dependencies(&RegistryClient::default(), &dependencies, 2).await;
+
+        assert_eq!(report.len(), 3);
+        assert_eq!(
+            report.up_to_date,
+            [UpToDate {
+                name: "current".to_string(),
+                constraint: "1.0.0".to_string(),
+                latest_version: "1.0.0".to_string(),
+            }]
+        );
+        assert_eq!(report.outdated[0].name(), "outdated");
+        assert!(report.skipped.is_empty());
+        assert_eq!(
+            report.errored[0].to_string(),
+            "broken: registry unavailable"
+        );
+
+        let streamed: Vec<_> = stream_dependencies(&RegistryClient::default(), &dependencies, 2)
+            .collect()
+            .await;
+
+        assert_eq!(streamed.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn check_streams_yield_the_outcome_of_every_dependency() {
+        let dependencies = vec![
+            StubDependency::new("current", Ok("1.0.0")),
+            StubDependency::new("outdated", Ok("2.0.0")),
+            StubDependency::new("broken", Err("registry unavailable")),
+        ];
+
+        let client = RegistryClient::default();
+        let mut outcomes: Vec<_> = check_stream(&client, &dependencies, 2)
+            .map(|outcome| match outcome {
+                CheckOutcome::UpToDate(_) => ("up to date", outcome.name().to_string()),
+                CheckOutcome::Outdated(_) => ("outdated", outcome.name().to_string()),
+                CheckOutcome::Skipped(_) => ("skipped", outcome.name().to_string()),
+                CheckOutcome::Errored(_) => ("errored", outcome.name().to_string()),
+            })
+            .collect()
+            .await;
+
+        outcomes.sort();
+
+        assert_eq!(
+            outcomes,
+            [
+                ("errored", "broken".to_string()),
+                ("outdated", "outdated".to_string()),
+                ("up to date", "current".to_string()),
+            ]
+        );
+
+        let offline = RegistryClient::default().offline();
+        let skipped: Vec<_> = check_stream(&offline, &dependencies, 2).collect().await;
+
+        assert!(skipped
+            .iter()
+            .all(|outcome| matches!(outcome, CheckOutcome::Skipped(_))));
+    }
+
+    #[tokio::test]
+    async fn progress_is_reported_for_every_dependency() {
+        #[derive(Default)]
+        struct Recorder(std::sync::Mutex<Vec<String>>);
+
+        impl ProgressReporter for Recorder {
+            fn checking(&self, total: usize) {
+                self.0.lock().unwrap().push(format!("checking {}", total));
+            }
+
+            fn completed(&self, outcome: &CheckOutcome) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("completed {}", outcome.name()));
+            }
+
+            fn failed(&self, err: &DependencyError) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("failed {}", err.name()));
+            }
+        }
+
+        let dependencies = vec![
+            StubDependency::new("current", Ok("1.0.0")),
+            StubDependency::new("broken", Err("registry unavailable")),
+        ];
+
+        let recorder = Recorder::default();
+        let report = check_dependencies_with_progress(
+            &RegistryClient::default(),
+            &dependencies,
+            1,
+            &recorder,
+        )
+        .await;
+
+        assert_eq!(report.len(), 2);
+        assert_eq!(
+            recorder.0.into_inner().unwrap(),
+            ["checking 2", "completed current", "failed broken"]
+        );
+    }
+
+    #[tokio::test]
+    async fn cancelled_checks_return_what_was_checked_so_far() {
+        let dependencies = vec![
+            StubDependency::new("current", Ok("1.0.0")),
+            StubDependency::new("outdated", Ok("2.0.0")),
+        ];
+        let client = RegistryClient::default();
+
+        let finished =
+            check_dependencies_until(&client, &dependencies, 2, &(), future::pending()).await;
+
+        assert_eq!(finished.len(), 2);
+        assert!(!finished.cancelled);
+
+        let cancelled =
+            check_dependencies_until(&client, &dependencies, 2, &(), future::ready(())).await;
+
+        assert!(cancelled.is_empty());
+        assert!(cancelled.cancelled);
+    }
+
+    #[tokio::test]
+    async fn check_dependencies_classifies_mismatches() {
+        let dependencies = vec![
+            StubDependency::new("major", Ok("2.0.0")),
+            StubDependency::new("patch", Ok("1.0.3")),
+        ];
+
+        let report = check_dependencies(&RegistryClient::default(), &dependencies, 2).await;
+
+        let mut kinds = report
+            .outdated
+            .into_iter()
+            .map(|mismatch| {
+                assert_eq!(mismatch.satisfiable_version(), Some("1.0.0"));
+                (mismatch.name().to_string(), mismatch.update_kind())
+            })
+            .collect::<Vec<_>>();
+
+        kinds.sort();
+
+        assert_eq!(
+            kinds,
+            vec![
+                ("major".to_string(), Some(UpdateKind::Major)),
+                ("patch".to_string(), Some(UpdateKind::Patch))
+            ]
+        );
+    }
+
+    #[test]
+    fn version_mismatches_expose_their_fields() {
+        let mismatch =
+            VersionMismatch::new("axios", "^0.21.0", "1.6.2").with_update_kind(UpdateKind::Major);
+
+        assert_eq!(mismatch.name(), "axios");
+        assert_eq!(mismatch.constraint(), "^0.21.0");
+        assert_eq!(mismatch.latest_version(), "1.6.2");
+        assert_eq!(mismatch.update_kind(), Some(UpdateKind::Major));
+        assert_eq!(mismatch.destruct(), ("axios", "^0.21.0", "1.6.2"));
+    }
+
+    #[test]
+    fn test_checksums_match() {
+        assert!(checksums_match("sha512-abc==", "sha512-abc== sha1-def="));
+        assert!(checksums_match("sha1-def=", "sha512-abc== sha1-def="));
+        assert!(!checksums_match("sha512-xyz==", "sha512-abc== sha1-def="));
+        assert!(checksums_match("A1B2", "a1b2"));
+        assert!(!checksums_match("a1b2", "a1b3"));
+    }
+
+    #[test]
+    fn test_constraint_floor() {
+        assert_eq!(constraint_floor("^1.2"), Some(Version::new(1, 2, 0)));
+        assert_eq!(
+            constraint_floor(">=0.11.14, <0.12"),
+            Some(Version::new(0, 11, 14))
+        );
+        assert_eq!(constraint_floor("~3.x"), Some(Version::new(3, 0, 0)));
+        assert_eq!(constraint_floor("*"), None);
+    }
+
+    #[tokio::test]
+    async fn check_dependencies_prefers_bulk_lookups() {
+        let dependencies = vec![
+            StubDependency::bulk("bulk-current", "1.0.0"),
+            StubDependency::bulk("bulk-outdated", "2.0.0"),
+            StubDependency::new("single-outdated", Ok("3.0.0")),
+        ];
+
+        let report = check_dependencies(&RegistryClient::default(), &dependencies, 2).await;
+
+        let mut outdated = report
+            .outdated
+            .into_iter()
+            .map(|mismatch| mismatch.name().to_string())
+            .collect::<Vec<_>>();
+
+        outdated.sort();
+
+        assert_eq!(outdated, vec!["bulk-outdated", "single-outdated"]);
+    }
+}
@@ -0,0 +1,248 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Which licenses dependencies may be published under, by SPDX identifier. A
+/// license is permitted if it is not denied, and if it is allowed whenever
+/// there is an allow list.
+///
+/// ```toml
+/// allow = ["MIT", "Apache-2.0", "BSD-3-Clause"]
+/// deny = ["GPL-3.0-only"]
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct LicensePolicy {
+    #[serde(default)]
+    allow: Vec<String>,
+
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+/// A dependency whose license the policy does not permit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseViolation {
+    name: String,
+
+    /// The license expression of the dependency, or `None` if it has no license.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+}
+
+/// A parsed SPDX license expression.
+#[derive(Debug, PartialEq, Eq)]
+enum Expression<'a> {
+    /// A license identifier, without its exception if it has one.
+    License(&'a str),
+    And(Box<Expression<'a>>, Box<Expression<'a>>),
+    Or(Box<Expression<'a>>, Box<Expression<'a>>),
+}
+
+impl LicensePolicy {
+    pub fn new(allow: Vec<String>, deny: Vec<String>) -> Self {
+        LicensePolicy { allow, deny }
+    }
+
+    /// Reads the policy from a TOML file.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Whether the license expression is permitted. Either side of an `OR` may be
+    /// picked, so only one of them has to be permitted, while both sides of an `AND`
+    /// have to be. Dependencies without a license are only permitted when there is
+    /// no allow list.
+    pub fn permits(&self, license: Option<&str>) -> bool {
+        let Some(license) = license else {
+            return self.allow.is_empty();
+        };
+
+        match parse(license) {
+            Some(expression) => self.permits_expression(&expression),
+            None => self.permits_license(license),
+        }
+    }
+
+    fn permits_expression(&self, expression: &Expression) -> bool {
+        match expression {
+            Expression::License(license) => self.permits_license(license),
+            Expression::And(left, right) => {
+                self.permits_expression(left) && self.permits_expression(right)
+            }
+            Expression::Or(left, right) => {
+                self.permits_expression(left) || self.permits_expression(right)
+            }
+        }
+    }
+
+    fn permits_license(&self, license: &str) -> bool {
+        // `GPL-2.0+` stands for version 2.0 or any later version
+        let license = license.trim_end_matches('+');
+        let listed = |list: &[String]| list.iter().any(|id| id.eq_ignore_ascii_case(license));
+
+        !listed(&self.deny) && (self.allow.is_empty() || listed(&self.allow))
+    }
+
+    /// The dependencies whose license is not permitted, out of their names and
+    /// license expressions.
+    pub fn violations<'a>(
+        &self,
+        licenses: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+    ) -> Vec<LicenseViolation> {
+        licenses
+            .into_iter()
+            .filter(|(_, license)| !self.permits(*license))
+            .map(|(name, license)| LicenseViolation {
+                name: name.to_string(),
+                license: license.map(str::to_string),
+            })
+            .collect()
+    }
+}
+
+impl LicenseViolation {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+}
+
+/// Splits an SPDX license expression into its identifiers, operators and
+/// parentheses. The `/` separator of older crates, e.g. `MIT/Apache-2.0`, is
+/// read as `OR`.
+fn tokenize(expression: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+
+    for word in expression.split_whitespace() {
+        let mut start = 0;
+
+        for (at, c) in word.char_indices() {
+            if matches!(c, '(' | ')' | '/') {
+                tokens.push(&word[start..at]);
+                tokens.push(if c == '/' { "OR" } else { &word[at..at + 1] });
+                start = at + 1;
+            }
+        }
+
+        tokens.push(&word[start..]);
+    }
+
+    tokens.retain(|token| !token.is_empty());
+    tokens
+}
+
+/// Parses an SPDX license expression, where `AND` binds tighter than `OR`.
+fn parse(expression: &str) -> Option<Expression<'_>> {
+    let tokens = tokenize(expression);
+    let mut position = 0;
+    let parsed = parse_or(&tokens, &mut position)?;
+
+    (position == tokens.len()).then_some(parsed)
+}
+
+fn parse_or<'a>(tokens: &[&'a str], position: &mut usize) -> Option<Expression<'a>> {
+    let mut expression = parse_and(tokens, position)?;
+
+    while tokens.get(*position) == Some(&"OR") {
+        *position += 1;
+        expression = Expression::Or(Box::new(expression), Box::new(parse_and(tokens, position)?));
+    }
+
+    Some(expression)
+}
+
+fn parse_and<'a>(tokens: &[&'a str], position: &mut usize) -> Option<Expression<'a>> {
+    let mut expression = parse_license(tokens, position)?;
+
+    while tokens.get(*position) == Some(&"AND") {
+        *position += 1;
+        expression = Expression::And(
+            Box::new(expression),
+            Box::new(parse_license(tokens, position)?),
+        );
+    }
+
+    Some(expression)
+}
+
+fn parse_license<'a>(tokens: &[&'a str], position: &mut usize) -> Option<Expression<'a>> {
+    let token = *tokens.get(*position)?;
+    *position += 1;
+
+    match token {
+        "(" => {
+            let expression = parse_or(tokens, position)?;
+
+            if tokens.get(*position) != Some(&")") {
+                return None;
+            }
+
+            *position += 1;
+            Some(expression)
+        }
+        ")" | "OR" | "AND" | "WITH" => None,
+        license => {
+            // Exceptions only widen what a license permits, so they are left out
+            if tokens.get(*position) == Some(&"WITH") {
+                *position += 2;
+            }
+
+            Some(Expression::License(license))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("MIT OR Apache-2.0 AND (BSD-3-Clause OR ISC)"),
+            Some(Expression::Or(
+                Box::new(Expression::License("MIT")),
+                Box::new(Expression::And(
+                    Box::new(Expression::License("Apache-2.0")),
+                    Box::new(Expression::Or(
+                        Box::new(Expression::License("BSD-3-Clause")),
+                        Box::new(Expression::License("ISC")),
+                    )),
+                )),
+            ))
+        );
+        assert_eq!(parse("MIT/Apache-2.0"), parse("MIT OR Apache-2.0"));
+        assert_eq!(
+            parse("GPL-2.0-or-later WITH Classpath-exception-2.0"),
+            Some(Expression::License("GPL-2.0-or-later"))
+        );
+        assert_eq!(parse("(MIT"), None);
+        assert_eq!(parse("MIT AND"), None);
+    }
+
+    #[test]
+    fn test_permits() {
+        let policy = LicensePolicy::new(
+            vec!["MIT".to_string(), "Apache-2.0".to_string()],
+            vec!["GPL-3.0-only".to_string()],
+        );
+
+        assert!(policy.permits(Some("MIT")));
+        assert!(policy.permits(Some("mit")));
+        assert!(policy.permits(Some("MIT OR GPL-3.0-only")));
+        assert!(!policy.permits(Some("MIT AND GPL-3.0-only")));
+        assert!(!policy.permits(Some("ISC")));
+        assert!(!policy.permits(None));
+
+        let deny_only = LicensePolicy::new(vec![], vec!["GPL-3.0-only".to_string()]);
+
+        assert!(deny_only.permits(Some("ISC")));
+        assert!(deny_only.permits(None));
+        assert!(!deny_only.permits(Some("GPL-3.0-only")));
+    }
+}
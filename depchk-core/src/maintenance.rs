@@ -0,0 +1,237 @@
+use std::time::{Duration, SystemTime};
+
+use reqwest::header::USER_AGENT;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::client::RegistryClient;
+use crate::transport::HttpClient;
+use crate::{Dependency, DirectResult};
+
+const GITHUB_REPOS_API: &str = "https://api.github.com/repos";
+
+/// A sign that a dependency is no longer maintained, which is reported whether
+/// the dependency is up to date or not.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaintenanceFlag {
+    /// No version was published for longer than the staleness threshold.
+    Stale,
+    /// The upstream repository of the dependency was archived by its owner.
+    Archived,
+    /// The latest version of the dependency was deprecated on its registry.
+    Deprecated,
+}
+
+/// How a dependency is maintained, as far as its registry and repository tell.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Maintenance {
+    last_published_at: Option<SystemTime>,
+    flags: Vec<MaintenanceFlag>,
+}
+
+/// The settings of the maintenance checks.
+#[derive(Clone, Debug)]
+pub struct MaintenanceCheck {
+    stale_after: Duration,
+    check_archived: bool,
+    github_token: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GitHubRepository {
+    #[serde(default)]
+    archived: bool,
+}
+
+impl MaintenanceFlag {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaintenanceFlag::Stale => "stale",
+            MaintenanceFlag::Archived => "archived",
+            MaintenanceFlag::Deprecated => "deprecated",
+        }
+    }
+}
+
+impl Maintenance {
+    /// When the most recent version of the dependency was published, if the
+    /// registry publishes release dates.
+    pub fn last_published_at(&self) -> Option<SystemTime> {
+        self.last_published_at
+    }
+
+    pub fn flags(&self) -> &[MaintenanceFlag] {
+        &self.flags
+    }
+}
+
+impl MaintenanceCheck {
+    /// Flags the dependencies that did not publish a version for longer than `stale_after`.
+    pub fn new(stale_after: Duration) -> Self {
+        MaintenanceCheck {
+            stale_after,
+            check_archived: false,
+            github_token: None,
+        }
+    }
+
+    /// Also flags the dependencies whose repository is archived, which is only known
+    /// for repositories hosted on GitHub. Without a token, GitHub only answers a
+    /// few dozen lookups an hour.
+    pub fn with_archived_check(mut self, github_token: Option<String>) -> Self {
+        self.check_archived = true;
+        self.github_token = github_token;
+        self
+    }
+
+    /// Looks up how the dependency is maintained. Lookups that fail leave out the
+    /// flags they would have raised, rather than failing the whole check.
+    pub async fn assess<T: Dependency + ?Sized>(
+        &self,
+        client: &RegistryClient,
+        dependency: &T,
+    ) -> Maintenance {
+        let release_dates = client.release_dates(dependency).await.unwrap_or_default();
+
+        // npm lists when the package was created and last modified among its
+        // release dates, neither of which is a publish
+        let last_published_at = release_dates
+            .into_iter()
+            .filter(|(version, _)| Version::parse(version).is_ok())
+            .map(|(_, released_at)| released_at)
+            .max();
+
+        let mut flags = Vec::new();
+
+        let is_stale = last_published_at
+            .and_then(|published_at| SystemTime::now().duration_since(published_at).ok())
+            .is_some_and(|age| age > self.stale_after);
+
+        if is_stale {
+            flags.push(MaintenanceFlag::Stale);
+        }
+
+        if self.check_archived && self.is_archived(client, dependency).await {
+            flags.push(MaintenanceFlag::Archived);
+        }
+
+        if let Ok(Some(_)) = client.deprecation(dependency).await {
+            flags.push(MaintenanceFlag::Deprecated);
+        }
+
+        Maintenance {
+            last_published_at,
+            flags,
+        }
+    }
+
+    async fn is_archived<T: Dependency + ?Sized>(
+        &self,
+        client: &RegistryClient,
+        dependency: &T,
+    ) -> bool {
+        let repository = client.repository(dependency).await.ok().flatten();
+
+        let Some((owner, name)) = repository.as_deref().and_then(github_repository) else {
+            return false;
+        };
+
+        let lookup = async {
+            let token = self.github_token.as_deref();
+            let archived = fetch_archived(client.transport(), &owner, &name, token).await?;
+
+            Ok(Some(archived))
+        };
+
+        // Counted as a request to GitHub, with the name of the repository
+        client
+            .lookup_in(GITHUB_REPOS_API, &format!("{}/{}", owner, name), lookup)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(false)
+    }
+}
+
+async fn fetch_archived(
+    client: &HttpClient,
+    owner: &str,
+    name: &str,
+    token: Option<&str>,
+) -> DirectResult<bool> {
+    let request = client
+        .get(format!("{}/{}/{}", GITHUB_REPOS_API, owner, name))
+        .header(USER_AGENT, "depchk");
+
+    let request = match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    };
+
+    let repository: GitHubRepository = client
+        .send(request)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(repository.archived)
+}
+
+/// The owner and name of a GitHub repository, out of any of the forms package
+/// manifests give repository URLs in, e.g. `git+https://github.com/owner/name.git`,
+/// `git@github.com:owner/name.git` or the `github:owner/name` shorthand of npm.
+pub(crate) fn github_repository(url: &str) -> Option<(String, String)> {
+    let path = ["github:", "git@github.com:"]
+        .iter()
+        .find_map(|prefix| url.strip_prefix(prefix))
+        .or_else(|| {
+            let (_, rest) = url.split_once("://")?;
+            let (host, path) = rest.split_once('/')?;
+            let host = host.rsplit('@').next()?;
+
+            matches!(host, "github.com" | "www.github.com").then_some(path)
+        })?;
+
+    let mut segments = path.split('/');
+    let owner = segments.next().filter(|owner| !owner.is_empty())?;
+    let name = segments.next()?.trim_end_matches(".git");
+
+    (!name.is_empty()).then(|| (owner.to_string(), name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_repository() {
+        let expected = Some(("axios".to_string(), "axios".to_string()));
+
+        assert_eq!(
+            github_repository("git+https://github.com/axios/axios.git"),
+            expected
+        );
+        assert_eq!(
+            github_repository("https://github.com/axios/axios"),
+            expected
+        );
+        assert_eq!(
+            github_repository("https://github.com/axios/axios/tree/v1.x"),
+            expected
+        );
+        assert_eq!(
+            github_repository("git+ssh://git@github.com/axios/axios.git"),
+            expected
+        );
+        assert_eq!(
+            github_repository("git@github.com:axios/axios.git"),
+            expected
+        );
+        assert_eq!(github_repository("github:axios/axios"), expected);
+
+        assert_eq!(github_repository("https://gitlab.com/axios/axios"), None);
+        assert_eq!(github_repository("https://github.com/axios"), None);
+    }
+}
@@ -0,0 +1,1951 @@
+use crate::client::Registry;
+use crate::encoding::encode_base64;
+use crate::error::RegistryResponse;
+use crate::registry::{PackageMetadata, RegistryBackend};
+use crate::time::parse_rfc3339;
+use crate::transport::HttpClient;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    fix::{json_string_span, ConstraintUpdate},
+    transitive::{LockfileGraph, TransitiveDependencies},
+    workspace::WorkspacePackage,
+    LockedChecksum, LockedVersions, OptionalResult,
+};
+use crate::{
+    DeclaredDependency, DepchkError, Dependency, DependencyFileParser, DirectResult, ParseWarning,
+    ProjectDependencies, SkipReason,
+};
+
+use std::collections::{HashMap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::process::Command;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+#[cfg(not(target_arch = "wasm32"))]
+use glob::{glob, Pattern};
+use node_semver::{Range, Version};
+use reqwest::header::ACCEPT;
+use reqwest::RequestBuilder;
+#[cfg(not(target_arch = "wasm32"))]
+use serde::de::IgnoredAny;
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A struct representing an npm package dependency from a
+/// package.json file.
+pub struct NpmDependency {
+    version: Range,
+    raw_version: String,
+    name: String,
+    registry: Arc<dyn RegistryBackend>,
+}
+
+/// The npm registry, or another registry that implements its API, such as a mirror
+/// or a private registry.
+pub struct NpmRegistry {
+    base_url: String,
+    url: String,
+    token: Option<String>,
+    tag: Option<String>,
+}
+
+pub type PackageJson = ProjectDependencies<NpmDependency>;
+
+pub(crate) const NPM_REGISTRY: &str = "https://registry.npmjs.org";
+
+/// The media type of the abbreviated package metadata document, which only
+/// carries the fields needed to install a package and is therefore a fraction
+/// of the size of the full document for popular packages.
+const ABBREVIATED_METADATA: &str =
+    "application/vnd.npm.install-v1+json; q=1.0, application/json; q=0.8, */*";
+
+/// A struct to encapsulate the parts of the package metadata
+/// document (the "packument") provided by the NPM api
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Packument {
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: HashMap<String, String>,
+
+    #[serde(default)]
+    versions: HashMap<String, PackumentVersion>,
+}
+
+/// The parts of the full package metadata document that are not part of the
+/// abbreviated one: the release dates and links of the package, and the licenses
+/// and attestations of its versions.
+#[derive(Serialize, Deserialize, Debug)]
+struct PackumentMetadata {
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: HashMap<String, String>,
+
+    #[serde(default)]
+    time: HashMap<String, String>,
+
+    #[serde(flatten)]
+    links: PackumentLinks,
+
+    #[serde(default)]
+    versions: HashMap<String, VersionMetadata>,
+}
+
+/// The links of a package.
+#[derive(Serialize, Deserialize, Debug)]
+struct PackumentLinks {
+    homepage: Option<String>,
+    repository: Option<Repository>,
+}
+
+/// A repository is given either as its URL or as an object with the URL in it.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+enum Repository {
+    Url(String),
+    Detailed { url: String },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct VersionMetadata {
+    license: Option<License>,
+
+    #[serde(default)]
+    dist: Dist,
+
+    #[serde(default)]
+    deprecated: Option<String>,
+}
+
+/// A license is given as an SPDX expression, or by older packages as an object
+/// with the license in its `type`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+enum License {
+    Expression(String),
+    Detailed {
+        #[serde(rename = "type")]
+        kind: String,
+    },
+}
+
+/// The distribution metadata of the published versions of a package, which are
+/// also only part of the full package metadata.
+#[derive(Serialize, Deserialize, Debug)]
+struct PackumentDists {
+    #[serde(default)]
+    versions: HashMap<String, VersionDist>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct VersionDist {
+    #[serde(default)]
+    dist: Dist,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Dist {
+    attestations: Option<Attestations>,
+
+    /// The Subresource Integrity checksum of the tarball, usually a SHA-512 one.
+    integrity: Option<String>,
+
+    /// The SHA-1 checksum of the tarball, in hexadecimal, which older lockfiles record.
+    shasum: Option<String>,
+}
+
+/// The attestations the registry keeps for a version, which include a provenance
+/// statement for versions published from a supported CI provider.
+#[derive(Serialize, Deserialize, Debug)]
+struct Attestations {
+    provenance: Option<serde_json::Value>,
+}
+
+/// The metadata of a single published version of a package
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PackumentVersion {
+    #[serde(default)]
+    deprecated: Option<String>,
+}
+
+/// A struct used to deserialize a package.json
+/// file into a format that can be more easily
+/// processed into the appropriate dependency.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageJsonRaw {
+    #[serde(default)]
+    name: Option<String>,
+
+    #[serde(default)]
+    dependencies: DeclaredEntries,
+
+    #[serde(default)]
+    dev_dependencies: DeclaredEntries,
+
+    #[serde(default)]
+    workspaces: Option<Workspaces>,
+}
+
+/// The entries of a dependency section of a package.json file, in the order they are
+/// declared, including the ones whose name is declared more than once.
+#[derive(Debug, Default)]
+struct DeclaredEntries(Vec<(String, String)>);
+
+impl Serialize for DeclaredEntries {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(name, version)| (name, version)))
+    }
+}
+
+impl<'de> Deserialize<'de> for DeclaredEntries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = DeclaredEntries;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of package names to versions")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+
+                Ok(DeclaredEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+/// A package installed by a package-lock.json file, either a direct or a
+/// transitive dependency.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockedPackage {
+    name: String,
+    version: String,
+    dev: bool,
+
+    /// The Subresource Integrity checksum of the installed tarball, if the lockfile records it.
+    integrity: Option<String>,
+
+    /// The URL the tarball was installed from, if the lockfile records it.
+    resolved: Option<String>,
+}
+
+/// The parts of a package-lock.json file that list the installed packages. Lockfile
+/// versions 2 and 3 list them under `packages` by their path in `node_modules`,
+/// while version 1 nests them under `dependencies`.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Deserialize, Debug)]
+struct PackageLockRaw {
+    #[serde(default)]
+    packages: HashMap<String, LockedEntry>,
+
+    #[serde(default)]
+    dependencies: HashMap<String, LockedEntry>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Deserialize, Debug, Default)]
+struct LockedEntry {
+    version: Option<String>,
+
+    /// The name of the package, when it is installed under an alias.
+    name: Option<String>,
+
+    #[serde(default)]
+    dev: bool,
+
+    /// Set for workspace packages, which are linked rather than installed.
+    #[serde(default)]
+    link: bool,
+
+    integrity: Option<String>,
+
+    resolved: Option<String>,
+
+    #[serde(default)]
+    dependencies: HashMap<String, LockedDependency>,
+
+    #[serde(default, rename = "optionalDependencies")]
+    optional_dependencies: HashMap<String, String>,
+
+    #[serde(default, rename = "peerDependencies")]
+    peer_dependencies: HashMap<String, String>,
+
+    /// The packages this one depends on, in version 1 lockfiles.
+    #[serde(default)]
+    requires: HashMap<String, String>,
+}
+
+/// A dependency of a package-lock.json entry, which is the version range the package
+/// depends on in lockfile versions 2 and 3, and a package installed in the
+/// `node_modules` directory of the package in version 1.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum LockedDependency {
+    Nested(Box<LockedEntry>),
+    Range(IgnoredAny),
+}
+
+/// The `workspaces` key of a package.json file. npm accepts a plain
+/// list of globs, while yarn also allows nesting them under `packages`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Workspaces {
+    Globs(Vec<String>),
+    Config { packages: Vec<String> },
+}
+
+impl NpmDependency {
+    /// Creates a new npm-compatible dependency from the given
+    /// name and version string.
+    ///
+    /// ```
+    /// # use depchk_core::npm::NpmDependency;
+    /// # use depchk_core::Dependency;
+    ///
+    /// let dependency = NpmDependency::new("axios", "^0.12");
+    ///
+    /// assert!(dependency.is_satisfied_by("0.12.0"));
+    /// ```
+    pub fn new(name: &str, version: &str) -> Self {
+        NpmDependency::try_new(name, version).unwrap()
+    }
+
+    /// Attempts to create a new npm-compatible dependency from the
+    /// given name and version string. However, if the version
+    /// string is not parsable, returns None.
+    ///
+    /// ```
+    /// # use depchk_core::npm::NpmDependency;
+    ///
+    /// let dependency = NpmDependency::try_new("axios", "^0.12");
+    /// let invalid = NpmDependency::try_new("axios", ">=0.10,!=0.11,<0.13");
+    ///
+    /// assert!(dependency.is_some());
+    /// assert!(invalid.is_none());
+    /// ```
+    pub fn try_new(name: &str, version: &str) -> Option<Self> {
+        let parsed: Range = version.parse().ok()?;
+
+        Some(NpmDependency {
+            name: name.to_string(),
+            raw_version: version.to_string(),
+            version: parsed,
+            registry: Arc::new(NpmRegistry::default()),
+        })
+    }
+
+    /// Resolves this dependency from the given registry instead of the npm registry.
+    pub fn with_registry_backend(mut self, registry: Arc<dyn RegistryBackend>) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Creates a vector of `Dependency` instances from the entries of a map.
+    /// This is used to convert the `package.json` format (in which the `dependencies` and
+    /// `devDependencies` keys are just a simple dictionary instead of an array).
+    ///
+    /// Entries that cannot be checked against the registry, such as `file:` or git
+    /// dependencies and invalid ranges, are skipped with a warning, as are the earlier
+    /// declarations of a package that is declared more than once.
+    ///
+    /// ```
+    /// # use depchk_core::npm::NpmDependency;
+    /// # use std::collections::HashMap;
+    ///
+    /// let map = HashMap::from([
+    ///     ("axios".to_string(), "0.12".to_string()),
+    ///     ("ui".to_string(), "file:../ui".to_string()),
+    /// ]);
+    ///
+    /// let (dependencies, warnings) = NpmDependency::from_map(map);
+    ///
+    /// assert_eq!(dependencies.len(), 1);
+    /// assert_eq!(warnings.len(), 1);
+    /// ```
+    pub fn from_map(
+        map: impl IntoIterator<Item = (String, String)>,
+    ) -> (Vec<Self>, Vec<ParseWarning>) {
+        let mut dependencies: Vec<Self> = Vec::new();
+        let mut warnings = Vec::new();
+
+        for (name, version) in map {
+            if let Some(index) = dependencies.iter().position(|earlier| earlier.name == name) {
+                let earlier = dependencies.remove(index);
+                warnings.push(ParseWarning::new(
+                    &name,
+                    &earlier.raw_version,
+                    SkipReason::Duplicate,
+                ));
+            }
+
+            match NpmDependency::try_new(&name, &version) {
+                Some(dependency) => dependencies.push(dependency),
+                None => {
+                    let reason = match is_specifier(&version) {
+                        true => SkipReason::UnsupportedSpecifier,
+                        false => SkipReason::InvalidConstraint,
+                    };
+
+                    warnings.push(ParseWarning::new(&name, &version, reason));
+                }
+            }
+        }
+
+        (dependencies, warnings)
+    }
+}
+
+impl TryFrom<(&str, &str)> for NpmDependency {
+    type Error = DepchkError;
+
+    /// Creates an npm dependency from its name and version range, failing with a
+    /// [`DepchkError::Constraint`] error when the range cannot be parsed.
+    fn try_from((name, version): (&str, &str)) -> DirectResult<Self> {
+        NpmDependency::try_new(name, version).ok_or_else(|| DepchkError::Constraint {
+            constraint: version.to_string(),
+            message: format!("{} does not have a valid version range", name),
+        })
+    }
+}
+
+/// npm dependencies are serialized as their name and version range, along with the URL
+/// of their registry when it is not the npm registry. Tokens are never serialized, and
+/// dependencies resolved through another [`RegistryBackend`] are deserialized as
+/// resolving from an npm registry at its URL.
+impl Serialize for NpmDependency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let registry = self.registry.url();
+
+        DeclaredDependency {
+            name: self.name.clone(),
+            constraint: self.raw_version.clone(),
+            registry: (registry != NPM_REGISTRY).then(|| registry.to_string()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NpmDependency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let declared = DeclaredDependency::deserialize(deserializer)?;
+
+        let mut dependency =
+            NpmDependency::try_from((declared.name.as_str(), declared.constraint.as_str()))
+                .map_err(serde::de::Error::custom)?;
+
+        if let Some(url) = &declared.registry {
+            let registry = match url.split_once('#') {
+                Some((url, tag)) => Registry::new(url).with_tag(tag),
+                None => Registry::new(url),
+            };
+
+            dependency.use_registry(&registry);
+        }
+
+        Ok(dependency)
+    }
+}
+
+impl NpmRegistry {
+    /// A registry at the given URL, which implements the API of the npm registry.
+    pub fn new(url: &str) -> Self {
+        let url = url.trim_end_matches('/');
+
+        NpmRegistry {
+            base_url: url.to_string(),
+            url: url.to_string(),
+            token: None,
+            tag: None,
+        }
+    }
+
+    /// Authenticates the requests to the registry with the token.
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Resolves the latest version of packages from the distribution tag instead of
+    /// `latest`. The tag then becomes the fragment of the URL of the registry, which
+    /// tells it apart from the untagged registry since it resolves other versions.
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.url = format!("{}#{}", self.base_url, tag);
+        self.tag = Some(tag.to_string());
+        self
+    }
+
+    /// The URL of the metadata document of the package.
+    fn package_url(&self, name: &str) -> String {
+        format!("{}/{}", self.base_url, name.replace('/', "%2F"))
+    }
+
+    /// A request for the metadata document of the package, authenticated with the
+    /// token of the registry if it has one.
+    fn request(&self, client: &HttpClient, name: &str) -> RequestBuilder {
+        let request = client.get(self.package_url(name));
+
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Fetches the abbreviated metadata document of the package.
+    pub async fn fetch_packument(
+        &self,
+        client: &HttpClient,
+        name: &str,
+    ) -> DirectResult<Packument> {
+        let res = client
+            .send(
+                self.request(client, name)
+                    .header(ACCEPT, ABBREVIATED_METADATA),
+            )
+            .await?
+            .error_for_registry_status()
+            .await?;
+
+        Ok(res.json().await?)
+    }
+}
+
+impl Default for NpmRegistry {
+    fn default() -> Self {
+        NpmRegistry::new(NPM_REGISTRY)
+    }
+}
+
+impl From<&Registry> for NpmRegistry {
+    fn from(registry: &Registry) -> Self {
+        let mut npm = NpmRegistry::new(registry.url());
+
+        if let Some(token) = registry.token() {
+            npm = npm.with_token(token);
+        }
+
+        match registry.tag() {
+            Some(tag) => npm.with_tag(tag),
+            None => npm,
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl RegistryBackend for NpmRegistry {
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    async fn latest_version(&self, client: &HttpClient, name: &str) -> DirectResult<String> {
+        let packument = self.fetch_packument(client, name).await?;
+        let tag = self.tag.as_deref().unwrap_or("latest");
+
+        let latest = packument
+            .tagged(tag)
+            .ok_or_else(|| format!("{} has no version tagged as {}", name, tag))?;
+
+        Ok(latest.to_string())
+    }
+
+    async fn versions(&self, client: &HttpClient, name: &str) -> DirectResult<Vec<String>> {
+        let packument = self.fetch_packument(client, name).await?;
+
+        Ok(packument
+            .versions()
+            .keys()
+            .filter(|version| version.parse::<Version>().is_ok())
+            .cloned()
+            .collect())
+    }
+
+    /// Fetches the full metadata document of the package, since the abbreviated one
+    /// leaves out the release dates, links and licenses.
+    async fn metadata(&self, client: &HttpClient, name: &str) -> DirectResult<PackageMetadata> {
+        let document: PackumentMetadata = client
+            .send(
+                self.request(client, name)
+                    .header(ACCEPT, "application/json"),
+            )
+            .await?
+            .error_for_registry_status()
+            .await?
+            .json()
+            .await?;
+
+        Ok(document.into_metadata(self.tag.as_deref().unwrap_or("latest")))
+    }
+}
+
+impl PackumentMetadata {
+    /// The metadata of the package, whose deprecation is the one of the version with
+    /// the distribution tag the registry resolves latest versions from.
+    fn into_metadata(self, tag: &str) -> PackageMetadata {
+        let mut metadata = PackageMetadata::default();
+
+        if let Some(homepage) = &self.links.homepage {
+            metadata = metadata.with_homepage(homepage);
+        }
+
+        if let Some(Repository::Url(url) | Repository::Detailed { url }) = &self.links.repository {
+            metadata = metadata.with_repository(url);
+        }
+
+        let deprecation = self
+            .dist_tags
+            .get(tag)
+            .and_then(|latest| self.versions.get(latest))
+            .and_then(|version| version.deprecated.as_deref());
+
+        if let Some(deprecation) = deprecation {
+            metadata = metadata.with_deprecation(deprecation);
+        }
+
+        for (tag, version) in &self.dist_tags {
+            metadata = metadata.with_dist_tag(tag, version);
+        }
+
+        for (version, released) in &self.time {
+            if let Some(released_at) = parse_rfc3339(released) {
+                metadata = metadata.with_release_date(version, released_at);
+            }
+        }
+
+        let mut attested = HashSet::new();
+
+        for (number, version) in &self.versions {
+            if let Some(License::Expression(license) | License::Detailed { kind: license }) =
+                &version.license
+            {
+                metadata = metadata.with_license(number, license);
+            }
+
+            let attestations = version.dist.attestations.as_ref();
+
+            if attestations.is_some_and(|attestations| attestations.provenance.is_some()) {
+                attested.insert(number.clone());
+            }
+        }
+
+        metadata.with_attested_versions(attested)
+    }
+}
+
+impl Packument {
+    /// The version tagged as `latest`, which is what npm installs by default.
+    pub fn latest(&self) -> Option<&str> {
+        self.tagged("latest")
+    }
+
+    /// The version with the distribution tag, such as `next`.
+    pub fn tagged(&self, tag: &str) -> Option<&str> {
+        self.dist_tags.get(tag).map(String::as_str)
+    }
+
+    pub fn dist_tags(&self) -> &HashMap<String, String> {
+        &self.dist_tags
+    }
+
+    pub fn versions(&self) -> &HashMap<String, PackumentVersion> {
+        &self.versions
+    }
+}
+
+impl PackumentDists {
+    /// The checksums of the tarballs of every version, as Subresource Integrity
+    /// strings, which list the SHA-1 checksum as well for older lockfiles.
+    fn checksums(self) -> HashMap<String, String> {
+        self.versions
+            .into_iter()
+            .map(|(version, VersionDist { dist })| {
+                let sha1 = dist
+                    .shasum
+                    .as_deref()
+                    .and_then(hex_to_base64)
+                    .map(|digest| format!("sha1-{}", digest));
+
+                let checksums: Vec<_> = dist.integrity.into_iter().chain(sha1).collect();
+
+                (version, checksums.join(" "))
+            })
+            .filter(|(_, checksum)| !checksum.is_empty())
+            .collect()
+    }
+}
+
+impl PackumentVersion {
+    /// The deprecation message of this version, if it was deprecated.
+    pub fn deprecated(&self) -> Option<&str> {
+        self.deprecated.as_deref()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Dependency for NpmDependency {
+    async fn fetch_latest_version(&self, client: &HttpClient) -> DirectResult<String> {
+        self.registry.latest_version(client, &self.name).await
+    }
+
+    /// Looks the dependencies up with the bulk lookup of their registry, if they all
+    /// resolve from the same one.
+    async fn fetch_latest_versions(
+        client: &HttpClient,
+        dependencies: &[&Self],
+    ) -> Option<DirectResult<HashMap<String, String>>> {
+        let registry = &dependencies.first()?.registry;
+
+        if dependencies
+            .iter()
+            .any(|dependency| dependency.registry.url() != registry.url())
+        {
+            return None;
+        }
+
+        let names: Vec<&str> = dependencies
+            .iter()
+            .map(|dependency| dependency.name.as_str())
+            .collect();
+
+        registry.latest_versions(client, &names).await
+    }
+
+    async fn fetch_metadata(&self, client: &HttpClient) -> DirectResult<Option<PackageMetadata>> {
+        Ok(Some(self.registry.metadata(client, &self.name).await?))
+    }
+
+    async fn fetch_release_dates(
+        &self,
+        client: &HttpClient,
+    ) -> DirectResult<HashMap<String, SystemTime>> {
+        let metadata = self.registry.metadata(client, &self.name).await?;
+
+        Ok(metadata.release_dates().clone())
+    }
+
+    async fn fetch_homepage(&self, client: &HttpClient) -> DirectResult<Option<String>> {
+        let metadata = self.registry.metadata(client, &self.name).await?;
+
+        Ok(metadata
+            .homepage()
+            .or(metadata.repository())
+            .map(str::to_string))
+    }
+
+    async fn fetch_repository(&self, client: &HttpClient) -> DirectResult<Option<String>> {
+        let metadata = self.registry.metadata(client, &self.name).await?;
+
+        Ok(metadata.repository().map(str::to_string))
+    }
+
+    async fn fetch_deprecation(&self, client: &HttpClient) -> DirectResult<Option<String>> {
+        let metadata = self.registry.metadata(client, &self.name).await?;
+
+        Ok(metadata.deprecation().map(str::to_string))
+    }
+
+    async fn fetch_license(
+        &self,
+        client: &HttpClient,
+        version: &str,
+    ) -> DirectResult<Option<String>> {
+        let metadata = self.registry.metadata(client, &self.name).await?;
+
+        Ok(metadata.license(version).map(str::to_string))
+    }
+
+    async fn fetch_attested_versions(
+        &self,
+        client: &HttpClient,
+    ) -> DirectResult<Option<HashSet<String>>> {
+        let metadata = self.registry.metadata(client, &self.name).await?;
+
+        Ok(metadata.attested_versions().cloned())
+    }
+
+    async fn fetch_versions(&self, client: &HttpClient) -> DirectResult<Vec<String>> {
+        self.registry.versions(client, &self.name).await
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_constraint(&self) -> &str {
+        &self.raw_version
+    }
+
+    fn get_registry(&self) -> &str {
+        self.registry.url()
+    }
+
+    fn use_registry(&mut self, registry: &Registry) {
+        self.registry = Arc::new(NpmRegistry::from(registry));
+    }
+
+    fn get_ecosystem(&self) -> Option<&str> {
+        Some("npm")
+    }
+
+    fn try_is_satisfied_by(&self, version: &str) -> DirectResult<bool> {
+        let parsed = Version::parse(version).map_err(|err| DepchkError::InvalidVersion {
+            version: version.to_string(),
+            message: err.to_string(),
+        })?;
+
+        Ok(self.version.satisfies(&parsed))
+    }
+
+    fn is_compatible_with(&self, other: &Self) -> bool {
+        self.version.allows_any(&other.version)
+    }
+}
+
+impl LockedPackage {
+    pub fn new(name: &str, version: &str, dev: bool) -> Self {
+        LockedPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            dev,
+            integrity: None,
+            resolved: None,
+        }
+    }
+
+    pub fn with_integrity(mut self, integrity: &str) -> Self {
+        self.integrity = Some(integrity.to_string());
+        self
+    }
+
+    pub fn with_resolved(mut self, resolved: &str) -> Self {
+        self.resolved = Some(resolved.to_string());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Whether the package is only installed as a dev dependency.
+    pub fn is_dev(&self) -> bool {
+        self.dev
+    }
+
+    pub fn integrity(&self) -> Option<&str> {
+        self.integrity.as_deref()
+    }
+
+    pub fn resolved(&self) -> Option<&str> {
+        self.resolved.as_deref()
+    }
+
+    /// The URL of the registry the package was installed from, out of the URL of its
+    /// tarball, e.g. `https://npm.example.com` out of
+    /// `https://npm.example.com/@acme/ui/-/ui-1.0.0.tgz`.
+    pub fn registry(&self) -> Option<&str> {
+        let (registry, _) = self
+            .resolved
+            .as_deref()?
+            .split_once(&format!("/{}/-/", self.name))?;
+
+        Some(registry)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LockedEntry {
+    fn locked(&self, name: &str, version: &str) -> LockedPackage {
+        let mut package = LockedPackage::new(name, version, self.dev);
+
+        if let Some(integrity) = &self.integrity {
+            package = package.with_integrity(integrity);
+        }
+
+        match &self.resolved {
+            Some(resolved) => package.with_resolved(resolved),
+            None => package,
+        }
+    }
+
+    /// The packages installed in the `node_modules` directory of this one, which only
+    /// version 1 lockfiles list.
+    fn nested(&self) -> impl Iterator<Item = (&String, &LockedEntry)> {
+        self.dependencies
+            .iter()
+            .filter_map(|(name, dependency)| match dependency {
+                LockedDependency::Nested(entry) => Some((name, entry.as_ref())),
+                LockedDependency::Range(_) => None,
+            })
+    }
+
+    /// The names of the packages this one depends on.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn required(&self) -> impl Iterator<Item = &String> {
+        let ranges = self
+            .dependencies
+            .iter()
+            .filter(|(_, dependency)| matches!(dependency, LockedDependency::Range(_)))
+            .map(|(name, _)| name);
+
+        ranges
+            .chain(self.optional_dependencies.keys())
+            .chain(self.peer_dependencies.keys())
+            .chain(self.requires.keys())
+    }
+}
+
+/// Builds the graph of the packages a package-lock.json file installs, and returns it
+/// along with the indices of the packages that are declared in `declared`. Packages
+/// are resolved the way Node.js resolves them, from the `node_modules` directory of
+/// the package that requires them up to the top-level one.
+#[cfg(not(target_arch = "wasm32"))]
+fn package_lock_graph(
+    raw: &PackageLockRaw,
+    declared: &HashSet<&str>,
+) -> (LockfileGraph, Vec<usize>) {
+    let mut installed: Vec<(String, &str, &str, &LockedEntry)> = Vec::new();
+
+    for (path, entry) in &raw.packages {
+        let Some(installed_as) = path
+            .rsplit("node_modules/")
+            .next()
+            .filter(|_| !path.is_empty())
+        else {
+            continue;
+        };
+
+        if let (Some(version), false) = (&entry.version, entry.link) {
+            let name = entry.name.as_deref().unwrap_or(installed_as);
+
+            installed.push((path.clone(), name, version, entry));
+        }
+    }
+
+    if raw.packages.is_empty() {
+        let mut pending: Vec<_> = raw
+            .dependencies
+            .iter()
+            .map(|(name, entry)| (format!("node_modules/{}", name), name, entry))
+            .collect();
+
+        while let Some((path, name, entry)) = pending.pop() {
+            pending.extend(entry.nested().map(|(nested, entry)| {
+                (format!("{}/node_modules/{}", path, nested), nested, entry)
+            }));
+
+            if let Some(version) = &entry.version {
+                installed.push((path, name, version, entry));
+            }
+        }
+    }
+
+    let mut graph = LockfileGraph::new();
+    let mut indices = HashMap::new();
+
+    for (path, name, version, _) in &installed {
+        indices.insert(path.as_str(), graph.add_package(name, version));
+    }
+
+    let resolve = |from: &str, name: &str| {
+        let mut base = from;
+
+        loop {
+            let candidate = match base.is_empty() {
+                true => format!("node_modules/{}", name),
+                false => format!("{}/node_modules/{}", base, name),
+            };
+
+            if let Some(index) = indices.get(candidate.as_str()) {
+                return Some(*index);
+            }
+
+            if base.is_empty() {
+                return None;
+            }
+
+            base = base.rfind("/node_modules/").map_or("", |at| &base[..at]);
+        }
+    };
+
+    for (path, _, _, entry) in &installed {
+        for required in entry.required() {
+            if let Some(dependency) = resolve(path, required) {
+                graph.add_dependency(indices[path.as_str()], dependency);
+            }
+        }
+    }
+
+    let direct = declared
+        .iter()
+        .filter_map(|name| resolve("", name))
+        .collect();
+
+    (graph, direct)
+}
+
+/// The package-lock.json file that sits next to the package.json file.
+pub fn package_lock_path(manifest: &str) -> PathBuf {
+    Path::new(manifest).with_file_name("package-lock.json")
+}
+
+/// Reads every package that a package-lock.json file installs, sorted by name
+/// and version. Linked workspace packages and the root package are left out.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_package_lock(path: &Path) -> DirectResult<Vec<LockedPackage>> {
+    let raw: PackageLockRaw = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let mut packages = Vec::new();
+
+    for (path, entry) in &raw.packages {
+        // The root package is listed under the empty path
+        let Some(installed_as) = path
+            .rsplit("node_modules/")
+            .next()
+            .filter(|_| !path.is_empty())
+        else {
+            continue;
+        };
+
+        if let (Some(version), false) = (&entry.version, entry.link) {
+            let name = entry.name.as_deref().unwrap_or(installed_as);
+
+            packages.push(entry.locked(name, version));
+        }
+    }
+
+    // Version 2 lockfiles list the packages under both keys for backwards
+    // compatibility, of which `packages` is the more accurate one
+    if raw.packages.is_empty() {
+        let mut pending: Vec<_> = raw.dependencies.iter().collect();
+
+        while let Some((name, entry)) = pending.pop() {
+            // Aliased packages are locked as `npm:<name>@<version>`
+            let locked = entry.version.as_deref().map(|version| {
+                version
+                    .strip_prefix("npm:")
+                    .and_then(|aliased| aliased.rsplit_once('@'))
+                    .unwrap_or((name, version))
+            });
+
+            if let Some((name, version)) = locked {
+                packages.push(entry.locked(name, version));
+            }
+
+            pending.extend(entry.nested());
+        }
+    }
+
+    packages.sort_unstable_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    packages.dedup();
+
+    Ok(packages)
+}
+
+/// The yarn.lock file that sits next to the package.json file.
+pub fn yarn_lock_path(manifest: &str) -> PathBuf {
+    Path::new(manifest).with_file_name("yarn.lock")
+}
+
+/// Reads every package that a yarn.lock file installs, sorted by name and version.
+/// Only the lockfiles of yarn 1 are supported, since later versions record the
+/// checksums of their own archives rather than of the registry's tarballs. yarn does
+/// not record which packages are dev dependencies, so none of them are.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_yarn_lock(path: &Path) -> DirectResult<Vec<LockedPackage>> {
+    let lockfile = fs::read_to_string(path)?;
+
+    if lockfile.contains("\n__metadata:") {
+        return Err("only yarn.lock files of yarn 1 are supported".into());
+    }
+
+    let mut packages = Vec::new();
+    let mut entry: Option<(String, LockedEntry)> = None;
+
+    // Every entry starts with the specifiers it resolves, e.g. `"lodash@^4.17.0",
+    // lodash@^4.17.21:`, followed by its fields indented by two spaces
+    for line in lockfile.lines().chain([""]) {
+        if let Some(field) = line
+            .strip_prefix("  ")
+            .filter(|field| !field.starts_with(' '))
+        {
+            let Some((_, locked)) = &mut entry else {
+                continue;
+            };
+
+            let value = |value: &str| Some(value.trim_matches('"').to_string());
+
+            match field.split_once(' ') {
+                Some(("version", field)) => locked.version = value(field),
+                Some(("integrity", field)) => locked.integrity = value(field),
+                Some(("resolved", field)) => locked.resolved = value(field),
+                _ => {}
+            }
+
+            continue;
+        }
+
+        if line.starts_with(' ') {
+            continue;
+        }
+
+        if let Some((name, locked)) = entry.take() {
+            if let Some(version) = &locked.version {
+                packages.push(locked.locked(&name, version));
+            }
+        }
+
+        if let Some(specifiers) = line.strip_suffix(':').filter(|_| !line.starts_with('#')) {
+            let specifier = specifiers.split(',').next().unwrap_or_default();
+
+            entry = yarn_package_name(specifier.trim().trim_matches('"'))
+                .map(|name| (name.to_string(), LockedEntry::default()));
+        }
+    }
+
+    packages.sort_unstable_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    packages.dedup();
+
+    Ok(packages)
+}
+
+/// The name of the package a yarn.lock specifier resolves, e.g. `@babel/core` out
+/// of `@babel/core@^7.0.0`, or `lodash` out of the alias `legacy@npm:lodash@^3.0.0`.
+#[cfg(not(target_arch = "wasm32"))]
+fn yarn_package_name(specifier: &str) -> Option<&str> {
+    let (name, range) = specifier
+        .rsplit_once('@')
+        .filter(|(name, _)| !name.is_empty())?;
+
+    match name.split_once("@npm:") {
+        Some((_, aliased)) => Some(aliased),
+        None if range.starts_with("npm:") => range.strip_prefix("npm:"),
+        None => Some(name),
+    }
+}
+
+/// Encodes a hexadecimal digest in base64, as Subresource Integrity strings list them.
+fn hex_to_base64(hex: &str) -> Option<String> {
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|at| u8::from_str_radix(hex.get(at..at + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    Some(encode_base64(&bytes))
+}
+
+impl Workspaces {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn into_globs(self) -> Vec<String> {
+        match self {
+            Workspaces::Globs(globs) => globs,
+            Workspaces::Config { packages } => packages,
+        }
+    }
+}
+
+impl From<PackageJsonRaw> for PackageJson {
+    fn from(value: PackageJsonRaw) -> Self {
+        let (dependencies, mut warnings) = NpmDependency::from_map(value.dependencies.0);
+        let (dev_dependencies, dev_warnings) = NpmDependency::from_map(value.dev_dependencies.0);
+
+        warnings.extend(dev_warnings);
+
+        PackageJson::new(dependencies, dev_dependencies).with_warnings(warnings)
+    }
+}
+
+#[async_trait(?Send)]
+impl DependencyFileParser for PackageJson {
+    type Output = NpmDependency;
+
+    fn parse_str(contents: &str) -> DirectResult<ProjectDependencies<Self::Output>> {
+        let raw: PackageJsonRaw = serde_json::from_str(contents)?;
+
+        Ok(PackageJson::from(raw))
+    }
+
+    /// Parses the package.json file of a workspace root along with the package.json
+    /// of every workspace package matched by its `workspaces` globs. Globs prefixed
+    /// with `!` exclude the packages they match. The root package is always returned
+    /// first, and packages without a `name` are named after their directory.
+    ///
+    /// The workspace manifests are read and parsed in parallel on the blocking thread
+    /// pool of the runtime, so that large monorepos do not stall other async work.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn parse_workspaces(
+        file_name: &str,
+    ) -> DirectResult<Vec<WorkspacePackage<Self::Output>>> {
+        let (_, mut root) = read_package_json_blocking(PathBuf::from(file_name)).await?;
+        let root_dir = Path::new(file_name).parent().unwrap_or(Path::new("."));
+
+        let globs = root
+            .workspaces
+            .take()
+            .map(Workspaces::into_globs)
+            .unwrap_or_default();
+
+        let (excluded, included): (Vec<_>, Vec<_>) =
+            globs.iter().partition(|pattern| pattern.starts_with('!'));
+
+        let excluded = excluded
+            .into_iter()
+            .map(|pattern| Pattern::new(&root_dir.join(&pattern[1..]).to_string_lossy()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut manifests = Vec::new();
+
+        for pattern in included {
+            let manifest_glob = root_dir.join(pattern).join("package.json");
+
+            for manifest in glob(&manifest_glob.to_string_lossy())? {
+                let manifest = manifest?;
+                let package_dir = manifest.parent().unwrap_or(root_dir);
+
+                if !excluded
+                    .iter()
+                    .any(|pattern| pattern.matches_path(package_dir))
+                {
+                    manifests.push(manifest);
+                }
+            }
+        }
+
+        let parsed =
+            futures::future::join_all(manifests.into_iter().map(read_package_json_blocking)).await;
+
+        let root_name = root.name.take().unwrap_or_else(|| file_name.to_string());
+        let mut packages = vec![WorkspacePackage::new(&root_name, PackageJson::from(root))];
+
+        for result in parsed {
+            let (manifest, mut raw) = result?;
+            let name = raw.name.take().unwrap_or_else(|| {
+                let package_dir = manifest.parent().unwrap_or(root_dir);
+
+                package_dir.to_string_lossy().to_string()
+            });
+
+            packages.push(WorkspacePackage::new(&name, PackageJson::from(raw)));
+        }
+
+        Ok(packages)
+    }
+
+    /// Reads the package-lock.json file next to the package.json file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parse_lockfile(file_name: &str) -> OptionalResult<LockedVersions> {
+        let lockfile = package_lock_path(file_name);
+
+        if !lockfile.is_file() {
+            return Ok(None);
+        }
+
+        let mut locked = LockedVersions::new();
+
+        for package in read_package_lock(&lockfile)? {
+            locked
+                .entry(package.name)
+                .or_default()
+                .push(package.version);
+        }
+
+        Ok(Some(locked))
+    }
+
+    /// Reads the package-lock.json file next to the package.json file. yarn.lock files
+    /// are not supported, since their entries are not keyed by where they are installed.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parse_transitive(file_name: &str) -> OptionalResult<TransitiveDependencies<Self::Output>> {
+        let lockfile = package_lock_path(file_name);
+
+        if !lockfile.is_file() {
+            return Ok(None);
+        }
+
+        let raw: PackageLockRaw = serde_json::from_str(&fs::read_to_string(&lockfile)?)?;
+
+        let declared = Self::parse_file(file_name)?;
+        let declared: HashSet<&str> = declared
+            .dependencies()
+            .iter()
+            .chain(declared.dev_dependencies())
+            .map(Dependency::get_name)
+            .collect();
+
+        let (graph, mut direct) = package_lock_graph(&raw, &declared);
+
+        direct.sort_unstable();
+
+        let mut transitive = TransitiveDependencies::new();
+
+        for (index, parents) in graph.transitive(&direct) {
+            let (name, version) = graph.package(index);
+
+            transitive.push(NpmDependency::new(name, version), version, parents);
+        }
+
+        Ok(Some(transitive))
+    }
+
+    /// Reads the package-lock.json file next to the package.json file, or the
+    /// yarn.lock file if there is none.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parse_lockfile_checksums(file_name: &str) -> OptionalResult<Vec<LockedChecksum>> {
+        let package_lock = package_lock_path(file_name);
+        let yarn_lock = yarn_lock_path(file_name);
+
+        let packages = match (package_lock.is_file(), yarn_lock.is_file()) {
+            (true, _) => read_package_lock(&package_lock)?,
+            (false, true) => read_yarn_lock(&yarn_lock)?,
+            (false, false) => return Ok(None),
+        };
+
+        Ok(Some(
+            packages
+                .iter()
+                .filter_map(|package| {
+                    let integrity = package.integrity()?;
+                    let locked = LockedChecksum::new(package.name(), package.version(), integrity);
+
+                    Some(match package.registry() {
+                        Some(registry) if registry != NPM_REGISTRY => {
+                            locked.with_registry(registry)
+                        }
+                        _ => locked,
+                    })
+                })
+                .collect(),
+        ))
+    }
+
+    /// Fetches the abbreviated metadata document of the package from the registry,
+    /// authenticated with its token if it has one.
+    async fn fetch_published_checksums(
+        client: &HttpClient,
+        registry: Option<&Registry>,
+        name: &str,
+    ) -> DirectResult<HashMap<String, String>> {
+        let registry = registry.map(NpmRegistry::from).unwrap_or_default();
+
+        let dists: PackumentDists = client
+            .send(
+                registry
+                    .request(client, name)
+                    .header(ACCEPT, ABBREVIATED_METADATA),
+            )
+            .await?
+            .error_for_registry_status()
+            .await?
+            .json()
+            .await?;
+
+        Ok(dists.checksums())
+    }
+
+    /// Replaces the range strings of the dependencies in the raw text of the
+    /// package.json file, so that its key order and indentation are kept.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_constraints(file_name: &str, updates: &[ConstraintUpdate]) -> DirectResult<String> {
+        let mut contents = fs::read_to_string(file_name)?;
+        let mut replacements = Vec::with_capacity(updates.len());
+
+        for update in updates {
+            let section = match update.is_dev() {
+                true => "devDependencies",
+                false => "dependencies",
+            };
+
+            let span = json_string_span(&contents, &[section, update.name()]).ok_or_else(|| {
+                format!(
+                    "{} is not declared in {} of {}",
+                    update.name(),
+                    section,
+                    file_name
+                )
+            })?;
+
+            replacements.push((span, serde_json::to_string(update.constraint())?));
+        }
+
+        // Replacing from the end of the file keeps the spans before them valid
+        replacements.sort_by_key(|(span, _)| std::cmp::Reverse(span.start));
+        replacements.dedup_by_key(|(span, _)| span.start);
+
+        for (span, constraint) in replacements {
+            contents.replace_range(span, &constraint);
+        }
+
+        Ok(contents)
+    }
+
+    /// Runs the package manager whose lockfile sits next to the package.json file,
+    /// checking pnpm, yarn and npm in that order. yarn 1 has no way to only update
+    /// its lockfile, so it installs the packages as well.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn sync_lockfile_command(file_name: &str, _updates: &[ConstraintUpdate]) -> Option<Command> {
+        let manifest = Path::new(file_name);
+
+        let (program, args): (_, &[_]) = if manifest.with_file_name("pnpm-lock.yaml").is_file() {
+            ("pnpm", &["install", "--lockfile-only"])
+        } else if yarn_lock_path(file_name).is_file() {
+            ("yarn", &["install"])
+        } else if package_lock_path(file_name).is_file() {
+            ("npm", &["install", "--package-lock-only"])
+        } else {
+            return None;
+        };
+
+        let mut command = Command::new(program);
+
+        command.args(args);
+
+        if let Some(dir) = manifest.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            command.current_dir(dir);
+        }
+
+        Some(command)
+    }
+}
+
+/// Whether the version of a dependency points somewhere else than a version published to
+/// the registry, such as `file:../ui`, `github:user/repo`, `user/repo` or `workspace:*`,
+/// which version ranges never do.
+fn is_specifier(version: &str) -> bool {
+    version.contains([':', '/'])
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_package_json(file_name: &str) -> DirectResult<PackageJsonRaw> {
+    let file = fs::read_to_string(file_name)?;
+
+    Ok(serde_json::from_str(&file)?)
+}
+
+/// Reads and parses the manifest on the blocking thread pool of the runtime.
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_package_json_blocking(manifest: PathBuf) -> DirectResult<(PathBuf, PackageJsonRaw)> {
+    let parsed = tokio::task::spawn_blocking(move || {
+        read_package_json(&manifest.to_string_lossy()).map(|raw| (manifest, raw))
+    })
+    .await
+    .map_err(io::Error::from)?;
+
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_dependency_creates_successfully_with_raw_version() {
+        let dependency = NpmDependency::new("axios", "0.12.0");
+
+        assert_eq!(dependency.get_name(), "axios");
+        assert!(dependency.is_satisfied_by("0.12.0"));
+        assert!(!dependency.is_satisfied_by("0.12.1"));
+    }
+
+    #[test]
+    fn package_dependency_creates_successfully_with_simple_requirements() {
+        let dependency = NpmDependency::new("axios", "^0.12");
+
+        assert_eq!(dependency.get_name(), "axios");
+        assert!(dependency.is_satisfied_by("0.12.0"));
+        assert!(dependency.is_satisfied_by("0.12.1"));
+        assert!(!dependency.is_satisfied_by("0.13.0"));
+    }
+
+    #[test]
+    fn package_dependency_creates_successfully_with_complex_requirements() {
+        let dependency = NpmDependency::new("axios", "0.9 || >=0.11 <0.13");
+
+        assert_eq!(dependency.get_name(), "axios");
+        assert!(dependency.is_satisfied_by("0.9.0"));
+        assert!(dependency.is_satisfied_by("0.11.0"));
+        assert!(dependency.is_satisfied_by("0.12.0"));
+        assert!(!dependency.is_satisfied_by("0.10.0"));
+        assert!(!dependency.is_satisfied_by("0.13.0"));
+    }
+
+    #[test]
+    fn malformed_versions_satisfy_nothing() {
+        let dependency = NpmDependency::new("axios", "*");
+
+        assert!(!dependency.is_satisfied_by("latest-and-greatest"));
+        assert!(matches!(
+            dependency.try_is_satisfied_by("latest-and-greatest"),
+            Err(DepchkError::InvalidVersion { version, .. }) if version == "latest-and-greatest"
+        ));
+    }
+
+    #[test]
+    fn package_json_is_parsed_from_a_reader() {
+        let contents = r#"{
+            "name": "app",
+            "dependencies": { "axios": "^1.6.0" },
+            "devDependencies": { "jest": "^29.0.0" }
+        }"#;
+
+        let dependencies = PackageJson::parse_reader(contents.as_bytes()).unwrap();
+
+        assert_eq!(dependencies.dependencies()[0].get_name(), "axios");
+        assert_eq!(dependencies.dev_dependencies()[0].get_name(), "jest");
+        assert!(PackageJson::parse_str("{ \"dependencies\": [] }").is_err());
+    }
+
+    #[test]
+    fn unsupported_entries_are_skipped_with_warnings() {
+        let project = PackageJson::parse_str(
+            r#"{
+                "dependencies": {
+                    "axios": "^0.21.0",
+                    "ui": "workspace:*",
+                    "theme": "acme/theme#v2",
+                    "left-pad": "not a range",
+                    "axios": "^1.6.0"
+                },
+                "devDependencies": { "jest": "^29.0.0" }
+            }"#,
+        )
+        .unwrap();
+
+        let declared = project
+            .iter()
+            .map(|dependency| (dependency.get_name(), dependency.get_constraint()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(declared, [("axios", "^1.6.0"), ("jest", "^29.0.0")]);
+        assert_eq!(
+            project.warnings(),
+            [
+                ParseWarning::new("ui", "workspace:*", SkipReason::UnsupportedSpecifier),
+                ParseWarning::new("theme", "acme/theme#v2", SkipReason::UnsupportedSpecifier),
+                ParseWarning::new("left-pad", "not a range", SkipReason::InvalidConstraint),
+                ParseWarning::new("axios", "^0.21.0", SkipReason::Duplicate),
+            ]
+        );
+    }
+
+    #[test]
+    fn package_json_round_trips_through_serde() {
+        let mut project = PackageJson::from_pairs(
+            vec![("@acme/ui", "^2.0.0"), ("axios", "^1.6.0")],
+            vec![("jest", "^29.0.0")],
+        )
+        .unwrap();
+
+        project.use_registries(|name| {
+            name.starts_with("@acme/").then(|| {
+                Registry::new("https://npm.acme.com")
+                    .with_token("secret")
+                    .with_tag("next")
+            })
+        });
+
+        let serialized = serde_json::to_string(&project).unwrap();
+        let deserialized: PackageJson = serde_json::from_str(&serialized).unwrap();
+
+        assert!(!serialized.contains("secret"));
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), serialized);
+
+        let declared = deserialized
+            .iter()
+            .map(|dependency| (dependency.get_name(), dependency.get_registry()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            declared,
+            [
+                ("@acme/ui", "https://npm.acme.com#next"),
+                ("axios", NPM_REGISTRY),
+                ("jest", NPM_REGISTRY)
+            ]
+        );
+    }
+
+    #[test]
+    fn packument_reads_latest_tag_and_deprecations() {
+        let packument: Packument = serde_json::from_str(
+            r#"{
+                "name": "request",
+                "dist-tags": { "latest": "2.88.2" },
+                "versions": {
+                    "2.88.2": { "version": "2.88.2", "deprecated": "request has been deprecated" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(packument.latest(), Some("2.88.2"));
+        assert_eq!(
+            packument.versions()["2.88.2"].deprecated(),
+            Some("request has been deprecated")
+        );
+    }
+
+    #[test]
+    fn only_versions_with_a_provenance_statement_are_attested() {
+        let document: PackumentMetadata = serde_json::from_str(
+            r#"{
+                "versions": {
+                    "1.6.0": { "dist": { "tarball": "https://registry.npmjs.org/axios/-/axios-1.6.0.tgz" } },
+                    "1.7.0": {
+                        "dist": {
+                            "attestations": {
+                                "url": "https://registry.npmjs.org/-/npm/v1/attestations/axios@1.7.0",
+                                "provenance": { "predicateType": "https://slsa.dev/provenance/v1" }
+                            }
+                        }
+                    },
+                    "1.7.1": { "dist": { "attestations": { "url": "https://example.com" } } },
+                    "1.7.2": {}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            document.into_metadata("latest").attested_versions(),
+            Some(&HashSet::from(["1.7.0".to_string()]))
+        );
+    }
+
+    #[test]
+    fn package_locks_list_every_installed_package() {
+        let lockfile =
+            std::env::temp_dir().join(format!("depchk-package-lock-{}.json", std::process::id()));
+
+        fs::write(
+            &lockfile,
+            r#"{
+                "lockfileVersion": 3,
+                "packages": {
+                    "": { "name": "app", "version": "1.0.0" },
+                    "node_modules/axios": { "version": "0.21.0" },
+                    "node_modules/axios/node_modules/follow-redirects": { "version": "1.13.0" },
+                    "node_modules/jest": { "version": "29.7.0", "dev": true },
+                    "node_modules/lodash-legacy": { "name": "lodash", "version": "3.10.1" },
+                    "node_modules/shared": { "resolved": "packages/shared", "link": true }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let packages = read_package_lock(&lockfile).unwrap();
+        fs::remove_file(&lockfile).unwrap();
+
+        assert_eq!(
+            packages,
+            [
+                LockedPackage::new("axios", "0.21.0", false),
+                LockedPackage::new("follow-redirects", "1.13.0", false),
+                LockedPackage::new("jest", "29.7.0", true),
+                LockedPackage::new("lodash", "3.10.1", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn updated_constraints_keep_the_formatting_of_the_manifest() {
+        let manifest =
+            std::env::temp_dir().join(format!("depchk-package-{}.json", std::process::id()));
+
+        fs::write(
+            &manifest,
+            "{\n    \"name\": \"app\",\n    \"dependencies\": {\n        \"react\": \"^17.0.2\",\n        \"axios\":\"~0.21.0\"\n    },\n    \"devDependencies\": { \"react\": \"17.0.2\" }\n}\n",
+        )
+        .unwrap();
+
+        let updated = PackageJson::update_constraints(
+            manifest.to_str().unwrap(),
+            &[
+                ConstraintUpdate::new("react", false, "^18.2.0"),
+                ConstraintUpdate::new("axios", false, "~1.6.0"),
+                ConstraintUpdate::new("react", true, "18.2.0"),
+            ],
+        );
+        let missing = PackageJson::update_constraints(
+            manifest.to_str().unwrap(),
+            &[ConstraintUpdate::new("lodash", false, "^4.17.21")],
+        );
+        fs::remove_file(&manifest).unwrap();
+
+        assert_eq!(
+            updated.unwrap(),
+            "{\n    \"name\": \"app\",\n    \"dependencies\": {\n        \"react\": \"^18.2.0\",\n        \"axios\":\"~1.6.0\"\n    },\n    \"devDependencies\": { \"react\": \"18.2.0\" }\n}\n"
+        );
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn lockfiles_are_synced_by_the_package_manager_they_belong_to() {
+        let dir = std::env::temp_dir().join(format!("depchk-sync-{}", std::process::id()));
+        let manifest = dir.join("package.json");
+        let manifest = manifest.to_str().unwrap();
+
+        fs::create_dir_all(&dir).unwrap();
+        let none = PackageJson::sync_lockfile_command(manifest, &[]);
+
+        fs::write(dir.join("package-lock.json"), "{}").unwrap();
+        let npm = PackageJson::sync_lockfile_command(manifest, &[]).unwrap();
+
+        fs::write(dir.join("pnpm-lock.yaml"), "").unwrap();
+        let pnpm = PackageJson::sync_lockfile_command(manifest, &[]).unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(none.is_none());
+        assert_eq!(npm.get_program(), "npm");
+        assert_eq!(
+            npm.get_args().collect::<Vec<_>>(),
+            ["install", "--package-lock-only"]
+        );
+        assert_eq!(npm.get_current_dir(), Some(dir.as_path()));
+        assert_eq!(pnpm.get_program(), "pnpm");
+    }
+
+    #[test]
+    fn package_lock_graphs_resolve_nested_packages() {
+        let raw: PackageLockRaw = serde_json::from_str(
+            r#"{
+                "lockfileVersion": 3,
+                "packages": {
+                    "": { "name": "app", "dependencies": { "axios": "^0.21.0", "debug": "^2.0.0" } },
+                    "node_modules/axios": {
+                        "version": "0.21.0",
+                        "dependencies": { "follow-redirects": "^1.10.0", "debug": "^4.0.0" }
+                    },
+                    "node_modules/axios/node_modules/debug": {
+                        "version": "4.3.4",
+                        "dependencies": { "ms": "2.1.2" }
+                    },
+                    "node_modules/follow-redirects": { "version": "1.13.0" },
+                    "node_modules/debug": { "version": "2.6.9", "dependencies": { "ms": "2.0.0" } },
+                    "node_modules/ms": { "version": "2.0.0" },
+                    "node_modules/axios/node_modules/ms": { "version": "2.1.2" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let (graph, direct) = package_lock_graph(&raw, &HashSet::from(["axios", "debug"]));
+
+        let mut transitive: Vec<_> = graph
+            .transitive(&direct)
+            .into_iter()
+            .map(|(index, parents)| {
+                let (name, version) = graph.package(index);
+                (format!("{}@{}", name, version), parents.join(","))
+            })
+            .collect();
+
+        transitive.sort();
+
+        assert_eq!(
+            transitive,
+            [
+                ("debug@4.3.4".to_string(), "axios".to_string()),
+                ("follow-redirects@1.13.0".to_string(), "axios".to_string()),
+                ("ms@2.0.0".to_string(), "debug".to_string()),
+                ("ms@2.1.2".to_string(), "axios".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn yarn_locks_list_every_installed_package() {
+        let lockfile =
+            std::env::temp_dir().join(format!("depchk-yarn-lock-{}.lock", std::process::id()));
+
+        fs::write(
+            &lockfile,
+            r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/core@^7.0.0", "@babel/core@^7.1.0":
+  version "7.23.0"
+  resolved "https://registry.yarnpkg.com/@babel/core/-/core-7.23.0.tgz#abc"
+  integrity sha512-babel==
+  dependencies:
+    debug "^4.1.0"
+
+debug@^4.1.0:
+  version "4.3.4"
+
+legacy@npm:lodash@^3.0.0:
+  version "3.10.1"
+  integrity sha1-lodash=
+"#,
+        )
+        .unwrap();
+
+        let packages = read_yarn_lock(&lockfile).unwrap();
+        fs::remove_file(&lockfile).unwrap();
+
+        assert_eq!(
+            packages,
+            [
+                LockedPackage::new("@babel/core", "7.23.0", false)
+                    .with_integrity("sha512-babel==")
+                    .with_resolved(
+                        "https://registry.yarnpkg.com/@babel/core/-/core-7.23.0.tgz#abc"
+                    ),
+                LockedPackage::new("debug", "4.3.4", false),
+                LockedPackage::new("lodash", "3.10.1", false).with_integrity("sha1-lodash="),
+            ]
+        );
+        assert_eq!(packages[0].registry(), Some("https://registry.yarnpkg.com"));
+        assert_eq!(packages[1].registry(), None);
+    }
+
+    #[test]
+    fn test_hex_to_base64() {
+        assert_eq!(
+            hex_to_base64("aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d").as_deref(),
+            Some("qvTGHdzF6KLavt4PO0gs2a6pQ00=")
+        );
+        assert_eq!(hex_to_base64("abcd").as_deref(), Some("q80="));
+        assert_eq!(hex_to_base64("not hex"), None);
+    }
+
+    #[test]
+    fn scoped_packages_are_encoded_in_the_api_url() {
+        assert_eq!(
+            NpmRegistry::default().package_url("@types/node"),
+            "https://registry.npmjs.org/@types%2Fnode"
+        );
+    }
+
+    #[test]
+    fn dependencies_can_use_another_registry() {
+        let mut dependency = NpmDependency::new("@types/node", "^18");
+
+        dependency.use_registry(&Registry::new("https://npm.example.com/repository/npm/"));
+
+        assert_eq!(
+            dependency.get_registry(),
+            "https://npm.example.com/repository/npm"
+        );
+        assert_eq!(
+            NpmRegistry::new("https://npm.example.com/repository/npm/").package_url("@types/node"),
+            "https://npm.example.com/repository/npm/@types%2Fnode"
+        );
+    }
+
+    /// A registry that publishes every package at the same version.
+    struct Pinned(&'static str);
+
+    #[async_trait]
+    impl RegistryBackend for Pinned {
+        fn url(&self) -> &str {
+            "pinned"
+        }
+
+        async fn latest_version(&self, _client: &HttpClient, _name: &str) -> DirectResult<String> {
+            Ok(self.0.to_string())
+        }
+
+        async fn versions(&self, _client: &HttpClient, _name: &str) -> DirectResult<Vec<String>> {
+            Ok(vec![self.0.to_string()])
+        }
+
+        async fn metadata(
+            &self,
+            _client: &HttpClient,
+            _name: &str,
+        ) -> DirectResult<PackageMetadata> {
+            Ok(PackageMetadata::default().with_deprecation("use fetch instead"))
+        }
+    }
+
+    #[tokio::test]
+    async fn dependencies_resolve_from_their_registry_backend() {
+        let client = HttpClient::default();
+        let dependency =
+            NpmDependency::new("axios", "^0.21").with_registry_backend(Arc::new(Pinned("1.6.2")));
+
+        assert_eq!(dependency.get_registry(), "pinned");
+        assert_eq!(
+            dependency.fetch_latest_version(&client).await.unwrap(),
+            "1.6.2"
+        );
+        assert_eq!(
+            dependency
+                .fetch_deprecation(&client)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("use fetch instead")
+        );
+    }
+
+    #[test]
+    fn full_metadata_documents_are_read_into_package_metadata() {
+        let document: PackumentMetadata = serde_json::from_str(
+            r#"{
+                "dist-tags": { "latest": "2.88.2" },
+                "time": { "2.88.2": "2020-02-11T16:50:18.051Z" },
+                "repository": { "type": "git", "url": "https://github.com/request/request.git" },
+                "versions": {
+                    "2.88.2": {
+                        "license": "Apache-2.0",
+                        "deprecated": "request has been deprecated"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let metadata = document.into_metadata("latest");
+
+        assert_eq!(metadata.homepage(), None);
+        assert_eq!(
+            metadata.repository(),
+            Some("https://github.com/request/request.git")
+        );
+        assert_eq!(metadata.deprecation(), Some("request has been deprecated"));
+        assert_eq!(metadata.license("2.88.2"), Some("Apache-2.0"));
+        assert!(metadata.release_dates().contains_key("2.88.2"));
+    }
+
+    #[test]
+    fn deprecations_are_read_from_the_tag_of_the_registry() {
+        let document = || -> PackumentMetadata {
+            serde_json::from_str(
+                r#"{
+                    "dist-tags": { "latest": "1.0.0", "next": "2.0.0-rc.1" },
+                    "versions": {
+                        "1.0.0": {},
+                        "2.0.0-rc.1": { "deprecated": "use 2.0.0-rc.2 instead" }
+                    }
+                }"#,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(document().into_metadata("latest").deprecation(), None);
+        assert_eq!(
+            document().into_metadata("next").deprecation(),
+            Some("use 2.0.0-rc.2 instead")
+        );
+    }
+}
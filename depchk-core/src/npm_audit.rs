@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+
+use node_semver::{Range, Version};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::audit::{AuditedPackage, Vulnerability};
+use crate::npm::LockedPackage;
+
+/// An advisory as returned by the bulk advisory endpoint of the registry.
+#[derive(Deserialize, Debug)]
+struct BulkAdvisory {
+    id: u64,
+    url: Option<String>,
+    title: Option<String>,
+
+    /// One of `low`, `moderate`, `high` or `critical`.
+    severity: Option<String>,
+
+    cvss: Option<BulkCvss>,
+    vulnerable_versions: String,
+}
+
+/// The CVSS score of an advisory, which is 0 for advisories that were not scored.
+#[derive(Deserialize, Debug)]
+struct BulkCvss {
+    score: f64,
+}
+
+impl BulkAdvisory {
+    /// The identifier of the advisory, which is its GHSA identifier when it links
+    /// to the GitHub Advisory Database, as the registry's own is only a number.
+    fn identifier(&self) -> String {
+        self.url
+            .as_deref()
+            .and_then(|url| url.rsplit('/').next())
+            .filter(|id| id.starts_with("GHSA-"))
+            .map_or_else(|| self.id.to_string(), str::to_string)
+    }
+
+    fn affects(&self, version: &str) -> bool {
+        match (
+            Version::parse(version),
+            Range::parse(&self.vulnerable_versions),
+        ) {
+            (Ok(version), Ok(range)) => range.satisfies(&version),
+            _ => true,
+        }
+    }
+
+    fn affecting(&self, package: &AuditedPackage) -> Vulnerability {
+        let mut vulnerability = Vulnerability::new(package, &self.identifier());
+
+        if let Some(title) = &self.title {
+            vulnerability = vulnerability.with_summary(title);
+        }
+
+        if let Some(cvss) = self.cvss.as_ref().filter(|cvss| cvss.score > 0.0) {
+            vulnerability = vulnerability.with_cvss_score(cvss.score);
+        }
+
+        match self
+            .severity
+            .as_deref()
+            .and_then(|severity| severity.parse().ok())
+        {
+            Some(severity) => vulnerability.with_severity(severity),
+            None => vulnerability,
+        }
+    }
+}
+
+/// Looks up the advisories of every installed package with the bulk advisory
+/// endpoint of the registry, which is what `npm audit` uses. All the packages
+/// are sent in a single request, with every version of them that is installed.
+pub async fn query_vulnerabilities(
+    client: &Client,
+    registry: &str,
+    packages: &[LockedPackage],
+) -> Result<Vec<Vulnerability>, Box<dyn Error>> {
+    let mut versions: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+
+    for package in packages {
+        versions
+            .entry(package.name())
+            .or_default()
+            .push(package.version());
+    }
+
+    let advisories: HashMap<String, Vec<BulkAdvisory>> = client
+        .post(format!("{}/-/npm/v1/security/advisories/bulk", registry))
+        .json(&versions)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(packages
+        .iter()
+        .flat_map(|package| {
+            let audited = AuditedPackage::new(package.name(), package.version(), "npm");
+
+            advisories
+                .get(package.name())
+                .into_iter()
+                .flatten()
+                .filter(|advisory| advisory.affects(package.version()))
+                .map(|advisory| advisory.affecting(&audited))
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::Severity;
+
+    #[test]
+    fn advisories_are_identified_by_their_ghsa_identifier() {
+        let advisories: Vec<BulkAdvisory> = serde_json::from_str(
+            r#"[
+                {
+                    "id": 1096366,
+                    "url": "https://github.com/advisories/GHSA-35jh-r3h4-6jhm",
+                    "title": "Command Injection in lodash",
+                    "severity": "high",
+                    "cvss": {
+                        "score": 7.2,
+                        "vectorString": "CVSS:3.1/AV:N/AC:L/PR:H/UI:N/S:U/C:H/I:H/A:H"
+                    },
+                    "vulnerable_versions": "<4.17.21"
+                },
+                {
+                    "id": 1094500,
+                    "title": "Prototype Pollution in lodash",
+                    "severity": "moderate",
+                    "cvss": { "score": 0, "vectorString": null },
+                    "vulnerable_versions": "<4.17.12"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(advisories[0].identifier(), "GHSA-35jh-r3h4-6jhm");
+        assert_eq!(advisories[1].identifier(), "1094500");
+
+        assert!(advisories[0].affects("4.17.15"));
+        assert!(!advisories[1].affects("4.17.15"));
+        assert!(!advisories[0].affects("4.17.21"));
+
+        let lodash = AuditedPackage::new("lodash", "4.17.11", "npm");
+        let (high, moderate) = (
+            advisories[0].affecting(&lodash),
+            advisories[1].affecting(&lodash),
+        );
+
+        assert_eq!(high.severity(), Some(Severity::High));
+        assert_eq!(high.cvss_score(), Some(7.2));
+        assert_eq!(moderate.severity(), Some(Severity::Medium));
+        assert_eq!(moderate.cvss_score(), None);
+    }
+}
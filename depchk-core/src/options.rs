@@ -0,0 +1,455 @@
+use std::time::Duration;
+
+use futures::Stream;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::Instant;
+
+use crate::cache::Cache;
+use crate::client::{
+    http_client_builder, PrereleasePolicies, PrereleasePolicy, Registry, RegistryClient,
+    RetryPolicy,
+};
+use crate::progress::ProgressReporter;
+use crate::transport::HttpClient;
+use crate::{
+    check_dependencies_with_progress, check_stream_with_progress, verify_checksums, CheckOutcome,
+    CheckReport, Dependency, DependencyFileParser, DirectResult, IntegrityMismatch, LockedChecksum,
+    ProjectDependencies,
+};
+
+/// The registry each package is resolved from, by name.
+type RegistryOverrides = Box<dyn Fn(&str) -> Option<Registry> + Send + Sync>;
+
+/// How dependencies are checked: how many lookups run at the same time, how long
+/// they may take, which registries they are made against and how their answers are
+/// cached and interpreted. The command line builds its checks from the same options.
+///
+#[cfg_attr(feature = "npm", doc = "```no_run")]
+#[cfg_attr(not(feature = "npm"), doc = "```ignore")]
+/// # async fn check() -> Result<(), depchk_core::DepchkError> {
+/// use std::time::Duration;
+///
+/// use depchk_core::client::{PrereleasePolicy, Registry};
+/// use depchk_core::npm::PackageJson;
+/// use depchk_core::options::CheckOptions;
+/// use depchk_core::DependencyFileParser;
+///
+/// let checker = CheckOptions::new()
+///     .with_concurrency(8)
+///     .with_timeout(Duration::from_secs(10))
+///     .with_registry(Registry::new("https://registry.npmmirror.com"))
+///     .with_prerelease_policy(|_| PrereleasePolicy::Never)
+///     .build()?;
+///
+/// let mut dependencies = PackageJson::parse_file("package.json")?;
+/// checker.use_registries(&mut dependencies);
+///
+/// let report = checker.check(dependencies.dependencies(), &()).await;
+///
+/// for mismatch in &report.outdated {
+///     println!("{} can be updated to {}", mismatch.name(), mismatch.latest_version());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct CheckOptions {
+    concurrency: usize,
+    timeout: Duration,
+    retry_policy: RetryPolicy,
+    #[cfg(not(target_arch = "wasm32"))]
+    deadline: Option<Duration>,
+    registry: Option<Registry>,
+    registries: Option<RegistryOverrides>,
+    cache: Option<Box<dyn Cache>>,
+    offline: bool,
+    min_release_age: Option<Duration>,
+    prerelease_policies: Option<PrereleasePolicies>,
+    tag: Option<String>,
+}
+
+/// Checks dependencies with the client and settings built from [`CheckOptions`].
+pub struct Checker {
+    client: RegistryClient,
+    concurrency: usize,
+    registry: Option<Registry>,
+    registries: Option<RegistryOverrides>,
+    tag: Option<String>,
+}
+
+impl CheckOptions {
+    pub fn new() -> Self {
+        CheckOptions {
+            concurrency: 16,
+            timeout: Duration::from_secs(30),
+            retry_policy: RetryPolicy::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            deadline: None,
+            registry: None,
+            registries: None,
+            cache: None,
+            offline: false,
+            min_release_age: None,
+            prerelease_policies: None,
+            tag: None,
+        }
+    }
+
+    /// Sets the maximum number of registry lookups that are run at the same time.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets how long a single registry request may take before it is abandoned.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets how long the whole check may take, counted from when the checker is built.
+    /// See [`RegistryClient::with_deadline`], which is not available in WASM either.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Resolves every dependency from the registry instead of the default one of its
+    /// ecosystem, unless [`CheckOptions::with_registries`] maps it to another one.
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Resolves dependencies from the registries they are mapped to by name. Dependencies
+    /// mapped to none are resolved from the registry of [`CheckOptions::with_registry`],
+    /// if there is one.
+    pub fn with_registries(
+        mut self,
+        registry_of: impl Fn(&str) -> Option<Registry> + Send + Sync + 'static,
+    ) -> Self {
+        self.registries = Some(Box::new(registry_of));
+        self
+    }
+
+    /// Keeps the latest versions that were looked up in the cache, such as a
+    /// [`DiskCache`](crate::cache::DiskCache), between checks.
+    pub fn with_cache(mut self, cache: impl Cache + 'static) -> Self {
+        self.cache = Some(Box::new(cache));
+        self
+    }
+
+    /// Answers every lookup from the cache. See [`RegistryClient::offline`].
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    /// See [`RegistryClient::with_min_release_age`].
+    pub fn with_min_release_age(mut self, min_release_age: Duration) -> Self {
+        self.min_release_age = Some(min_release_age);
+        self
+    }
+
+    /// See [`RegistryClient::with_prerelease_policy`].
+    pub fn with_prerelease_policy(
+        mut self,
+        policy: impl Fn(&str) -> PrereleasePolicy + Send + Sync + 'static,
+    ) -> Self {
+        self.prerelease_policies = Some(Box::new(policy));
+        self
+    }
+
+    /// Resolves the latest version of packages from a distribution tag other than
+    /// `latest`, such as `next`. See [`Registry::with_tag`].
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_string());
+        self
+    }
+
+    /// Builds the HTTP client and the registry client the checks are made with.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build(self) -> DirectResult<Checker> {
+        let http = http_client_builder().timeout(self.timeout).build()?;
+
+        self.build_with(http)
+    }
+
+    /// Builds the HTTP client and the registry client the checks are made with. The
+    /// fetch API of the browser has no timeout, so the one of the options is ignored.
+    #[cfg(target_arch = "wasm32")]
+    pub fn build(self) -> DirectResult<Checker> {
+        let http = http_client_builder().build()?;
+
+        self.build_with(http)
+    }
+
+    /// Builds the registry client the checks are made with around an HTTP client that
+    /// was already configured, whose timeout is then left as it is. An [`HttpClient`]
+    /// with another transport answers the lookups without going over the network.
+    pub fn build_with(self, http: impl Into<HttpClient>) -> DirectResult<Checker> {
+        let mut client = RegistryClient::new(http).with_retry_policy(self.retry_policy);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(deadline) = self.deadline {
+            client = client.with_deadline(Instant::now() + deadline);
+        }
+
+        if let Some(min_release_age) = self.min_release_age {
+            client = client.with_min_release_age(min_release_age);
+        }
+
+        if let Some(policies) = self.prerelease_policies {
+            client = client.with_prerelease_policy(policies);
+        }
+
+        if self.offline {
+            client = client.offline();
+        }
+
+        if let Some(cache) = self.cache {
+            client = client.with_cache(cache);
+        }
+
+        Ok(Checker {
+            client,
+            concurrency: self.concurrency,
+            registry: self.registry,
+            registries: self.registries,
+            tag: self.tag,
+        })
+    }
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        CheckOptions::new()
+    }
+}
+
+impl Checker {
+    pub fn client(&self) -> &RegistryClient {
+        &self.client
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// The registry the dependency is resolved from under the options, if it is not
+    /// the one it would be resolved from anyway.
+    pub fn registry_of<T: Dependency>(&self, dependency: &T) -> Option<Registry> {
+        let registry = self.registry_named(dependency.get_name());
+
+        match (&self.tag, registry) {
+            (Some(tag), registry) => Some(
+                registry
+                    .unwrap_or_else(|| Registry::new(dependency.get_registry()))
+                    .with_tag(tag),
+            ),
+            (None, registry) => registry,
+        }
+    }
+
+    /// The registry packages of the name are resolved from under the options, without
+    /// the distribution tag latest versions are resolved from.
+    fn registry_named(&self, name: &str) -> Option<Registry> {
+        self.registries
+            .as_ref()
+            .and_then(|registry_of| registry_of(name))
+            .or_else(|| self.registry.clone())
+    }
+
+    /// Points the dependencies at the registries of the options. See
+    /// [`ProjectDependencies::use_registries`].
+    pub fn use_registries<T: Dependency>(&self, dependencies: &mut ProjectDependencies<T>) {
+        for dependency in dependencies.iter_mut() {
+            if let Some(registry) = self.registry_of(dependency) {
+                dependency.use_registry(&registry);
+            }
+        }
+    }
+
+    /// Checks every dependency, telling the reporter about the progress of the check
+    /// as it goes. See [`check_dependencies_with_progress`].
+    pub async fn check<T: Dependency>(
+        &self,
+        dependencies: &[T],
+        progress: &dyn ProgressReporter,
+    ) -> CheckReport {
+        check_dependencies_with_progress(&self.client, dependencies, self.concurrency, progress)
+            .await
+    }
+
+    /// Compares the checksums the lockfile records against the ones the registry of each
+    /// package publishes. Packages are looked up on the registry the lockfile resolved
+    /// them from, with the token the options give that registry, or else on the one the
+    /// options resolve them from. See [`verify_checksums`].
+    pub async fn verify_checksums<P: DependencyFileParser>(
+        &self,
+        locked: &[LockedChecksum],
+    ) -> Vec<DirectResult<IntegrityMismatch>> {
+        let registry_of = |package: &LockedChecksum| {
+            let registry = self.registry_named(package.name());
+
+            match package.registry() {
+                Some(url) => Some(
+                    registry
+                        .filter(|registry| {
+                            registry.url().trim_end_matches('/') == url.trim_end_matches('/')
+                        })
+                        .unwrap_or_else(|| Registry::new(url)),
+                ),
+                None => registry,
+            }
+        };
+
+        verify_checksums::<P>(&self.client, locked, registry_of, self.concurrency).await
+    }
+
+    /// Checks every dependency, yielding the outcome of each one as soon as it is known.
+    /// See [`check_stream_with_progress`].
+    pub fn check_stream<'a, T: Dependency>(
+        &'a self,
+        dependencies: &'a [T],
+        progress: &'a dyn ProgressReporter,
+    ) -> impl Stream<Item = CheckOutcome> + 'a {
+        check_stream_with_progress(&self.client, dependencies, self.concurrency, progress)
+    }
+}
+
+#[cfg(all(test, feature = "npm"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use reqwest::header::AUTHORIZATION;
+    use reqwest::{Request, Response};
+
+    use super::*;
+    use crate::npm::{NpmDependency, PackageJson};
+    use crate::transport::HttpTransport;
+
+    /// Answers every request with the same abbreviated packument, remembering what was
+    /// requested along with the token it was sent with.
+    #[derive(Default)]
+    struct Recorded {
+        requested: Mutex<Vec<(String, Option<String>)>>,
+    }
+
+    #[async_trait]
+    impl HttpTransport for Recorded {
+        async fn execute(&self, request: Request) -> DirectResult<Response> {
+            let token = request
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|token| token.to_str().ok())
+                .map(str::to_string);
+
+            self.requested
+                .lock()
+                .unwrap()
+                .push((request.url().to_string(), token));
+
+            let packument = r#"{"versions": {"1.0.0": {"dist": {"integrity": "sha512-abc"}}}}"#;
+
+            Ok(Response::from(http::Response::new(packument)))
+        }
+    }
+
+    #[test]
+    fn registries_are_overridden_by_name() {
+        let checker = CheckOptions::new()
+            .with_registry(Registry::new("https://mirror.example.com"))
+            .with_registries(|name| {
+                name.starts_with("@acme/")
+                    .then(|| Registry::new("https://npm.acme.com").with_token("secret"))
+            })
+            .build()
+            .unwrap();
+
+        let mut dependencies = ProjectDependencies::from(vec![
+            NpmDependency::new("@acme/ui", "^1.0.0"),
+            NpmDependency::new("axios", "^1.0.0"),
+        ]);
+
+        checker.use_registries(&mut dependencies);
+
+        let registries: Vec<_> = dependencies.iter().map(Dependency::get_registry).collect();
+
+        assert_eq!(
+            registries,
+            ["https://npm.acme.com", "https://mirror.example.com"]
+        );
+    }
+
+    #[test]
+    fn tags_apply_to_every_registry() {
+        let checker = CheckOptions::new()
+            .with_registries(|name| {
+                (name == "@acme/ui").then(|| Registry::new("https://npm.acme.com"))
+            })
+            .with_tag("next")
+            .with_concurrency(0)
+            .build()
+            .unwrap();
+
+        let mut dependencies = ProjectDependencies::from(vec![
+            NpmDependency::new("@acme/ui", "^1.0.0"),
+            NpmDependency::new("axios", "^1.0.0"),
+        ]);
+
+        checker.use_registries(&mut dependencies);
+
+        let registries: Vec<_> = dependencies.iter().map(Dependency::get_registry).collect();
+
+        assert_eq!(
+            registries,
+            [
+                "https://npm.acme.com#next",
+                "https://registry.npmjs.org#next"
+            ]
+        );
+        assert_eq!(checker.concurrency(), 1);
+    }
+
+    #[tokio::test]
+    async fn checksums_are_verified_on_the_registry_of_the_lockfile() {
+        let transport = Arc::new(Recorded::default());
+        let checker = CheckOptions::new()
+            .with_registries(|name| {
+                name.starts_with("@acme/")
+                    .then(|| Registry::new("https://npm.acme.com/").with_token("secret"))
+            })
+            .build_with(HttpClient::default().with_transport(Arc::clone(&transport)))
+            .unwrap();
+
+        let locked = [
+            LockedChecksum::new("@acme/ui", "1.0.0", "sha512-abc")
+                .with_registry("https://npm.acme.com"),
+            LockedChecksum::new("@acme/ui", "2.0.0", "sha512-def")
+                .with_registry("https://npm.acme.com"),
+        ];
+
+        let results = checker.verify_checksums::<PackageJson>(&locked).await;
+
+        // The version that is not published there is an error rather than a mismatch
+        assert_eq!(results.len(), 1);
+        assert!(results[0]
+            .as_ref()
+            .is_err_and(|err| err.to_string().contains("2.0.0 is not published")));
+
+        assert_eq!(
+            *transport.requested.lock().unwrap(),
+            [(
+                "https://npm.acme.com/@acme%2Fui".to_string(),
+                Some("Bearer secret".to_string())
+            )]
+        );
+    }
+}
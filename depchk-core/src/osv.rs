@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use futures::{StreamExt, TryStreamExt};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::audit::{AuditedPackage, Vulnerability};
+use crate::cvss;
+
+const OSV_API: &str = "https://api.osv.dev/v1";
+
+/// The most queries the OSV batch endpoint accepts in a single request.
+const BATCH_SIZE: usize = 1000;
+
+#[derive(Serialize, Debug)]
+struct BatchQuery<'a> {
+    queries: Vec<Query<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct Query<'a> {
+    package: QueryPackage<'a>,
+    version: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct QueryPackage<'a> {
+    name: &'a str,
+    ecosystem: &'a str,
+}
+
+/// The answer to a batch query, with one result per query in the same order.
+#[derive(Deserialize, Debug)]
+struct BatchResponse {
+    results: Vec<BatchResult>,
+}
+
+/// The vulnerabilities that affect a single queried package. Batch queries only
+/// return their identifiers, the details have to be looked up one by one.
+#[derive(Deserialize, Debug)]
+struct BatchResult {
+    #[serde(default)]
+    vulns: Vec<VulnerabilityId>,
+}
+
+#[derive(Deserialize, Debug)]
+struct VulnerabilityId {
+    id: String,
+}
+
+/// A vulnerability, in the OSV schema.
+#[derive(Deserialize, Debug)]
+struct OsvVulnerability {
+    id: String,
+
+    #[serde(default)]
+    aliases: Vec<String>,
+
+    summary: Option<String>,
+
+    #[serde(default)]
+    affected: Vec<Affected>,
+
+    #[serde(default)]
+    severity: Vec<OsvSeverity>,
+
+    database_specific: Option<DatabaseSpecific>,
+}
+
+/// A CVSS vector of the vulnerability, of the CVSS version its type names.
+#[derive(Deserialize, Debug)]
+struct OsvSeverity {
+    score: String,
+}
+
+/// The fields the database the vulnerability comes from adds to it, of which only
+/// the severity GitHub rates its advisories with is of interest.
+#[derive(Deserialize, Debug)]
+struct DatabaseSpecific {
+    severity: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Affected {
+    package: Option<AffectedPackage>,
+
+    #[serde(default)]
+    ranges: Vec<AffectedRange>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AffectedPackage {
+    name: String,
+    ecosystem: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct AffectedRange {
+    #[serde(default)]
+    events: Vec<RangeEvent>,
+}
+
+/// An event of an affected range, of which only the versions the vulnerability
+/// is fixed in are of interest.
+#[derive(Deserialize, Debug)]
+struct RangeEvent {
+    fixed: Option<String>,
+}
+
+impl OsvVulnerability {
+    /// The vulnerability as it affects the given package. Only the fixed versions
+    /// of the ranges that affect that package are kept, since a single advisory
+    /// may cover several packages.
+    fn affecting(&self, package: &AuditedPackage) -> Vulnerability {
+        let fixed_versions = self
+            .affected
+            .iter()
+            .filter(|affected| {
+                affected.package.as_ref().is_some_and(|affected| {
+                    affected.name == package.name() && affected.ecosystem == package.ecosystem()
+                })
+            })
+            .flat_map(|affected| &affected.ranges)
+            .flat_map(|range| &range.events)
+            .filter_map(|event| event.fixed.clone())
+            .collect();
+
+        let mut vulnerability = Vulnerability::new(package, &self.id)
+            .with_aliases(self.aliases.clone())
+            .with_fixed_versions(fixed_versions);
+
+        if let Some(summary) = &self.summary {
+            vulnerability = vulnerability.with_summary(summary);
+        }
+
+        // Only CVSS v3 vectors are scored, the other ones are skipped
+        if let Some(score) = self
+            .severity
+            .iter()
+            .find_map(|severity| cvss::base_score(&severity.score))
+        {
+            vulnerability = vulnerability.with_cvss_score(score);
+        }
+
+        match self
+            .database_specific
+            .as_ref()
+            .and_then(|specific| specific.severity.as_deref()?.parse().ok())
+        {
+            Some(severity) => vulnerability.with_severity(severity),
+            None => vulnerability,
+        }
+    }
+}
+
+/// Looks up the vulnerabilities that affect the packages with the OSV batch query
+/// API, then fetches the details of each of them, running at most `concurrency`
+/// requests at the same time.
+pub async fn query_vulnerabilities(
+    client: &Client,
+    packages: &[AuditedPackage],
+    concurrency: usize,
+) -> Result<Vec<Vulnerability>, Box<dyn Error>> {
+    let mut affected = Vec::new();
+
+    for batch in packages.chunks(BATCH_SIZE) {
+        let query = BatchQuery {
+            queries: batch
+                .iter()
+                .map(|package| Query {
+                    package: QueryPackage {
+                        name: package.name(),
+                        ecosystem: package.ecosystem(),
+                    },
+                    version: package.version(),
+                })
+                .collect(),
+        };
+
+        let response: BatchResponse = client
+            .post(format!("{}/querybatch", OSV_API))
+            .json(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        for (package, result) in batch.iter().zip(response.results) {
+            affected.extend(result.vulns.into_iter().map(|vuln| (package, vuln.id)));
+        }
+    }
+
+    let mut ids: Vec<_> = affected.iter().map(|(_, id)| id.as_str()).collect();
+
+    ids.sort_unstable();
+    ids.dedup();
+
+    let details: HashMap<_, _> = futures::stream::iter(ids)
+        .map(|id| async move {
+            let vulnerability: OsvVulnerability = client
+                .get(format!("{}/vulns/{}", OSV_API, id))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            Ok::<_, Box<dyn Error>>((id, vulnerability))
+        })
+        .buffer_unordered(concurrency.max(1))
+        .try_collect()
+        .await?;
+
+    Ok(affected
+        .iter()
+        .filter_map(|(package, id)| Some(details.get(id.as_str())?.affecting(package)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::Severity;
+
+    #[test]
+    fn vulnerabilities_only_keep_the_fixed_versions_of_the_audited_package() {
+        let vulnerability: OsvVulnerability = serde_json::from_str(
+            r#"{
+                "id": "GHSA-35jh-r3h4-6jhm",
+                "aliases": ["CVE-2021-23337"],
+                "summary": "Command Injection in lodash",
+                "severity": [
+                    {
+                        "type": "CVSS_V3",
+                        "score": "CVSS:3.1/AV:N/AC:L/PR:H/UI:N/S:U/C:H/I:H/A:H"
+                    }
+                ],
+                "database_specific": { "severity": "HIGH" },
+                "affected": [
+                    {
+                        "package": { "name": "lodash", "ecosystem": "npm" },
+                        "ranges": [
+                            {
+                                "type": "SEMVER",
+                                "events": [{ "introduced": "0" }, { "fixed": "4.17.21" }]
+                            }
+                        ]
+                    },
+                    {
+                        "package": { "name": "lodash-es", "ecosystem": "npm" },
+                        "ranges": [
+                            {
+                                "type": "SEMVER",
+                                "events": [{ "introduced": "0" }, { "fixed": "4.17.21" }]
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let vulnerability =
+            vulnerability.affecting(&AuditedPackage::new("lodash", "4.17.15", "npm"));
+
+        assert_eq!(vulnerability.package(), "lodash");
+        assert_eq!(vulnerability.version(), "4.17.15");
+        assert_eq!(vulnerability.id(), "GHSA-35jh-r3h4-6jhm");
+        assert_eq!(vulnerability.aliases(), ["CVE-2021-23337"]);
+        assert_eq!(vulnerability.summary(), Some("Command Injection in lodash"));
+        assert_eq!(vulnerability.fixed_versions(), ["4.17.21"]);
+        assert_eq!(vulnerability.cvss_score(), Some(7.2));
+        assert_eq!(vulnerability.severity(), Some(Severity::High));
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{CheckOutcome, DependencyError};
+
+/// Observes a check while it runs, so that long checks can show their progress, like
+/// the progress bar of the command line. Every method does nothing by default, and
+/// they are called from the tasks that check the dependencies, possibly at the same
+/// time.
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use depchk_core::progress::ProgressReporter;
+/// use depchk_core::CheckOutcome;
+///
+/// #[derive(Default)]
+/// struct Counter {
+///     total: AtomicUsize,
+///     done: AtomicUsize,
+/// }
+///
+/// impl ProgressReporter for Counter {
+///     fn checking(&self, total: usize) {
+///         self.total.fetch_add(total, Ordering::Relaxed);
+///     }
+///
+///     fn completed(&self, _outcome: &CheckOutcome) {
+///         self.done.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+/// ```
+pub trait ProgressReporter: Send + Sync {
+    /// Called once before any dependency is checked, with how many will be.
+    fn checking(&self, _total: usize) {}
+
+    /// Called when the check of a dependency starts.
+    fn started(&self, _name: &str) {}
+
+    /// Called when a dependency was found to be up to date or outdated.
+    fn completed(&self, _outcome: &CheckOutcome) {}
+
+    /// Called when a dependency could not be checked, or was skipped.
+    fn failed(&self, _err: &DependencyError) {}
+}
+
+/// Reports nothing.
+impl ProgressReporter for () {}
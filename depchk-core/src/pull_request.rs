@@ -0,0 +1,238 @@
+use std::error::Error;
+
+use reqwest::header::USER_AGENT;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::encode_base64;
+
+const GITHUB_REPOS_API: &str = "https://api.github.com/repos";
+
+/// A GitHub repository that updates are proposed to, along with a token that may
+/// push branches to it and open pull requests.
+pub struct GitHubRepository {
+    client: Client,
+    repository: String,
+    token: String,
+}
+
+/// An update of a file of the repository, proposed as a pull request from a branch
+/// of its own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PullRequest {
+    branch: String,
+    title: String,
+    body: String,
+    commit_message: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RepositoryRaw {
+    default_branch: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct GitRef {
+    object: GitObject,
+}
+
+#[derive(Deserialize, Debug)]
+struct GitObject {
+    sha: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct FileContents {
+    sha: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct PullRequestRaw {
+    html_url: String,
+}
+
+#[derive(Serialize, Debug)]
+struct NewRef<'a> {
+    #[serde(rename = "ref")]
+    reference: String,
+    sha: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct UpdatedFile<'a> {
+    message: &'a str,
+    content: String,
+    sha: &'a str,
+    branch: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct NewPullRequest<'a> {
+    title: &'a str,
+    head: &'a str,
+    base: &'a str,
+    body: &'a str,
+}
+
+impl PullRequest {
+    /// A pull request from `branch`, whose single commit has the title as its message
+    /// unless another one is given.
+    pub fn new(branch: &str, title: &str, body: &str) -> Self {
+        PullRequest {
+            branch: branch.to_string(),
+            title: title.to_string(),
+            body: body.to_string(),
+            commit_message: None,
+        }
+    }
+
+    pub fn with_commit_message(mut self, commit_message: &str) -> Self {
+        self.commit_message = Some(commit_message.to_string());
+        self
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// The message of the commit of the pull request.
+    pub fn commit_message(&self) -> &str {
+        self.commit_message.as_deref().unwrap_or(&self.title)
+    }
+}
+
+impl GitHubRepository {
+    /// The repository, given as `owner/name`.
+    pub fn new(client: &Client, repository: &str, token: &str) -> Result<Self, Box<dyn Error>> {
+        let is_valid = repository.split_once('/').is_some_and(|(owner, name)| {
+            !owner.is_empty() && !name.is_empty() && !name.contains('/')
+        });
+
+        if !is_valid {
+            return Err(format!("{} is not a repository, expected owner/name", repository).into());
+        }
+
+        Ok(GitHubRepository {
+            client: client.clone(),
+            repository: repository.to_string(),
+            token: token.to_string(),
+        })
+    }
+
+    /// The branch pull requests are opened against unless another one is chosen.
+    pub async fn default_branch(&self) -> Result<String, Box<dyn Error>> {
+        let repository: RepositoryRaw = self
+            .get("")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(repository.default_branch)
+    }
+
+    /// Creates the branch of the pull request from `base`, commits the new contents
+    /// of the file at `path` to it and opens the pull request, returning its URL.
+    /// Fails if the branch already exists, so that pull requests that are still open
+    /// are never overwritten.
+    pub async fn open_pull_request(
+        &self,
+        base: &str,
+        request: &PullRequest,
+        path: &str,
+        contents: &str,
+    ) -> Result<String, Box<dyn Error>> {
+        let base_ref: GitRef = self
+            .get(&format!("/git/ref/heads/{}", base))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let response = self
+            .post("/git/refs")
+            .json(&NewRef {
+                reference: format!("refs/heads/{}", request.branch),
+                sha: &base_ref.object.sha,
+            })
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::UNPROCESSABLE_ENTITY {
+            return Err(format!(
+                "the branch {} already exists in {}",
+                request.branch, self.repository
+            )
+            .into());
+        }
+
+        response.error_for_status()?;
+
+        let file: FileContents = self
+            .get(&format!("/contents/{}", path))
+            .query(&[("ref", &request.branch)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        self.client
+            .put(self.url(&format!("/contents/{}", path)))
+            .bearer_auth(&self.token)
+            .header(USER_AGENT, "depchk")
+            .json(&UpdatedFile {
+                message: request.commit_message(),
+                content: encode_base64(contents.as_bytes()),
+                sha: &file.sha,
+                branch: &request.branch,
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let pull_request: PullRequestRaw = self
+            .post("/pulls")
+            .json(&NewPullRequest {
+                title: &request.title,
+                head: &request.branch,
+                base,
+                body: &request.body,
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(pull_request.html_url)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}{}", GITHUB_REPOS_API, self.repository, path)
+    }
+
+    fn get(&self, path: &str) -> RequestBuilder {
+        self.client
+            .get(self.url(path))
+            .bearer_auth(&self.token)
+            .header(USER_AGENT, "depchk")
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        self.client
+            .post(self.url(path))
+            .bearer_auth(&self.token)
+            .header(USER_AGENT, "depchk")
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::{HashMap, HashSet};
+use std::time::SystemTime;
+
+use crate::transport::HttpClient;
+use crate::DirectResult;
+use async_trait::async_trait;
+use futures::try_join;
+
+/// A registry that dependencies resolve their packages from. Dependencies look their
+/// packages up through one rather than calling a registry API themselves, so that
+/// other registries, such as internal mirrors or test doubles, can be plugged in
+/// without changing how dependencies are checked.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use async_trait::async_trait;
+/// # use depchk_core::registry::{PackageMetadata, RegistryBackend};
+/// # use depchk_core::transport::HttpClient;
+/// # use depchk_core::DepchkError;
+/// /// A registry that publishes every package at the same version.
+/// struct Pinned;
+///
+/// #[async_trait]
+/// impl RegistryBackend for Pinned {
+///     fn url(&self) -> &str {
+///         "pinned"
+///     }
+///
+///     async fn latest_version(&self, _client: &HttpClient, _name: &str) -> Result<String, DepchkError> {
+///         Ok("1.0.0".to_string())
+///     }
+///
+///     async fn versions(&self, _client: &HttpClient, _name: &str) -> Result<Vec<String>, DepchkError> {
+///         Ok(vec!["1.0.0".to_string()])
+///     }
+///
+///     async fn metadata(
+///         &self,
+///         _client: &HttpClient,
+///         _name: &str,
+///     ) -> Result<PackageMetadata, DepchkError> {
+///         Ok(PackageMetadata::default())
+///     }
+/// }
+/// ```
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait RegistryBackend: Send + Sync {
+    /// An identifier for the registry, usually its URL, used to tell apart
+    /// identically named packages of different registries.
+    fn url(&self) -> &str;
+
+    /// Resolves the version of the package that is installed by default.
+    async fn latest_version(&self, client: &HttpClient, name: &str) -> DirectResult<String>;
+
+    /// Resolves every published version of the package.
+    async fn versions(&self, client: &HttpClient, name: &str) -> DirectResult<Vec<String>>;
+
+    /// Fetches what the registry publishes about the package besides its versions.
+    async fn metadata(&self, client: &HttpClient, name: &str) -> DirectResult<PackageMetadata>;
+
+    /// Resolves the latest versions of many packages in as few requests as possible,
+    /// keyed by name. Registries without a bulk lookup endpoint return `None`, and
+    /// each package is then looked up on its own.
+    async fn latest_versions(
+        &self,
+        _client: &HttpClient,
+        _names: &[&str],
+    ) -> Option<DirectResult<HashMap<String, String>>> {
+        None
+    }
+}
+
+/// What a registry publishes about a package besides its versions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PackageMetadata {
+    homepage: Option<String>,
+    repository: Option<String>,
+    deprecation: Option<String>,
+    release_dates: HashMap<String, SystemTime>,
+    licenses: HashMap<String, String>,
+    attested_versions: Option<HashSet<String>>,
+    dist_tags: HashMap<String, String>,
+}
+
+/// The latest version of a package, as looked up by [`latest_version`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VersionInfo {
+    version: String,
+    published_at: Option<SystemTime>,
+    deprecation: Option<String>,
+    dist_tags: HashMap<String, String>,
+}
+
+impl PackageMetadata {
+    pub fn with_homepage(mut self, homepage: &str) -> Self {
+        self.homepage = Some(homepage.to_string());
+        self
+    }
+
+    pub fn with_repository(mut self, repository: &str) -> Self {
+        self.repository = Some(repository.to_string());
+        self
+    }
+
+    /// Marks the latest version as deprecated, with the message of its deprecation.
+    pub fn with_deprecation(mut self, message: &str) -> Self {
+        self.deprecation = Some(message.to_string());
+        self
+    }
+
+    pub fn with_release_date(mut self, version: &str, released_at: SystemTime) -> Self {
+        self.release_dates.insert(version.to_string(), released_at);
+        self
+    }
+
+    /// Sets the license of a version, as an SPDX expression.
+    pub fn with_license(mut self, version: &str, license: &str) -> Self {
+        self.licenses
+            .insert(version.to_string(), license.to_string());
+        self
+    }
+
+    /// Sets the versions that were published with a provenance statement, for
+    /// registries that keep them.
+    pub fn with_attested_versions(mut self, versions: HashSet<String>) -> Self {
+        self.attested_versions = Some(versions);
+        self
+    }
+
+    /// Points a distribution tag, such as `next`, at a version, for registries that
+    /// have them.
+    pub fn with_dist_tag(mut self, tag: &str, version: &str) -> Self {
+        self.dist_tags.insert(tag.to_string(), version.to_string());
+        self
+    }
+
+    pub fn homepage(&self) -> Option<&str> {
+        self.homepage.as_deref()
+    }
+
+    pub fn repository(&self) -> Option<&str> {
+        self.repository.as_deref()
+    }
+
+    /// The deprecation message of the latest version, if it is deprecated.
+    pub fn deprecation(&self) -> Option<&str> {
+        self.deprecation.as_deref()
+    }
+
+    pub fn release_dates(&self) -> &HashMap<String, SystemTime> {
+        &self.release_dates
+    }
+
+    pub fn license(&self, version: &str) -> Option<&str> {
+        self.licenses.get(version).map(String::as_str)
+    }
+
+    /// The versions published with a provenance statement, or `None` if the
+    /// registry does not keep them.
+    pub fn attested_versions(&self) -> Option<&HashSet<String>> {
+        self.attested_versions.as_ref()
+    }
+
+    /// The versions the distribution tags of the package point at, keyed by tag.
+    pub fn dist_tags(&self) -> &HashMap<String, String> {
+        &self.dist_tags
+    }
+}
+
+impl VersionInfo {
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// When the version was published, if the registry publishes release dates.
+    pub fn published_at(&self) -> Option<SystemTime> {
+        self.published_at
+    }
+
+    /// The deprecation message of the version, if it is deprecated.
+    pub fn deprecation(&self) -> Option<&str> {
+        self.deprecation.as_deref()
+    }
+
+    /// The versions the distribution tags of the package point at, keyed by tag.
+    /// Empty for registries without distribution tags.
+    pub fn dist_tags(&self) -> &HashMap<String, String> {
+        &self.dist_tags
+    }
+}
+
+/// Looks up the latest version of a package from the registry, along with when it was
+/// published, whether it is deprecated and the distribution tags of the package. Unlike
+/// checking a dependency, this needs no constraint, so it serves consumers that only
+/// want to know what the latest version is.
+///
+#[cfg_attr(feature = "npm", doc = "```no_run")]
+#[cfg_attr(not(feature = "npm"), doc = "```ignore")]
+/// # async fn lookup() -> Result<(), depchk_core::DepchkError> {
+/// use depchk_core::npm::NpmRegistry;
+/// use depchk_core::registry::latest_version;
+/// use depchk_core::transport::HttpClient;
+///
+/// let client = HttpClient::default();
+/// let info = latest_version("axios", &NpmRegistry::default(), &client).await?;
+///
+/// println!("axios {} ({:?})", info.version(), info.dist_tags().get("next"));
+/// # Ok(())
+/// # }
+/// ```
+pub async fn latest_version(
+    name: &str,
+    registry: &dyn RegistryBackend,
+    client: &HttpClient,
+) -> DirectResult<VersionInfo> {
+    let (version, metadata) = try_join!(
+        registry.latest_version(client, name),
+        registry.metadata(client, name)
+    )?;
+
+    Ok(VersionInfo {
+        published_at: metadata.release_dates().get(&version).copied(),
+        deprecation: metadata.deprecation().map(str::to_string),
+        dist_tags: metadata.dist_tags,
+        version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// A registry whose only package was released at 2.0.0 and previewed at 3.0.0-rc.1.
+    struct Tagged;
+
+    #[async_trait]
+    impl RegistryBackend for Tagged {
+        fn url(&self) -> &str {
+            "tagged"
+        }
+
+        async fn latest_version(&self, _client: &HttpClient, _name: &str) -> DirectResult<String> {
+            Ok("2.0.0".to_string())
+        }
+
+        async fn versions(&self, _client: &HttpClient, _name: &str) -> DirectResult<Vec<String>> {
+            Ok(vec!["2.0.0".to_string(), "3.0.0-rc.1".to_string()])
+        }
+
+        async fn metadata(
+            &self,
+            _client: &HttpClient,
+            _name: &str,
+        ) -> DirectResult<PackageMetadata> {
+            Ok(PackageMetadata::default()
+                .with_release_date("2.0.0", SystemTime::UNIX_EPOCH + Duration::from_secs(60))
+                .with_dist_tag("latest", "2.0.0")
+                .with_dist_tag("next", "3.0.0-rc.1"))
+        }
+    }
+
+    #[tokio::test]
+    async fn latest_versions_are_looked_up_without_a_constraint() {
+        let info = latest_version("widget", &Tagged, &HttpClient::default())
+            .await
+            .unwrap();
+
+        assert_eq!(info.version(), "2.0.0");
+        assert_eq!(
+            info.published_at(),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(60))
+        );
+        assert_eq!(info.deprecation(), None);
+        assert_eq!(
+            info.dist_tags().get("next").map(String::as_str),
+            Some("3.0.0-rc.1")
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use crate::maintenance::MaintenanceFlag;
+use crate::UpdateKind;
+
+/// The highest risk score, which every score is capped at.
+pub const MAX_SCORE: u8 = 100;
+
+/// The libyears after which being behind adds no more to the score.
+const MAX_LIBYEARS: f64 = 4.0;
+
+/// What is known of the risk a dependency poses, which is combined into a single
+/// score from 0 (no known risk) to 100. Signals that were not checked for add
+/// nothing to the score, e.g. vulnerabilities count only when the dependencies
+/// were audited.
+///
+/// | Signal                          | Points                    |
+/// | ------------------------------- | ------------------------- |
+/// | Major, minor or patch update    | 20, 10 or 5               |
+/// | Libyears behind                 | 5 a year, up to 20        |
+/// | Vulnerability                   | 25 each                   |
+/// | Informational advisory          | 10 each                   |
+/// | Deprecated, archived or stale   | 30, 25 or 15              |
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RiskFactors {
+    update_kind: Option<UpdateKind>,
+    libyears: Option<f64>,
+    vulnerabilities: usize,
+    informational: usize,
+    flags: Vec<MaintenanceFlag>,
+}
+
+impl RiskFactors {
+    /// How far behind the latest version the dependency is.
+    pub fn with_update(mut self, update_kind: Option<UpdateKind>, libyears: Option<f64>) -> Self {
+        self.update_kind = update_kind;
+        self.libyears = libyears;
+        self
+    }
+
+    /// How many vulnerabilities, and how many informational advisories such as
+    /// unmaintained or unsound notices, the dependency has.
+    pub fn with_advisories(mut self, vulnerabilities: usize, informational: usize) -> Self {
+        self.vulnerabilities = vulnerabilities;
+        self.informational = informational;
+        self
+    }
+
+    pub fn with_flags(mut self, flags: &[MaintenanceFlag]) -> Self {
+        self.flags = flags.to_vec();
+        self
+    }
+
+    pub fn score(&self) -> u8 {
+        let update = match self.update_kind {
+            Some(UpdateKind::Major) => 20,
+            Some(UpdateKind::Minor) => 10,
+            Some(UpdateKind::Patch) => 5,
+            None => 0,
+        };
+
+        let libyears = self.libyears.map_or(0, |libyears| {
+            (libyears.clamp(0.0, MAX_LIBYEARS) * 5.0).round() as usize
+        });
+
+        let advisories = 25 * self.vulnerabilities + 10 * self.informational;
+
+        let maintenance: usize = self
+            .flags
+            .iter()
+            .map(|flag| match flag {
+                MaintenanceFlag::Deprecated => 30,
+                MaintenanceFlag::Archived => 25,
+                MaintenanceFlag::Stale => 15,
+            })
+            .sum();
+
+        (update + libyears + advisories + maintenance).min(MAX_SCORE as usize) as u8
+    }
+}
+
+/// The risk score of a whole project, which is as risky as its riskiest dependency.
+/// Returns `None` if no dependency was scored.
+pub fn project_score(scores: impl IntoIterator<Item = u8>) -> Option<u8> {
+    scores.into_iter().max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        assert_eq!(RiskFactors::default().score(), 0);
+
+        let outdated = RiskFactors::default().with_update(Some(UpdateKind::Major), Some(1.46));
+        assert_eq!(outdated.score(), 27);
+
+        let abandoned = outdated
+            .clone()
+            .with_flags(&[MaintenanceFlag::Stale, MaintenanceFlag::Archived]);
+        assert_eq!(abandoned.score(), 67);
+
+        let vulnerable = abandoned.with_advisories(2, 0);
+        assert_eq!(vulnerable.score(), MAX_SCORE);
+
+        let ancient = RiskFactors::default().with_update(Some(UpdateKind::Patch), Some(12.0));
+        assert_eq!(ancient.score(), 25);
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use flate2::read::GzDecoder;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::audit::{AuditedPackage, Vulnerability};
+use crate::client::RegistryClient;
+use crate::cvss;
+
+/// An archive of the main branch of the RustSec advisory database.
+const ADVISORY_DB_ARCHIVE: &str =
+    "https://github.com/rustsec/advisory-db/archive/refs/heads/main.tar.gz";
+
+/// How long a downloaded copy of the advisory database is used before it is
+/// downloaded again. Advisories are published a few times a week at most.
+const REFRESH_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The metadata of an advisory, which is the TOML front matter of its Markdown file.
+#[derive(Deserialize, Debug)]
+struct AdvisoryFile {
+    advisory: AdvisoryMetadata,
+
+    #[serde(default)]
+    versions: AdvisoryVersions,
+}
+
+#[derive(Deserialize, Debug)]
+struct AdvisoryMetadata {
+    id: String,
+    package: String,
+
+    #[serde(default)]
+    aliases: Vec<String>,
+
+    /// The CVSS vector of the vulnerability, which informational advisories have none of.
+    cvss: Option<String>,
+
+    /// Set for advisories that are not vulnerabilities, e.g. `unmaintained`.
+    informational: Option<String>,
+
+    /// Set, to the date of the withdrawal, for advisories that were published in error.
+    withdrawn: Option<toml::Value>,
+}
+
+/// The versions that are not affected, as Cargo version requirements. Every
+/// other version is.
+#[derive(Deserialize, Debug, Default)]
+struct AdvisoryVersions {
+    #[serde(default)]
+    patched: Vec<String>,
+
+    #[serde(default)]
+    unaffected: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Advisory {
+    metadata: AdvisoryMetadata,
+    versions: AdvisoryVersions,
+    title: Option<String>,
+}
+
+impl Advisory {
+    /// Reads an advisory from its Markdown file, which starts with its metadata
+    /// in a TOML code block, followed by its title as a heading.
+    fn parse(markdown: &str) -> Result<Self, Box<dyn Error>> {
+        let front_matter = markdown
+            .trim_start()
+            .strip_prefix("```toml")
+            .and_then(|rest| rest.split_once("```"))
+            .ok_or("the advisory has no TOML front matter")?;
+
+        let AdvisoryFile { advisory, versions } = toml::from_str(front_matter.0)?;
+
+        let title = front_matter
+            .1
+            .lines()
+            .find_map(|line| line.strip_prefix("# "))
+            .map(|title| title.trim().to_string());
+
+        Ok(Advisory {
+            metadata: advisory,
+            versions,
+            title,
+        })
+    }
+
+    /// Whether the version is affected, which is when it matches none of the
+    /// patched or unaffected requirements. Requirements that cannot be read are
+    /// ignored, so that no advisory goes unreported.
+    fn affects(&self, version: &Version) -> bool {
+        !self
+            .versions
+            .patched
+            .iter()
+            .chain(&self.versions.unaffected)
+            .filter_map(|requirement| VersionReq::parse(requirement).ok())
+            .any(|requirement| requirement.matches(version))
+    }
+
+    fn affecting(&self, package: &AuditedPackage) -> Vulnerability {
+        let vulnerability = Vulnerability::new(package, &self.metadata.id)
+            .with_aliases(self.metadata.aliases.clone())
+            .with_fixed_versions(self.versions.patched.clone());
+
+        let vulnerability = match &self.title {
+            Some(title) => vulnerability.with_summary(title),
+            None => vulnerability,
+        };
+
+        let vulnerability = match self.metadata.cvss.as_deref().and_then(cvss::base_score) {
+            Some(score) => vulnerability.with_cvss_score(score),
+            None => vulnerability,
+        };
+
+        match &self.metadata.informational {
+            Some(informational) => vulnerability.with_informational(informational),
+            None => vulnerability,
+        }
+    }
+}
+
+/// The advisories of the RustSec advisory database, by crate.
+#[derive(Debug, Default)]
+pub struct AdvisoryDatabase {
+    advisories: HashMap<String, Vec<Advisory>>,
+}
+
+impl AdvisoryDatabase {
+    /// Loads the advisory database, from the copy in the cache directory while it
+    /// is recent enough, and downloading it again otherwise. The copy is used
+    /// however old it is when the client is offline or the download fails.
+    pub async fn load(
+        client: &RegistryClient,
+        cache_dir: Option<&Path>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let cached = cache_dir.map(|dir| dir.join("advisory-db.tar.gz"));
+        let age = cached.as_deref().and_then(|path| {
+            let modified = fs::metadata(path).and_then(|metadata| metadata.modified());
+
+            SystemTime::now().duration_since(modified.ok()?).ok()
+        });
+
+        if let (Some(path), Some(age)) = (&cached, age) {
+            if client.is_offline() || age < REFRESH_AFTER {
+                return Self::from_archive(&fs::read(path)?);
+            }
+        }
+
+        if client.is_offline() {
+            return Err("the RustSec advisory database has not been downloaded yet".into());
+        }
+
+        match download(client).await {
+            Ok(archive) => {
+                if let Some(path) = &cached {
+                    // Failing to cache the advisory database only means it is
+                    // downloaded again next time
+                    let _ = save(path, &archive);
+                }
+
+                Self::from_archive(&archive)
+            }
+            Err(err) => match cached.filter(|_| age.is_some()) {
+                Some(path) => Self::from_archive(&fs::read(path)?),
+                None => Err(err),
+            },
+        }
+    }
+
+    /// Reads the advisories of crates out of a gzipped tarball of the repository.
+    fn from_archive(archive: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut tarball = tar::Archive::new(GzDecoder::new(archive));
+        let mut advisories = Vec::new();
+
+        for entry in tarball.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.into_owned();
+
+            // Paths are prefixed by the directory of the archive, e.g.
+            // `advisory-db-main/crates/hyper/RUSTSEC-2021-0078.md`
+            let is_crate_advisory = path
+                .components()
+                .nth(1)
+                .is_some_and(|dir| dir.as_os_str() == "crates")
+                && path.extension().is_some_and(|extension| extension == "md");
+
+            if !is_crate_advisory {
+                continue;
+            }
+
+            let mut markdown = String::new();
+            entry.read_to_string(&mut markdown)?;
+            advisories.push(Advisory::parse(&markdown)?);
+        }
+
+        Ok(Self::from_advisories(advisories))
+    }
+
+    fn from_advisories(advisories: impl IntoIterator<Item = Advisory>) -> Self {
+        let mut database = AdvisoryDatabase::default();
+
+        for advisory in advisories {
+            if advisory.metadata.withdrawn.is_some() {
+                continue;
+            }
+
+            database
+                .advisories
+                .entry(advisory.metadata.package.clone())
+                .or_insert_with(Vec::new)
+                .push(advisory);
+        }
+
+        database
+    }
+
+    /// The advisories that affect the packages, including the informational ones
+    /// about unmaintained or unsound crates. Only crates.io packages are covered.
+    pub fn vulnerabilities(&self, packages: &[AuditedPackage]) -> Vec<Vulnerability> {
+        packages
+            .iter()
+            .filter(|package| package.ecosystem() == "crates.io")
+            .flat_map(|package| {
+                let version = Version::parse(package.version()).ok();
+                let advisories = self.advisories.get(package.name());
+
+                advisories
+                    .into_iter()
+                    .flatten()
+                    .filter(move |advisory| {
+                        version
+                            .as_ref()
+                            .is_none_or(|version| advisory.affects(version))
+                    })
+                    .map(move |advisory| advisory.affecting(package))
+            })
+            .collect()
+    }
+}
+
+async fn download(client: &RegistryClient) -> Result<Vec<u8>, Box<dyn Error>> {
+    let archive = client
+        .http()
+        .get(ADVISORY_DB_ARCHIVE)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    Ok(archive.to_vec())
+}
+
+/// Writes the archive next to its final path first, so that concurrent runs never
+/// read a partial copy.
+fn save(path: &Path, archive: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let partial = path.with_extension(format!("{}.partial", std::process::id()));
+
+    fs::write(&partial, archive)?;
+    fs::rename(partial, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::Severity;
+
+    const ADVISORY: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-2021-0078"
+package = "hyper"
+date = "2021-07-07"
+url = "https://github.com/hyperium/hyper/security/advisories/GHSA-f3pg-qwvg-p99c"
+categories = ["http"]
+aliases = ["CVE-2021-32715", "GHSA-f3pg-qwvg-p99c"]
+cvss = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:L/A:N"
+
+[versions]
+patched = [">= 0.14.10"]
+unaffected = ["< 0.12.0"]
+```
+
+# Lenient `hyper` header parsing of `Content-Length` could allow request smuggling
+
+`hyper`'s HTTP/1 server code had a flaw that incorrectly parses and accepts requests
+with a `Content-Length` header with a prefixed plus sign.
+"#;
+
+    const UNMAINTAINED: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-2020-0036"
+package = "failure"
+date = "2020-05-02"
+informational = "unmaintained"
+
+[versions]
+patched = []
+```
+
+# failure is officially deprecated/unmaintained
+"#;
+
+    #[test]
+    fn advisories_only_affect_versions_that_are_neither_patched_nor_unaffected() {
+        let database = AdvisoryDatabase::from_advisories([
+            Advisory::parse(ADVISORY).unwrap(),
+            Advisory::parse(UNMAINTAINED).unwrap(),
+        ]);
+
+        let vulnerabilities = database.vulnerabilities(&[
+            AuditedPackage::new("hyper", "0.14.9", "crates.io"),
+            AuditedPackage::new("hyper", "0.14.10", "crates.io"),
+            AuditedPackage::new("hyper", "0.11.27", "crates.io"),
+            AuditedPackage::new("failure", "0.1.8", "crates.io"),
+            AuditedPackage::new("hyper", "0.14.9", "npm"),
+        ]);
+
+        assert_eq!(vulnerabilities.len(), 2);
+
+        assert_eq!(vulnerabilities[0].id(), "RUSTSEC-2021-0078");
+        assert_eq!(vulnerabilities[0].version(), "0.14.9");
+        assert_eq!(
+            vulnerabilities[0].aliases(),
+            ["CVE-2021-32715", "GHSA-f3pg-qwvg-p99c"]
+        );
+        assert_eq!(
+            vulnerabilities[0].summary(),
+            Some(
+                "Lenient `hyper` header parsing of `Content-Length` could allow request smuggling"
+            )
+        );
+        assert_eq!(vulnerabilities[0].fixed_versions(), [">= 0.14.10"]);
+        assert_eq!(vulnerabilities[0].informational(), None);
+        assert_eq!(vulnerabilities[0].cvss_score(), Some(5.3));
+        assert_eq!(vulnerabilities[0].severity(), Some(Severity::Medium));
+
+        assert_eq!(vulnerabilities[1].id(), "RUSTSEC-2020-0036");
+        assert_eq!(vulnerabilities[1].informational(), Some("unmaintained"));
+        assert_eq!(vulnerabilities[1].severity(), None);
+    }
+}
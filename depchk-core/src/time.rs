@@ -0,0 +1,112 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A point in time that durations, such as how long requests take, are measured from.
+/// Browsers only provide one through their performance API.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// Waits for the duration, on the timer of the runtime natively and on the timers of
+/// the browser in WASM, where there is no tokio timer.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    let _ = wasm_timer::Delay::new(duration).await;
+}
+
+/// Parses an RFC 3339 timestamp, such as the ones registries report release dates
+/// with, e.g. `2023-04-01T12:30:00.123Z` or `2023-04-01T12:30:00+02:00`.
+pub(crate) fn parse_rfc3339(timestamp: &str) -> Option<SystemTime> {
+    let (date, time) = timestamp.split_once(['T', 't', ' '])?;
+
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+
+    let offset_start = time.find(['Z', 'z', '+', '-'])?;
+    let (time, offset) = time.split_at(offset_start);
+
+    let mut time = time.splitn(3, ':');
+    let hours: i64 = time.next()?.parse().ok()?;
+    let minutes: i64 = time.next()?.parse().ok()?;
+    let seconds: f64 = time.next()?.parse().ok()?;
+
+    let offset = match offset.as_bytes()[0] {
+        b'Z' | b'z' => 0,
+        sign => {
+            let (offset_hours, offset_minutes) = offset[1..].split_once(':')?;
+            let offset = offset_hours.parse::<i64>().ok()? * 3_600
+                + offset_minutes.parse::<i64>().ok()? * 60;
+
+            if sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+    };
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let seconds_since_epoch =
+        days_from_civil(year, month, day) * 86_400 + hours * 3_600 + minutes * 60 - offset;
+
+    let since_epoch = Duration::try_from_secs_f64(seconds_since_epoch as f64 + seconds).ok()?;
+
+    Some(UNIX_EPOCH + since_epoch)
+}
+
+/// Parses a date, e.g. `2025-09-01`, as the midnight UTC it starts at.
+pub(crate) fn parse_date(date: &str) -> Option<SystemTime> {
+    match date.contains(['T', 't', ' ']) {
+        true => None,
+        false => parse_rfc3339(&format!("{}T00:00:00Z", date)),
+    }
+}
+
+/// The number of days since the Unix epoch of a date of the proleptic Gregorian
+/// calendar, from Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rfc3339() {
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z"), Some(UNIX_EPOCH));
+        assert_eq!(
+            parse_rfc3339("2024-02-29T12:34:56.500Z"),
+            Some(UNIX_EPOCH + Duration::from_millis(1_709_210_096_500))
+        );
+        assert_eq!(
+            parse_rfc3339("2024-02-29T14:34:56.000000+02:00"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_210_096))
+        );
+        assert_eq!(parse_rfc3339("yesterday"), None);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            parse_date("2024-02-29"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(parse_date("2024-02-29T00:00:00Z"), None);
+        assert_eq!(parse_date("2024-13-01"), None);
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::Dependency;
+
+/// The packages a lockfile installs and the packages each of them depends on.
+#[derive(Clone, Debug, Default)]
+pub struct LockfileGraph {
+    packages: Vec<LockedNode>,
+}
+
+#[derive(Clone, Debug)]
+struct LockedNode {
+    name: String,
+    version: String,
+    dependencies: Vec<usize>,
+}
+
+/// The packages that are only installed because the declared dependencies depend on
+/// them, each pinned to the version the lockfile locks it to.
+pub struct TransitiveDependencies<T: Dependency> {
+    dependencies: Vec<T>,
+    versions: Vec<String>,
+    parents: Vec<Vec<String>>,
+}
+
+impl LockfileGraph {
+    pub fn new() -> Self {
+        LockfileGraph::default()
+    }
+
+    /// Adds a locked package to the graph, returning the index it is known by.
+    pub fn add_package(&mut self, name: &str, version: &str) -> usize {
+        self.packages.push(LockedNode {
+            name: name.to_string(),
+            version: version.to_string(),
+            dependencies: Vec::new(),
+        });
+
+        self.packages.len() - 1
+    }
+
+    /// Records that the package at index `package` depends on the one at `dependency`.
+    pub fn add_dependency(&mut self, package: usize, dependency: usize) {
+        self.packages[package].dependencies.push(dependency);
+    }
+
+    /// The name and version of the package at the index.
+    pub fn package(&self, index: usize) -> (&str, &str) {
+        let node = &self.packages[index];
+
+        (&node.name, &node.version)
+    }
+
+    /// Walks the graph from the declared dependencies at the `direct` indices, and
+    /// returns the index of every package they pull in, along with the names of the
+    /// declared dependencies it is pulled in by. Packages that are declared themselves,
+    /// in the same version, are left out.
+    pub fn transitive(&self, direct: &[usize]) -> Vec<(usize, Vec<String>)> {
+        let direct_set: HashSet<_> = direct.iter().copied().collect();
+        let mut parents: Vec<BTreeSet<&str>> = vec![BTreeSet::new(); self.packages.len()];
+
+        for &root in direct {
+            let mut seen = HashSet::from([root]);
+            let mut pending = vec![root];
+
+            while let Some(index) = pending.pop() {
+                for &dependency in &self.packages[index].dependencies {
+                    if seen.insert(dependency) {
+                        parents[dependency].insert(&self.packages[root].name);
+                        pending.push(dependency);
+                    }
+                }
+            }
+        }
+
+        parents
+            .into_iter()
+            .enumerate()
+            .filter(|(index, parents)| !parents.is_empty() && !direct_set.contains(index))
+            .map(|(index, parents)| (index, parents.into_iter().map(str::to_string).collect()))
+            .collect()
+    }
+}
+
+impl<T: Dependency> TransitiveDependencies<T> {
+    pub fn new() -> Self {
+        TransitiveDependencies {
+            dependencies: Vec::new(),
+            versions: Vec::new(),
+            parents: Vec::new(),
+        }
+    }
+
+    /// Adds a transitive dependency, whose constraint only allows its locked `version`,
+    /// along with the names of the declared dependencies that pull it in.
+    pub fn push(&mut self, dependency: T, version: &str, parents: Vec<String>) {
+        self.dependencies.push(dependency);
+        self.versions.push(version.to_string());
+        self.parents.push(parents);
+    }
+
+    /// The transitive dependencies, pinned to their locked versions.
+    pub fn dependencies(&self) -> &[T] {
+        &self.dependencies
+    }
+
+    pub fn len(&self) -> usize {
+        self.dependencies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dependencies.is_empty()
+    }
+
+    /// The locked version of the transitive dependency with the given name and
+    /// constraint, as found in a mismatch.
+    pub fn version(&self, name: &str, constraint: &str) -> Option<&str> {
+        self.position(name, constraint)
+            .map(|index| self.versions[index].as_str())
+    }
+
+    /// The declared dependencies that pull in the transitive dependency with the
+    /// given name and locked version.
+    pub fn parents(&self, name: &str, version: &str) -> &[String] {
+        self.dependencies
+            .iter()
+            .zip(&self.versions)
+            .position(|(dependency, locked)| dependency.get_name() == name && locked == version)
+            .map_or(&[], |index| &self.parents[index])
+    }
+
+    /// Only keeps the parents that `keep` returns true for, dropping the transitive
+    /// dependencies that none of their parents are kept for.
+    pub fn retain_parents(&mut self, keep: impl Fn(&str) -> bool) {
+        for parents in &mut self.parents {
+            parents.retain(|parent| keep(parent));
+        }
+
+        let mut index = 0;
+
+        while index < self.parents.len() {
+            match self.parents[index].is_empty() {
+                true => {
+                    self.dependencies.remove(index);
+                    self.versions.remove(index);
+                    self.parents.remove(index);
+                }
+                false => index += 1,
+            }
+        }
+    }
+
+    fn position(&self, name: &str, constraint: &str) -> Option<usize> {
+        self.dependencies.iter().position(|dependency| {
+            dependency.get_name() == name && dependency.get_constraint() == constraint
+        })
+    }
+}
+
+impl<T: Dependency> Default for TransitiveDependencies<T> {
+    fn default() -> Self {
+        TransitiveDependencies::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitive() {
+        let mut graph = LockfileGraph::new();
+
+        let reqwest = graph.add_package("reqwest", "0.11.14");
+        let hyper = graph.add_package("hyper", "0.14.27");
+        let tokio = graph.add_package("tokio", "1.25.0");
+        let bytes = graph.add_package("bytes", "1.4.0");
+
+        graph.add_dependency(reqwest, hyper);
+        graph.add_dependency(reqwest, tokio);
+        graph.add_dependency(hyper, tokio);
+        graph.add_dependency(hyper, bytes);
+        graph.add_dependency(tokio, bytes);
+
+        assert_eq!(
+            graph.transitive(&[reqwest, tokio]),
+            vec![
+                (hyper, vec!["reqwest".to_string()]),
+                (bytes, vec!["reqwest".to_string(), "tokio".to_string()]),
+            ]
+        );
+        assert_eq!(graph.package(bytes), ("bytes", "1.4.0"));
+    }
+}
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::{Client, IntoUrl, Request, RequestBuilder, Response};
+
+#[cfg(feature = "tracing")]
+use crate::time::Instant;
+use crate::DirectResult;
+
+/// Sends the requests dependencies and registries make, so that they can be answered
+/// by something other than the network, such as mocks or recorded responses, without
+/// spinning up a local server. [`reqwest::Client`] sends them over the network.
+///
+/// ```
+/// # use async_trait::async_trait;
+/// # use depchk_core::transport::HttpTransport;
+/// # use depchk_core::DepchkError;
+/// # use reqwest::{Request, Response};
+/// /// Answers every request with the same body.
+/// struct Canned(&'static str);
+///
+/// #[async_trait]
+/// impl HttpTransport for Canned {
+///     async fn execute(&self, _request: Request) -> Result<Response, DepchkError> {
+///         Ok(Response::from(http::Response::new(self.0)))
+///     }
+/// }
+/// ```
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait HttpTransport: Send + Sync {
+    async fn execute(&self, request: Request) -> DirectResult<Response>;
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl HttpTransport for Client {
+    async fn execute(&self, request: Request) -> DirectResult<Response> {
+        Ok(Client::execute(self, request).await?)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<T: HttpTransport + ?Sized> HttpTransport for Arc<T> {
+    async fn execute(&self, request: Request) -> DirectResult<Response> {
+        (**self).execute(request).await
+    }
+}
+
+/// The HTTP client lookups are made with. Requests are built with a [`reqwest::Client`]
+/// and sent through its transport, which is the same client unless another one is set.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: Client,
+    transport: Arc<dyn HttpTransport>,
+}
+
+impl HttpClient {
+    pub fn new(client: Client) -> Self {
+        HttpClient {
+            transport: Arc::new(client.clone()),
+            client,
+        }
+    }
+
+    /// Sends every request through the transport instead of over the network.
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// The client requests are built with, for APIs that are not looked up through
+    /// the transport.
+    pub fn inner(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn get(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    /// Builds the request and sends it through the transport.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "request", level = "debug", skip_all, fields(url = tracing::field::Empty))
+    )]
+    pub async fn send(&self, request: RequestBuilder) -> DirectResult<Response> {
+        let request = request.build()?;
+
+        #[cfg(feature = "tracing")]
+        let started = {
+            tracing::Span::current().record("url", request.url().as_str());
+            Instant::now()
+        };
+
+        let response = self.transport.execute(request).await;
+
+        #[cfg(feature = "tracing")]
+        match &response {
+            Ok(response) => tracing::debug!(
+                status = response.status().as_u16(),
+                elapsed = ?started.elapsed(),
+                "received response"
+            ),
+            Err(err) => tracing::debug!(error = %err, "request failed"),
+        }
+
+        response
+    }
+}
+
+impl From<Client> for HttpClient {
+    fn from(client: Client) -> Self {
+        HttpClient::new(client)
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        HttpClient::new(Client::new())
+    }
+}
+
+#[cfg(all(test, feature = "npm"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::npm::NpmDependency;
+    use crate::Dependency;
+
+    /// Answers every request with the same packument, remembering what was requested.
+    #[derive(Default)]
+    struct Recorded {
+        requested: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl HttpTransport for Recorded {
+        async fn execute(&self, request: Request) -> DirectResult<Response> {
+            self.requested
+                .lock()
+                .unwrap()
+                .push(request.url().to_string());
+
+            let packument = r#"{"dist-tags": {"latest": "1.6.2"}, "versions": {}}"#;
+
+            Ok(Response::from(http::Response::new(packument)))
+        }
+    }
+
+    #[tokio::test]
+    async fn lookups_are_sent_through_the_transport() {
+        let transport = Arc::new(Recorded::default());
+        let client = HttpClient::default().with_transport(Arc::clone(&transport));
+
+        let dependency = NpmDependency::new("axios", "^0.21");
+
+        assert_eq!(
+            dependency.fetch_latest_version(&client).await.unwrap(),
+            "1.6.2"
+        );
+        assert_eq!(
+            *transport.requested.lock().unwrap(),
+            ["https://registry.npmjs.org/axios"]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+/// The most downloaded packages of each registry, one name per line, most
+/// popular first.
+const POPULAR_NPM: &str = include_str!("popular/npm.txt");
+const POPULAR_CRATES: &str = include_str!("popular/crates.txt");
+
+/// Names shorter than this are not compared, as almost any other short name is a
+/// single edit away from them.
+const MIN_LENGTH: usize = 5;
+
+/// Names at least this long may be two edits away from a popular package.
+const TWO_EDITS_LENGTH: usize = 9;
+
+fn popular_packages(ecosystem: &str) -> &'static str {
+    match ecosystem {
+        "npm" => POPULAR_NPM,
+        "crates.io" => POPULAR_CRATES,
+        _ => "",
+    }
+}
+
+/// crates.io treats `-` and `_` as the same character, so `serde-json` is
+/// `serde_json` rather than a lookalike of it.
+fn normalize(name: &str, ecosystem: &str) -> String {
+    match ecosystem {
+        "crates.io" => name.to_ascii_lowercase().replace('-', "_"),
+        _ => name.to_ascii_lowercase(),
+    }
+}
+
+/// The popular package of the ecosystem that the name looks like a misspelling
+/// of, e.g. `lodash` for `lodahs`, or `None` if the name is a popular package
+/// itself or is not close to any of them.
+pub fn lookalike(name: &str, ecosystem: &str) -> Option<&'static str> {
+    let popular = popular_packages(ecosystem);
+    let name = normalize(name, ecosystem);
+
+    if popular
+        .lines()
+        .any(|package| normalize(package, ecosystem) == name)
+    {
+        return None;
+    }
+
+    popular.lines().find(|package| {
+        let package_name = normalize(package, ecosystem);
+        let max_distance = match package_name.len() {
+            length if length < MIN_LENGTH => return false,
+            length if length < TWO_EDITS_LENGTH => 1,
+            _ => 2,
+        };
+
+        length_gap(&name, &package_name) <= max_distance
+            && edit_distance(&name, &package_name) <= max_distance
+    })
+}
+
+fn length_gap(a: &str, b: &str) -> usize {
+    a.chars().count().abs_diff(b.chars().count())
+}
+
+/// The optimal string alignment distance between two names: the number of
+/// insertions, deletions, substitutions and transpositions of adjacent characters
+/// that turn one into the other, without editing any character twice.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Only the last three rows of the distance matrix are needed
+    let mut before_previous = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("lodash", "lodash"), 0);
+        assert_eq!(edit_distance("lodahs", "lodash"), 1);
+        assert_eq!(edit_distance("lodas", "lodash"), 1);
+        assert_eq!(edit_distance("1odash", "lodash"), 1);
+        assert_eq!(edit_distance("expresss", "express"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_lookalike() {
+        assert_eq!(lookalike("lodahs", "npm"), Some("lodash"));
+        assert_eq!(lookalike("reqeusts", "npm"), None);
+        assert_eq!(lookalike("lodash", "npm"), None);
+        assert_eq!(lookalike("left-pad", "npm"), None);
+        assert_eq!(lookalike("crossenv", "npm"), Some("cross-env"));
+
+        assert_eq!(lookalike("serde-json", "crates.io"), None);
+        assert_eq!(lookalike("serde_jsno", "crates.io"), Some("serde_json"));
+        assert_eq!(lookalike("tokoi", "crates.io"), Some("tokio"));
+
+        // Popular packages of one registry are not lookalikes on another
+        assert_eq!(lookalike("lodahs", "crates.io"), None);
+        assert_eq!(lookalike("lodahs", "PyPI"), None);
+    }
+}
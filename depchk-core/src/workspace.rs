@@ -0,0 +1,183 @@
+use crate::{Dependency, ProjectDependencies};
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+/// A single package that is part of a workspace (or monorepo),
+/// along with the dependencies it declares.
+pub struct WorkspacePackage<T: Dependency> {
+    name: String,
+    dependencies: ProjectDependencies<T>,
+}
+
+/// An external dependency that is declared with conflicting
+/// version constraints by two or more packages of the same workspace.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VersionSkew {
+    name: String,
+    constraints: Vec<SkewedConstraint>,
+}
+
+/// The constraint that a single workspace package declares
+/// for a skewed dependency.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SkewedConstraint {
+    package: String,
+    constraint: String,
+}
+
+impl<T: Dependency> WorkspacePackage<T> {
+    pub fn new(name: &str, dependencies: ProjectDependencies<T>) -> Self {
+        WorkspacePackage {
+            name: name.to_string(),
+            dependencies,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn dependencies(&self) -> &ProjectDependencies<T> {
+        &self.dependencies
+    }
+
+    pub fn dependencies_mut(&mut self) -> &mut ProjectDependencies<T> {
+        &mut self.dependencies
+    }
+}
+
+impl VersionSkew {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn constraints(&self) -> &[SkewedConstraint] {
+        &self.constraints
+    }
+}
+
+impl SkewedConstraint {
+    pub fn package(&self) -> &str {
+        &self.package
+    }
+
+    pub fn constraint(&self) -> &str {
+        &self.constraint
+    }
+}
+
+/// Finds every external dependency for which at least two workspace packages
+/// declare constraints that cannot be satisfied by the same version. Dependencies
+/// on other packages of the workspace are ignored, since those are resolved locally.
+///
+#[cfg_attr(feature = "npm", doc = "```")]
+#[cfg_attr(not(feature = "npm"), doc = "```ignore")]
+/// # use depchk_core::npm::NpmDependency;
+/// # use depchk_core::workspace::{find_version_skew, WorkspacePackage};
+/// # use depchk_core::ProjectDependencies;
+///
+/// let packages = vec![
+///     WorkspacePackage::new("web", ProjectDependencies::from(vec![NpmDependency::new("react", "^17")])),
+///     WorkspacePackage::new("docs", ProjectDependencies::from(vec![NpmDependency::new("react", "^18")])),
+/// ];
+///
+/// let skew = find_version_skew(&packages);
+///
+/// assert_eq!(skew.len(), 1);
+/// assert_eq!(skew[0].name(), "react");
+/// ```
+pub fn find_version_skew<T: Dependency>(packages: &[WorkspacePackage<T>]) -> Vec<VersionSkew> {
+    let internal: HashSet<&str> = packages.iter().map(|package| package.name()).collect();
+    let mut declared: BTreeMap<&str, Vec<(&str, &T)>> = BTreeMap::new();
+
+    for package in packages {
+        for dependency in package.dependencies.iter() {
+            if internal.contains(dependency.get_name()) {
+                continue;
+            }
+
+            declared
+                .entry(dependency.get_name())
+                .or_default()
+                .push((package.name(), dependency));
+        }
+    }
+
+    declared
+        .into_iter()
+        .filter(|(_, declarations)| has_conflict(declarations))
+        .map(|(name, declarations)| VersionSkew {
+            name: name.to_string(),
+            constraints: declarations
+                .into_iter()
+                .map(|(package, dependency)| SkewedConstraint {
+                    package: package.to_string(),
+                    constraint: dependency.get_constraint().to_string(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn has_conflict<T: Dependency>(declarations: &[(&str, &T)]) -> bool {
+    declarations.iter().enumerate().any(|(i, (_, dependency))| {
+        declarations[i + 1..]
+            .iter()
+            .any(|(_, other)| !dependency.is_compatible_with(other))
+    })
+}
+
+#[cfg(all(test, feature = "npm"))]
+mod tests {
+    use super::*;
+    use crate::npm::NpmDependency;
+
+    fn package(name: &str, dependencies: &[(&str, &str)]) -> WorkspacePackage<NpmDependency> {
+        let dependencies = dependencies
+            .iter()
+            .map(|(name, version)| NpmDependency::new(name, version))
+            .collect::<Vec<_>>();
+
+        WorkspacePackage::new(name, ProjectDependencies::from(dependencies))
+    }
+
+    #[test]
+    fn overlapping_constraints_are_not_skewed() {
+        let packages = vec![
+            package("web", &[("react", "^17.0.1")]),
+            package("docs", &[("react", "^17.0.2")]),
+        ];
+
+        assert!(find_version_skew(&packages).is_empty());
+    }
+
+    #[test]
+    fn conflicting_constraints_report_every_declaration() {
+        let packages = vec![
+            package("web", &[("react", "^17"), ("axios", "^1")]),
+            package("docs", &[("react", "^18")]),
+            package("admin", &[("react", "^17.0.2")]),
+        ];
+
+        let skew = find_version_skew(&packages);
+
+        assert_eq!(skew.len(), 1);
+        assert_eq!(skew[0].name(), "react");
+        assert_eq!(skew[0].constraints().len(), 3);
+        assert_eq!(skew[0].constraints()[1].package(), "docs");
+        assert_eq!(skew[0].constraints()[1].constraint(), "^18");
+    }
+
+    #[test]
+    fn internal_dependencies_are_ignored() {
+        let packages = vec![
+            package("web", &[("ui", "^1")]),
+            package("docs", &[("ui", "^2")]),
+            package("ui", &[]),
+        ];
+
+        assert!(find_version_skew(&packages).is_empty());
+    }
+}
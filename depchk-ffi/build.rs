@@ -0,0 +1,22 @@
+fn main() {
+    #[cfg(feature = "header")]
+    generate_header();
+}
+
+/// Writes the C header of the bindings to include/depchk.h, so that the committed one
+/// always matches the C API. CI builds with the feature and fails on a diff.
+#[cfg(feature = "header")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("the C API could not be turned into a header")
+        .write_to_file(format!("{}/include/depchk.h", crate_dir));
+}
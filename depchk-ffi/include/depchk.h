@@ -0,0 +1,60 @@
+#ifndef DEPCHK_H
+#define DEPCHK_H
+
+/* Generated by cbindgen from depchk-ffi/src/lib.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Whether a check succeeded. Unless the arguments are invalid, JSON is written to
+// the output either way, holding the report or an `error` message.
+typedef enum DepchkStatus {
+  DEPCHK_STATUS_OK = 0,
+  DEPCHK_STATUS_INVALID_ARGUMENT = 1,
+  DEPCHK_STATUS_FAILED = 2,
+} DepchkStatus;
+
+// How a manifest is checked. Fields left zeroed, or NULL, keep their defaults.
+typedef struct DepchkOptions {
+  // The maximum number of registry lookups that are run at the same time.
+  uint32_t concurrency;
+  // How long a single registry request may take, in milliseconds.
+  uint64_t timeout_ms;
+  // The URL of the registry npm dependencies are resolved from instead of the
+  // default one.
+  const char *registry;
+} DepchkOptions;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Checks the dependencies of a package.json or Cargo.toml file against their
+// registries, writing the JSON report to `out_json`. The report holds the outcome of
+// the `dependencies` and the `dev_dependencies`, each split into the `up_to_date`,
+// `outdated`, `skipped` and `errored` ones, along with the `warnings` about entries
+// of the file that were left out.
+//
+// # Safety
+//
+// `path` must be a NUL-terminated string, `options` NULL or a valid pointer, and
+// `out_json` a valid pointer. The string written to `out_json` must be freed with
+// [`depchk_string_free`].
+enum DepchkStatus depchk_check_manifest(const char *path,
+                                        const struct DepchkOptions *options,
+                                        char **out_json);
+
+// Frees a string written by [`depchk_check_manifest`]. Does nothing for NULL.
+//
+// # Safety
+//
+// `json` must be NULL or a string written by depchk, which was not freed yet.
+void depchk_string_free(char *json);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* DEPCHK_H */
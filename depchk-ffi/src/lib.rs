@@ -0,0 +1,233 @@
+//! A C API around depchk-core, so that editors and tools not written in Rust can check
+//! dependency files and read the report as JSON. The header, include/depchk.h, is
+//! generated from this file with cbindgen. With the `python` feature, the library is
+//! also the `depchk` Python module.
+
+#[cfg(feature = "python")]
+mod python;
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::ptr;
+use std::time::Duration;
+
+use depchk_core::blocking::Checker;
+use depchk_core::cargo::CargoToml;
+use depchk_core::client::Registry;
+use depchk_core::npm::PackageJson;
+use depchk_core::options::CheckOptions;
+use depchk_core::{CheckReport, DepchkError, DependencyError, DependencyFileParser};
+use serde_json::{json, Value};
+
+/// How a manifest is checked. Fields left zeroed, or NULL, keep their defaults.
+#[repr(C)]
+pub struct DepchkOptions {
+    /// The maximum number of registry lookups that are run at the same time.
+    pub concurrency: u32,
+
+    /// How long a single registry request may take, in milliseconds.
+    pub timeout_ms: u64,
+
+    /// The URL of the registry npm dependencies are resolved from instead of the
+    /// default one.
+    pub registry: *const c_char,
+}
+
+/// Whether a check succeeded. Unless the arguments are invalid, JSON is written to
+/// the output either way, holding the report or an `error` message.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum DepchkStatus {
+    Ok = 0,
+    InvalidArgument = 1,
+    Failed = 2,
+}
+
+/// Checks the dependencies of a package.json or Cargo.toml file against their
+/// registries, writing the JSON report to `out_json`. The report holds the outcome of
+/// the `dependencies` and the `dev_dependencies`, each split into the `up_to_date`,
+/// `outdated`, `skipped` and `errored` ones, along with the `warnings` about entries
+/// of the file that were left out.
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string, `options` NULL or a valid pointer, and
+/// `out_json` a valid pointer. The string written to `out_json` must be freed with
+/// [`depchk_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn depchk_check_manifest(
+    path: *const c_char,
+    options: *const DepchkOptions,
+    out_json: *mut *mut c_char,
+) -> DepchkStatus {
+    if path.is_null() || out_json.is_null() {
+        return DepchkStatus::InvalidArgument;
+    }
+
+    let (status, json) = match CStr::from_ptr(path).to_str() {
+        Ok(path) => {
+            let checked = panic::catch_unwind(AssertUnwindSafe(|| {
+                check_manifest(path, check_options(options.as_ref())?)
+            }));
+
+            match checked {
+                Ok(Ok(report)) => (DepchkStatus::Ok, report),
+                Ok(Err(err)) => (DepchkStatus::Failed, json!({ "error": err.to_string() })),
+                Err(_) => (
+                    DepchkStatus::Failed,
+                    json!({ "error": "the check panicked" }),
+                ),
+            }
+        }
+        Err(_) => (
+            DepchkStatus::InvalidArgument,
+            json!({ "error": "the path is not valid UTF-8" }),
+        ),
+    };
+
+    *out_json = CString::new(json.to_string())
+        .map(CString::into_raw)
+        .unwrap_or(ptr::null_mut());
+
+    status
+}
+
+/// Frees a string written by [`depchk_check_manifest`]. Does nothing for NULL.
+///
+/// # Safety
+///
+/// `json` must be NULL or a string written by depchk, which was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn depchk_string_free(json: *mut c_char) {
+    if !json.is_null() {
+        drop(CString::from_raw(json));
+    }
+}
+
+/// The name of the dependency file, if it is one that can be checked.
+fn manifest_name(path: &str) -> Result<&str, DepchkError> {
+    match Path::new(path).file_name().and_then(|name| name.to_str()) {
+        Some(name @ ("package.json" | "Cargo.toml")) => Ok(name),
+        _ => Err(format!("{} is neither a package.json nor a Cargo.toml file", path).into()),
+    }
+}
+
+/// The dependencies of the dependency file as JSON, along with the warnings about
+/// entries that were left out.
+#[cfg(feature = "python")]
+fn parse_manifest(path: &str) -> Result<Value, DepchkError> {
+    let dependencies = match manifest_name(path)? {
+        "package.json" => serde_json::to_value(PackageJson::parse_file(path)?)?,
+        _ => serde_json::to_value(CargoToml::parse_file(path)?)?,
+    };
+
+    Ok(dependencies)
+}
+
+fn check_manifest(path: &str, options: CheckOptions) -> Result<Value, DepchkError> {
+    let name = manifest_name(path)?;
+    let checker = Checker::new(options)?;
+
+    match name {
+        "package.json" => check_file::<PackageJson>(&checker, path),
+        _ => check_file::<CargoToml>(&checker, path),
+    }
+}
+
+fn check_options(options: Option<&DepchkOptions>) -> Result<CheckOptions, DepchkError> {
+    let mut check_options = CheckOptions::new();
+
+    let Some(options) = options else {
+        return Ok(check_options);
+    };
+
+    if options.concurrency > 0 {
+        check_options = check_options.with_concurrency(options.concurrency as usize);
+    }
+
+    if options.timeout_ms > 0 {
+        check_options = check_options.with_timeout(Duration::from_millis(options.timeout_ms));
+    }
+
+    if !options.registry.is_null() {
+        // SAFETY: the caller of depchk_check_manifest guarantees the pointer is valid
+        let registry = unsafe { CStr::from_ptr(options.registry) }
+            .to_str()
+            .map_err(|_| "the registry is not valid UTF-8")?;
+
+        check_options = check_options.with_registry(Registry::new(registry));
+    }
+
+    Ok(check_options)
+}
+
+fn check_file<P: DependencyFileParser>(
+    checker: &Checker,
+    path: &str,
+) -> Result<Value, DepchkError> {
+    let dependencies = checker.parse_file::<P>(path)?;
+
+    Ok(json!({
+        "dependencies": report_json(checker.check(dependencies.dependencies())),
+        "dev_dependencies": report_json(checker.check(dependencies.dev_dependencies())),
+        "warnings": dependencies.warnings(),
+    }))
+}
+
+fn report_json(report: CheckReport) -> Value {
+    json!({
+        "up_to_date": report.up_to_date,
+        "outdated": report.outdated,
+        "skipped": errors_json(&report.skipped),
+        "errored": errors_json(&report.errored),
+    })
+}
+
+fn errors_json(errors: &[DependencyError]) -> Value {
+    errors
+        .iter()
+        .map(|err| json!({ "name": err.name(), "error": err.error().to_string() }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn check(path: &str) -> (DepchkStatus, Value) {
+        let path = CString::new(path).unwrap();
+        let mut json = ptr::null_mut();
+
+        let status = depchk_check_manifest(path.as_ptr(), ptr::null(), &mut json);
+        let report = serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+
+        depchk_string_free(json);
+
+        (status, report)
+    }
+
+    #[test]
+    fn failures_are_reported_as_json() {
+        let (status, report) = unsafe { check("requirements.txt") };
+
+        assert_eq!(status, DepchkStatus::Failed);
+        assert_eq!(
+            report["error"],
+            "requirements.txt is neither a package.json nor a Cargo.toml file"
+        );
+
+        let (status, report) = unsafe { check("does/not/exist/package.json") };
+
+        assert_eq!(status, DepchkStatus::Failed);
+        assert!(report["error"].is_string());
+    }
+
+    #[test]
+    fn null_arguments_are_rejected() {
+        let status =
+            unsafe { depchk_check_manifest(ptr::null(), ptr::null(), &mut ptr::null_mut()) };
+
+        assert_eq!(status, DepchkStatus::InvalidArgument);
+    }
+}
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use depchk_core::client::Registry;
+use depchk_core::options::CheckOptions;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde_json::Value;
+
+use crate::{check_manifest, parse_manifest};
+
+create_exception!(depchk, DepchkError, PyException);
+
+/// Parses a package.json or Cargo.toml file into a dict of its `dependencies` and
+/// `dev_dependencies`, each a list of dicts with their `name` and `constraint`.
+#[pyfunction]
+fn parse(py: Python<'_>, path: &str) -> PyResult<Py<PyAny>> {
+    let dependencies = parse_manifest(path).map_err(|err| DepchkError::new_err(err.to_string()))?;
+
+    Ok(to_python(py, &dependencies)?.unbind())
+}
+
+/// Checks the dependencies of a package.json or Cargo.toml file against their
+/// registries, returning the same report as `depchk_check_manifest` of the C API as a
+/// dict. The timeout of each registry request is given in seconds.
+#[pyfunction]
+#[pyo3(signature = (path, *, concurrency=None, timeout=None, registry=None))]
+fn check(
+    py: Python<'_>,
+    path: &str,
+    concurrency: Option<usize>,
+    timeout: Option<f64>,
+    registry: Option<&str>,
+) -> PyResult<Py<PyAny>> {
+    let mut options = CheckOptions::new();
+
+    if let Some(concurrency) = concurrency {
+        options = options.with_concurrency(concurrency);
+    }
+
+    if let Some(timeout) = timeout {
+        let timeout = Duration::try_from_secs_f64(timeout)
+            .map_err(|err| DepchkError::new_err(format!("invalid timeout: {}", err)))?;
+
+        options = options.with_timeout(timeout);
+    }
+
+    if let Some(registry) = registry {
+        options = options.with_registry(Registry::new(registry));
+    }
+
+    // Other Python threads can run while the registries are waited on
+    let report = py
+        .detach(|| check_manifest(path, options))
+        .map_err(|err| DepchkError::new_err(err.to_string()))?;
+
+    Ok(to_python(py, &report)?.unbind())
+}
+
+fn to_python<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    let object = match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(value) => value.into_pyobject(py)?.to_owned().into_any(),
+        Value::Number(number) => match (number.as_i64(), number.as_u64()) {
+            (Some(number), _) => number.into_pyobject(py)?.into_any(),
+            (None, Some(number)) => number.into_pyobject(py)?.into_any(),
+            _ => number.as_f64().into_pyobject(py)?.into_any(),
+        },
+        Value::String(value) => value.into_pyobject(py)?.into_any(),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| to_python(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+
+            PyList::new(py, items)?.into_any()
+        }
+        Value::Object(entries) => {
+            let dict = PyDict::new(py);
+
+            for (key, value) in entries {
+                dict.set_item(key, to_python(py, value)?)?;
+            }
+
+            dict.into_any()
+        }
+    };
+
+    Ok(object)
+}
+
+#[pymodule]
+#[pyo3(name = "depchk")]
+fn depchk(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_function(wrap_pyfunction!(check, m)?)?;
+    m.add("DepchkError", m.py().get_type::<DepchkError>())?;
+
+    Ok(())
+}
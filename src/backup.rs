@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::LOCKFILES;
+
+/// Where the files a fix changes are saved, next to the dependency file.
+const BACKUP_DIRECTORY: &str = ".depchk/backup";
+
+/// The files a fix may change: the dependency file, and the lockfiles next to it that
+/// `--sync-lockfile` updates. Only the ones that exist are listed.
+fn fixed_files(file_name: &str) -> Result<(PathBuf, Vec<PathBuf>), Box<dyn Error>> {
+    let path = Path::new(file_name);
+    let directory = path
+        .parent()
+        .filter(|directory| !directory.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    let mut files = vec![path.file_name().ok_or("the dependency file has no name")?];
+
+    files.extend(
+        LOCKFILES
+            .iter()
+            .map(|lockfile| Path::new(lockfile).as_os_str()),
+    );
+
+    let files = files
+        .into_iter()
+        .map(|file| directory.join(file))
+        .filter(|file| file.exists())
+        .collect();
+
+    Ok((directory.join(BACKUP_DIRECTORY), files))
+}
+
+/// Saves the dependency file and its lockfiles before a fix changes them, replacing
+/// the backup of the previous fix. The backup directory ignores itself, so that it is
+/// never committed.
+pub fn save(file_name: &str) -> Result<(), Box<dyn Error>> {
+    let (backup, files) = fixed_files(file_name)?;
+
+    if backup.exists() {
+        fs::remove_dir_all(&backup)?;
+    }
+
+    fs::create_dir_all(&backup)?;
+
+    if let Some(depchk) = backup.parent() {
+        fs::write(depchk.join(".gitignore"), "*\n")?;
+    }
+
+    for file in files {
+        if let Some(name) = file.file_name() {
+            fs::copy(&file, backup.join(name))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Restores the dependency file and its lockfiles as they were before the last fix,
+/// and removes the backup. Returns the restored files.
+pub fn restore(file_name: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let (backup, _) = fixed_files(file_name)?;
+    let name = Path::new(file_name)
+        .file_name()
+        .ok_or("the dependency file has no name")?;
+
+    if !backup.join(name).exists() {
+        return Err(format!("there is no fix of {} to undo", file_name).into());
+    }
+
+    let directory = backup
+        .parent()
+        .and_then(Path::parent)
+        .ok_or("the backup is not next to the dependency file")?;
+
+    let mut restored = Vec::new();
+
+    for entry in fs::read_dir(&backup)? {
+        let entry = entry?;
+        let destination = directory.join(entry.file_name());
+
+        fs::copy(entry.path(), &destination)?;
+        restored.push(destination);
+    }
+
+    fs::remove_dir_all(&backup)?;
+    restored.sort();
+
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_restore() {
+        let directory = std::env::temp_dir().join(format!("depchk-backup-{}", std::process::id()));
+        let manifest = directory.join("package.json");
+        let lockfile = directory.join("package-lock.json");
+        let file_name = manifest.to_str().unwrap();
+
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(&manifest, r#"{"dependencies":{"axios":"^0.21.0"}}"#).unwrap();
+        fs::write(&lockfile, "{}").unwrap();
+
+        assert!(restore(file_name).is_err());
+
+        save(file_name).unwrap();
+        fs::write(&manifest, r#"{"dependencies":{"axios":"^1.6.2"}}"#).unwrap();
+        fs::write(&lockfile, r#"{"lockfileVersion":3}"#).unwrap();
+
+        assert_eq!(
+            restore(file_name).unwrap(),
+            [lockfile.clone(), manifest.clone()]
+        );
+        assert_eq!(
+            fs::read_to_string(&manifest).unwrap(),
+            r#"{"dependencies":{"axios":"^0.21.0"}}"#
+        );
+        assert_eq!(fs::read_to_string(&lockfile).unwrap(), "{}");
+        assert!(restore(file_name).is_err());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,145 @@
+use std::error::Error;
+use std::fmt::Write;
+use std::path::Path;
+use std::process::Command;
+
+use crate::{UpdateChoice, LOCKFILES};
+
+/// The message of a commit that makes the updates, following Conventional Commits
+/// the way Dependabot does, e.g. `chore(deps): bump axios from 0.21.4 to 1.6.2`.
+/// Updates of several packages are listed in the body, and the scope is `deps-dev`
+/// when they are all dev dependencies.
+pub fn commit_message(choices: &[UpdateChoice]) -> String {
+    let scope = match choices.iter().all(|choice| choice.update.is_dev()) {
+        true => "deps-dev",
+        false => "deps",
+    };
+
+    let bump = |choice: &UpdateChoice| {
+        format!(
+            "{} from {} to {}",
+            choice.update.name(),
+            choice.current,
+            choice.latest
+        )
+    };
+
+    let group = choices.first().and_then(|choice| choice.group.as_deref());
+
+    let mut message = match (choices, group) {
+        ([choice], _) => return format!("chore({}): bump {}", scope, bump(choice)),
+        (_, Some(group)) => format!(
+            "chore({}): bump the {} group with {} updates\n\n",
+            scope,
+            group,
+            choices.len()
+        ),
+        _ => format!("chore({}): bump {} dependencies\n\n", scope, choices.len()),
+    };
+
+    for choice in choices {
+        let _ = writeln!(message, "Bumps {}.", bump(choice));
+    }
+
+    message
+}
+
+/// Commits the dependency file with git, along with the lockfiles next to it. Only
+/// these files are committed, whatever else is staged.
+pub fn commit(file_name: &str, message: &str) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(file_name);
+    let directory = path
+        .parent()
+        .filter(|directory| !directory.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    let mut files = vec![path.file_name().ok_or("the dependency file has no name")?];
+
+    files.extend(
+        LOCKFILES
+            .iter()
+            .map(Path::new)
+            .filter(|lockfile| directory.join(lockfile).exists())
+            .map(Path::as_os_str),
+    );
+
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .current_dir(directory)
+            .args(args)
+            .arg("--")
+            .args(&files)
+            .status()
+            .map_err(|err| format!("could not run git: {}", err))?;
+
+        match status.success() {
+            true => Ok(()),
+            false => Err(format!("git could not commit the updates ({})", status)),
+        }
+    };
+
+    git(&["add"])?;
+    git(&["commit", "--quiet", "--message", message])?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use depchk_core::fix::ConstraintUpdate;
+
+    use super::*;
+
+    fn choice(name: &str, dev: bool, current: &str, latest: &str) -> UpdateChoice {
+        UpdateChoice {
+            update: ConstraintUpdate::new(name, dev, &format!("^{}", latest)),
+            declared: format!("^{}", current),
+            current: current.to_string(),
+            latest: latest.to_string(),
+            update_kind: None,
+            group: None,
+        }
+    }
+
+    #[test]
+    fn test_commit_message() {
+        assert_eq!(
+            commit_message(&[choice("axios", false, "0.12.0", "1.7.2")]),
+            "chore(deps): bump axios from 0.12.0 to 1.7.2"
+        );
+        assert_eq!(
+            commit_message(&[choice("jest", true, "28.1.3", "29.7.0")]),
+            "chore(deps-dev): bump jest from 28.1.3 to 29.7.0"
+        );
+
+        let mut babel = vec![
+            choice("@babel/core", true, "7.22.0", "7.23.5"),
+            choice("@babel/preset-env", true, "7.22.0", "7.23.5"),
+        ];
+
+        for choice in &mut babel {
+            choice.group = Some("babel".to_string());
+        }
+
+        assert_eq!(
+            commit_message(&babel),
+            concat!(
+                "chore(deps-dev): bump the babel group with 2 updates\n\n",
+                "Bumps @babel/core from 7.22.0 to 7.23.5.\n",
+                "Bumps @babel/preset-env from 7.22.0 to 7.23.5.\n",
+            )
+        );
+
+        assert_eq!(
+            commit_message(&[
+                choice("axios", false, "0.12.0", "1.7.2"),
+                choice("jest", true, "28.1.3", "29.7.0"),
+            ]),
+            concat!(
+                "chore(deps): bump 2 dependencies\n\n",
+                "Bumps axios from 0.12.0 to 1.7.2.\n",
+                "Bumps jest from 28.1.3 to 29.7.0.\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,548 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, ValueEnum};
+use depchk_core::audit::Severity;
+use depchk_core::client::Registry;
+use depchk_core::ignore::Ignore;
+use glob::Pattern;
+use serde::{Deserialize, Deserializer};
+
+use crate::output::{Grouping, OutputTypes, SortOrder};
+use crate::{
+    AdvisoryDatabase, CheckArgs, DependencyType, FailOn, PinStyle, Prereleases, UpdateType,
+};
+
+/// The config files that are looked up next to the dependency file, in order.
+const CONFIG_FILES: [&str; 2] = [".depchk.toml", "depchk.json"];
+
+/// The config file of the user, in the depchk directory of their config directory.
+const USER_CONFIG_FILE: &str = "depchk/config.toml";
+
+/// The defaults of the options of a check, out of a `.depchk.toml` or `depchk.json`
+/// file, the `depchk` key of package.json or the config file of the user. Each key is
+/// named after the option it sets, e.g. `fail_on = "major"` for `--fail-on major`, and
+/// options given on the command line or in their `DEPCHK_*` environment variables,
+/// e.g. `DEPCHK_FAIL_ON`, take precedence. Paths are relative to the config file.
+///
+/// ```toml
+/// output = "json"
+/// dev = true
+/// fail_on = "minor"
+/// registry = "https://npm.example.com"
+/// ignore = ["@types/*", { name = "webpack", until = "2025-09-01" }]
+/// deny_list = "policies/deny.toml"
+///
+/// [[package]]
+/// name = "react*"
+/// update_type = "minor"
+/// prereleases = "never"
+///
+/// [[package]]
+/// name = "@acme/*"
+/// registry = "https://npm.acme.com"
+/// token_env = "ACME_NPM_TOKEN"
+///
+/// [profile.ci]
+/// output = "junit"
+/// fail_on = "major"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(deserialize_with = "value_enum")]
+    output: Option<OutputTypes>,
+    pretty: Option<bool>,
+    wide: Option<bool>,
+    dev: Option<bool>,
+    workspaces: Option<bool>,
+    concurrency: Option<NonZeroUsize>,
+    timeout: Option<u64>,
+    retries: Option<u32>,
+    cache_dir: Option<PathBuf>,
+    cache_ttl: Option<u64>,
+    min_release_age: Option<u64>,
+    #[serde(deserialize_with = "value_enum")]
+    prereleases: Option<Prereleases>,
+    #[serde(deserialize_with = "value_enum")]
+    fail_on: Option<FailOn>,
+    max_libyears: Option<f64>,
+    max_major_behind: Option<usize>,
+    max_outdated_count: Option<usize>,
+    #[serde(deserialize_with = "value_enum")]
+    sort: Option<SortOrder>,
+    #[serde(deserialize_with = "value_enum")]
+    group_by: Option<Grouping>,
+    registry: Option<String>,
+    ignore: Option<Vec<Ignore>>,
+    audit: Option<bool>,
+    #[serde(deserialize_with = "value_enum")]
+    advisory_database: Option<AdvisoryDatabase>,
+    min_severity: Option<Severity>,
+    yanked: Option<bool>,
+    license_policy: Option<PathBuf>,
+    deny_list: Option<PathBuf>,
+    groups: Option<PathBuf>,
+    #[serde(deserialize_with = "value_enum")]
+    pin_style: Option<PinStyle>,
+    #[serde(deserialize_with = "value_enum")]
+    update_type: Option<UpdateType>,
+    provenance: Option<bool>,
+    maintenance: Option<bool>,
+    stale_after: Option<u64>,
+    transitive: Option<bool>,
+    risk: Option<bool>,
+    min_score: Option<u8>,
+    package: Vec<PackageRule>,
+    profile: BTreeMap<String, Config>,
+}
+
+/// Options that only apply to the packages whose name matches a glob pattern. When
+/// several rules match a package, the first one that sets an option wins.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackageRule {
+    #[serde(deserialize_with = "pattern")]
+    name: Pattern,
+    #[serde(default, deserialize_with = "value_enum")]
+    update_type: Option<UpdateType>,
+    #[serde(default, deserialize_with = "value_enum")]
+    prereleases: Option<Prereleases>,
+    #[serde(default)]
+    registry: Option<String>,
+    #[serde(default)]
+    token_env: Option<String>,
+}
+
+/// Deserializes the options whose values are those of a [`ValueEnum`], as they are
+/// written on the command line.
+fn value_enum<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ValueEnum,
+{
+    let Some(value) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    T::from_str(&value, true)
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("invalid value {}", value)))
+}
+
+fn pattern<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pattern, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+
+    Pattern::new(&pattern)
+        .map_err(|err| serde::de::Error::custom(format!("invalid pattern {}: {}", pattern, err)))
+}
+
+impl PackageRule {
+    /// The biggest kind of update that is reported and applied for the package, if the
+    /// rule matches it and limits it.
+    pub fn update_type(&self, name: &str) -> Option<UpdateType> {
+        self.update_type.filter(|_| self.name.matches(name))
+    }
+
+    /// Whether prereleases can be the latest version of the package, if the rule matches
+    /// it and decides it.
+    pub fn prereleases(&self, name: &str) -> Option<Prereleases> {
+        self.prereleases.filter(|_| self.name.matches(name))
+    }
+
+    pub fn sets_prereleases(&self) -> bool {
+        self.prereleases.is_some()
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.name.matches(name)
+    }
+
+    /// The registry the packages are resolved from, if the rule maps them to one, which
+    /// is authenticated with the token in the environment variable of `token_env`.
+    pub fn registry(&self) -> Result<Option<Registry>, String> {
+        let Some(url) = &self.registry else {
+            return Ok(None);
+        };
+
+        let registry = Registry::new(url);
+
+        let Some(variable) = &self.token_env else {
+            return Ok(Some(registry));
+        };
+
+        match std::env::var(variable) {
+            Ok(token) if !token.is_empty() => Ok(Some(registry.with_token(&token))),
+            _ => Err(format!(
+                "the token of the registry {} is not set in {}",
+                url, variable
+            )),
+        }
+    }
+}
+
+/// Calls the macro with the options a config can set, which are named after the fields of
+/// [`CheckArgs`] and the ids of their arguments.
+macro_rules! with_options {
+    ($macro:ident) => {
+        $macro!(
+            output,
+            pretty,
+            wide,
+            dev,
+            workspaces,
+            concurrency,
+            timeout,
+            retries,
+            cache_dir,
+            cache_ttl,
+            min_release_age,
+            prereleases,
+            fail_on,
+            max_libyears,
+            max_major_behind,
+            max_outdated_count,
+            sort,
+            group_by,
+            registry,
+            ignore,
+            audit,
+            advisory_database,
+            min_severity,
+            yanked,
+            license_policy,
+            deny_list,
+            groups,
+            pin_style,
+            update_type,
+            provenance,
+            maintenance,
+            stale_after,
+            transitive,
+            risk,
+            min_score,
+        )
+    };
+}
+
+impl Config {
+    /// Reads a config file, as JSON if its extension is `.json` and as TOML otherwise.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+
+        let parsed = match path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            true => serde_json::from_str(&contents).map_err(|err| err.to_string()),
+            false => toml::from_str(&contents).map_err(|err| err.to_string()),
+        };
+
+        let config: Config =
+            parsed.map_err(|err| format!("invalid config file {}: {}", path.display(), err))?;
+
+        config.read_from(path)
+    }
+
+    /// Reads the config embedded in the `depchk` key of a package.json file, if it has one.
+    pub fn embedded(manifest: &Path) -> Result<Option<Self>, Box<dyn Error>> {
+        #[derive(Deserialize)]
+        struct PackageJson {
+            #[serde(default)]
+            depchk: Option<Config>,
+        }
+
+        // A missing package.json is reported by the check itself
+        let Ok(contents) = fs::read_to_string(manifest) else {
+            return Ok(None);
+        };
+
+        let package_json: PackageJson = serde_json::from_str(&contents)
+            .map_err(|err| format!("invalid depchk config in {}: {}", manifest.display(), err))?;
+
+        package_json
+            .depchk
+            .map(|config| config.read_from(manifest))
+            .transpose()
+    }
+
+    /// Checks the config read from a file, and resolves its paths against the directory
+    /// of the file.
+    fn read_from(mut self, path: &Path) -> Result<Self, Box<dyn Error>> {
+        if self.min_score.is_some_and(|score| score > 100) {
+            return Err(format!("the min_score of {} is over 100", path.display()).into());
+        }
+
+        for (name, profile) in std::mem::take(&mut self.profile) {
+            if !profile.profile.is_empty() {
+                return Err(format!(
+                    "the {} profile of {} has profiles of its own",
+                    name,
+                    path.display()
+                )
+                .into());
+            }
+
+            self.profile.insert(name, profile.read_from(path)?);
+        }
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let paths = [
+            &mut self.cache_dir,
+            &mut self.license_policy,
+            &mut self.deny_list,
+            &mut self.groups,
+        ];
+
+        for path in paths.into_iter().flatten() {
+            *path = directory.join(&*path);
+        }
+
+        Ok(self)
+    }
+
+    /// Reads the config file of the user, e.g. ~/.config/depchk/config.toml, if there is one.
+    fn user() -> Result<Option<Self>, Box<dyn Error>> {
+        match dirs::config_dir().map(|dir| dir.join(USER_CONFIG_FILE)) {
+            Some(path) if path.is_file() => Ok(Some(Config::load(&path)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// The first of the config files next to the dependency file, if there is one.
+    fn find(file: &Path) -> Option<PathBuf> {
+        let directory = file.parent().unwrap_or(Path::new(""));
+
+        CONFIG_FILES
+            .iter()
+            .map(|name| directory.join(name))
+            .find(|path| path.is_file())
+    }
+
+    /// Overrides the options of this config with the ones the other config sets. The
+    /// package rules of the other config come first, so that they win.
+    fn merge(self, other: Config) -> Config {
+        macro_rules! merge {
+            ($($option:ident),* $(,)?) => {
+                Config {
+                    $($option: other.$option.or(self.$option),)*
+                    package: other.package.into_iter().chain(self.package).collect(),
+                    profile: merge_profiles(self.profile, other.profile),
+                }
+            };
+        }
+
+        with_options!(merge)
+    }
+
+    /// The config with the options of one of its profiles on top.
+    fn with_profile(mut self, name: &str) -> Result<Config, String> {
+        match self.profile.remove(name) {
+            Some(profile) => Ok(self.merge(profile)),
+            None => Err(format!("the config has no {} profile", name)),
+        }
+    }
+
+    /// Sets the options that were not given on the command line or in the environment to
+    /// the values of the config, out of the matches the options were parsed from.
+    pub fn apply(self, args: &mut CheckArgs, matches: &ArgMatches) {
+        macro_rules! apply {
+            ($($option:ident),* $(,)?) => {$(
+                if let Some(value) = self.$option {
+                    if !is_given(matches, stringify!($option)) {
+                        args.$option = value.into();
+                    }
+                }
+            )*};
+        }
+
+        with_options!(apply);
+
+        args.packages = self.package;
+    }
+}
+
+/// Merges the profiles of two configs, the ones of the other config taking precedence
+/// over the ones of the same name.
+fn merge_profiles(
+    mut profiles: BTreeMap<String, Config>,
+    other: BTreeMap<String, Config>,
+) -> BTreeMap<String, Config> {
+    for (name, profile) in other {
+        let profile = match profiles.remove(&name) {
+            Some(base) => base.merge(profile),
+            None => profile,
+        };
+
+        profiles.insert(name, profile);
+    }
+
+    profiles
+}
+
+/// Whether an option was given on the command line or in its `DEPCHK_*` environment
+/// variable, rather than left to its default.
+fn is_given(matches: &ArgMatches, id: &str) -> bool {
+    matches!(
+        matches.value_source(id),
+        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+    )
+}
+
+/// Applies the config of the check to its options. The config file, which is the one
+/// given with `--config` or else the first of [`CONFIG_FILES`] found next to the
+/// dependency file, takes precedence over the config embedded in package.json, which
+/// takes precedence over the config file of the user. The profile of `--profile`, if
+/// any, takes precedence over all of them.
+pub fn configure(
+    args: &mut CheckArgs,
+    matches: &ArgMatches,
+    dependency_type: DependencyType,
+    file: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let embedded = match dependency_type {
+        DependencyType::Npm => Config::embedded(file)?,
+        DependencyType::Cargo => None,
+    };
+
+    let config_file = match &args.config {
+        Some(path) => Some(Config::load(path)?),
+        None => Config::find(file)
+            .as_deref()
+            .map(Config::load)
+            .transpose()?,
+    };
+
+    let config = [Config::user()?, embedded, config_file]
+        .into_iter()
+        .flatten()
+        .reduce(Config::merge);
+
+    let config = match (config, &args.profile) {
+        (Some(config), Some(profile)) => Some(config.with_profile(profile)?),
+        (None, Some(profile)) => {
+            return Err(format!("there is no config to take the {} profile from", profile).into())
+        }
+        (config, None) => config,
+    };
+
+    if let Some(config) = config {
+        config.apply(args, matches);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, FromArgMatches};
+
+    use super::*;
+    use crate::Cli;
+
+    #[test]
+    fn test_apply() {
+        let config: Config = toml::from_str(
+            r#"
+            output = "json"
+            dev = true
+            fail_on = "minor"
+            concurrency = 4
+            ignore = ["@types/*"]
+            deny_list = "deny.toml"
+
+            [[package]]
+            name = "react*"
+            update_type = "minor"
+
+            [[package]]
+            name = "@acme/*"
+            registry = "https://npm.acme.com/"
+            token_env = "DEPCHK_TEST_UNSET_TOKEN"
+            "#,
+        )
+        .unwrap();
+
+        let matches = Cli::command()
+            .try_get_matches_from(["depchk", "npm", "--fail-on", "major"])
+            .unwrap();
+        let mut args = Cli::from_arg_matches(&matches).unwrap().check;
+
+        config.apply(&mut args, &matches);
+
+        assert_eq!(args.output, Some(OutputTypes::Json));
+        assert!(args.dev);
+        assert_eq!(args.fail_on, FailOn::Major);
+        assert_eq!(args.concurrency.get(), 4);
+        assert_eq!(args.ignore, [Ignore::new("@types/*").unwrap()]);
+        assert_eq!(args.deny_list, Some(PathBuf::from("deny.toml")));
+        assert_eq!(args.update_type_of("react-dom"), Some(UpdateType::Minor));
+        assert_eq!(args.update_type_of("webpack"), None);
+
+        assert!(args.packages[1].matches("@acme/ui"));
+        assert!(args.packages[1].registry().is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let embedded: Config = serde_json::from_str(
+            r#"{"output": "json", "dev": true, "package": [{"name": "react", "update_type": "patch"}]}"#,
+        )
+        .unwrap();
+        let file: Config = toml::from_str(
+            r#"
+            output = "yaml"
+
+            [[package]]
+            name = "react"
+            update_type = "minor"
+            "#,
+        )
+        .unwrap();
+
+        let config = embedded.merge(file);
+
+        assert_eq!(config.output, Some(OutputTypes::Yaml));
+        assert_eq!(config.dev, Some(true));
+        assert_eq!(
+            config.package[0].update_type("react"),
+            Some(UpdateType::Minor)
+        );
+    }
+
+    #[test]
+    fn test_with_profile() {
+        let config = || {
+            toml::from_str::<Config>(
+                r#"
+                output = "table"
+                dev = true
+
+                [profile.ci]
+                output = "junit"
+                fail_on = "major"
+                "#,
+            )
+            .unwrap()
+        };
+
+        let ci = config().with_profile("ci").unwrap();
+
+        assert_eq!(ci.output, Some(OutputTypes::Junit));
+        assert_eq!(ci.dev, Some(true));
+        assert_eq!(ci.fail_on, Some(FailOn::Major));
+        assert!(config().with_profile("local").is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(toml::from_str::<Config>("fail_on = \"sometimes\"").is_err());
+        assert!(toml::from_str::<Config>("fial_on = \"major\"").is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"output": "yaml", "dev": true}"#).is_ok());
+    }
+}
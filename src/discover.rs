@@ -0,0 +1,266 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+
+use crate::DependencyType;
+
+/// The dependency files of ecosystems depchk does not support yet, which are warned
+/// about when they are found while detecting the ones to check.
+const UNSUPPORTED_MANIFESTS: [&str; 7] = [
+    "requirements.txt",
+    "pyproject.toml",
+    "Pipfile",
+    "go.mod",
+    "Gemfile",
+    "composer.json",
+    "pom.xml",
+];
+
+/// Directories that hold installed or built packages rather than projects, whose
+/// dependency files are never checked.
+const SKIPPED_DIRS: [&str; 2] = ["node_modules", "target"];
+
+/// The type of the dependency file, out of its name.
+fn dependency_type_of(path: &Path) -> Option<DependencyType> {
+    let name = path.file_name()?;
+
+    DependencyType::value_variants()
+        .iter()
+        .find(|dependency_type| name == dependency_type.default_file())
+        .copied()
+}
+
+/// Whether the path is in a directory whose dependency files are never checked.
+fn is_skipped(path: &Path) -> bool {
+    path.components()
+        .any(|component| SKIPPED_DIRS.iter().any(|dir| component.as_os_str() == *dir))
+}
+
+/// The dependency files of the directory that can be checked, along with their type.
+/// Unless quiet, the ones of other ecosystems are warned about.
+pub fn detect(dir: &Path, quiet: bool) -> Vec<(DependencyType, PathBuf)> {
+    if !quiet {
+        for manifest in UNSUPPORTED_MANIFESTS {
+            if dir.join(manifest).is_file() {
+                eprintln!(
+                    "Warning: {} is not supported, so it is not checked",
+                    manifest
+                );
+            }
+        }
+    }
+
+    DependencyType::value_variants()
+        .iter()
+        .map(|dependency_type| (*dependency_type, dir.join(dependency_type.default_file())))
+        .filter(|(_, file)| file.is_file())
+        .collect()
+}
+
+/// Every dependency file that can be checked in the directory and its subdirectories,
+/// sorted by path so that the files of a project are next to each other. Hidden
+/// directories are not searched, nor are the ones of installed or built packages.
+pub fn find_recursive(dir: &Path) -> io::Result<Vec<(DependencyType, PathBuf)>> {
+    let mut found = Vec::new();
+
+    search(dir, &mut found)?;
+
+    found.sort_by(|(_, left), (_, right)| left.cmp(right));
+
+    Ok(found)
+}
+
+fn search(dir: &Path, found: &mut Vec<(DependencyType, PathBuf)>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+
+        if name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        // Paths under the current directory are given as they would be on the command line
+        let path = entry.path();
+        let path = path.strip_prefix(".").unwrap_or(&path).to_path_buf();
+
+        if entry.file_type()?.is_dir() {
+            if !is_skipped(Path::new(&name)) {
+                search(&path, found)?;
+            }
+        } else if let Some(dependency_type) = dependency_type_of(&path) {
+            found.push((dependency_type, path));
+        }
+    }
+
+    Ok(())
+}
+
+/// The leading components of the glob pattern that hold no wildcard, which name the
+/// directory it searches rather than what it matches.
+fn literal_prefix(pattern: &str) -> PathBuf {
+    Path::new(pattern)
+        .components()
+        .take_while(|component| {
+            !component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '['])
+        })
+        .collect()
+}
+
+/// The dependency files that match the glob patterns, sorted by path. Those of
+/// installed or built packages are left out, so that `packages/**/package.json` does
+/// not match the packages in node_modules, although a pattern can still search such a
+/// directory by naming it. Other files are skipped, and warned about unless quiet,
+/// but every pattern has to match at least one dependency file.
+pub fn find_matching(
+    patterns: &[String],
+    quiet: bool,
+) -> Result<Vec<(DependencyType, PathBuf)>, Box<dyn Error>> {
+    let mut found = Vec::new();
+
+    for pattern in patterns {
+        let prefix = literal_prefix(pattern);
+        let mut matched = false;
+
+        for path in glob::glob(pattern)? {
+            let path = path?;
+
+            if is_skipped(path.strip_prefix(&prefix).unwrap_or(&path)) || !path.is_file() {
+                continue;
+            }
+
+            let Some(dependency_type) = dependency_type_of(&path) else {
+                if !quiet {
+                    eprintln!(
+                        "Warning: {} is not a dependency file, so it is not checked",
+                        path.display()
+                    );
+                }
+
+                continue;
+            };
+
+            found.push((dependency_type, path));
+            matched = true;
+        }
+
+        if !matched {
+            return Err(format!("no package.json or Cargo.toml file matches {}", pattern).into());
+        }
+    }
+
+    found.sort_by(|(_, left), (_, right)| left.cmp(right));
+    found.dedup_by(|(_, left), (_, right)| left == right);
+
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_dependency_files() {
+        let directory =
+            std::env::temp_dir().join(format!("depchk-discover-{}", std::process::id()));
+
+        for dir in [
+            "packages/a/node_modules/b",
+            "packages/c",
+            "crates/d",
+            ".git",
+        ] {
+            fs::create_dir_all(directory.join(dir)).unwrap();
+        }
+
+        for file in [
+            "package.json",
+            "packages/a/package.json",
+            "packages/a/Cargo.toml",
+            "packages/a/node_modules/b/package.json",
+            "packages/c/package.json",
+            "packages/c/README.md",
+            "crates/d/Cargo.toml",
+            ".git/package.json",
+        ] {
+            fs::write(directory.join(file), "").unwrap();
+        }
+
+        let found = find_recursive(&directory).unwrap();
+
+        assert_eq!(
+            found,
+            [
+                (DependencyType::Cargo, directory.join("crates/d/Cargo.toml")),
+                (DependencyType::Npm, directory.join("package.json")),
+                (
+                    DependencyType::Cargo,
+                    directory.join("packages/a/Cargo.toml")
+                ),
+                (
+                    DependencyType::Npm,
+                    directory.join("packages/a/package.json")
+                ),
+                (
+                    DependencyType::Npm,
+                    directory.join("packages/c/package.json")
+                ),
+            ]
+        );
+
+        let pattern = directory.join("packages/**/package.json");
+        let found = find_matching(&[pattern.to_string_lossy().into_owned()], true).unwrap();
+
+        assert_eq!(
+            found,
+            [
+                (
+                    DependencyType::Npm,
+                    directory.join("packages/a/package.json")
+                ),
+                (
+                    DependencyType::Npm,
+                    directory.join("packages/c/package.json")
+                ),
+            ]
+        );
+
+        // Only the directories the pattern matches are skipped, not the ones it names
+        let pattern = directory.join("packages/a/node_modules/*/package.json");
+        let found = find_matching(&[pattern.to_string_lossy().into_owned()], true).unwrap();
+
+        assert_eq!(
+            found,
+            [(
+                DependencyType::Npm,
+                directory.join("packages/a/node_modules/b/package.json")
+            )]
+        );
+
+        // Other files are skipped, as long as the pattern matches a dependency file
+        let pattern = directory.join("packages/c/*");
+        let found = find_matching(&[pattern.to_string_lossy().into_owned()], true).unwrap();
+
+        assert_eq!(
+            found,
+            [(
+                DependencyType::Npm,
+                directory.join("packages/c/package.json")
+            )]
+        );
+
+        let pattern = directory.join("packages/c/*.md");
+        let err = find_matching(&[pattern.to_string_lossy().into_owned()], true).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("no package.json or Cargo.toml file matches"));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
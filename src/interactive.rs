@@ -0,0 +1,123 @@
+use std::error::Error;
+use std::fmt::Write;
+
+use depchk_core::fix::unified_diff;
+use depchk_core::UpdateKind;
+use dialoguer::{Confirm, MultiSelect};
+
+use crate::{update_units, UpdateChoice};
+
+impl UpdateChoice {
+    fn label(&self) -> String {
+        let mut label = format!(
+            "{} {} -> {}",
+            self.update.name(),
+            self.declared,
+            self.update.constraint()
+        );
+
+        if let Some(kind) = self.update_kind {
+            let _ = write!(label, " ({})", kind);
+        }
+
+        if self.update.is_dev() {
+            label.push_str(" [dev]");
+        }
+
+        label
+    }
+}
+
+/// Asks which of the updates to apply, first offering to select every update of
+/// a kind at once, then listing each update in a checkbox list to fine-tune the
+/// selection. The updates of a group of related packages are listed as one. Returns the chosen updates, which are none if either prompt is
+/// cancelled with Esc or q.
+pub fn select_updates(choices: Vec<UpdateChoice>) -> Result<Vec<UpdateChoice>, Box<dyn Error>> {
+    let kinds: Vec<_> = [UpdateKind::Major, UpdateKind::Minor, UpdateKind::Patch]
+        .into_iter()
+        .filter(|kind| {
+            choices
+                .iter()
+                .any(|choice| choice.update_kind == Some(*kind))
+        })
+        .collect();
+
+    let preselected = match kinds.is_empty() {
+        true => Vec::new(),
+        false => {
+            let items: Vec<_> = kinds
+                .iter()
+                .map(|kind| {
+                    let count = choices
+                        .iter()
+                        .filter(|choice| choice.update_kind == Some(*kind))
+                        .count();
+
+                    format!("All {} updates ({})", kind, count)
+                })
+                .collect();
+
+            let selected = MultiSelect::new()
+                .with_prompt("Select every update of a kind (space to toggle, enter to go on)")
+                .items(&items)
+                .interact_opt()?;
+
+            match selected {
+                Some(selected) => selected.into_iter().map(|index| kinds[index]).collect(),
+                None => return Ok(Vec::new()),
+            }
+        }
+    };
+
+    let units = update_units(choices);
+
+    let items: Vec<_> = units
+        .iter()
+        .map(|unit| {
+            let labels: Vec<_> = unit.iter().map(UpdateChoice::label).collect();
+
+            match &unit[0].group {
+                Some(group) => format!("{} group: {}", group, labels.join(", ")),
+                None => labels.join(", "),
+            }
+        })
+        .collect();
+
+    let defaults: Vec<_> = units
+        .iter()
+        .map(|unit| {
+            unit.iter().any(|choice| {
+                choice
+                    .update_kind
+                    .is_some_and(|kind| preselected.contains(&kind))
+            })
+        })
+        .collect();
+
+    let selected = MultiSelect::new()
+        .with_prompt("Updates to apply (space to toggle, a to toggle all, enter to confirm)")
+        .items(&items)
+        .defaults(&defaults)
+        .max_length(20)
+        .interact_opt()?
+        .unwrap_or_default();
+
+    Ok(units
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| selected.contains(index))
+        .flat_map(|(_, unit)| unit)
+        .collect())
+}
+
+/// Shows the lines of the dependency file the updates change, and asks whether to
+/// write them.
+pub fn confirm_changes(file_name: &str, before: &str, after: &str) -> Result<bool, Box<dyn Error>> {
+    eprint!("{}", unified_diff(file_name, before, after));
+
+    Ok(Confirm::new()
+        .with_prompt(format!("Write these changes to {}?", file_name))
+        .default(true)
+        .interact_opt()?
+        .unwrap_or(false))
+}
@@ -1,66 +1,824 @@
 #[macro_use]
 extern crate prettytable;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::io::{self, IsTerminal};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
 
-use depchk::npm::PackageJson;
-use depchk::*;
+use tokio::time::Instant;
 
-use reqwest::Client;
+use depchk_core::audit::{audit_dependencies, AdvisorySource, Severity, Vulnerability};
+use depchk_core::cache::DiskCache;
+use depchk_core::cargo::CargoToml;
+use depchk_core::changelog::{ChangelogLookup, ReleaseNotes};
+use depchk_core::client::{
+    http_client_builder, LookupStats, NotCached, PrereleasePolicy, Registry, RegistryClient,
+    RetryPolicy,
+};
+use depchk_core::denylist::DenyList;
+use depchk_core::groups::UpdateGroups;
+use depchk_core::ignore::Ignore;
+use depchk_core::license::LicensePolicy;
+use depchk_core::maintenance::MaintenanceCheck;
+use depchk_core::npm::{package_lock_path, PackageJson};
+use depchk_core::options::{CheckOptions, Checker};
+use depchk_core::progress::ProgressReporter;
+use depchk_core::pull_request::GitHubRepository;
+use depchk_core::transitive::TransitiveDependencies;
+use depchk_core::workspace::{find_version_skew, WorkspacePackage};
+use depchk_core::*;
 
-use clap::{Parser, ValueEnum};
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use futures::{future, stream, StreamExt};
+use serde::Serialize;
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt;
+use tracing_subscriber::prelude::*;
 
-use prettytable::Table;
+mod backup;
+mod commit;
+mod config;
+mod discover;
+mod interactive;
+mod output;
+mod pr;
+mod progress_bar;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
-enum OutputTypes {
-    Table,
-    Json,
-    Yaml,
-    Csv,
-}
+use config::PackageRule;
+use pr::PrArgs;
+use progress_bar::ProgressBar;
+
+use output::audit::{print_audits, AuditReport};
+use output::explain::print_explanations;
+use output::prometheus::push_metrics;
+use output::stream::{print_streamed_skew, stream_mismatches};
+use output::verify::{print_verifications, VerifyReport};
+use output::{
+    combined_summary, print_reports, save_reports, BadgeFormat, Baseline, Column,
+    DeclaredDependency, FailedCheck, Grouping, OutputTypes, PrintOptions, Report, Section,
+    SortOrder, Summary, Thresholds, TransitiveFinding, DEFAULT_COLUMNS, SCHEMA, WIDE_COLUMNS,
+};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
 enum DependencyType {
     Npm,
+    Cargo,
+}
+
+impl DependencyType {
+    /// The dependency file that is checked when none is given.
+    fn default_file(&self) -> &'static str {
+        match self {
+            DependencyType::Npm => "package.json",
+            DependencyType::Cargo => "Cargo.toml",
+        }
+    }
+}
+
+/// Which outdated dependencies make depchk exit with a failure status.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum FailOn {
+    /// Fails if any dependency is a major update behind
+    Major,
+    /// Fails if any dependency is a minor or major update behind
+    Minor,
+    /// Fails if any dependency is a patch, minor or major update behind
+    Patch,
+    /// Fails if any dependency is outdated, even if the kind of update is unknown
+    Any,
+    /// Never fails because of outdated dependencies
+    #[default]
+    Never,
+}
+
+/// The biggest kind of update that is reported and applied, see `--update-type`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum UpdateType {
+    /// Only patch updates
+    Patch,
+    /// Patch and minor updates, which are semver-compatible
+    Minor,
+    /// Every kind of update
+    Major,
+}
+
+impl UpdateType {
+    fn kind(self) -> UpdateKind {
+        match self {
+            UpdateType::Patch => UpdateKind::Patch,
+            UpdateType::Minor => UpdateKind::Minor,
+            UpdateType::Major => UpdateKind::Major,
+        }
+    }
+}
+
+/// Whether prereleases can be reported as the latest version, see `--prereleases`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum Prereleases {
+    /// The latest version is the one the registry reports, which on npm may be a
+    /// prerelease tagged as latest
+    #[default]
+    Registry,
+    /// Prereleases are never the latest version
+    Never,
+    /// Prereleases are only the latest version of dependencies whose constraint
+    /// includes one, e.g. `^2.0.0-beta.1`
+    Constraint,
+    /// The newest version is the latest, whether it is a prerelease or not
+    Always,
+}
+
+impl Prereleases {
+    fn policy(self) -> PrereleasePolicy {
+        match self {
+            Prereleases::Registry => PrereleasePolicy::Registry,
+            Prereleases::Never => PrereleasePolicy::Never,
+            Prereleases::Constraint => PrereleasePolicy::Constraint,
+            Prereleases::Always => PrereleasePolicy::Always,
+        }
+    }
+}
+
+/// How `--fix` writes the constraints it updates, see `--pin-style`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum PinStyle {
+    /// The way each constraint was written, e.g. `~4.17` becomes `~4.18`
+    #[default]
+    Keep,
+    /// Caret ranges of the latest version, e.g. `^4.18.2`
+    Caret,
+    /// Tilde ranges of the latest version, e.g. `~4.18.2`
+    Tilde,
+    /// The latest version alone, e.g. `4.18.2`
+    Exact,
+}
+
+impl PinStyle {
+    fn style(self) -> fix::RangeStyle {
+        match self {
+            PinStyle::Keep => fix::RangeStyle::Keep,
+            PinStyle::Caret => fix::RangeStyle::Caret,
+            PinStyle::Tilde => fix::RangeStyle::Tilde,
+            PinStyle::Exact => fix::RangeStyle::Exact,
+        }
+    }
+}
+
+/// The advisory database `--audit` and `depchk audit` look vulnerabilities up in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum AdvisoryDatabase {
+    /// The OSV database, at osv.dev
+    Osv,
+    /// The GitHub Advisory Database, which needs a token in the GITHUB_TOKEN environment
+    /// variable
+    Github,
+    /// The RustSec advisory database, which also lists unmaintained crates. Only covers
+    /// Cargo dependencies.
+    Rustsec,
+    /// The bulk advisory endpoint of the npm registry, which audits every package of the
+    /// package-lock.json file next to the manifest, transitive dependencies included
+    Npm,
+}
+
+impl AdvisoryDatabase {
+    /// The advisory database that is used unless another one is chosen, which for
+    /// npm projects depends on whether they have a lockfile.
+    fn default_for(dependency_type: DependencyType, manifest: &str) -> Self {
+        match dependency_type {
+            DependencyType::Npm if package_lock_path(manifest).is_file() => AdvisoryDatabase::Npm,
+            DependencyType::Npm => AdvisoryDatabase::Osv,
+            DependencyType::Cargo => AdvisoryDatabase::Rustsec,
+        }
+    }
+
+    fn source(&self, args: &CheckArgs, manifest: &str) -> Result<AdvisorySource, Box<dyn Error>> {
+        match self {
+            AdvisoryDatabase::Npm => Ok(AdvisorySource::NpmRegistry {
+                lockfile: package_lock_path(manifest),
+                dev: args.dev,
+            }),
+            AdvisoryDatabase::Osv => Ok(AdvisorySource::Osv),
+            AdvisoryDatabase::Rustsec => Ok(AdvisorySource::RustSec {
+                cache_dir: args.cache_dir(),
+            }),
+            AdvisoryDatabase::Github => match std::env::var("GITHUB_TOKEN") {
+                Ok(token) if !token.is_empty() => Ok(AdvisorySource::Github { token }),
+                _ => Err("the GitHub Advisory Database needs a token in GITHUB_TOKEN".into()),
+            },
+        }
+    }
 }
 
+/// The lockfiles that `--sync-lockfile` updates next to the dependency file, which
+/// `--commit` commits and `depchk undo` restores along with it.
+const LOCKFILES: [&str; 4] = [
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "Cargo.lock",
+];
+
+/// The exit status when the check passed.
+const EXIT_SUCCESS: u8 = 0;
+
+/// The exit status when outdated dependencies fail the check, see `--fail-on`.
+const EXIT_OUTDATED: u8 = 1;
+
+/// The exit status when the dependencies fall further behind than the thresholds
+/// allow, see `--max-libyears`, `--max-major-behind` and `--max-outdated-count`.
+const EXIT_THRESHOLD_EXCEEDED: u8 = 3;
+
+/// The exit status when the dependencies have known vulnerabilities, see `--audit`.
+const EXIT_VULNERABLE: u8 = 4;
+
+/// The exit status when locked versions were yanked or unpublished, see `--yanked`.
+const EXIT_UNAVAILABLE: u8 = 5;
+
+/// The exit status when dependencies are published under licenses the policy does not
+/// permit, see `--license-policy`.
+const EXIT_LICENSE_VIOLATION: u8 = 6;
+
+/// The exit status when the latest version of a dependency lost the provenance of the
+/// version in use, see `--provenance`.
+const EXIT_PROVENANCE_LOST: u8 = 7;
+
+/// The exit status when dependencies are on the deny list, see `--deny-list`.
+const EXIT_DENIED: u8 = 8;
+
+/// The exit status when the risk score of the project reaches the gate, see `--min-score`.
+const EXIT_RISKY: u8 = 9;
+
+/// The exit status when the lockfile pins packages to checksums the registry does not
+/// publish, see `depchk verify`.
+const EXIT_TAMPERED: u8 = 10;
+
+/// The exit status when some dependencies could not be checked, or when the check
+/// could not be run at all. Takes precedence over every other exit status.
+const EXIT_ERROR: u8 = 2;
+
+/// The exit statuses above, as listed at the end of `--help`.
+const EXIT_STATUSES: &str = "\
+Exit status:
+  0   the check passed
+  1   outdated dependencies fail the check, see --fail-on
+  2   some dependencies could not be checked, or the check could not be run
+  3   the dependencies fall further behind than the --max-* thresholds allow
+  4   the dependencies have known vulnerabilities, see --audit
+  5   locked versions were yanked or unpublished, see --yanked
+  6   dependencies are published under licenses the --license-policy does not permit
+  7   the latest version of a dependency lost its provenance, see --provenance
+  8   dependencies are on the --deny-list
+  9   the risk score of the project reaches --min-score
+  10  the lockfile pins checksums the registry does not publish, see depchk verify";
+
 #[derive(Debug, Default)]
 struct DependencyCheckErrors {
-    errors: Vec<Box<dyn Error>>,
+    errors: Vec<DepchkError>,
     msg: String,
 }
 
+/// The reports of the dependency files checked so far, when several are checked, so
+/// that they are printed as one once all of them are.
+#[derive(Default)]
+struct Reports {
+    checks: Vec<Report>,
+    audits: Vec<AuditReport>,
+    verifications: Vec<VerifyReport>,
+}
+
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, after_help = EXIT_STATUSES)]
 #[command(propagate_version = true)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    check: CheckArgs,
+}
+
+// The options of a check, which are shared by the subcommands that run one. Not a doc
+// comment, since clap would take it as the description of the commands it is flattened into.
+#[derive(Args, Clone, Debug)]
+struct CheckArgs {
     /// If true, also checks the dev dependencies for updates
-    #[arg(short, long)]
+    #[arg(short, long, env = "DEPCHK_DEV")]
     dev: bool,
 
-    /// What type of dependency is being parsed
-    dependency: DependencyType,
+    /// If true, treats the dependency file as the root of a workspace (monorepo) and also
+    /// reports external dependencies that workspace packages declare with conflicting constraints
+    #[arg(short, long, env = "DEPCHK_WORKSPACES")]
+    workspaces: bool,
+
+    /// The maximum number of registry lookups that are run at the same time
+    #[arg(short, long, default_value = "16", env = "DEPCHK_CONCURRENCY")]
+    concurrency: NonZeroUsize,
+
+    /// If true, prints every mismatch as soon as it is found instead of waiting for all checks
+    /// to finish. JSON and YAML outputs are written as one document per mismatch.
+    #[arg(short, long)]
+    stream: bool,
+
+    /// If true, always queries the registries instead of reusing cached responses
+    #[arg(long)]
+    no_cache: bool,
+
+    /// If true, never queries the registries and answers every lookup from the cache,
+    /// however old. Dependencies that are not cached are reported as unknown.
+    #[arg(long, conflicts_with = "no_cache")]
+    offline: bool,
+
+    /// The directory registry responses and advisory databases are cached in. Defaults to
+    /// the depchk directory of the cache directory of the user, e.g. ~/.cache/depchk.
+    #[arg(long, value_name = "DIR", env = "DEPCHK_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// How long, in seconds, cached registry responses are reused before being fetched again
+    #[arg(long, default_value_t = 3600, env = "DEPCHK_CACHE_TTL")]
+    cache_ttl: u64,
+
+    /// How many times a registry lookup that failed for a transient reason is retried
+    #[arg(long, default_value_t = 3, env = "DEPCHK_RETRIES")]
+    retries: u32,
+
+    /// The base delay, in milliseconds, of the exponential backoff between retries
+    #[arg(long, default_value_t = 250)]
+    retry_delay: u64,
+
+    /// How long, in seconds, a single registry request may take before it is abandoned
+    #[arg(long, default_value_t = 30, env = "DEPCHK_TIMEOUT")]
+    timeout: u64,
+
+    /// How long, in seconds, the whole check may take. Dependencies that have not
+    /// been resolved by then are reported as timed out.
+    #[arg(long)]
+    deadline: Option<u64>,
+
+    /// How old, in days, a version has to be before it is reported as the latest one.
+    /// Newer releases are skipped in favor of the newest version that is old enough,
+    /// which guards against compromised releases that have not been caught yet.
+    #[arg(long, value_name = "DAYS", env = "DEPCHK_MIN_RELEASE_AGE")]
+    min_release_age: Option<u64>,
+
+    /// Whether prerelease versions, e.g. `2.0.0-beta.1`, can be reported as the latest
+    /// version of the dependencies. The `[[package]]` rules of the config file can set
+    /// it for some packages only.
+    #[arg(value_enum, long, env = "DEPCHK_PRERELEASES")]
+    prereleases: Option<Prereleases>,
+
+    /// The URL of the npm registry the dependencies are looked up in instead of
+    /// registry.npmjs.org, such as a mirror. The `[[package]]` rules of the config file
+    /// can map some packages to other registries, with the tokens they need.
+    #[arg(long, value_name = "URL", env = "DEPCHK_REGISTRY")]
+    registry: Option<String>,
+
+    /// The distribution tag of the npm registry the latest version of the dependencies
+    /// is read from instead of `latest`, e.g. `next` to check against upcoming releases
+    #[arg(long, env = "DEPCHK_TAG")]
+    tag: Option<String>,
+
+    /// Glob patterns of the names of packages to leave out of the check, separated by
+    /// commas, e.g. `@types/*`. Ignores of the config file can expire at an `until` date.
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        value_delimiter = ',',
+        env = "DEPCHK_IGNORE"
+    )]
+    ignore: Vec<Ignore>,
+
+    /// If true, prints timing, request and cache statistics of the run to stderr
+    #[arg(long)]
+    stats: bool,
+
+    /// If true, prints what the check does as it goes to stderr, such as which
+    /// dependency files were parsed and every request made to a registry
+    #[arg(short, long, env = "DEPCHK_VERBOSE")]
+    verbose: bool,
+
+    /// Which outdated dependencies fail the check, which then exits with status 1.
+    /// Dependencies that could not be checked always fail it with status 2.
+    #[arg(value_enum, long, default_value_t, env = "DEPCHK_FAIL_ON")]
+    fail_on: FailOn,
+
+    /// Fails the check when the dependencies are, all together, more than this many
+    /// libyears behind
+    #[arg(long, value_name = "LIBYEARS", env = "DEPCHK_MAX_LIBYEARS")]
+    max_libyears: Option<f64>,
+
+    /// Fails the check when more than this many dependencies are a major update behind
+    #[arg(long, value_name = "COUNT", env = "DEPCHK_MAX_MAJOR_BEHIND")]
+    max_major_behind: Option<usize>,
+
+    /// Fails the check when more than this many dependencies are outdated
+    #[arg(long, value_name = "COUNT", env = "DEPCHK_MAX_OUTDATED_COUNT")]
+    max_outdated_count: Option<usize>,
+
+    /// If true, only prints the report itself, without the summary footer or warnings.
+    /// Errors are still reported on stderr.
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// What type of dependency is being parsed. If not given, every dependency file
+    /// found in the current directory is checked.
+    dependency: Option<DependencyType>,
 
     /// Path to the dependency file. If not given, assumes that it is in the current directory.
     /// The actual default is determined by the dependency type. For example, for the npm dependency type,
-    /// the default is "package.json"
+    /// the default is "package.json", and for the cargo dependency type, "Cargo.toml"
     file: Option<PathBuf>,
 
+    /// If true, checks every dependency file in the current directory and its
+    /// subdirectories, such as the packages of a monorepo, in a single report keyed by
+    /// the path of each dependency file, whose thresholds apply to all of them together.
+    /// Hidden, node_modules and target directories are not searched. With a dependency
+    /// type, only its dependency files are checked.
+    #[arg(short, long, conflicts_with = "file")]
+    recursive: bool,
+
+    /// Glob patterns of the dependency files to check, e.g. `packages/**/package.json`,
+    /// in a single report keyed by the path of each dependency file. Files in
+    /// node_modules and target directories are left out, unless the pattern names them,
+    /// and files that are not dependency files are skipped.
+    #[arg(long, value_name = "PATTERN", conflicts_with_all = ["file", "recursive"])]
+    glob: Vec<String>,
+
+    /// A config file with the defaults of these options, which options given on the command
+    /// line take precedence over. Defaults to the .depchk.toml or depchk.json file next to
+    /// the dependency file, if there is one. The `depchk` key of package.json can hold a
+    /// config as well, which the config file takes precedence over, and the config.toml
+    /// file of the depchk directory of the user's config directory, e.g.
+    /// ~/.config/depchk/config.toml, holds defaults for every project.
+    #[arg(long, value_name = "FILE", env = "DEPCHK_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// The profile of the config file to use, e.g. ci for the options of its
+    /// `[profile.ci]` table, which take precedence over the other options of the file
+    #[arg(long, env = "DEPCHK_PROFILE")]
+    profile: Option<String>,
+
     /// The display type of the output
-    #[arg(value_enum, short, long)]
+    #[arg(value_enum, short, long, env = "DEPCHK_OUTPUT")]
     output: Option<OutputTypes>,
+
+    /// If true, the JSON output is indented over several lines instead of written on one
+    #[arg(long, conflicts_with = "stream", env = "DEPCHK_PRETTY")]
+    pretty: bool,
+
+    /// Writes the output to this file instead of stdout, replacing it in one go once the
+    /// check has finished. A one line summary is still printed to stdout.
+    #[arg(long, conflicts_with = "stream")]
+    out: Option<PathBuf>,
+
+    /// If true, reports every dependency with its status, including the up to date ones,
+    /// instead of only the outdated ones
+    #[arg(short, long, conflicts_with = "stream")]
+    all: bool,
+
+    /// A JSON report saved by an earlier run. Only the mismatches that are new, changed
+    /// or resolved since then are reported, and only new and changed ones fail the check.
+    #[arg(long, conflicts_with_all = ["stream", "all"])]
+    baseline: Option<PathBuf>,
+
+    /// The columns mismatches are listed with by the table, CSV, JSON, YAML and TOML
+    /// outputs, separated by commas. JSON, YAML and TOML always have the name, constraint
+    /// and version fields, and the other columns only add fields.
+    #[arg(
+        value_enum,
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["stream", "all"]
+    )]
+    columns: Option<Vec<Column>>,
+
+    /// If true, mismatches are also listed with the release date of their latest
+    /// version, their registry and their homepage
+    #[arg(long, conflicts_with_all = ["stream", "all", "columns"], env = "DEPCHK_WIDE")]
+    wide: bool,
+
+    /// The URL of a Prometheus Pushgateway to push the metrics of the prometheus output
+    /// to, under the "depchk" job, whatever the output type is
+    #[arg(long, conflicts_with = "stream")]
+    pushgateway: Option<String>,
+
+    /// The order mismatches are reported in. Ignored when streaming.
+    #[arg(value_enum, long, default_value_t, env = "DEPCHK_SORT")]
+    sort: SortOrder,
+
+    /// How mismatches are grouped together in the report. Ignored when streaming.
+    #[arg(value_enum, long, default_value_t, env = "DEPCHK_GROUP_BY")]
+    group_by: Grouping,
+
+    /// If true, also looks up the dependencies in the advisory database and reports
+    /// their known vulnerabilities, which fail the check
+    #[arg(long, conflicts_with = "stream", env = "DEPCHK_AUDIT")]
+    audit: bool,
+
+    /// The advisory database vulnerabilities are looked up in. Defaults to rustsec for
+    /// Cargo dependencies, to npm for npm projects with a package-lock.json file and to
+    /// osv otherwise.
+    #[arg(value_enum, long, env = "DEPCHK_ADVISORY_DATABASE")]
+    advisory_database: Option<AdvisoryDatabase>,
+
+    /// Only reports the vulnerabilities that are at least this severe: low, medium, high
+    /// or critical. Vulnerabilities the advisory database does not rate are reported
+    /// anyway, but informational advisories such as unmaintained crates are not.
+    #[arg(long, value_name = "SEVERITY", env = "DEPCHK_MIN_SEVERITY")]
+    min_severity: Option<Severity>,
+
+    /// If true, also reports dependencies whose version in the lockfile was yanked or
+    /// unpublished, which fail the check whether or not they are outdated
+    #[arg(long, conflicts_with = "stream", env = "DEPCHK_YANKED")]
+    yanked: bool,
+
+    /// A TOML file with `allow` and `deny` lists of SPDX license identifiers. Dependencies
+    /// whose license is not permitted fail the check.
+    #[arg(long, conflicts_with = "stream", env = "DEPCHK_LICENSE_POLICY")]
+    license_policy: Option<PathBuf>,
+
+    /// A TOML file of `[[deny]]` entries, each with the `name` of a package that must not
+    /// be used, the npm-style version ranges it is denied in (every version if there are
+    /// none) and the `reason` it is denied. Denied dependencies fail the check.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "stream",
+        env = "DEPCHK_DENY_LIST"
+    )]
+    deny_list: Option<PathBuf>,
+
+    /// A TOML file of `[[group]]` entries, each with the `name` of a group of related
+    /// packages and the glob `patterns` their names match, e.g. `@babel/*`. The outdated
+    /// packages of a group are bumped together by `--interactive` and `depchk pr`, and
+    /// `--group-by group` lists them together.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "stream",
+        env = "DEPCHK_GROUPS"
+    )]
+    groups: Option<PathBuf>,
+
+    /// If true, rewrites the constraints of the outdated dependencies in package.json to
+    /// allow their latest version, keeping the operator of each constraint and the
+    /// formatting of the file. Constraints that are not a single version, such as
+    /// `^16 || ^17`, are left as they are.
+    #[arg(long, conflicts_with = "stream")]
+    fix: bool,
+
+    /// How `--fix`, `--interactive` and `depchk pr` write the constraints they update:
+    /// keeping the operator of each constraint, or normalizing them all to caret ranges,
+    /// tilde ranges or exact versions
+    #[arg(value_enum, long, default_value_t, env = "DEPCHK_PIN_STYLE")]
+    pin_style: PinStyle,
+
+    /// The biggest kind of update to report, and to apply with `--fix`, e.g. minor to
+    /// leave major updates out. Mismatches whose kind of update cannot be worked out
+    /// are left out as well. The `[[package]]` rules of the config file can set it for
+    /// some packages only.
+    #[arg(
+        value_enum,
+        long,
+        conflicts_with = "stream",
+        env = "DEPCHK_UPDATE_TYPE"
+    )]
+    update_type: Option<UpdateType>,
+
+    /// If true, lists the updates `--fix` would make in a checkbox list to choose which
+    /// ones to apply, and shows the changes to the dependency file before writing it.
+    /// Implies `--fix`.
+    #[arg(long, conflicts_with = "stream")]
+    interactive: bool,
+
+    /// If true, runs the package manager after `--fix` or `--interactive` updated the
+    /// dependency file, so that the lockfile next to it matches the new constraints:
+    /// `npm install --package-lock-only`, `pnpm install --lockfile-only`, `yarn install`
+    /// or `cargo update -p` with the updated crates.
+    #[arg(long)]
+    sync_lockfile: bool,
+
+    /// If true, `--fix` and `--interactive` commit their changes to the dependency file and
+    /// its lockfile with git, one commit for each package or group of `--groups`, with
+    /// Conventional Commits messages such as `chore(deps): bump axios from 0.21.4 to 1.6.2`
+    #[arg(long, conflicts_with = "dry_run")]
+    commit: bool,
+
+    /// If true, `--fix` and `--interactive` print a unified diff of the changes they would
+    /// make to the dependency file to stderr, instead of writing them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// A file to write the unified diff of the changes `--fix` and `--interactive` make to
+    /// the dependency file to, as a patch that `git apply` accepts
+    #[arg(long, value_name = "FILE")]
+    patch_file: Option<PathBuf>,
+
+    /// If true, checks whether the latest version of each npm dependency carries a
+    /// provenance attestation. Dependencies whose latest version lost the provenance
+    /// of the version in use fail the check.
+    #[arg(long, conflicts_with = "stream", env = "DEPCHK_PROVENANCE")]
+    provenance: bool,
+
+    /// If true, looks up when each dependency last published a version and flags the ones
+    /// that look abandoned, even when they are up to date
+    #[arg(long, conflicts_with = "stream", env = "DEPCHK_MAINTENANCE")]
+    maintenance: bool,
+
+    /// How long, in days, a dependency may go without publishing a version before it is
+    /// flagged as stale by `--maintenance`
+    #[arg(
+        long,
+        value_name = "DAYS",
+        default_value_t = 730,
+        env = "DEPCHK_STALE_AFTER"
+    )]
+    stale_after: u64,
+
+    /// If true, `--maintenance` also flags the dependencies whose GitHub repository is
+    /// archived. A token in the GITHUB_TOKEN environment variable raises GitHub's rate limit.
+    #[arg(long, requires = "maintenance")]
+    check_archived: bool,
+
+    /// If true, also checks the packages the lockfile installs only because the declared
+    /// dependencies depend on them, and reports the outdated ones along with the declared
+    /// dependencies that pull them in. With `--audit`, they are audited as well.
+    #[arg(long, conflicts_with = "stream", env = "DEPCHK_TRANSITIVE")]
+    transitive: bool,
+
+    /// If true, scores how risky each dependency is from 0 to 100, out of how outdated it
+    /// is, its maintenance flags and, with `--audit`, its known vulnerabilities. The
+    /// project scores as high as its riskiest dependency.
+    #[arg(long, conflicts_with = "stream", env = "DEPCHK_RISK")]
+    risk: bool,
+
+    /// Fails the check when the risk score of the project is at least this high. Implies
+    /// `--risk`.
+    #[arg(
+        long,
+        value_name = "SCORE",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        conflicts_with = "stream",
+        env = "DEPCHK_MIN_SCORE"
+    )]
+    min_score: Option<u8>,
+
+    /// The `[[package]]` rules of the config file, which set options for some packages only.
+    #[arg(skip)]
+    packages: Vec<PackageRule>,
+
+    /// Set by `depchk audit`, which only audits the dependencies.
+    #[arg(skip)]
+    audit_only: bool,
+
+    /// Set by `depchk verify`, which only verifies the checksums of the lockfile.
+    #[arg(skip)]
+    verify_only: bool,
+
+    /// Set by `depchk pr`, which opens pull requests with the updates.
+    #[arg(skip)]
+    pull_request: Option<PrArgs>,
+
+    /// Set by `depchk explain`, which prints what changed in the outdated dependencies.
+    #[arg(skip)]
+    explain_only: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Checks the dependencies, which is what depchk does without a subcommand. If the
+    /// dependency type is not given, every dependency file found in the current
+    /// directory is checked, in a single report keyed by dependency file whose thresholds
+    /// apply to all of them together.
+    Check {
+        #[command(flatten)]
+        check: CheckArgs,
+    },
+    /// Prints the JSON Schema of the JSON, YAML and TOML reports
+    Schema,
+    /// Checks the dependencies and prints a badge of how many are outdated, in the
+    /// style of shields.io. The --output option is ignored.
+    Badge {
+        /// Whether to print the badge itself, or a shields.io endpoint that renders it
+        #[arg(value_enum, long, default_value_t)]
+        format: BadgeFormat,
+
+        #[command(flatten)]
+        check: CheckArgs,
+    },
+    /// Looks up the dependencies in the advisory database and reports their known
+    /// vulnerabilities, instead of whether they are up to date. The version of each
+    /// dependency that is audited is the one a fresh install resolves to.
+    Audit {
+        #[command(flatten)]
+        check: CheckArgs,
+    },
+    /// Compares the checksums in the lockfile (Cargo.lock, package-lock.json or
+    /// yarn.lock) with the ones the registry publishes for the same versions, and
+    /// reports the packages whose checksums differ.
+    Verify {
+        #[command(flatten)]
+        check: CheckArgs,
+    },
+    /// Checks the dependencies and opens pull requests on GitHub that update the
+    /// constraints of the outdated ones, like `--fix` would. Needs a token in the
+    /// GITHUB_TOKEN environment variable. With --dry-run, the pull requests are only
+    /// printed.
+    Pr {
+        #[command(flatten)]
+        pr: PrArgs,
+
+        #[command(flatten)]
+        check: CheckArgs,
+    },
+    /// Checks the dependencies and prints, for each outdated one, the release notes of
+    /// the versions between the one in use and the latest, out of the GitHub releases
+    /// or the changelog of its repository. A token in the GITHUB_TOKEN environment
+    /// variable raises GitHub's rate limit. The --output option is ignored.
+    Explain {
+        #[command(flatten)]
+        check: CheckArgs,
+    },
+    /// Restores the dependency file and its lockfiles as they were before the last
+    /// `--fix` or `--interactive` changed them, out of the backup they saved in the
+    /// .depchk/backup directory next to the dependency file.
+    Undo {
+        /// What type of dependency is being parsed
+        #[arg(value_enum)]
+        dependency: DependencyType,
+
+        /// Path to the dependency file. Defaults to the one of the dependency type in
+        /// the current directory.
+        file: Option<PathBuf>,
+    },
+}
+
+impl CheckArgs {
+    /// The directory responses are cached in, unless caching is disabled.
+    fn cache_dir(&self) -> Option<PathBuf> {
+        match self.no_cache {
+            true => None,
+            false => self.cache_dir.clone().or_else(DiskCache::default_dir),
+        }
+    }
+
+    /// The biggest kind of update that is reported and applied for a package, out of
+    /// the first package rule that limits it or else `--update-type`.
+    fn update_type_of(&self, name: &str) -> Option<UpdateType> {
+        self.packages
+            .iter()
+            .find_map(|rule| rule.update_type(name))
+            .or(self.update_type)
+    }
+
+    /// The columns mismatches are listed with.
+    fn columns(&self) -> &[Column] {
+        match &self.columns {
+            Some(columns) => columns,
+            None if self.wide => WIDE_COLUMNS,
+            None => DEFAULT_COLUMNS,
+        }
+    }
+
+    fn thresholds(&self) -> Thresholds {
+        Thresholds {
+            max_libyears: self.max_libyears,
+            max_major_behind: self.max_major_behind,
+            max_outdated_count: self.max_outdated_count,
+        }
+    }
+
+    fn print_options(&self) -> PrintOptions<'_> {
+        PrintOptions {
+            grouping: self.group_by,
+            quiet: self.quiet,
+            all: self.all,
+            columns: self.columns(),
+            pretty: self.pretty,
+        }
+    }
 }
 
-impl Default for OutputTypes {
-    fn default() -> Self {
-        OutputTypes::Table
+impl FailOn {
+    fn is_failed_by(&self, summary: &Summary) -> bool {
+        match self {
+            FailOn::Major => summary.outdated_by_at_least(UpdateKind::Major) > 0,
+            FailOn::Minor => summary.outdated_by_at_least(UpdateKind::Minor) > 0,
+            FailOn::Patch => summary.outdated_by_at_least(UpdateKind::Patch) > 0,
+            FailOn::Any => summary.outdated() > 0,
+            FailOn::Never => false,
+        }
     }
 }
 
 impl DependencyCheckErrors {
-    fn new(err: Vec<Box<dyn Error>>) -> Self {
+    fn new(err: Vec<DepchkError>) -> Self {
         let msg = err
             .iter()
             .map(|error| error.to_string())
@@ -69,8 +827,27 @@ impl DependencyCheckErrors {
         DependencyCheckErrors { errors: err, msg }
     }
 
+    /// Separates the errors of lookups that could not be answered from the offline
+    /// cache, returning the names of the dependencies whose latest version is unknown.
+    fn take_unknown(&mut self) -> Vec<String> {
+        let (unknown, errors): (Vec<_>, Vec<_>) = std::mem::take(&mut self.errors)
+            .into_iter()
+            .partition(DepchkError::is_not_cached);
+
+        *self = DependencyCheckErrors::new(errors);
+
+        unknown
+            .into_iter()
+            .filter_map(|err| match err {
+                DepchkError::Dependency(err) => Some(err.name().to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
     fn join(&mut self, mut err: DependencyCheckErrors) {
         self.errors.append(&mut err.errors);
+        *self = DependencyCheckErrors::new(std::mem::take(&mut self.errors));
     }
 }
 
@@ -86,132 +863,1758 @@ impl Error for DependencyCheckErrors {
     }
 }
 
-fn handle_dependency_result(
-    results: Vec<DependencyMismatchResult>,
-) -> (Vec<VersionMismatch>, DependencyCheckErrors) {
-    let (mismatches, errs): (Vec<_>, Vec<_>) = results.into_iter().partition(|res| res.is_ok());
-    let mismatches = mismatches.into_iter().map(|res| res.unwrap()).collect();
+fn handle_check_report(report: CheckReport) -> (Vec<VersionMismatch>, DependencyCheckErrors) {
+    let errs = report
+        .skipped
+        .into_iter()
+        .chain(report.errored)
+        .map(DepchkError::from)
+        .collect();
+
+    (report.outdated, DependencyCheckErrors::new(errs))
+}
+
+/// Lists the dependencies of a section, once they have been checked.
+fn declared_dependencies<'a, T: Dependency>(
+    client: &'a RegistryClient,
+    dependencies: &'a [T],
+    section: Section,
+) -> impl Iterator<Item = DeclaredDependency> + 'a {
+    dependencies
+        .iter()
+        .map(move |dependency| DeclaredDependency {
+            name: dependency.get_name().to_string(),
+            constraint: dependency.get_constraint().to_string(),
+            section,
+            latest_version: client.resolved_version(dependency),
+            registry: dependency.get_registry().to_string(),
+            homepage: None,
+            license: None,
+            last_published_at: None,
+            flags: Vec::new(),
+            risk_score: None,
+        })
+}
+
+/// Looks up the homepages of the checked dependencies, which registries only
+/// report through a request of their own.
+async fn add_homepages<T: Dependency>(
+    client: &RegistryClient,
+    dependencies: &ProjectDependencies<T>,
+    declared: &mut [DeclaredDependency],
+    concurrency: usize,
+) {
+    let homepages: HashMap<_, _> = stream::iter(dependencies.iter())
+        .filter(|dependency| {
+            future::ready(declared.iter().any(|d| d.name == dependency.get_name()))
+        })
+        .map(|dependency| async move {
+            let homepage = client.homepage(dependency).await.ok().flatten();
+
+            (dependency.get_name(), homepage)
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    for dependency in declared {
+        dependency.homepage = homepages.get(dependency.name.as_str()).cloned().flatten();
+    }
+}
+
+/// Looks up the licenses of the checked dependencies, at the version a fresh install
+/// resolves to, which registries only report through requests of their own.
+async fn add_licenses<T: Dependency>(
+    client: &RegistryClient,
+    dependencies: &ProjectDependencies<T>,
+    declared: &mut [DeclaredDependency],
+    concurrency: usize,
+) {
+    let licenses: HashMap<_, _> = stream::iter(dependencies.iter())
+        .filter(|dependency| {
+            future::ready(declared.iter().any(|d| d.name == dependency.get_name()))
+        })
+        .map(|dependency| async move {
+            let license = match dependency.satisfiable_version(client).await {
+                Some(version) => client
+                    .license(dependency, &version.to_string())
+                    .await
+                    .ok()
+                    .flatten(),
+                None => None,
+            };
 
-    let errs = errs.into_iter().map(|res| res.unwrap_err()).collect();
+            (dependency.get_name(), license)
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
 
-    (mismatches, DependencyCheckErrors::new(errs))
+    for dependency in declared {
+        dependency.license = licenses.get(dependency.name.as_str()).cloned().flatten();
+    }
 }
 
-fn print_table_mismatches(mismatches: &Mismatches) {
-    let mut table = Table::new();
+/// Checks the transitive dependencies for newer versions than the locked ones, and
+/// audits the locked versions if an advisory source is given. Transitive dependencies
+/// whose lookup failed are left out, as they are not the project's to fix.
+async fn check_transitive<T: Dependency>(
+    client: &RegistryClient,
+    transitive: &TransitiveDependencies<T>,
+    source: Option<&AdvisorySource>,
+    min_severity: Option<Severity>,
+    concurrency: usize,
+) -> Result<Vec<TransitiveFinding>, Box<dyn Error>> {
+    let mut findings: BTreeMap<(String, String), TransitiveFinding> = BTreeMap::new();
+
+    let new_finding = |name: &str, version: &str| TransitiveFinding {
+        name: name.to_string(),
+        version: version.to_string(),
+        latest_version: None,
+        update_kind: None,
+        advisories: Vec::new(),
+        via: transitive.parents(name, version).to_vec(),
+    };
 
-    table.set_titles(row![b->"Package Name", b->"Version Constraint", b->"Latest Version"]);
+    for mismatch in check_dependencies(client, transitive.dependencies(), concurrency)
+        .await
+        .outdated
+    {
+        let (name, constraint, latest) = mismatch.destruct();
 
-    for mismatch in &mismatches.dependencies {
-        let (name, constraint, version) = mismatch.destruct();
+        if let Some(version) = transitive.version(name, constraint) {
+            let finding = findings
+                .entry((name.to_string(), version.to_string()))
+                .or_insert_with(|| new_finding(name, version));
 
-        table.add_row(row![FG->name, FB->constraint, FR->version]);
+            finding.latest_version = Some(latest.to_string());
+            finding.update_kind = mismatch.update_kind();
+        }
     }
 
-    if mismatches.dev_dependencies.is_some() {
-        table.add_row(row![bH3->"Dev Dependencies"]);
+    if let Some(source) = source {
+        let vulnerabilities = audit(
+            client,
+            transitive.dependencies(),
+            concurrency,
+            source,
+            min_severity,
+        )
+        .await?;
 
-        for mismatch in mismatches.dev_dependencies.as_ref().unwrap() {
-            let (name, constraint, version) = mismatch.destruct();
+        for vulnerability in &vulnerabilities {
+            let (name, version) = (vulnerability.package(), vulnerability.version());
 
-            table.add_row(row![FG->name, FB->constraint, FR->version]);
+            findings
+                .entry((name.to_string(), version.to_string()))
+                .or_insert_with(|| new_finding(name, version))
+                .advisories
+                .push(vulnerability.id().to_string());
         }
     }
 
-    table.printstd();
+    Ok(findings.into_values().collect())
 }
 
-fn print_csv_mismatches(mismatches: &Mismatches) {
-    for mismatch in &mismatches.dependencies {
-        let (name, constraint, version) = mismatch.destruct();
+/// Looks up how the checked dependencies are maintained, and flags the mismatches
+/// of the ones that look abandoned as well.
+async fn add_maintenance<T: Dependency>(
+    client: &RegistryClient,
+    dependencies: &ProjectDependencies<T>,
+    report: &mut Report,
+    check: &MaintenanceCheck,
+    concurrency: usize,
+) {
+    let declared = &report.dependencies;
+    let assessments: HashMap<_, _> = stream::iter(dependencies.iter())
+        .filter(|dependency| {
+            future::ready(declared.iter().any(|d| d.name == dependency.get_name()))
+        })
+        .map(|dependency| async move {
+            (
+                dependency.get_name(),
+                check.assess(client, dependency).await,
+            )
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
 
-        println!("{},{},{}", name, constraint, version);
+    for dependency in &mut report.dependencies {
+        if let Some(maintenance) = assessments.get(dependency.name.as_str()) {
+            dependency.last_published_at = maintenance.last_published_at();
+            dependency.flags = maintenance.flags().to_vec();
+        }
     }
 
-    if mismatches.dev_dependencies.is_none() {
-        return;
+    let mismatches = &mut report.mismatches;
+    let all = mismatches
+        .dependencies
+        .iter_mut()
+        .chain(mismatches.dev_dependencies.iter_mut().flatten());
+
+    for mismatch in all {
+        if let Some(maintenance) = assessments.get(mismatch.name()) {
+            *mismatch = mismatch.clone().with_flags(maintenance.flags().to_vec());
+        }
     }
-    for mismatch in mismatches.dev_dependencies.as_ref().unwrap() {
-        let (name, constraint, version) = mismatch.destruct();
+}
+
+/// Looks up the release notes of the versions each outdated dependency is behind by,
+/// keyed by the name of the dependency. Dependencies whose version in use is unknown
+/// are left out.
+async fn fetch_changelogs<T: Dependency>(
+    client: &RegistryClient,
+    dependencies: &ProjectDependencies<T>,
+    report: &Report,
+    concurrency: usize,
+) -> HashMap<String, Vec<ReleaseNotes>> {
+    let token = std::env::var("GITHUB_TOKEN").ok();
+    let lookup = ChangelogLookup::new(token.filter(|token| !token.is_empty()));
+
+    let mismatches: HashMap<_, _> = report
+        .mismatches
+        .dependencies
+        .iter()
+        .chain(report.mismatches.dev_dependencies.iter().flatten())
+        .map(|mismatch| (mismatch.name(), mismatch))
+        .collect();
+
+    let lookups = dependencies.iter().filter_map(|dependency| {
+        let mismatch = mismatches.get(dependency.get_name())?;
+
+        Some((
+            dependency,
+            mismatch.satisfiable_version()?,
+            mismatch.latest_version(),
+        ))
+    });
+
+    let lookup = &lookup;
+
+    stream::iter(lookups)
+        .map(|(dependency, from, to)| async move {
+            (
+                dependency.get_name().to_string(),
+                lookup.between(client, dependency, from, to).await,
+            )
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await
+}
+
+fn failed_checks(err: &DependencyCheckErrors, section: Section) -> Vec<FailedCheck> {
+    err.errors
+        .iter()
+        .filter_map(|err| match err {
+            DepchkError::Dependency(err) => Some(err),
+            _ => None,
+        })
+        .map(|err| FailedCheck {
+            name: err.name().to_string(),
+            section,
+            message: err.error().to_string(),
+        })
+        .collect()
+}
+
+/// Checks the dependencies of the other packages of the workspace as well, for
+/// `--group-by workspace`, and labels every mismatch with the package that declares
+/// it. The first package is the root one, whose dependencies the report already has.
+async fn check_workspace<T: Dependency>(
+    checker: &Checker,
+    packages: Vec<WorkspacePackage<T>>,
+    report: &mut Report,
+    args: &CheckArgs,
+    now: SystemTime,
+    progress: &dyn ProgressReporter,
+) -> Result<DependencyCheckErrors, Box<dyn Error>> {
+    let mut packages = packages.into_iter();
+    let mut err = DependencyCheckErrors::default();
+
+    let Some(root) = packages.next() else {
+        return Ok(err);
+    };
+
+    report.assign_workspace(root.name());
+
+    for mut package in packages {
+        let dependencies = package.dependencies_mut();
+
+        dependencies.retain(|dependency| {
+            let name = dependency.get_name();
+            !args.ignore.iter().any(|ignore| ignore.ignores(name, now))
+        });
 
-        println!("{},{},{}", name, constraint, version);
+        checker.use_registries(dependencies);
+
+        let (mut package_report, package_err) = to_report(
+            checker.client(),
+            &report.manifest,
+            report.dependency_type,
+            package.dependencies(),
+            args,
+            progress,
+        )
+        .await?;
+
+        package_report.assign_workspace(package.name());
+        report.append(package_report);
+        err.join(package_err);
     }
+
+    Ok(err)
 }
 
-async fn to_mismatches<T: Dependency>(
-    dependencies: ProjectDependencies<T>,
-    include_dev_dependencies: bool,
-) -> Result<(Mismatches, DependencyCheckErrors), Box<dyn Error>> {
-    let client = Client::builder().build()?;
-    let (mismatches, mut err) =
-        handle_dependency_result(dependencies.check_dependencies(&client).await);
+async fn to_report<T: Dependency>(
+    client: &RegistryClient,
+    manifest: &str,
+    dependency_type: DependencyType,
+    dependencies: &ProjectDependencies<T>,
+    args: &CheckArgs,
+    progress: &dyn ProgressReporter,
+) -> Result<(Report, DependencyCheckErrors), Box<dyn Error>> {
+    let started_at = SystemTime::now();
+    let concurrency = args.concurrency.get();
+
+    let (mismatches, mut err) = handle_check_report(
+        check_dependencies_with_progress(
+            client,
+            dependencies.dependencies(),
+            concurrency,
+            progress,
+        )
+        .await,
+    );
 
-    let (dev_mismatches, dev_err) = {
-        if include_dev_dependencies {
-            let (mismatch, err) =
-                handle_dependency_result(dependencies.check_dev_dependencies(&client).await);
-            (Some(mismatch), err)
-        } else {
-            (None, DependencyCheckErrors::default())
-        }
+    let mut unknown = err.take_unknown();
+    let mut errors = failed_checks(&err, Section::Dependencies);
+    let mut declared: Vec<_> =
+        declared_dependencies(client, dependencies.dependencies(), Section::Dependencies).collect();
+
+    let dev_mismatches = if args.dev {
+        let (mismatch, mut dev_err) = handle_check_report(
+            check_dependencies_with_progress(
+                client,
+                dependencies.dev_dependencies(),
+                concurrency,
+                progress,
+            )
+            .await,
+        );
+
+        unknown.append(&mut dev_err.take_unknown());
+        errors.append(&mut failed_checks(&dev_err, Section::DevDependencies));
+        declared.extend(declared_dependencies(
+            client,
+            dependencies.dev_dependencies(),
+            Section::DevDependencies,
+        ));
+
+        err.join(dev_err);
+
+        Some(mismatch)
+    } else {
+        None
     };
 
-    let all_mismatches = Mismatches {
-        dependencies: mismatches,
-        dev_dependencies: dev_mismatches,
+    if args.columns().contains(&Column::Homepage) {
+        add_homepages(client, dependencies, &mut declared, concurrency).await;
+    }
+
+    let report = Report {
+        manifest: manifest.to_string(),
+        dependency_type,
+        started_at,
+        finished_at: SystemTime::now(),
+        mismatches: Mismatches {
+            dependencies: mismatches,
+            dev_dependencies: dev_mismatches,
+            version_skew: None,
+            unknown: (!unknown.is_empty()).then_some(unknown),
+        },
+        dependencies: declared,
+        errors,
+        drift: None,
+        vulnerabilities: None,
+        unavailable: None,
+        license_violations: None,
+        missing_provenance: None,
+        denied: None,
+        transitive: None,
     };
 
-    err.join(dev_err);
+    Ok((report, err))
+}
+
+/// The options of the check, with the dependencies mapped to registries by the
+/// `[[package]]` rules, falling back to `--registry`.
+fn check_options(args: &CheckArgs, registries: Vec<(PackageRule, Registry)>) -> CheckOptions {
+    let mut options = CheckOptions::new()
+        .with_concurrency(args.concurrency.get())
+        .with_timeout(Duration::from_secs(args.timeout))
+        .with_retry_policy(RetryPolicy::new(
+            args.retries,
+            Duration::from_millis(args.retry_delay),
+        ))
+        .with_registries(move |name| {
+            registries
+                .iter()
+                .find(|(rule, _)| rule.matches(name))
+                .map(|(_, registry)| registry.clone())
+        });
+
+    if let Some(registry) = &args.registry {
+        options = options.with_registry(Registry::new(registry));
+    }
+
+    if let Some(tag) = &args.tag {
+        options = options.with_tag(tag);
+    }
+
+    if let Some(deadline) = args.deadline {
+        options = options.with_deadline(Duration::from_secs(deadline));
+    }
+
+    if let Some(days) = args.min_release_age {
+        options = options.with_min_release_age(Duration::from_secs(days * 24 * 60 * 60));
+    }
+
+    if args.prereleases.is_some() || args.packages.iter().any(PackageRule::sets_prereleases) {
+        let (prereleases, packages) = (args.prereleases, args.packages.clone());
+
+        options = options.with_prerelease_policy(move |name| {
+            packages
+                .iter()
+                .find_map(|rule| rule.prereleases(name))
+                .or(prereleases)
+                .unwrap_or_default()
+                .policy()
+        });
+    }
+
+    if args.offline {
+        options = options.offline();
+    }
 
-    Ok((all_mismatches, err))
+    match args.cache_dir() {
+        Some(dir) => options.with_cache(DiskCache::new(dir, Duration::from_secs(args.cache_ttl))),
+        None => options,
+    }
 }
 
+/// Checks the dependency file. Its report is printed right away, unless it is collected
+/// in `reports` to be printed along with the ones of the other dependency files.
 async fn depchk(
+    args: CheckArgs,
+    dependency_type: DependencyType,
     path: PathBuf,
-    include_dev_dependencies: bool,
+    reports: Option<&mut Reports>,
+) -> Result<u8, Box<dyn Error>> {
+    match dependency_type {
+        DependencyType::Npm => {
+            check_project::<PackageJson>(args, dependency_type, path, reports).await
+        }
+        DependencyType::Cargo => {
+            check_project::<CargoToml>(args, dependency_type, path, reports).await
+        }
+    }
+}
+
+async fn check_project<P: DependencyFileParser>(
+    args: CheckArgs,
     dependency_type: DependencyType,
-    output_type: OutputTypes,
-) -> Result<(), Box<dyn Error>> {
-    let package_json = path.to_str().unwrap();
+    path: PathBuf,
+    reports: Option<&mut Reports>,
+) -> Result<u8, Box<dyn Error>> {
+    let file_name = path
+        .to_str()
+        .ok_or_else(|| format!("{} is not a valid UTF-8 path", path.display()))?;
+    let collecting = reports.is_some();
+    let output_type = args.output.unwrap_or_default();
 
-    let dependencies = match dependency_type {
-        DependencyType::Npm => PackageJson::parse_file(package_json)?,
-    };
+    if args.baseline.is_some() && !output_type.supports_baseline() {
+        return Err(
+            "--baseline is only supported by the table, json, yaml, toml and markdown outputs"
+                .into(),
+        );
+    }
+
+    if args.provenance && dependency_type != DependencyType::Npm {
+        return Err(
+            "--provenance is only supported by npm, the only registry with provenance attestations"
+                .into(),
+        );
+    }
 
-    let (mismatches, err) = to_mismatches(dependencies, include_dev_dependencies).await?;
+    let mut registries = Vec::new();
 
-    match output_type {
-        OutputTypes::Table => print_table_mismatches(&mismatches),
-        OutputTypes::Json => println!("{}", serde_json::to_string(&mismatches)?),
-        OutputTypes::Yaml => println!("{}", serde_yaml::to_string(&mismatches)?),
-        OutputTypes::Csv => print_csv_mismatches(&mismatches),
+    for rule in &args.packages {
+        if let Some(registry) = rule.registry()? {
+            registries.push((rule.clone(), registry));
+        }
     }
 
-    if !err.errors.is_empty() {
-        return Err(Box::new(err));
+    if (args.registry.is_some() || args.tag.is_some() || !registries.is_empty())
+        && dependency_type != DependencyType::Npm
+    {
+        return Err(
+            "--registry, --tag and package registries are only supported for npm dependencies"
+                .into(),
+        );
     }
 
-    Ok(())
-}
+    let checker = check_options(&args, registries).build()?;
+    let client = checker.client();
+    let concurrency = checker.concurrency();
+
+    let fix = args.fix || args.interactive || args.pull_request.is_some();
+
+    if fix && dependency_type != DependencyType::Npm {
+        return Err("--fix and depchk pr are only supported for package.json files".into());
+    }
+
+    if args.pull_request.is_some()
+        && (args.fix || args.interactive || args.sync_lockfile || args.commit)
+    {
+        return Err(
+            "depchk pr cannot be combined with --fix, --interactive, --sync-lockfile or --commit"
+                .into(),
+        );
+    }
+
+    if (args.sync_lockfile || args.dry_run || args.patch_file.is_some() || args.commit) && !fix {
+        return Err(
+            "--sync-lockfile, --dry-run, --patch-file and --commit only work along with --fix or --interactive"
+                .into(),
+        );
+    }
+
+    if args.explain_only && args.stream {
+        return Err("depchk explain cannot stream its output".into());
+    }
+
+    if args.verify_only {
+        let verifications = reports.map(|reports| &mut reports.verifications);
+
+        return verify_project::<P>(&args, &checker, file_name, dependency_type, verifications)
+            .await;
+    }
+
+    let baseline = args.baseline.as_deref().map(Baseline::load).transpose()?;
+    let license_policy = args
+        .license_policy
+        .as_deref()
+        .map(LicensePolicy::load)
+        .transpose()?;
+    let deny_list = args.deny_list.as_deref().map(DenyList::load).transpose()?;
+    let groups = args.groups.as_deref().map(UpdateGroups::load).transpose()?;
+
+    if args.group_by == Grouping::Group && groups.is_none() {
+        return Err("--group-by group needs the groups of --groups".into());
+    }
+
+    if args.group_by == Grouping::Workspace && !args.workspaces {
+        return Err("--group-by workspace needs the workspace of --workspaces".into());
+    }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let cli = Cli::parse();
+    if args.group_by == Grouping::Workspace && fix {
+        return Err(
+            "--fix and depchk pr only update the root package, so they cannot be combined with --group-by workspace"
+                .into(),
+        );
+    }
 
-    let file = match cli.dependency {
-        DependencyType::Npm => cli.file.unwrap_or_else(|| PathBuf::from("package.json")),
+    let advisory_source = match args.audit || args.audit_only {
+        true => Some(
+            args.advisory_database
+                .unwrap_or(AdvisoryDatabase::default_for(dependency_type, file_name))
+                .source(&args, file_name)?,
+        ),
+        false => None,
     };
 
-    let result = tokio::runtime::Builder::new_current_thread()
-        .enable_time()
-        .enable_io()
-        .build()
-        .expect("Could not build async runtime")
-        .block_on(depchk(
-            file,
-            cli.dev,
-            cli.dependency,
-            cli.output.unwrap_or_default(),
-        ));
+    let now = SystemTime::now();
+
+    if !args.quiet {
+        for ignore in args.ignore.iter().filter(|ignore| ignore.is_expired(now)) {
+            eprintln!(
+                "Warning: the ignore of {} expired after {}, so it is checked again",
+                ignore.pattern(),
+                ignore.until().unwrap_or_default()
+            );
+        }
+    }
+
+    let started = Instant::now();
+    let mut dependencies = P::parse_file(file_name)?;
+    let parse_time = started.elapsed();
+
+    dependencies.retain(|dependency| {
+        let name = dependency.get_name();
+        !args.ignore.iter().any(|ignore| ignore.ignores(name, now))
+    });
+
+    checker.use_registries(&mut dependencies);
+
+    if !args.quiet {
+        print_skipped(file_name, &dependencies);
+        warn_lookalikes(&dependencies);
+    }
 
-    result
+    // The deny list checks the locked versions if there is a lockfile, while --yanked
+    // cannot do without one
+    let locked = match args.yanked || deny_list.is_some() {
+        true => P::parse_lockfile(file_name)?,
+        false => None,
+    };
+
+    if args.yanked && locked.is_none() {
+        return Err(
+            "--yanked needs a lockfile, either a Cargo.lock or a package-lock.json file".into(),
+        );
+    }
+
+    let transitive = match args.transitive {
+        true => {
+            let mut transitive = P::parse_transitive(file_name)?.ok_or(
+                "--transitive needs a lockfile, either a Cargo.lock or a package-lock.json file",
+            )?;
+            let names: HashSet<&str> = selected(&args, &dependencies)
+                .map(Dependency::get_name)
+                .collect();
+
+            // Packages that only dev dependencies pull in are left out without --dev
+            transitive.retain_parents(|parent| names.contains(parent));
+
+            Some(transitive)
+        }
+        false => None,
+    };
+
+    if let Some(source) = advisory_source.as_ref().filter(|_| args.audit_only) {
+        return audit_project(
+            &args,
+            client,
+            source,
+            file_name,
+            dependency_type,
+            &dependencies,
+            reports.map(|reports| &mut reports.audits),
+        )
+        .await;
+    }
+
+    let mut workspace_parse_time = None;
+
+    // Workspace manifests are parsed while the root dependencies are already being
+    // checked, instead of holding back every registry lookup until they are all read.
+    let workspace_packages = async {
+        if !args.workspaces {
+            return Ok(None);
+        }
+
+        let started = Instant::now();
+        let packages = P::parse_workspaces(file_name).await;
+
+        workspace_parse_time = Some(started.elapsed());
+
+        packages.map(Some)
+    };
+
+    let started = Instant::now();
+
+    let (err, summary, failed_policy) = if args.stream && output_type.is_streamable() {
+        let (result, workspace_packages) = futures::join!(
+            stream_mismatches(
+                client,
+                file_name,
+                &dependencies,
+                args.dev,
+                concurrency,
+                output_type,
+                args.quiet
+            ),
+            workspace_packages
+        );
+
+        let (mut err, summary) = result?;
+        let version_skew = workspace_packages?.map(|packages| find_version_skew(&packages));
+
+        // NDJSON output has already reported these as they were found.
+        for name in err.take_unknown() {
+            if output_type != OutputTypes::Ndjson && !args.quiet {
+                eprintln!("{}: {}", name, NotCached);
+            }
+        }
+
+        if let Some(version_skew) = &version_skew {
+            if output_type == OutputTypes::Table && !version_skew.is_empty() {
+                println!("\nVersion Skew");
+            }
+
+            print_streamed_skew(version_skew, output_type)?;
+        }
+
+        (err, summary, None)
+    } else {
+        let progress_bar = ProgressBar::default();
+        let progress: &dyn ProgressReporter = match !args.quiet && io::stderr().is_terminal() {
+            true => &progress_bar,
+            false => &(),
+        };
+
+        let (result, workspace_packages) = futures::join!(
+            to_report(
+                client,
+                file_name,
+                dependency_type,
+                &dependencies,
+                &args,
+                progress
+            ),
+            workspace_packages
+        );
+
+        let (mut report, mut err) = result?;
+        let workspace_packages = workspace_packages?;
+
+        report.mismatches.version_skew = workspace_packages.as_deref().map(find_version_skew);
+
+        if let Some(packages) = workspace_packages.filter(|_| args.group_by == Grouping::Workspace)
+        {
+            err.join(check_workspace(&checker, packages, &mut report, &args, now, progress).await?);
+        }
+
+        // Compared first, so that the mismatches left out are not taken as resolved
+        if let Some(baseline) = baseline {
+            report.compare_with(baseline);
+        }
+
+        report.retain_updates_up_to(|name| args.update_type_of(name).map(UpdateType::kind));
+
+        if let Some(groups) = &groups {
+            report.assign_groups(groups);
+        }
+
+        let summary = report.summary();
+
+        if let Some(source) = &advisory_source {
+            report.vulnerabilities = Some(
+                audit(
+                    client,
+                    selected(&args, &dependencies),
+                    concurrency,
+                    source,
+                    args.min_severity,
+                )
+                .await?,
+            );
+        }
+
+        if let Some(transitive) = &transitive {
+            report.transitive = Some(
+                check_transitive(
+                    client,
+                    transitive,
+                    advisory_source.as_ref(),
+                    args.min_severity,
+                    concurrency,
+                )
+                .await?,
+            );
+        }
+
+        if let Some(locked) = locked.as_ref().filter(|_| args.yanked) {
+            report.unavailable = Some(
+                find_unavailable_versions(
+                    client,
+                    selected(&args, &dependencies),
+                    locked,
+                    concurrency,
+                )
+                .await,
+            );
+        }
+
+        if let Some(policy) = &license_policy {
+            add_licenses(client, &dependencies, &mut report.dependencies, concurrency).await;
+
+            report.license_violations =
+                Some(policy.violations(
+                    report.dependencies.iter().map(|dependency| {
+                        (dependency.name.as_str(), dependency.license.as_deref())
+                    }),
+                ));
+        } else if args.columns().contains(&Column::License) {
+            add_licenses(client, &dependencies, &mut report.dependencies, concurrency).await;
+        }
+
+        let columns = args.columns();
+
+        let risk = args.risk || args.min_score.is_some() || columns.contains(&Column::Risk);
+
+        if args.maintenance
+            || risk
+            || columns.contains(&Column::LastPublished)
+            || columns.contains(&Column::Flags)
+        {
+            let mut check = MaintenanceCheck::new(Duration::from_secs(args.stale_after * 86_400));
+
+            if args.check_archived {
+                let token = std::env::var("GITHUB_TOKEN").ok();
+
+                check = check.with_archived_check(token.filter(|token| !token.is_empty()));
+            }
+
+            add_maintenance(client, &dependencies, &mut report, &check, concurrency).await;
+        }
+
+        if let Some(deny_list) = &deny_list {
+            report.denied = Some(
+                deny_list
+                    .find_denied(
+                        client,
+                        selected(&args, &dependencies),
+                        locked.as_ref(),
+                        concurrency,
+                    )
+                    .await,
+            );
+        }
+
+        if args.provenance {
+            report.missing_provenance = Some(
+                find_missing_provenance(client, selected(&args, &dependencies), concurrency).await,
+            );
+        }
+
+        if risk {
+            report.score_risks();
+        }
+
+        let failed_policy = failed_policy(&report, args.min_score);
+
+        report.sort(args.sort, args.group_by);
+
+        let options = args.print_options();
+
+        let changelogs = match args.explain_only || args.pull_request.is_some() {
+            true => fetch_changelogs(client, &dependencies, &report, concurrency).await,
+            false => HashMap::new(),
+        };
+
+        match &args.out {
+            _ if args.explain_only => print_explanations(&report, &changelogs),
+            // Collected reports are printed along with the others once they are all done
+            _ if collecting => {}
+            Some(path) => {
+                save_reports(path, std::slice::from_ref(&report), output_type, options)?;
+
+                if !args.quiet {
+                    println!("{}", report.summary());
+                }
+            }
+            None => print_reports(std::slice::from_ref(&report), output_type, options)?,
+        }
+
+        if args.fix || args.interactive {
+            fix_constraints::<P>(file_name, &report, &args)?;
+        }
+
+        if let Some(pr) = &args.pull_request {
+            open_pull_requests::<P>(
+                client,
+                file_name,
+                dependency_type,
+                &report,
+                &changelogs,
+                &args,
+                pr,
+            )
+            .await?;
+        }
+
+        match reports {
+            Some(reports) if !args.explain_only => reports.checks.push(report),
+            Some(_) => {}
+            None => {
+                if let Some(pushgateway) = &args.pushgateway {
+                    push_report_metrics(&args, pushgateway, std::slice::from_ref(&report)).await?;
+                }
+            }
+        }
+
+        (err, summary, failed_policy)
+    };
+
+    if args.stats {
+        print_stats(
+            parse_time,
+            workspace_parse_time,
+            started.elapsed(),
+            &client.stats(),
+        );
+    }
+
+    if !err.errors.is_empty() {
+        return Err(Box::new(err));
+    }
+
+    // The thresholds of several dependency files are compared with all of them at once
+    let exceeded = match collecting {
+        true => Vec::new(),
+        false => summary.exceeded(&args.thresholds()),
+    };
+
+    for violation in &exceeded {
+        eprintln!("Policy violation: {}", violation);
+    }
+
+    if args.fail_on.is_failed_by(&summary) {
+        return Ok(EXIT_OUTDATED);
+    }
+
+    if !exceeded.is_empty() {
+        return Ok(EXIT_THRESHOLD_EXCEEDED);
+    }
+
+    Ok(failed_policy.unwrap_or(EXIT_SUCCESS))
+}
+
+/// The exit status of several checks, out of the ones that take precedence: an error
+/// first, then outdated dependencies, exceeded thresholds and the other checks in the
+/// order of their exit status.
+fn first_failure(status: u8, other: u8) -> u8 {
+    let precedence = |status: u8| match status {
+        EXIT_SUCCESS => u8::MAX,
+        EXIT_ERROR => 0,
+        status => status,
+    };
+
+    match precedence(other) < precedence(status) {
+        true => other,
+        false => status,
+    }
+}
+
+/// The exit status of the first check beyond outdatedness that the report fails,
+/// if any: known vulnerabilities, unavailable locked versions, license violations,
+/// lost provenance, denied dependencies or a risk score at the `--min-score` gate.
+fn failed_policy(report: &Report, min_score: Option<u8>) -> Option<u8> {
+    let found = |list: Option<usize>| list.is_some_and(|len| len > 0);
+
+    let transitive_vulnerable = report
+        .transitive
+        .iter()
+        .flatten()
+        .any(|finding| !finding.advisories.is_empty());
+
+    if found(report.vulnerabilities.as_ref().map(Vec::len)) || transitive_vulnerable {
+        return Some(EXIT_VULNERABLE);
+    }
+
+    if found(report.unavailable.as_ref().map(Vec::len)) {
+        return Some(EXIT_UNAVAILABLE);
+    }
+
+    if found(report.license_violations.as_ref().map(Vec::len)) {
+        return Some(EXIT_LICENSE_VIOLATION);
+    }
+
+    if report
+        .missing_provenance
+        .iter()
+        .flatten()
+        .any(MissingProvenance::is_lost)
+    {
+        return Some(EXIT_PROVENANCE_LOST);
+    }
+
+    if found(report.denied.as_ref().map(Vec::len)) {
+        return Some(EXIT_DENIED);
+    }
+
+    if let (Some(min_score), Some(risk_score)) = (min_score, report.risk_score()) {
+        if risk_score >= min_score {
+            return Some(EXIT_RISKY);
+        }
+    }
+
+    None
+}
+
+/// An update of the constraint of an outdated dependency, which `--fix` applies and
+/// `--interactive` offers to.
+struct UpdateChoice {
+    update: fix::ConstraintUpdate,
+    /// The constraint that is declared in the dependency file.
+    declared: String,
+    /// The version in use, or the declared constraint if it is not known.
+    current: String,
+    latest: String,
+    update_kind: Option<UpdateKind>,
+    /// The group of related packages the update is made along with, if any.
+    group: Option<String>,
+}
+
+/// The updates that make the constraints of the outdated dependencies allow their
+/// latest version, written in the given style. Constraints that cannot be updated
+/// automatically are warned about.
+fn update_choices(report: &Report, style: PinStyle) -> Vec<UpdateChoice> {
+    let mismatches = report
+        .mismatches
+        .dependencies
+        .iter()
+        .map(|mismatch| (mismatch, false))
+        .chain(
+            report
+                .mismatches
+                .dev_dependencies
+                .iter()
+                .flatten()
+                .map(|mismatch| (mismatch, true)),
+        );
+
+    let mut choices = Vec::new();
+
+    for (mismatch, dev) in mismatches {
+        let (name, constraint, latest) = mismatch.destruct();
+
+        match fix::updated_constraint(constraint, latest, style.style()) {
+            Some(updated) => choices.push(UpdateChoice {
+                update: fix::ConstraintUpdate::new(name, dev, &updated),
+                declared: constraint.to_string(),
+                current: mismatch
+                    .satisfiable_version()
+                    .unwrap_or(constraint)
+                    .to_string(),
+                latest: latest.to_string(),
+                update_kind: mismatch.update_kind(),
+                group: mismatch.group().map(str::to_string),
+            }),
+            None => eprintln!(
+                "Warning: the constraint {} of {} cannot be updated automatically",
+                constraint, name
+            ),
+        }
+    }
+
+    choices
+}
+
+/// Splits the updates into the units they are chosen and committed in: the updates
+/// of each group of related packages together, and every other update on its own.
+fn update_units(choices: Vec<UpdateChoice>) -> Vec<Vec<UpdateChoice>> {
+    let mut units: Vec<Vec<UpdateChoice>> = Vec::new();
+
+    for choice in choices {
+        let unit = choice.group.as_ref().and_then(|group| {
+            units
+                .iter_mut()
+                .find(|unit| unit[0].group.as_ref() == Some(group))
+        });
+
+        match unit {
+            Some(unit) => unit.push(choice),
+            None => units.push(vec![choice]),
+        }
+    }
+
+    units
+}
+
+/// Opens a pull request for each group of updates of the outdated dependencies,
+/// or only prints them with `--dry-run`. The pull requests are made out of the
+/// dependency file that was checked, which should match the one of the base branch.
+async fn open_pull_requests<P: DependencyFileParser>(
+    client: &RegistryClient,
+    file_name: &str,
+    dependency_type: DependencyType,
+    report: &Report,
+    changelogs: &HashMap<String, Vec<ReleaseNotes>>,
+    args: &CheckArgs,
+    pr: &PrArgs,
+) -> Result<(), Box<dyn Error>> {
+    let choices = update_choices(report, args.pin_style);
+
+    if choices.is_empty() {
+        if !args.quiet {
+            eprintln!("No updates to open pull requests for");
+        }
+
+        return Ok(());
+    }
+
+    let path = pr
+        .path
+        .clone()
+        .unwrap_or_else(|| file_name.trim_start_matches("./").to_string());
+    let original = std::fs::read_to_string(file_name)?;
+    let requests = pr::pull_requests(choices, pr.group, &path, dependency_type, changelogs);
+
+    if args.dry_run {
+        for (request, updates) in requests {
+            let updated = P::update_constraints(file_name, &updates)?;
+
+            eprintln!(
+                "{} ({})\n\n{}",
+                request.title(),
+                request.branch(),
+                request.body()
+            );
+            eprint!("{}", fix::unified_diff(&path, &original, &updated));
+        }
+
+        return Ok(());
+    }
+
+    let token = match std::env::var("GITHUB_TOKEN") {
+        Ok(token) if !token.is_empty() => token,
+        _ => return Err("depchk pr needs a token in GITHUB_TOKEN".into()),
+    };
+
+    let repository = GitHubRepository::new(client.http(), &pr.repo, &token)?;
+    let base = match &pr.base {
+        Some(base) => base.clone(),
+        None => repository.default_branch().await?,
+    };
+
+    for (request, updates) in requests {
+        let updated = P::update_constraints(file_name, &updates)?;
+        let url = repository
+            .open_pull_request(&base, &request, &path, &updated)
+            .await?;
+
+        eprintln!("Opened {}", url);
+    }
+
+    Ok(())
+}
+
+/// Rewrites the dependency file so that the constraints of the outdated dependencies
+/// allow their latest version, letting the updates be chosen first with `--interactive`,
+/// syncing the lockfile afterwards with `--sync-lockfile` and committing them with
+/// `--commit`. With `--dry-run`, the changes are only shown as a diff. The report is
+/// left as it is, so it still lists them as outdated. Messages, diffs and the output of
+/// the package manager go to stderr to keep the report on stdout intact.
+fn fix_constraints<P: DependencyFileParser>(
+    file_name: &str,
+    report: &Report,
+    args: &CheckArgs,
+) -> Result<(), Box<dyn Error>> {
+    let mut choices = update_choices(report, args.pin_style);
+
+    if args.interactive && !choices.is_empty() {
+        choices = interactive::select_updates(choices)?;
+    }
+
+    if choices.is_empty() {
+        return Ok(());
+    }
+
+    let updates: Vec<_> = choices.iter().map(|choice| choice.update.clone()).collect();
+    let original = std::fs::read_to_string(file_name)?;
+    let updated = P::update_constraints(file_name, &updates)?;
+
+    if let Some(patch_file) = &args.patch_file {
+        std::fs::write(
+            patch_file,
+            fix::unified_diff(file_name, &original, &updated),
+        )?;
+    }
+
+    if args.dry_run {
+        eprint!("{}", fix::unified_diff(file_name, &original, &updated));
+        return Ok(());
+    }
+
+    if args.interactive && !interactive::confirm_changes(file_name, &original, &updated)? {
+        return Ok(());
+    }
+
+    backup::save(file_name)?;
+
+    match args.commit {
+        // Each package or group is written, synced and committed in turn
+        true => {
+            for unit in update_units(choices) {
+                let updates: Vec<_> = unit.iter().map(|choice| choice.update.clone()).collect();
+
+                std::fs::write(file_name, P::update_constraints(file_name, &updates)?)?;
+
+                if args.sync_lockfile {
+                    sync_lockfile::<P>(file_name, &updates)?;
+                }
+
+                commit::commit(file_name, &commit::commit_message(&unit))?;
+            }
+        }
+        false => {
+            std::fs::write(file_name, updated)?;
+
+            if args.sync_lockfile {
+                sync_lockfile::<P>(file_name, &updates)?;
+            }
+        }
+    }
+
+    if !args.quiet {
+        eprintln!(
+            "Updated {} {} in {}, depchk undo restores the previous version",
+            updates.len(),
+            match updates.len() {
+                1 => "dependency",
+                _ => "dependencies",
+            },
+            file_name
+        );
+    }
+
+    Ok(())
+}
+
+/// Runs the package manager so that the lockfile next to the dependency file matches
+/// the updated constraints, with its output on stderr.
+fn sync_lockfile<P: DependencyFileParser>(
+    file_name: &str,
+    updates: &[fix::ConstraintUpdate],
+) -> Result<(), Box<dyn Error>> {
+    match P::sync_lockfile_command(file_name, updates) {
+        Some(mut command) => {
+            let program = command.get_program().to_string_lossy().into_owned();
+            let status = command
+                .stdout(std::io::stderr())
+                .status()
+                .map_err(|err| format!("could not run {}: {}", program, err))?;
+
+            if !status.success() {
+                return Err(format!("{} could not sync the lockfile ({})", program, status).into());
+            }
+        }
+        None => eprintln!(
+            "Warning: there is no lockfile next to {} to sync",
+            file_name
+        ),
+    }
+
+    Ok(())
+}
+
+/// Looks up the known vulnerabilities of the dependencies, only keeping the ones
+/// that are at least as severe as `min_severity` if there is one.
+async fn audit<'a, T: Dependency + 'a>(
+    client: &RegistryClient,
+    dependencies: impl IntoIterator<Item = &'a T>,
+    concurrency: usize,
+    source: &AdvisorySource,
+    min_severity: Option<Severity>,
+) -> Result<Vec<Vulnerability>, Box<dyn Error>> {
+    let mut vulnerabilities = audit_dependencies(client, dependencies, concurrency, source).await?;
+
+    if let Some(min_severity) = min_severity {
+        vulnerabilities.retain(|vulnerability| vulnerability.is_at_least(min_severity));
+    }
+
+    Ok(vulnerabilities)
+}
+
+/// The dependencies that are audited or looked up in the lockfile, which include
+/// the dev dependencies only if they are checked as well.
+fn selected<'a, T: Dependency>(
+    args: &CheckArgs,
+    dependencies: &'a ProjectDependencies<T>,
+) -> impl Iterator<Item = &'a T> {
+    let dev_dependencies = match args.dev {
+        true => dependencies.dev_dependencies(),
+        false => &[],
+    };
+
+    dependencies.dependencies().iter().chain(dev_dependencies)
+}
+
+/// Prints the section of the entries of the dependency file that were skipped while
+/// parsing it, since they cannot be checked against a registry.
+fn print_skipped<T: Dependency>(file_name: &str, dependencies: &ProjectDependencies<T>) {
+    let warnings = dependencies.warnings();
+
+    if warnings.is_empty() {
+        return;
+    }
+
+    eprintln!("Skipped entries of {}:", file_name);
+
+    for warning in warnings {
+        eprintln!("  {}", warning);
+    }
+}
+
+/// Warns about the dependencies whose name is a likely misspelling of a popular
+/// package of the same registry, which typosquatting packages count on.
+fn warn_lookalikes<T: Dependency>(dependencies: &ProjectDependencies<T>) {
+    let all = dependencies
+        .dependencies()
+        .iter()
+        .chain(dependencies.dev_dependencies());
+
+    for dependency in all {
+        let name = dependency.get_name();
+        let lookalike = dependency
+            .get_ecosystem()
+            .and_then(|ecosystem| typosquat::lookalike(name, ecosystem));
+
+        if let Some(popular) = lookalike {
+            eprintln!(
+                "Warning: {} looks like a misspelling of the popular package {}",
+                name, popular
+            );
+        }
+    }
+}
+
+/// Pushes the metrics of the reports to the Pushgateway.
+async fn push_report_metrics(
+    args: &CheckArgs,
+    pushgateway: &str,
+    reports: &[Report],
+) -> Result<(), Box<dyn Error>> {
+    let http = http_client_builder()
+        .timeout(Duration::from_secs(args.timeout))
+        .build()?;
+
+    push_metrics(&http, pushgateway, reports).await
+}
+
+/// Runs `depchk audit`, which only reports the known vulnerabilities of the
+/// dependencies. The audit is printed right away, unless it is collected in `audits`.
+async fn audit_project<T: Dependency>(
+    args: &CheckArgs,
+    client: &RegistryClient,
+    source: &AdvisorySource,
+    manifest: &str,
+    dependency_type: DependencyType,
+    dependencies: &ProjectDependencies<T>,
+    audits: Option<&mut Vec<AuditReport>>,
+) -> Result<u8, Box<dyn Error>> {
+    let vulnerabilities = audit(
+        client,
+        selected(args, dependencies),
+        args.concurrency.get(),
+        source,
+        args.min_severity,
+    )
+    .await?;
+
+    let report = AuditReport::new(manifest, dependency_type, vulnerabilities);
+
+    let vulnerable = !report.vulnerabilities.is_empty();
+
+    match audits {
+        Some(audits) => audits.push(report),
+        None => print_audits(&[report], args.output.unwrap_or_default(), args.quiet)?,
+    }
+
+    match vulnerable {
+        false => Ok(EXIT_SUCCESS),
+        true => Ok(EXIT_VULNERABLE),
+    }
+}
+
+/// Runs `depchk verify`, which only compares the checksums of the lockfile with the
+/// ones the registry publishes. The outcome is printed right away, unless it is
+/// collected in `verifications`.
+async fn verify_project<P: DependencyFileParser>(
+    args: &CheckArgs,
+    checker: &Checker,
+    manifest: &str,
+    dependency_type: DependencyType,
+    verifications: Option<&mut Vec<VerifyReport>>,
+) -> Result<u8, Box<dyn Error>> {
+    let locked = P::parse_lockfile_checksums(manifest)?.ok_or(
+        "depchk verify needs a lockfile, either a Cargo.lock, a package-lock.json or a yarn.lock file",
+    )?;
+
+    let results = checker.verify_checksums::<P>(&locked).await;
+
+    let mut mismatches = Vec::new();
+    let mut failed = false;
+
+    for result in results {
+        match result {
+            Ok(mismatch) => mismatches.push(mismatch),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                failed = true;
+            }
+        }
+    }
+
+    let report = VerifyReport::new(manifest, dependency_type, locked.len(), mismatches);
+
+    let tampered = !report.mismatches.is_empty();
+
+    match verifications {
+        Some(verifications) => verifications.push(report),
+        None => print_verifications(&[report], args.output.unwrap_or_default(), args.quiet)?,
+    }
+
+    match (failed, tampered) {
+        (true, _) => Ok(EXIT_ERROR),
+        (false, false) => Ok(EXIT_SUCCESS),
+        (false, true) => Ok(EXIT_TAMPERED),
+    }
+}
+
+/// The dependency files to check along with their type, out of the command line or
+/// else the ones found in the current directory.
+fn manifests(args: &CheckArgs) -> Result<Vec<(DependencyType, PathBuf)>, Box<dyn Error>> {
+    let found = if args.recursive {
+        discover::find_recursive(Path::new("."))?
+    } else if !args.glob.is_empty() {
+        discover::find_matching(&args.glob, args.quiet)?
+    } else if let Some(dependency_type) = args.dependency {
+        let file = args
+            .file
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(dependency_type.default_file()));
+
+        return Ok(vec![(dependency_type, file)]);
+    } else {
+        discover::detect(Path::new(""), args.quiet)
+    };
+
+    // A dependency type limits the search to its own dependency files
+    let found: Vec<_> = found
+        .into_iter()
+        .filter(|(dependency_type, _)| args.dependency.is_none_or(|only| only == *dependency_type))
+        .collect();
+
+    match found.is_empty() {
+        true => Err("no package.json or Cargo.toml file was found to check".into()),
+        false => Ok(found),
+    }
+}
+
+/// Prints the reports collected while checking several dependency files as a single
+/// report, or writes it to `--out`, and pushes their metrics to the Pushgateway.
+async fn print_collected(args: &CheckArgs, reports: &Reports) -> Result<(), Box<dyn Error>> {
+    let output_type = args.output.unwrap_or_default();
+
+    if !reports.audits.is_empty() {
+        print_audits(&reports.audits, output_type, args.quiet)?;
+    }
+
+    if !reports.verifications.is_empty() {
+        print_verifications(&reports.verifications, output_type, args.quiet)?;
+    }
+
+    if reports.checks.is_empty() {
+        return Ok(());
+    }
+
+    match &args.out {
+        Some(path) => {
+            save_reports(path, &reports.checks, output_type, args.print_options())?;
+
+            if !args.quiet {
+                println!("{}", combined_summary(&reports.checks));
+            }
+        }
+        None => print_reports(&reports.checks, output_type, args.print_options())?,
+    }
+
+    if let Some(pushgateway) = &args.pushgateway {
+        push_report_metrics(args, pushgateway, &reports.checks).await?;
+    }
+
+    Ok(())
+}
+
+/// Prints the spans and events of the checks to stderr. Only the ones of depchk itself are
+/// printed, since the HTTP stack underneath it traces every connection it makes.
+fn log_to_stderr() {
+    let targets = Targets::new().with_target("depchk_core", Level::DEBUG);
+
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_writer(io::stderr))
+        .with(targets)
+        .init();
+}
+
+fn print_stats(
+    parse_time: Duration,
+    workspace_parse_time: Option<Duration>,
+    check_time: Duration,
+    stats: &LookupStats,
+) {
+    eprintln!("Parse time:           {:.2?}", parse_time);
+
+    if let Some(workspace_parse_time) = workspace_parse_time {
+        eprintln!("Workspace parse time: {:.2?}", workspace_parse_time);
+    }
+
+    eprintln!("Check time:           {:.2?}", check_time);
+    eprintln!(
+        "Network time:         {:.2?} (all requests combined)",
+        stats.network_time()
+    );
+
+    for (registry, count) in stats.requests() {
+        eprintln!("Requests:             {} to {}", count, registry);
+    }
+
+    let lookups = stats.cache_hits() + stats.cache_misses();
+
+    if lookups > 0 {
+        eprintln!(
+            "Cache hit rate:       {:.1}% ({} of {} lookups)",
+            stats.cache_hits() as f64 * 100.0 / lookups as f64,
+            stats.cache_hits(),
+            lookups
+        );
+    }
+
+    let slowest = stats.slowest(5);
+
+    if !slowest.is_empty() {
+        eprintln!("Slowest packages:");
+
+        for (name, duration) in slowest {
+            eprintln!("  {:<30} {:.2?}", name, duration);
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    ExitCode::from(run(&Cli::command().get_matches()))
+}
+
+/// Runs what the command line asks for, and returns the exit status.
+fn run(matches: &ArgMatches) -> u8 {
+    let Cli { command, check } = Cli::from_arg_matches(matches).unwrap_or_else(|err| err.exit());
+
+    // The options of the check are parsed from the matches of its subcommand, if any
+    let check_matches = matches.subcommand().map_or(matches, |(_, matches)| matches);
+
+    let mut badge = None;
+
+    let args = match command {
+        Some(Command::Schema) => {
+            print!("{}", SCHEMA);
+            return EXIT_SUCCESS;
+        }
+        Some(Command::Check { check }) => check,
+        Some(Command::Badge { format, check }) => {
+            badge = Some(format);
+            check
+        }
+        Some(Command::Audit { check }) => CheckArgs {
+            audit_only: true,
+            ..check
+        },
+        Some(Command::Verify { check }) => CheckArgs {
+            verify_only: true,
+            ..check
+        },
+        Some(Command::Pr { pr, check }) => CheckArgs {
+            pull_request: Some(pr),
+            ..check
+        },
+        Some(Command::Explain { check }) => CheckArgs {
+            explain_only: true,
+            ..check
+        },
+        Some(Command::Undo { dependency, file }) => {
+            let file = file.unwrap_or_else(|| PathBuf::from(dependency.default_file()));
+
+            return match backup::restore(&file.to_string_lossy()) {
+                Ok(restored) => {
+                    for file in restored {
+                        eprintln!("Restored {}", file.display());
+                    }
+
+                    EXIT_SUCCESS
+                }
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    EXIT_ERROR
+                }
+            };
+        }
+        None => check,
+    };
+
+    let manifests = match manifests(&args) {
+        Ok(manifests) => manifests,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            return EXIT_ERROR;
+        }
+    };
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .enable_io()
+        .build()
+        .expect("Could not build async runtime");
+
+    let several = manifests.len() > 1;
+    let mut logging = false;
+    let mut status = EXIT_SUCCESS;
+
+    // The reports of several dependency files are printed as one once all are checked,
+    // as the first of them is configured to be
+    let mut reports = Reports::default();
+    let mut printed_as = None;
+
+    for (index, (dependency_type, file)) in manifests.into_iter().enumerate() {
+        let mut args = args.clone();
+
+        let result = match config::configure(&mut args, check_matches, dependency_type, &file) {
+            Ok(()) => {
+                if args.verbose && !logging {
+                    log_to_stderr();
+                    logging = true;
+                }
+
+                // The badge is the output of depchk badge, whatever the config says
+                if let Some(format) = badge {
+                    args.output = Some(format.output_type());
+                }
+
+                if several && args.stream && args.output.unwrap_or_default().is_streamable() {
+                    eprintln!(
+                        "Error: --stream cannot be used when several dependency files are checked"
+                    );
+                    return EXIT_ERROR;
+                }
+
+                if several {
+                    // Explanations are not part of the report, so they are printed right away
+                    if args.explain_only {
+                        match index {
+                            0 => println!("{}", file.display()),
+                            _ => println!("\n{}", file.display()),
+                        }
+                    }
+
+                    printed_as.get_or_insert_with(|| args.clone());
+
+                    runtime.block_on(depchk(args, dependency_type, file, Some(&mut reports)))
+                } else {
+                    runtime.block_on(depchk(args, dependency_type, file, None))
+                }
+            }
+            Err(err) => Err(err),
+        };
+
+        // Errors are printed by hand rather than returned from main, which would print
+        // their debug representation. Either way, they never end up on stdout.
+        match result {
+            Ok(code) => status = first_failure(status, code),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                status = EXIT_ERROR;
+            }
+        }
+    }
+
+    if let Some(args) = printed_as {
+        if let Err(err) = runtime.block_on(print_collected(&args, &reports)) {
+            eprintln!("Error: {}", err);
+            status = EXIT_ERROR;
+        }
+
+        // Thresholds apply to the dependency files together, like in a single project
+        let exceeded = combined_summary(&reports.checks).exceeded(&args.thresholds());
+
+        for violation in &exceeded {
+            eprintln!("Policy violation: {}", violation);
+        }
+
+        if !exceeded.is_empty() {
+            status = first_failure(status, EXIT_THRESHOLD_EXCEEDED);
+        }
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_first_failure() {
+        assert_eq!(
+            first_failure(EXIT_SUCCESS, EXIT_VULNERABLE),
+            EXIT_VULNERABLE
+        );
+        assert_eq!(
+            first_failure(EXIT_VULNERABLE, EXIT_SUCCESS),
+            EXIT_VULNERABLE
+        );
+        assert_eq!(first_failure(EXIT_VULNERABLE, EXIT_OUTDATED), EXIT_OUTDATED);
+        assert_eq!(
+            first_failure(EXIT_DENIED, EXIT_THRESHOLD_EXCEEDED),
+            EXIT_THRESHOLD_EXCEEDED
+        );
+        assert_eq!(first_failure(EXIT_OUTDATED, EXIT_ERROR), EXIT_ERROR);
+        assert_eq!(first_failure(EXIT_ERROR, EXIT_OUTDATED), EXIT_ERROR);
+    }
+
+    #[test]
+    fn test_check_several_dependency_files() {
+        let directory = std::env::temp_dir().join(format!("depchk-glob-{}", std::process::id()));
+
+        fs::create_dir_all(directory.join("crates/cli")).unwrap();
+        fs::write(
+            directory.join("package.json"),
+            r#"{ "name": "app", "version": "1.0.0" }"#,
+        )
+        .unwrap();
+        fs::write(
+            directory.join("crates/cli/Cargo.toml"),
+            "[package]\nname = \"cli\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        let path = |path: &str| directory.join(path).to_string_lossy().into_owned();
+
+        let matches = Cli::command().get_matches_from([
+            "depchk".to_string(),
+            "--offline".to_string(),
+            "--quiet".to_string(),
+            "--cache-dir".to_string(),
+            path("cache"),
+            "--glob".to_string(),
+            path("package.json"),
+            "--glob".to_string(),
+            path("crates/*/Cargo.toml"),
+            "--output".to_string(),
+            "json".to_string(),
+            "--out".to_string(),
+            path("report.json"),
+        ]);
+
+        assert_eq!(run(&matches), EXIT_SUCCESS);
+
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(directory.join("report.json")).unwrap())
+                .unwrap();
+        let manifests = report["manifests"].as_object().unwrap();
+
+        assert_eq!(report["schema_version"], 1);
+        assert_eq!(manifests.len(), 2);
+        assert_eq!(manifests[&path("package.json")]["package_manager"], "npm");
+        assert_eq!(
+            manifests[&path("crates/cli/Cargo.toml")]["package_manager"],
+            "cargo"
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
 }
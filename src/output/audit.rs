@@ -0,0 +1,187 @@
+use std::error::Error;
+use std::fmt::Write;
+
+use depchk_core::audit::{Severity, Vulnerability};
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+
+use super::json::{JsonReports, SCHEMA_VERSION};
+use super::{print_document, table_heading, OutputTypes};
+use crate::DependencyType;
+
+/// The outcome of `depchk audit`, which only looks for vulnerabilities and does
+/// not check whether the dependencies are up to date.
+#[derive(Serialize)]
+pub struct AuditReport {
+    pub schema_version: u32,
+    pub manifest: String,
+    pub package_manager: DependencyType,
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+impl AuditReport {
+    pub fn new(
+        manifest: &str,
+        package_manager: DependencyType,
+        vulnerabilities: Vec<Vulnerability>,
+    ) -> Self {
+        AuditReport {
+            schema_version: SCHEMA_VERSION,
+            manifest: manifest.to_string(),
+            package_manager,
+            vulnerabilities,
+        }
+    }
+}
+
+/// Builds a table of the vulnerabilities, with the advisory identifiers, their
+/// severity and the versions they are fixed in.
+pub fn vulnerability_table(vulnerabilities: &[Vulnerability]) -> Table {
+    let mut table = Table::new();
+
+    table.set_titles(row![
+        b->"Package Name",
+        b->"Version",
+        b->"Advisory",
+        b->"Severity",
+        b->"Summary",
+        b->"Fixed In"
+    ]);
+
+    for vulnerability in vulnerabilities {
+        let mut advisory = vulnerability.id().to_string();
+
+        for alias in vulnerability.aliases() {
+            let _ = write!(advisory, "\n{}", alias);
+        }
+
+        if let Some(informational) = vulnerability.informational() {
+            let _ = write!(advisory, "\n({})", informational);
+        }
+
+        table.add_row(Row::new(vec![
+            Cell::new(vulnerability.package()).style_spec("FG"),
+            Cell::new(vulnerability.version()).style_spec("Fr"),
+            Cell::new(&advisory),
+            Cell::new(&severity(vulnerability)).style_spec(match vulnerability.severity() {
+                Some(Severity::Critical | Severity::High) => "Fr",
+                Some(Severity::Medium) => "Fy",
+                _ => "",
+            }),
+            Cell::new(vulnerability.summary().unwrap_or_default()),
+            Cell::new(&vulnerability.fixed_versions().join(", ")).style_spec("Fg"),
+        ]));
+    }
+
+    table
+}
+
+/// The severity of the vulnerability along with its CVSS score, e.g. "high (7.5)",
+/// or an empty string if neither is known.
+pub fn severity(vulnerability: &Vulnerability) -> String {
+    match (vulnerability.severity(), vulnerability.cvss_score()) {
+        (Some(severity), Some(score)) => format!("{} ({:.1})", severity, score),
+        (Some(severity), None) => severity.to_string(),
+        (None, Some(score)) => format!("{:.1}", score),
+        (None, None) => String::new(),
+    }
+}
+
+/// Prints the outcome of the audits in the given output type, of which only the
+/// table and the JSON, YAML and TOML outputs are supported. The audits of several
+/// dependency files are printed as one, headed or keyed by their dependency file.
+pub fn print_audits(
+    reports: &[AuditReport],
+    output_type: OutputTypes,
+    quiet: bool,
+) -> Result<(), Box<dyn Error>> {
+    match output_type {
+        OutputTypes::Table => {
+            for (index, report) in reports.iter().enumerate() {
+                if reports.len() > 1 {
+                    println!("{}", table_heading(index, &report.manifest));
+                }
+
+                if !report.vulnerabilities.is_empty() {
+                    vulnerability_table(&report.vulnerabilities).printstd();
+                }
+            }
+
+            if !quiet {
+                let vulnerabilities: Vec<_> = reports
+                    .iter()
+                    .flat_map(|report| report.vulnerabilities.iter().cloned())
+                    .collect();
+
+                println!("{}", audit_summary(&vulnerabilities));
+            }
+        }
+        OutputTypes::Json | OutputTypes::Yaml | OutputTypes::Toml => match reports {
+            [report] => print_document(report, output_type)?,
+            _ => print_document(
+                &JsonReports::new(
+                    reports
+                        .iter()
+                        .map(|report| (report.manifest.as_str(), report)),
+                ),
+                output_type,
+            )?,
+        },
+        _ => {
+            return Err("depchk audit only supports the table, json, yaml and toml outputs".into())
+        }
+    }
+
+    Ok(())
+}
+
+/// A one line summary of the vulnerabilities, e.g. "2 known vulnerabilities in 1
+/// dependency".
+pub fn audit_summary(vulnerabilities: &[Vulnerability]) -> String {
+    let mut packages: Vec<_> = vulnerabilities.iter().map(Vulnerability::package).collect();
+
+    packages.sort_unstable();
+    packages.dedup();
+
+    match vulnerabilities.len() {
+        0 => "No known vulnerabilities".to_string(),
+        1 => "1 known vulnerability in 1 dependency".to_string(),
+        count => format!(
+            "{} known vulnerabilities in {} {}",
+            count,
+            packages.len(),
+            if packages.len() == 1 {
+                "dependency"
+            } else {
+                "dependencies"
+            }
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use depchk_core::audit::AuditedPackage;
+
+    use super::*;
+
+    #[test]
+    fn test_audit_summary() {
+        let lodash = AuditedPackage::new("lodash", "4.17.15", "npm");
+        let axios = AuditedPackage::new("axios", "0.21.0", "npm");
+
+        assert_eq!(audit_summary(&[]), "No known vulnerabilities");
+        assert_eq!(
+            audit_summary(&[Vulnerability::new(&lodash, "GHSA-35jh-r3h4-6jhm")]),
+            "1 known vulnerability in 1 dependency"
+        );
+        assert_eq!(
+            audit_summary(&[
+                Vulnerability::new(&lodash, "GHSA-35jh-r3h4-6jhm"),
+                Vulnerability::new(&lodash, "GHSA-p6mc-m468-83gw"),
+                Vulnerability::new(&axios, "GHSA-cph5-m8f7-6c5x"),
+            ]),
+            "3 known vulnerabilities in 2 dependencies"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt::Write;
+
+use clap::ValueEnum;
+use depchk_core::UpdateKind;
+use serde_json::{json, Value};
+
+use super::{combined_summary, escape_xml, OutputTypes, Report};
+
+const LABEL: &str = "dependencies";
+
+/// How `depchk badge` writes the badge.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BadgeFormat {
+    /// A standalone SVG image
+    #[default]
+    Svg,
+    /// A JSON payload for the shields.io endpoint badge, which renders it
+    Json,
+}
+
+impl BadgeFormat {
+    /// The output type the badge is rendered as.
+    pub fn output_type(&self) -> OutputTypes {
+        match self {
+            BadgeFormat::Svg => OutputTypes::BadgeSvg,
+            BadgeFormat::Json => OutputTypes::BadgeJson,
+        }
+    }
+}
+
+/// The message and the color of the badge, as a shields.io color name and as
+/// the hex code the SVG is drawn with. The badge of several dependency files counts
+/// the outdated dependencies of all of them.
+fn message(reports: &[Report]) -> (String, &'static str, &'static str) {
+    let summary = combined_summary(reports);
+
+    if summary.outdated() == 0 {
+        return match reports.iter().all(|report| report.errors.is_empty()) {
+            true => ("up to date".to_string(), "brightgreen", "#4c1"),
+            false => ("unknown".to_string(), "lightgrey", "#9f9f9f"),
+        };
+    }
+
+    let message = format!("{} outdated", summary.outdated());
+
+    if summary.outdated_by_at_least(UpdateKind::Major) > 0 {
+        (message, "red", "#e05d44")
+    } else if summary.outdated_by_at_least(UpdateKind::Minor) > 0 {
+        (message, "yellow", "#dfb317")
+    } else {
+        (message, "yellowgreen", "#a4a61d")
+    }
+}
+
+/// Renders the badge as a shields.io endpoint payload.
+pub fn render_badge_json(reports: &[Report]) -> Value {
+    let (message, color, _) = message(reports);
+
+    json!({
+        "schemaVersion": 1,
+        "label": LABEL,
+        "message": message,
+        "color": color,
+    })
+}
+
+/// Renders the badge as a flat shields.io style SVG. Text widths are estimated
+/// from the number of characters, since the font is not available to measure them.
+pub fn render_badge_svg(reports: &[Report]) -> String {
+    let (message, _, color) = message(reports);
+
+    let text_width = |text: &str| text.chars().count() * 7 + 10;
+    let (label_width, message_width) = (text_width(LABEL), text_width(&message));
+    let width = label_width + message_width;
+
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"20\" role=\"img\" aria-label=\"{}: {}\">",
+        width, LABEL, escape_xml(&message)
+    );
+    let _ = writeln!(svg, "<title>{}: {}</title>", LABEL, escape_xml(&message));
+    let _ = writeln!(
+        svg,
+        "<linearGradient id=\"s\" x2=\"0\" y2=\"100%\"><stop offset=\"0\" stop-color=\"#bbb\" stop-opacity=\".1\"/><stop offset=\"1\" stop-opacity=\".1\"/></linearGradient>"
+    );
+    let _ = writeln!(
+        svg,
+        "<clipPath id=\"r\"><rect width=\"{}\" height=\"20\" rx=\"3\" fill=\"#fff\"/></clipPath>",
+        width
+    );
+    let _ = writeln!(
+        svg,
+        "<g clip-path=\"url(#r)\"><rect width=\"{}\" height=\"20\" fill=\"#555\"/><rect x=\"{}\" width=\"{}\" height=\"20\" fill=\"{}\"/><rect width=\"{}\" height=\"20\" fill=\"url(#s)\"/></g>",
+        label_width, label_width, message_width, color, width
+    );
+    let _ = writeln!(
+        svg,
+        "<g fill=\"#fff\" text-anchor=\"middle\" font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">"
+    );
+
+    for (text, x) in [
+        (LABEL.to_string(), label_width / 2),
+        (escape_xml(&message), label_width + message_width / 2),
+    ] {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"15\" fill=\"#010101\" fill-opacity=\".3\">{}</text><text x=\"{}\" y=\"14\">{}</text>",
+            x, text, x, text
+        );
+    }
+
+    svg.push_str("</g>\n</svg>");
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use depchk_core::{Mismatches, VersionMismatch};
+
+    use super::*;
+    use crate::output::{FailedCheck, Section};
+    use crate::DependencyType;
+
+    fn report(mismatches: Vec<VersionMismatch>) -> Report {
+        Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: mismatches,
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        }
+    }
+
+    #[test]
+    fn test_render_badge_json() {
+        assert_eq!(
+            render_badge_json(&[report(vec![])]),
+            json!({
+                "schemaVersion": 1,
+                "label": "dependencies",
+                "message": "up to date",
+                "color": "brightgreen",
+            })
+        );
+
+        let outdated = [report(vec![
+            VersionMismatch::new("react", "^17.0.0", "18.2.0").with_update_kind(UpdateKind::Major),
+            VersionMismatch::new("axios", "~1.3.0", "1.4.0").with_update_kind(UpdateKind::Minor),
+        ])];
+
+        assert_eq!(render_badge_json(&outdated)["message"], "2 outdated");
+        assert_eq!(render_badge_json(&outdated)["color"], "red");
+
+        let mut failed = report(vec![]);
+
+        failed.errors.push(FailedCheck {
+            name: "lodash".to_string(),
+            section: Section::Dependencies,
+            message: "timed out".to_string(),
+        });
+
+        assert_eq!(render_badge_json(&[failed])["message"], "unknown");
+
+        let several = [
+            report(vec![VersionMismatch::new("axios", "~1.3.0", "1.4.0")
+                .with_update_kind(UpdateKind::Minor)]),
+            report(vec![
+                VersionMismatch::new("serde", "0.9", "1.0.0").with_update_kind(UpdateKind::Major)
+            ]),
+        ];
+
+        assert_eq!(render_badge_json(&several)["message"], "2 outdated");
+        assert_eq!(render_badge_json(&several)["color"], "red");
+    }
+
+    #[test]
+    fn test_render_badge_svg() {
+        let svg = render_badge_svg(&[report(vec![VersionMismatch::new(
+            "axios", "~1.3.0", "1.3.5",
+        )
+        .with_update_kind(UpdateKind::Patch)])]);
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"174\""));
+        assert!(svg.contains("<title>dependencies: 1 outdated</title>"));
+        assert!(svg.contains("fill=\"#a4a61d\""));
+        assert!(svg.ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,269 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use depchk_core::{UpdateKind, VersionMismatch};
+use serde::Deserialize;
+
+use super::json::SCHEMA_VERSION;
+use super::{Report, Section};
+
+/// A JSON report saved by an earlier run, of which only the mismatches are read.
+#[derive(Deserialize)]
+struct BaselineReport {
+    schema_version: u32,
+    mismatches: Vec<BaselineMismatch>,
+}
+
+#[derive(Deserialize)]
+struct BaselineMismatch {
+    name: String,
+    section: Section,
+    constraint: String,
+    latest_version: String,
+    satisfiable_version: Option<String>,
+    update_kind: Option<UpdateKind>,
+    libyears: Option<f64>,
+}
+
+/// The mismatches of an earlier run, which the current one is compared against.
+pub struct Baseline {
+    mismatches: Vec<(Section, VersionMismatch)>,
+}
+
+/// How the mismatches changed since the baseline. Mismatches that are not in the
+/// baseline are new, while the ones that are in it as they are now did not change.
+#[derive(Default)]
+pub struct Drift {
+    /// The mismatches of the baseline that have been resolved since.
+    pub resolved: Vec<(Section, VersionMismatch)>,
+    /// The mismatches whose constraint or latest version changed since the
+    /// baseline, as they were in the baseline.
+    pub changed: Vec<(Section, VersionMismatch)>,
+    unchanged: Vec<(Section, String)>,
+}
+
+/// Whether a mismatch is new or has changed since the baseline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    New,
+    Changed,
+}
+
+impl Baseline {
+    /// Reads a report that was saved with `--output json`.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let report: BaselineReport = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| format!("{} is not a depchk JSON report: {}", path.display(), err))?;
+
+        if report.schema_version != SCHEMA_VERSION {
+            return Err(format!(
+                "{} has schema version {}, while only version {} is supported",
+                path.display(),
+                report.schema_version,
+                SCHEMA_VERSION
+            )
+            .into());
+        }
+
+        let mismatches = report
+            .mismatches
+            .into_iter()
+            .map(|previous| {
+                let mismatch = VersionMismatch::new(
+                    &previous.name,
+                    &previous.constraint,
+                    &previous.latest_version,
+                );
+
+                let mismatch = match previous.satisfiable_version {
+                    Some(version) => mismatch.with_satisfiable_version(&version),
+                    None => mismatch,
+                };
+                let mismatch = match previous.update_kind {
+                    Some(update_kind) => mismatch.with_update_kind(update_kind),
+                    None => mismatch,
+                };
+                let mismatch = match previous.libyears {
+                    Some(libyears) => mismatch.with_libyears(libyears),
+                    None => mismatch,
+                };
+
+                (previous.section, mismatch)
+            })
+            .collect();
+
+        Ok(Baseline { mismatches })
+    }
+}
+
+impl Drift {
+    /// Whether the mismatch is new or changed since the baseline, or `None` if it
+    /// is exactly as it was.
+    pub fn change(&self, section: Section, mismatch: &VersionMismatch) -> Option<Change> {
+        let is_in = |mismatches: &[(Section, VersionMismatch)]| {
+            mismatches
+                .iter()
+                .any(|(s, previous)| *s == section && previous.name() == mismatch.name())
+        };
+
+        if self
+            .unchanged
+            .iter()
+            .any(|(s, name)| *s == section && name == mismatch.name())
+        {
+            None
+        } else if is_in(&self.changed) {
+            Some(Change::Changed)
+        } else {
+            Some(Change::New)
+        }
+    }
+}
+
+impl Report {
+    /// Compares the mismatches with the ones of the baseline. A mismatch of the
+    /// baseline is only resolved if its dependency is now up to date or no longer
+    /// declared, and not if it could not be checked this time around.
+    pub fn compare_with(&mut self, baseline: Baseline) {
+        let mut drift = Drift::default();
+
+        for (section, previous) in baseline.mismatches {
+            if section == Section::DevDependencies && self.mismatches.dev_dependencies.is_none() {
+                continue;
+            }
+
+            let current = self
+                .mismatches(section)
+                .iter()
+                .find(|mismatch| mismatch.name() == previous.name());
+
+            match current {
+                Some(current) if current.destruct() == previous.destruct() => {
+                    drift.unchanged.push((section, previous.name().to_string()))
+                }
+                Some(_) => drift.changed.push((section, previous)),
+                None => {
+                    let was_checked =
+                        self.errors
+                            .iter()
+                            .all(|err| err.section != section || err.name != previous.name())
+                            && !self.mismatches.unknown.as_ref().is_some_and(|unknown| {
+                                unknown.iter().any(|n| n == previous.name())
+                            });
+
+                    if was_checked {
+                        drift.resolved.push((section, previous));
+                    }
+                }
+            }
+        }
+
+        self.drift = Some(drift);
+    }
+
+    /// The mismatches of the section that are new or changed since the baseline, or
+    /// every mismatch of it without a baseline.
+    pub fn drifted_mismatches(&self, section: Section) -> impl Iterator<Item = &VersionMismatch> {
+        self.mismatches(section).iter().filter(move |mismatch| {
+            self.drift
+                .as_ref()
+                .is_none_or(|drift| drift.change(section, mismatch).is_some())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use depchk_core::Mismatches;
+
+    use super::*;
+    use crate::output::FailedCheck;
+    use crate::DependencyType;
+
+    #[test]
+    fn test_compare_with() {
+        let mut report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![
+                    VersionMismatch::new("react", "^17.0.0", "18.2.0"),
+                    VersionMismatch::new("axios", "~0.27.0", "1.4.0"),
+                    VersionMismatch::new("jest", "^28.0.0", "29.5.0"),
+                ],
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![],
+            errors: vec![FailedCheck {
+                name: "left-pad".to_string(),
+                section: Section::Dependencies,
+                message: "timed out".to_string(),
+            }],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let baseline = Baseline {
+            mismatches: vec![
+                (
+                    Section::Dependencies,
+                    VersionMismatch::new("react", "^17.0.0", "18.2.0"),
+                ),
+                (
+                    Section::Dependencies,
+                    VersionMismatch::new("axios", "~0.27.0", "1.3.0"),
+                ),
+                (
+                    Section::Dependencies,
+                    VersionMismatch::new("lodash", "~4.16.0", "4.17.21"),
+                ),
+                (
+                    Section::Dependencies,
+                    VersionMismatch::new("left-pad", "^0.1.0", "1.3.0"),
+                ),
+                (
+                    Section::DevDependencies,
+                    VersionMismatch::new("mocha", "^9.0.0", "10.2.0"),
+                ),
+            ],
+        };
+
+        report.compare_with(baseline);
+
+        let drift = report.drift.as_ref().unwrap();
+        let names = |mismatches: &[(Section, VersionMismatch)]| -> Vec<String> {
+            mismatches
+                .iter()
+                .map(|(_, mismatch)| mismatch.name().to_string())
+                .collect()
+        };
+
+        assert_eq!(names(&drift.resolved), ["lodash"]);
+        assert_eq!(names(&drift.changed), ["axios"]);
+        assert_eq!(
+            report
+                .drifted_mismatches(Section::Dependencies)
+                .map(|mismatch| (
+                    mismatch.name(),
+                    drift.change(Section::Dependencies, mismatch)
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("axios", Some(Change::Changed)),
+                ("jest", Some(Change::New))
+            ]
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use clap::ValueEnum;
+use depchk_core::VersionMismatch;
+
+use super::{flag_names, format_date, Report};
+
+/// A column of the outputs that list mismatches in columns.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    /// The name of the package
+    Name,
+    /// The version constraint it is declared with
+    Constraint,
+    /// The highest version the constraint allows
+    Satisfiable,
+    /// The latest version
+    Latest,
+    /// Whether the update is a major, minor or patch update
+    Type,
+    /// How many libyears the satisfiable version is behind the latest one
+    Age,
+    /// When the latest version was released
+    Released,
+    /// How many days ago the latest version was released, which is how long the
+    /// project has been behind it
+    Behind,
+    /// The registry the package is resolved from
+    Registry,
+    /// The homepage of the package, or its repository if it has no homepage
+    Homepage,
+    /// The license of the satisfiable version
+    License,
+    /// When the package last published a version
+    LastPublished,
+    /// The signs that the package is no longer maintained, e.g. `stale` or `archived`
+    Flags,
+    /// The risk score of the package, from 0 to 100
+    Risk,
+}
+
+/// The columns that are listed unless others are chosen.
+pub const DEFAULT_COLUMNS: &[Column] = &[
+    Column::Name,
+    Column::Constraint,
+    Column::Latest,
+    Column::Type,
+];
+
+/// The columns that are listed in wide mode.
+pub const WIDE_COLUMNS: &[Column] = &[
+    Column::Name,
+    Column::Constraint,
+    Column::Latest,
+    Column::Type,
+    Column::Released,
+    Column::Behind,
+    Column::Registry,
+    Column::Homepage,
+];
+
+impl Column {
+    /// The title of the column in tables.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Column::Name => "Package Name",
+            Column::Constraint => "Version Constraint",
+            Column::Satisfiable => "Satisfiable Version",
+            Column::Latest => "Latest Version",
+            Column::Type => "Update",
+            Column::Age => "Libyears",
+            Column::Released => "Released",
+            Column::Behind => "Days Behind",
+            Column::Registry => "Registry",
+            Column::Homepage => "Homepage",
+            Column::License => "License",
+            Column::LastPublished => "Last Published",
+            Column::Flags => "Flags",
+            Column::Risk => "Risk",
+        }
+    }
+
+    /// The name of the column in structured outputs, e.g. CSV headers.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Column::Name => "name",
+            Column::Constraint => "constraint",
+            Column::Satisfiable => "satisfiable_version",
+            Column::Latest => "latest_version",
+            Column::Type => "update_kind",
+            Column::Age => "libyears",
+            Column::Released => "released_at",
+            Column::Behind => "days_behind",
+            Column::Registry => "registry",
+            Column::Homepage => "homepage",
+            Column::License => "license",
+            Column::LastPublished => "last_published_at",
+            Column::Flags => "flags",
+            Column::Risk => "risk_score",
+        }
+    }
+
+    /// The value of the column for the mismatch, which is empty when it is not known.
+    /// Release dates are written in full, unless `short_dates` is set.
+    pub fn value(&self, report: &Report, mismatch: &VersionMismatch, short_dates: bool) -> String {
+        let (name, constraint, version) = mismatch.destruct();
+
+        match self {
+            Column::Name => name.to_string(),
+            Column::Constraint => constraint.to_string(),
+            Column::Satisfiable => mismatch
+                .satisfiable_version()
+                .unwrap_or_default()
+                .to_string(),
+            Column::Latest => version.to_string(),
+            Column::Type => mismatch
+                .update_kind()
+                .map_or("", |kind| kind.name())
+                .to_string(),
+            Column::Age => mismatch
+                .libyears()
+                .map_or(String::new(), |libyears| format!("{:.1}", libyears)),
+            Column::Released => mismatch.released_at().map_or(String::new(), |released_at| {
+                format_date(released_at, short_dates)
+            }),
+            Column::Behind => mismatch
+                .days_behind(report.finished_at)
+                .map_or(String::new(), |days| days.to_string()),
+            Column::Registry => report
+                .declared_dependency(name)
+                .map_or(String::new(), |dependency| dependency.registry.clone()),
+            Column::Homepage => report
+                .declared_dependency(name)
+                .and_then(|dependency| dependency.homepage.clone())
+                .unwrap_or_default(),
+            Column::License => report
+                .declared_dependency(name)
+                .and_then(|dependency| dependency.license.clone())
+                .unwrap_or_default(),
+            Column::LastPublished => report
+                .declared_dependency(name)
+                .and_then(|dependency| dependency.last_published_at)
+                .map_or(String::new(), |published_at| {
+                    format_date(published_at, short_dates)
+                }),
+            Column::Flags => flag_names(mismatch.flags()),
+            Column::Risk => report
+                .declared_dependency(name)
+                .and_then(|dependency| dependency.risk_score)
+                .map_or(String::new(), |score| score.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use depchk_core::maintenance::MaintenanceFlag;
+    use depchk_core::{Mismatches, UpdateKind};
+
+    use super::*;
+    use crate::output::{DeclaredDependency, Section};
+    use crate::DependencyType;
+
+    #[test]
+    fn test_value() {
+        let mismatch = VersionMismatch::new("react", "^17.0.0", "18.2.0")
+            .with_satisfiable_version("17.0.2")
+            .with_update_kind(UpdateKind::Major)
+            .with_libyears(1.46)
+            .with_released_at(SystemTime::UNIX_EPOCH + Duration::from_secs(1_655_000_000))
+            .with_flags(vec![MaintenanceFlag::Archived]);
+
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            mismatches: Mismatches {
+                dependencies: vec![mismatch.clone()],
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![DeclaredDependency {
+                name: "react".to_string(),
+                constraint: "^17.0.0".to_string(),
+                section: Section::Dependencies,
+                latest_version: Some("18.2.0".to_string()),
+                registry: "https://registry.npmjs.org".to_string(),
+                homepage: Some("https://react.dev".to_string()),
+                license: Some("MIT".to_string()),
+                last_published_at: Some(
+                    SystemTime::UNIX_EPOCH + Duration::from_secs(1_655_000_000),
+                ),
+                flags: vec![MaintenanceFlag::Archived],
+                risk_score: Some(32),
+            }],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let values = |short_dates| {
+            [
+                Column::Satisfiable,
+                Column::Type,
+                Column::Age,
+                Column::Released,
+                Column::Behind,
+                Column::Registry,
+                Column::Homepage,
+                Column::License,
+                Column::LastPublished,
+                Column::Flags,
+                Column::Risk,
+            ]
+            .map(|column| column.value(&report, &mismatch, short_dates))
+        };
+
+        assert_eq!(
+            values(false),
+            [
+                "17.0.2",
+                "major",
+                "1.5",
+                "2022-06-12T02:13:20Z",
+                "520",
+                "https://registry.npmjs.org",
+                "https://react.dev",
+                "MIT",
+                "2022-06-12T02:13:20Z",
+                "archived",
+                "32"
+            ]
+        );
+        assert_eq!(values(true)[3], "2022-06-12");
+        assert_eq!(values(true)[4], "520");
+    }
+}
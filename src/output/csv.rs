@@ -0,0 +1,346 @@
+use std::error::Error;
+use std::io;
+
+use csv::WriterBuilder;
+use depchk_core::workspace::VersionSkew;
+use depchk_core::VersionMismatch;
+use serde::Serialize;
+
+use super::{Column, DeclaredDependency, PrintOptions, Report, Section};
+
+/// The section of version skew rows, which are not tied to a section of the
+/// dependency file.
+const VERSION_SKEW: &str = "version_skew";
+
+const HEADER: [&str; 6] = [
+    "manifest",
+    "section",
+    "name",
+    "constraint",
+    "latest_version",
+    "update_kind",
+];
+
+const INVENTORY_HEADER: [&str; 8] = [
+    "manifest",
+    "section",
+    "name",
+    "constraint",
+    "satisfiable_version",
+    "latest_version",
+    "update_kind",
+    "status",
+];
+
+/// A single row of CSV output. Mismatch rows have the dependency file as their
+/// manifest, while version skew rows have the workspace package that declares
+/// the skewed constraint.
+#[derive(Serialize)]
+pub struct Row<'a> {
+    manifest: &'a str,
+    section: &'a str,
+    name: &'a str,
+    constraint: &'a str,
+    latest_version: &'a str,
+    update_kind: &'a str,
+}
+
+/// A row of CSV output when every dependency is reported, which has the status of
+/// the dependency and its satisfiable version on top of what a [`Row`] has.
+#[derive(Serialize)]
+struct InventoryRow<'a> {
+    manifest: &'a str,
+    section: &'a str,
+    name: &'a str,
+    constraint: &'a str,
+    satisfiable_version: &'a str,
+    latest_version: &'a str,
+    update_kind: &'a str,
+    status: &'a str,
+}
+
+impl<'a> InventoryRow<'a> {
+    fn new(report: &'a Report, dependency: &'a DeclaredDependency) -> Self {
+        InventoryRow {
+            manifest: &report.manifest,
+            section: dependency.section.name(),
+            name: &dependency.name,
+            constraint: &dependency.constraint,
+            satisfiable_version: report.satisfiable_version(dependency).unwrap_or_default(),
+            latest_version: report.latest_version(dependency).unwrap_or_default(),
+            update_kind: report
+                .mismatch(dependency)
+                .and_then(|mismatch| mismatch.update_kind())
+                .map_or("", |kind| kind.name()),
+            status: report.status(dependency).name(),
+        }
+    }
+
+    fn version_skew(row: Row<'a>) -> Self {
+        InventoryRow {
+            manifest: row.manifest,
+            section: row.section,
+            name: row.name,
+            constraint: row.constraint,
+            satisfiable_version: "",
+            latest_version: "",
+            update_kind: "",
+            status: "",
+        }
+    }
+}
+
+impl<'a> Row<'a> {
+    pub fn mismatch(manifest: &'a str, section: Section, mismatch: &'a VersionMismatch) -> Self {
+        let (name, constraint, version) = mismatch.destruct();
+
+        Row {
+            manifest,
+            section: section.name(),
+            name,
+            constraint,
+            latest_version: version,
+            update_kind: mismatch.update_kind().map_or("", |kind| kind.name()),
+        }
+    }
+
+    pub fn version_skew(skew: &'a VersionSkew) -> impl Iterator<Item = Row<'a>> {
+        skew.constraints().iter().map(|constraint| Row {
+            manifest: constraint.package(),
+            section: VERSION_SKEW,
+            name: skew.name(),
+            constraint: constraint.constraint(),
+            latest_version: "",
+            update_kind: "",
+        })
+    }
+
+    /// Writes the row to stdout right away, for streamed output.
+    pub fn print(&self) -> Result<(), Box<dyn Error>> {
+        write_rows(io::stdout(), None, [self])
+    }
+}
+
+/// Writes the header row to stdout, for streamed output.
+pub fn print_header() -> Result<(), Box<dyn Error>> {
+    write_rows(io::stdout(), Some(&HEADER), [] as [Row; 0])
+}
+
+/// Writes the mismatches of the reports as CSV with the given columns, or every
+/// dependency if `all` is set. Every row starts with its manifest and section either
+/// way, so the rows of several dependency files share a single header.
+pub fn write_csv_mismatches(
+    out: &mut dyn io::Write,
+    reports: &[Report],
+    options: PrintOptions,
+) -> Result<(), Box<dyn Error>> {
+    if options.all {
+        return write_rows(
+            out,
+            Some(&INVENTORY_HEADER),
+            reports.iter().flat_map(inventory_rows),
+        );
+    }
+
+    let header: Vec<_> = HEADER[..2]
+        .iter()
+        .copied()
+        .chain(options.columns.iter().map(Column::key))
+        .collect();
+
+    write_rows(
+        out,
+        Some(&header),
+        reports
+            .iter()
+            .flat_map(|report| rows(report, options.columns)),
+    )
+}
+
+/// The mismatch rows with the given columns, followed by the version skew rows,
+/// which only have a name and a constraint.
+fn rows(report: &Report, columns: &[Column]) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+
+    for section in [Section::Dependencies, Section::DevDependencies] {
+        rows.extend(report.mismatches(section).iter().map(|mismatch| {
+            [report.manifest.clone(), section.name().to_string()]
+                .into_iter()
+                .chain(
+                    columns
+                        .iter()
+                        .map(|column| column.value(report, mismatch, false)),
+                )
+                .collect()
+        }));
+    }
+
+    if let Some(version_skew) = &report.mismatches.version_skew {
+        rows.extend(version_skew.iter().flat_map(Row::version_skew).map(|row| {
+            [row.manifest, row.section]
+                .into_iter()
+                .chain(columns.iter().map(|column| match column {
+                    Column::Name => row.name,
+                    Column::Constraint => row.constraint,
+                    _ => "",
+                }))
+                .map(str::to_string)
+                .collect()
+        }));
+    }
+
+    rows
+}
+
+fn inventory_rows(report: &Report) -> Vec<InventoryRow<'_>> {
+    let mut rows: Vec<_> = report
+        .dependencies
+        .iter()
+        .map(|dependency| InventoryRow::new(report, dependency))
+        .collect();
+
+    if let Some(version_skew) = &report.mismatches.version_skew {
+        rows.extend(
+            version_skew
+                .iter()
+                .flat_map(Row::version_skew)
+                .map(InventoryRow::version_skew),
+        );
+    }
+
+    rows
+}
+
+/// Writes the rows, after the given header row if there is one. The header is not
+/// derived from the rows, so that it is written even when there are none.
+fn write_rows(
+    writer: impl io::Write,
+    header: Option<&[&str]>,
+    rows: impl IntoIterator<Item = impl Serialize>,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = WriterBuilder::new().has_headers(false).from_writer(writer);
+
+    if let Some(header) = header {
+        writer.write_record(header)?;
+    }
+
+    for row in rows {
+        writer.serialize(row)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use depchk_core::{Mismatches, UpdateKind};
+
+    use super::*;
+    use crate::DependencyType;
+
+    fn declared(name: &str, constraint: &str, latest_version: &str) -> DeclaredDependency {
+        DeclaredDependency {
+            name: name.to_string(),
+            constraint: constraint.to_string(),
+            section: Section::Dependencies,
+            latest_version: Some(latest_version.to_string()),
+            registry: "https://registry.npmjs.org".to_string(),
+            homepage: None,
+            license: None,
+            last_published_at: None,
+            flags: Vec::new(),
+            risk_score: None,
+        }
+    }
+
+    #[test]
+    fn test_write_rows() {
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("react", "^16, \"17\"", "18.2.0")
+                    .with_update_kind(UpdateKind::Major)],
+                dev_dependencies: Some(vec![VersionMismatch::new("jest", "^28.0.0", "29.5.0")]),
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let mut output = Vec::new();
+
+        write_csv_mismatches(&mut output, &[report], PrintOptions::default()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                "manifest,section,name,constraint,latest_version,update_kind\n",
+                "package.json,dependencies,react,\"^16, \"\"17\"\"\",18.2.0,major\n",
+                "package.json,dev_dependencies,jest,^28.0.0,29.5.0,\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_inventory_rows() {
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("react", "^17.0.0", "18.2.0")
+                    .with_satisfiable_version("17.0.2")
+                    .with_update_kind(UpdateKind::Major)],
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![
+                declared("axios", "^1.0.0", "1.4.0"),
+                declared("react", "^17.0.0", "18.2.0"),
+            ],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let mut output = Vec::new();
+
+        let options = PrintOptions {
+            all: true,
+            ..PrintOptions::default()
+        };
+
+        write_csv_mismatches(&mut output, &[report], options).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                "manifest,section,name,constraint,satisfiable_version,latest_version,update_kind,status\n",
+                "package.json,dependencies,axios,^1.0.0,1.4.0,1.4.0,,up_to_date\n",
+                "package.json,dependencies,react,^17.0.0,17.0.2,18.2.0,major,outdated\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use serde_json::{json, Value};
+
+use super::{finished_at, format_timestamp, package_url, random_uuid, Report, Section};
+
+/// The version of the CycloneDX specification the document follows.
+const SPEC_VERSION: &str = "1.5";
+
+/// Renders every checked dependency as a CycloneDX JSON document. The version
+/// constraint of each component is recorded as a property, as is the latest
+/// version of the components that are outdated. When several dependency files are
+/// checked, so is the one that declares each component.
+pub fn render_cyclonedx(reports: &[Report]) -> Value {
+    let several = reports.len() > 1;
+
+    let components: Vec<_> = reports
+        .iter()
+        .flat_map(|report| {
+            report
+                .dependencies
+                .iter()
+                .map(move |dependency| (report, dependency))
+        })
+        .map(|(report, dependency)| {
+            let purl = package_url(report.dependency_type, &dependency.name);
+            let mut bom_ref = format!("{}#{}", purl, dependency.section.name());
+
+            let mut properties = vec![
+                json!({ "name": "depchk:constraint", "value": dependency.constraint }),
+                json!({ "name": "depchk:section", "value": dependency.section.name() }),
+            ];
+
+            if let Some(mismatch) = report.mismatch(dependency) {
+                properties.push(json!({
+                    "name": "depchk:latest_version",
+                    "value": mismatch.latest_version(),
+                }));
+
+                if let Some(update_kind) = mismatch.update_kind() {
+                    properties.push(json!({
+                        "name": "depchk:update_kind",
+                        "value": update_kind.name(),
+                    }));
+                }
+            }
+
+            // The same dependency may be declared by several dependency files
+            if several {
+                bom_ref = format!("{}:{}", report.manifest, bom_ref);
+
+                properties.push(json!({ "name": "depchk:manifest", "value": report.manifest }));
+            }
+
+            json!({
+                "type": "library",
+                "bom-ref": bom_ref,
+                "name": dependency.name,
+                "purl": purl,
+                "scope": match dependency.section {
+                    Section::Dependencies => "required",
+                    Section::DevDependencies => "optional",
+                },
+                "properties": properties,
+            })
+        })
+        .collect();
+
+    let manifests: Vec<_> = reports
+        .iter()
+        .map(|report| json!({ "name": "depchk:manifest", "value": report.manifest }))
+        .collect();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": SPEC_VERSION,
+        "serialNumber": format!("urn:uuid:{}", random_uuid()),
+        "version": 1,
+        "metadata": {
+            "timestamp": format!("{}Z", format_timestamp(finished_at(reports))),
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "depchk",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "properties": manifests,
+        },
+        "components": components,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use depchk_core::{Mismatches, VersionMismatch};
+
+    use super::*;
+    use crate::output::DeclaredDependency;
+    use crate::DependencyType;
+
+    #[test]
+    fn test_render_cyclonedx() {
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![],
+                dev_dependencies: Some(vec![VersionMismatch::new(
+                    "@types/node",
+                    "^18.0.0",
+                    "20.1.0",
+                )]),
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![DeclaredDependency {
+                name: "@types/node".to_string(),
+                constraint: "^18.0.0".to_string(),
+                section: Section::DevDependencies,
+                latest_version: None,
+                registry: "https://registry.npmjs.org".to_string(),
+                homepage: None,
+                license: None,
+                last_published_at: None,
+                flags: Vec::new(),
+                risk_score: None,
+            }],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let bom = render_cyclonedx(&[report]);
+
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["metadata"]["timestamp"], "1970-01-01T00:00:00Z");
+
+        let component = &bom["components"][0];
+
+        assert_eq!(component["purl"], "pkg:npm/%40types/node");
+        assert_eq!(component["scope"], "optional");
+        assert_eq!(
+            component["properties"][2],
+            json!({ "name": "depchk:latest_version", "value": "20.1.0" })
+        );
+    }
+}
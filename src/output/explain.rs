@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use depchk_core::changelog::ReleaseNotes;
+use depchk_core::VersionMismatch;
+
+use super::Report;
+
+/// Prints what changed in each outdated dependency of the report, as `depchk explain`
+/// does, out of the release notes that were found for them by name.
+pub fn print_explanations(report: &Report, changelogs: &HashMap<String, Vec<ReleaseNotes>>) {
+    let mismatches = report
+        .mismatches
+        .dependencies
+        .iter()
+        .chain(report.mismatches.dev_dependencies.iter().flatten());
+
+    let explanations: Vec<_> = mismatches
+        .map(|mismatch| {
+            let notes = changelogs
+                .get(mismatch.name())
+                .map_or(&[][..], Vec::as_slice);
+
+            explanation(mismatch, notes)
+        })
+        .collect();
+
+    match explanations.is_empty() {
+        true => println!("All dependencies are up to date"),
+        false => print!("{}", explanations.join("\n")),
+    }
+}
+
+/// The heading of an outdated dependency followed by the release notes of the versions
+/// it is behind by, with each line of the notes indented under its version.
+fn explanation(mismatch: &VersionMismatch, notes: &[ReleaseNotes]) -> String {
+    let (name, constraint, latest) = mismatch.destruct();
+
+    let mut explanation = format!(
+        "{} {} -> {}",
+        name,
+        mismatch.satisfiable_version().unwrap_or(constraint),
+        latest
+    );
+
+    if let Some(kind) = mismatch.update_kind() {
+        let _ = write!(explanation, " ({})", kind);
+    }
+
+    explanation.push('\n');
+
+    if notes.is_empty() {
+        explanation.push_str("  No release notes found\n");
+    }
+
+    for release in notes {
+        let _ = match release.url() {
+            Some(url) => writeln!(explanation, "\n  {} ({})", release.version(), url),
+            None => writeln!(explanation, "\n  {}", release.version()),
+        };
+
+        for line in release.notes().lines() {
+            match line.is_empty() {
+                true => explanation.push('\n'),
+                false => {
+                    let _ = writeln!(explanation, "    {}", line);
+                }
+            }
+        }
+    }
+
+    explanation
+}
+
+#[cfg(test)]
+mod tests {
+    use depchk_core::UpdateKind;
+
+    use super::*;
+
+    #[test]
+    fn test_explanation() {
+        let mismatch = VersionMismatch::new("axios", "^1.5.0", "1.6.2")
+            .with_satisfiable_version("1.5.1")
+            .with_update_kind(UpdateKind::Minor);
+
+        let notes = [
+            ReleaseNotes::new("1.6.2", "### Bug Fixes\n\n- Fixed the fetch adapter")
+                .with_url("https://github.com/axios/axios/releases/tag/v1.6.2"),
+            ReleaseNotes::new("1.6.0", "- Added a fetch adapter"),
+        ];
+
+        assert_eq!(
+            explanation(&mismatch, &notes),
+            concat!(
+                "axios 1.5.1 -> 1.6.2 (minor)\n",
+                "\n",
+                "  1.6.2 (https://github.com/axios/axios/releases/tag/v1.6.2)\n",
+                "    ### Bug Fixes\n",
+                "\n",
+                "    - Fixed the fetch adapter\n",
+                "\n",
+                "  1.6.0\n",
+                "    - Added a fetch adapter\n",
+            )
+        );
+
+        assert_eq!(
+            explanation(&VersionMismatch::new("react", "^17.0.2", "18.2.0"), &[]),
+            "react ^17.0.2 -> 18.2.0\n  No release notes found\n"
+        );
+    }
+}
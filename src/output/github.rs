@@ -0,0 +1,220 @@
+use std::env;
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::{self, Write as _};
+
+use super::markdown::render_markdown;
+use super::{DeclaredDependency, PrintOptions, Report};
+
+/// Renders the report as GitHub Actions workflow commands, so that every outdated
+/// dependency shows up as a warning annotation on the dependency file. Dependencies
+/// that could not be checked are reported as errors, and unknown ones as notices.
+///
+/// When the source of the dependency file is given, annotations point at the line
+/// the dependency is declared on.
+pub fn render_annotations(report: &Report, source: Option<&str>) -> String {
+    let mut commands = String::new();
+
+    for dependency in &report.dependencies {
+        let (level, title, message) = if let Some(mismatch) = report.mismatch(dependency) {
+            let (name, constraint, version) = mismatch.destruct();
+
+            (
+                "warning",
+                "Outdated dependency",
+                match mismatch.update_kind() {
+                    Some(update_kind) => format!(
+                        "{} is outdated: latest version {} ({} update) does not satisfy {}",
+                        name, version, update_kind, constraint
+                    ),
+                    None => format!(
+                        "{} is outdated: latest version {} does not satisfy {}",
+                        name, version, constraint
+                    ),
+                },
+            )
+        } else if report.is_unknown(dependency) {
+            (
+                "notice",
+                "Unknown dependency version",
+                format!(
+                    "The latest version of {} is unknown, it is not in the offline cache",
+                    dependency.name
+                ),
+            )
+        } else if let Some(err) = report.error(dependency) {
+            (
+                "error",
+                "Dependency check failed",
+                format!("Could not check {}: {}", dependency.name, err.message),
+            )
+        } else {
+            continue;
+        };
+
+        let _ = write!(
+            commands,
+            "::{} file={}",
+            level,
+            escape_property(&report.manifest)
+        );
+
+        if let Some(line) = source.and_then(|source| declaration_line(source, dependency)) {
+            let _ = write!(commands, ",line={}", line);
+        }
+
+        let _ = writeln!(
+            commands,
+            ",title={}::{}",
+            escape_property(title),
+            escape_data(&message)
+        );
+    }
+
+    if let Some(version_skew) = &report.mismatches.version_skew {
+        for skew in version_skew {
+            let constraints = skew
+                .constraints()
+                .iter()
+                .map(|constraint| format!("{} ({})", constraint.constraint(), constraint.package()))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let _ = writeln!(
+                commands,
+                "::warning file={},title={}::{}",
+                escape_property(&report.manifest),
+                escape_property("Version skew"),
+                escape_data(&format!(
+                    "{} is declared with conflicting constraints: {}",
+                    skew.name(),
+                    constraints
+                ))
+            );
+        }
+    }
+
+    commands
+}
+
+/// Appends a Markdown summary of the report to the job summary, if depchk is running
+/// inside GitHub Actions. Does nothing otherwise.
+pub fn write_step_summary(report: &Report, options: PrintOptions) -> io::Result<()> {
+    let path = match env::var_os("GITHUB_STEP_SUMMARY") {
+        Some(path) if !path.is_empty() => path,
+        _ => return Ok(()),
+    };
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    write!(
+        file,
+        "# depchk: `{}`\n\n{}\n",
+        report.manifest,
+        render_markdown(report, options)
+    )
+}
+
+/// Finds the (1-based) line the dependency is declared on. This is a best effort
+/// text search that understands the usual layouts of package.json and Cargo.toml.
+fn declaration_line(source: &str, dependency: &DeclaredDependency) -> Option<usize> {
+    let name = dependency.name.as_str();
+    let quoted = format!("\"{}\"", name);
+    let table = format!(".{}]", name);
+
+    source
+        .lines()
+        .position(|line| {
+            let line = line.trim_start();
+
+            line.starts_with(&quoted)
+                || line.ends_with(&table)
+                || line
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+        .map(|index| index + 1)
+}
+
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use depchk_core::{Mismatches, VersionMismatch};
+
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::output::Section;
+    use crate::DependencyType;
+
+    fn declared(name: &str) -> DeclaredDependency {
+        DeclaredDependency {
+            name: name.to_string(),
+            constraint: "^1.0.0".to_string(),
+            section: Section::Dependencies,
+            latest_version: None,
+            registry: "https://registry.npmjs.org".to_string(),
+            homepage: None,
+            license: None,
+            last_published_at: None,
+            flags: Vec::new(),
+            risk_score: None,
+        }
+    }
+
+    #[test]
+    fn test_render_annotations() {
+        let report = Report {
+            manifest: "web/package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("react", "^17.0.0", "18.2.0")],
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![declared("react"), declared("lodash")],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let source = "{\n  \"dependencies\": {\n    \"lodash\": \"^4.17.0\",\n    \"react\": \"^17.0.0\"\n  }\n}";
+
+        assert_eq!(
+            render_annotations(&report, Some(source)),
+            "::warning file=web/package.json,line=4,title=Outdated dependency::react is outdated: latest version 18.2.0 does not satisfy ^17.0.0\n"
+        );
+    }
+
+    #[test]
+    fn test_declaration_line() {
+        let source = "[dependencies]\nserde_json = \"1\"\nserde = { version = \"1\" }\n\n[dependencies.tokio]\nversion = \"1\"";
+
+        assert_eq!(declaration_line(source, &declared("serde")), Some(3));
+        assert_eq!(declaration_line(source, &declared("serde_json")), Some(2));
+        assert_eq!(declaration_line(source, &declared("tokio")), Some(5));
+        assert_eq!(declaration_line(source, &declared("clap")), None);
+    }
+
+    #[test]
+    fn test_escape_property() {
+        assert_eq!(escape_property("a:b,c%\n"), "a%3Ab%2Cc%25%0A");
+    }
+}
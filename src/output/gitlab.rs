@@ -0,0 +1,189 @@
+use serde_json::{json, Value};
+
+use std::time::SystemTime;
+
+use super::{finished_at, format_timestamp, Report};
+use crate::DependencyType;
+
+/// The version of GitLab's security report schema the report follows.
+const SCHEMA_VERSION: &str = "15.0.7";
+
+/// Renders the reports in the format of GitLab's dependency scanning report, so that
+/// outdated dependencies show up in the merge request security widget. Every outdated
+/// dependency becomes a finding of `Info` severity, and every checked dependency is
+/// listed in the dependency list of its dependency file.
+pub fn render_gitlab(reports: &[Report]) -> Value {
+    let tool = json!({
+        "id": "depchk",
+        "name": "depchk",
+        "version": env!("CARGO_PKG_VERSION"),
+        "vendor": { "name": "depchk" },
+    });
+
+    let vulnerabilities: Vec<_> = reports
+        .iter()
+        .flat_map(|report| {
+            report
+                .dependencies
+                .iter()
+                .map(move |dependency| (report, dependency))
+        })
+        .filter_map(|(report, dependency)| {
+            let (name, constraint, version) = report.mismatch(dependency)?.destruct();
+
+            Some(json!({
+                "id": format!("depchk:{}:{}:{}", report.manifest, name, version),
+                "name": format!("Outdated dependency {}", name),
+                "description": format!(
+                    "The latest version of {}, {}, does not satisfy the constraint {} declared in {}.",
+                    name, version, constraint, dependency.section.name()
+                ),
+                "severity": "Info",
+                "solution": format!("Update {} to {}.", name, version),
+                "identifiers": [{
+                    "type": "depchk_outdated",
+                    "name": format!("Outdated {} {}", name, constraint),
+                    "value": format!("{}@{}", name, version),
+                }],
+                "location": {
+                    "file": report.manifest,
+                    "dependency": {
+                        "package": { "name": name },
+                        "version": constraint,
+                    },
+                },
+            }))
+        })
+        .collect();
+
+    let dependency_files: Vec<_> = reports
+        .iter()
+        .map(|report| {
+            let dependencies: Vec<_> = report
+                .dependencies
+                .iter()
+                .map(|dependency| {
+                    json!({
+                        "package": { "name": dependency.name },
+                        "version": dependency.constraint,
+                    })
+                })
+                .collect();
+
+            json!({
+                "path": report.manifest,
+                "package_manager": package_manager(report.dependency_type),
+                "dependencies": dependencies,
+            })
+        })
+        .collect();
+
+    let started_at = reports
+        .iter()
+        .map(|report| report.started_at)
+        .min()
+        .unwrap_or_else(SystemTime::now);
+
+    json!({
+        "version": SCHEMA_VERSION,
+        "scan": {
+            "analyzer": tool,
+            "scanner": tool,
+            "type": "dependency_scanning",
+            "start_time": format_timestamp(started_at),
+            "end_time": format_timestamp(finished_at(reports)),
+            "status": "success",
+        },
+        "vulnerabilities": vulnerabilities,
+        "dependency_files": dependency_files,
+    })
+}
+
+fn package_manager(dependency_type: DependencyType) -> &'static str {
+    match dependency_type {
+        DependencyType::Npm => "npm",
+        DependencyType::Cargo => "cargo",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use depchk_core::{Mismatches, VersionMismatch};
+
+    use super::*;
+    use crate::output::{DeclaredDependency, Section};
+
+    #[test]
+    fn test_render_gitlab() {
+        let report = Report {
+            manifest: "Cargo.toml".to_string(),
+            dependency_type: DependencyType::Cargo,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH + Duration::from_secs(3),
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("reqwest", "0.11", "0.12.4")],
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![
+                DeclaredDependency {
+                    name: "reqwest".to_string(),
+                    constraint: "0.11".to_string(),
+                    section: Section::Dependencies,
+                    latest_version: None,
+                    registry: "https://index.crates.io".to_string(),
+                    homepage: None,
+                    license: None,
+                    last_published_at: None,
+                    flags: Vec::new(),
+                    risk_score: None,
+                },
+                DeclaredDependency {
+                    name: "serde".to_string(),
+                    constraint: "1".to_string(),
+                    section: Section::Dependencies,
+                    latest_version: None,
+                    registry: "https://index.crates.io".to_string(),
+                    homepage: None,
+                    license: None,
+                    last_published_at: None,
+                    flags: Vec::new(),
+                    risk_score: None,
+                },
+            ],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let gitlab = render_gitlab(&[report]);
+
+        assert_eq!(gitlab["scan"]["type"], "dependency_scanning");
+        assert_eq!(gitlab["scan"]["end_time"], "1970-01-01T00:00:03");
+        assert_eq!(gitlab["vulnerabilities"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            gitlab["vulnerabilities"][0]["location"]["dependency"]["package"]["name"],
+            "reqwest"
+        );
+        assert_eq!(
+            gitlab["vulnerabilities"][0]["solution"],
+            "Update reqwest to 0.12.4."
+        );
+        assert_eq!(gitlab["dependency_files"][0]["package_manager"], "cargo");
+        assert_eq!(
+            gitlab["dependency_files"][0]["dependencies"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use std::fmt::Write;
+
+use super::{escape_xml as escape, Report, Status};
+
+const STYLE: &str = r##"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem; color: #1f2328; }
+h1 { font-size: 1.5rem; }
+.summary { display: flex; gap: 1rem; margin-bottom: 1rem; }
+.summary div { padding: 0.5rem 1rem; border-radius: 6px; background: #f6f8fa; }
+.summary strong { display: block; font-size: 1.25rem; }
+input, select { padding: 0.3rem; margin: 0 0.5rem 1rem 0; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.4rem 0.75rem; border-bottom: 1px solid #d0d7de; }
+th { cursor: pointer; user-select: none; background: #f6f8fa; }
+th[data-order="asc"]::after { content: " \25B2"; }
+th[data-order="desc"]::after { content: " \25BC"; }
+tr.outdated td.status { color: #cf222e; font-weight: 600; }
+tr.error td.status { color: #bc4c00; font-weight: 600; }
+tr.unknown td.status { color: #6e7781; }
+tr.current td.status { color: #1a7f37; }
+td.major { color: #cf222e; font-weight: 600; }
+td.minor { color: #9a6700; font-weight: 600; }
+td.patch { color: #1a7f37; }
+"##;
+
+const SCRIPT: &str = r##"
+const rows = Array.from(document.querySelectorAll("#dependencies tbody tr"));
+const search = document.getElementById("search");
+const status = document.getElementById("status");
+
+function filter() {
+  const query = search.value.toLowerCase();
+  for (const row of rows) {
+    const matchesQuery = row.dataset.name.toLowerCase().includes(query);
+    const matchesStatus = !status.value || row.classList.contains(status.value);
+    row.hidden = !(matchesQuery && matchesStatus);
+  }
+}
+
+search.addEventListener("input", filter);
+status.addEventListener("change", filter);
+
+document.querySelectorAll("#dependencies th").forEach((header, column) => {
+  header.addEventListener("click", () => {
+    const order = header.dataset.order === "asc" ? "desc" : "asc";
+    document.querySelectorAll("#dependencies th").forEach((th) => delete th.dataset.order);
+    header.dataset.order = order;
+
+    const body = document.querySelector("#dependencies tbody");
+    rows
+      .sort((a, b) => {
+        const left = a.children[column].textContent;
+        const right = b.children[column].textContent;
+        const compared = left.localeCompare(right, undefined, { numeric: true });
+        return order === "asc" ? compared : -compared;
+      })
+      .forEach((row) => body.appendChild(row));
+  });
+});
+
+filter();
+"##;
+
+/// The class of the rows of dependencies with the given status, which decides how
+/// they are colored.
+fn status_class(status: Status) -> &'static str {
+    match status {
+        Status::Outdated => "outdated",
+        Status::Errored => "error",
+        Status::Skipped => "unknown",
+        Status::UpToDate => "current",
+    }
+}
+
+fn status_label(status: Status) -> &'static str {
+    match status {
+        Status::Outdated => "Outdated",
+        Status::Errored => "Error",
+        Status::Skipped => "Unknown",
+        Status::UpToDate => "Up to date",
+    }
+}
+
+/// Renders the reports as a standalone HTML page with summary counts and a sortable,
+/// filterable table of every checked dependency, which also tells the dependency file
+/// of each when several are checked. The page has no external assets, so it can be
+/// published as is, for example as a CI artifact.
+pub fn render_html(reports: &[Report]) -> String {
+    let statuses: Vec<_> = reports
+        .iter()
+        .flat_map(|report| {
+            report
+                .dependencies
+                .iter()
+                .map(move |dependency| (report, dependency, report.status(dependency)))
+        })
+        .collect();
+
+    let count = |status: Status| statuses.iter().filter(|(_, _, s)| *s == status).count();
+
+    let several = reports.len() > 1;
+    let title = match reports {
+        [report] => format!("depchk report for {}", escape(&report.manifest)),
+        _ => format!("depchk report for {} dependency files", reports.len()),
+    };
+    let mut html = String::new();
+
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>",
+        title, STYLE, title
+    );
+
+    html.push_str("<div class=\"summary\">\n");
+
+    for (label, value) in [
+        ("Dependencies", statuses.len()),
+        ("Outdated", count(Status::Outdated)),
+        ("Errors", count(Status::Errored)),
+        ("Unknown", count(Status::Skipped)),
+    ] {
+        let _ = writeln!(html, "<div><strong>{}</strong>{}</div>", value, label);
+    }
+
+    html.push_str("</div>\n");
+    html.push_str(
+        "<input id=\"search\" type=\"search\" placeholder=\"Filter by package name\">\n\
+         <select id=\"status\">\n\
+         <option value=\"\">All statuses</option>\n",
+    );
+
+    for status in [
+        Status::Outdated,
+        Status::Errored,
+        Status::Skipped,
+        Status::UpToDate,
+    ] {
+        let _ = writeln!(
+            html,
+            "<option value=\"{}\">{}</option>",
+            status_class(status),
+            status_label(status)
+        );
+    }
+
+    html.push_str("</select>\n<table id=\"dependencies\">\n<thead><tr>");
+
+    if several {
+        html.push_str("<th>Manifest</th>");
+    }
+
+    html.push_str(
+        "<th>Package Name</th><th>Section</th><th>Version Constraint</th>\
+         <th>Latest Version</th><th>Update</th><th>Status</th></tr></thead>\n<tbody>\n",
+    );
+
+    for (report, dependency, status) in &statuses {
+        let update_kind = report
+            .mismatch(dependency)
+            .and_then(|mismatch| mismatch.update_kind())
+            .map_or("", |kind| kind.name());
+
+        let latest = match report.mismatch(dependency) {
+            Some(mismatch) => escape(mismatch.latest_version()),
+            None => match report.error(dependency) {
+                Some(err) => escape(&err.message),
+                None => escape(dependency.latest_version.as_deref().unwrap_or_default()),
+            },
+        };
+
+        let _ = write!(
+            html,
+            "<tr class=\"{}\" data-name=\"{}\">",
+            status_class(*status),
+            escape(&dependency.name),
+        );
+
+        if several {
+            let _ = write!(html, "<td>{}</td>", escape(&report.manifest));
+        }
+
+        let _ = writeln!(
+            html,
+            "<td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td class=\"status\">{}</td></tr>",
+            escape(&dependency.name),
+            dependency.section.name(),
+            escape(&dependency.constraint),
+            latest,
+            update_kind,
+            update_kind,
+            status_label(*status),
+        );
+    }
+
+    let _ = write!(
+        html,
+        "</tbody>\n</table>\n<script>{}</script>\n</body>\n</html>",
+        SCRIPT
+    );
+
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use depchk_core::{Mismatches, UpdateKind, VersionMismatch};
+
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::output::{DeclaredDependency, Section};
+    use crate::DependencyType;
+
+    #[test]
+    fn test_render_html() {
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("react", "<18", "18.2.0")
+                    .with_update_kind(UpdateKind::Major)],
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: Some(vec!["lodash".to_string()]),
+            },
+            dependencies: vec![
+                DeclaredDependency {
+                    name: "react".to_string(),
+                    constraint: "<18".to_string(),
+                    section: Section::Dependencies,
+                    latest_version: None,
+                    registry: "https://registry.npmjs.org".to_string(),
+                    homepage: None,
+                    license: None,
+                    last_published_at: None,
+                    flags: Vec::new(),
+                    risk_score: None,
+                },
+                DeclaredDependency {
+                    name: "lodash".to_string(),
+                    constraint: "^4.17.0".to_string(),
+                    section: Section::Dependencies,
+                    latest_version: None,
+                    registry: "https://registry.npmjs.org".to_string(),
+                    homepage: None,
+                    license: None,
+                    last_published_at: None,
+                    flags: Vec::new(),
+                    risk_score: None,
+                },
+            ],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let html = render_html(&[report]);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<div><strong>2</strong>Dependencies</div>"));
+        assert!(html.contains("<div><strong>1</strong>Outdated</div>"));
+        assert!(html.contains(
+            "<tr class=\"outdated\" data-name=\"react\"><td>react</td><td>dependencies</td><td>&lt;18</td><td>18.2.0</td><td class=\"major\">major</td>"
+        ));
+        assert!(html.contains("<tr class=\"unknown\" data-name=\"lodash\">"));
+    }
+}
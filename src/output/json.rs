@@ -0,0 +1,511 @@
+use std::collections::BTreeMap;
+
+use depchk_core::audit::Vulnerability;
+use depchk_core::denylist::DeniedDependency;
+use depchk_core::license::LicenseViolation;
+use depchk_core::maintenance::MaintenanceFlag;
+use depchk_core::workspace::VersionSkew;
+use depchk_core::{MissingProvenance, UnavailableVersion, UpdateKind, VersionMismatch};
+use serde::Serialize;
+
+use super::baseline::{Change, Drift};
+use super::{
+    format_date, Column, DeclaredDependency, FailedCheck, PrintOptions, Report, Section, Status,
+    TransitiveFinding,
+};
+use crate::DependencyType;
+
+/// The version of the JSON report schema. It changes whenever a field is removed
+/// or changes meaning, while new fields may be added to the same version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The JSON Schema document of the report, printed by `depchk schema`.
+pub const SCHEMA: &str = include_str!("schema.json");
+
+/// The report as it is written by the JSON, YAML and TOML outputs. Its layout is part
+/// of the command line interface, see `schema.json`, so it is kept apart from the
+/// library types that it is built from.
+#[derive(Serialize)]
+pub struct JsonReport<'a> {
+    schema_version: u32,
+    manifest: &'a str,
+    package_manager: DependencyType,
+    mismatches: Vec<MismatchRecord<'a>>,
+    errors: &'a [FailedCheck],
+    unknown: &'a [String],
+    version_skew: &'a [VersionSkew],
+
+    /// Every checked dependency, only when they are all reported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependencies: Option<Vec<DependencyRecord<'a>>>,
+
+    /// The changes since the baseline, only when compared against one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    drift: Option<DriftRecord<'a>>,
+
+    /// The known vulnerabilities, only when the dependencies were audited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vulnerabilities: Option<&'a [Vulnerability]>,
+
+    /// The yanked or unpublished locked versions, only when the lockfile was checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unavailable: Option<&'a [UnavailableVersion]>,
+
+    /// The dependencies whose license is not permitted, only when checked against a policy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license_violations: Option<&'a [LicenseViolation]>,
+
+    /// The dependencies whose latest version has no provenance attestation, only when
+    /// provenance was checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    missing_provenance: Option<&'a [MissingProvenance]>,
+
+    /// The dependencies that the deny list does not permit, only when checked against one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    denied: Option<&'a [DeniedDependency]>,
+
+    /// The outdated or vulnerable transitive dependencies, only when the lockfile was
+    /// checked for them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transitive: Option<&'a [TransitiveFinding]>,
+
+    /// The dependencies that show signs of no longer being maintained, only when their
+    /// maintenance was checked.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unmaintained: Vec<UnmaintainedRecord<'a>>,
+
+    /// The risk score of the project, only when the risks of the dependencies were scored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    risk_score: Option<u8>,
+}
+
+/// The reports of several dependency files as they are written by the JSON, YAML and
+/// TOML outputs, keyed by the path of their dependency file.
+#[derive(Serialize)]
+pub struct JsonReports<'a, T> {
+    schema_version: u32,
+    manifests: BTreeMap<&'a str, T>,
+}
+
+/// What the JSON, YAML and TOML outputs write: the report of the dependency file, or
+/// the reports of every dependency file when several are checked.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum JsonOutput<'a> {
+    Report(Box<JsonReport<'a>>),
+    Reports(JsonReports<'a, JsonReport<'a>>),
+}
+
+#[derive(Serialize)]
+struct UnmaintainedRecord<'a> {
+    name: &'a str,
+    section: Section,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_published_at: Option<String>,
+
+    flags: &'a [MaintenanceFlag],
+}
+
+#[derive(Serialize)]
+struct DriftRecord<'a> {
+    new: Vec<MismatchRecord<'a>>,
+    changed: Vec<ChangeRecord<'a>>,
+    resolved: Vec<MismatchRecord<'a>>,
+}
+
+#[derive(Serialize)]
+struct ChangeRecord<'a> {
+    previous: MismatchRecord<'a>,
+    current: MismatchRecord<'a>,
+}
+
+#[derive(Serialize)]
+struct MismatchRecord<'a> {
+    name: &'a str,
+    section: Section,
+    constraint: &'a str,
+    latest_version: &'a str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    satisfiable_version: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update_kind: Option<UpdateKind>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    libyears: Option<f64>,
+
+    /// The group of related packages of `--groups`, always listed when there is one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<&'a str>,
+
+    /// The workspace package that declares the dependency, always listed when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workspace: Option<&'a str>,
+
+    /// Only listed when the column is chosen, like the registry and the homepage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    released_at: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    days_behind: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    registry: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homepage: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_published_at: Option<String>,
+
+    /// The signs that the dependency is no longer maintained, always listed when there are any.
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    flags: &'a [MaintenanceFlag],
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    risk_score: Option<u8>,
+}
+
+#[derive(Serialize)]
+struct DependencyRecord<'a> {
+    name: &'a str,
+    section: Section,
+    constraint: &'a str,
+    status: Status,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    satisfiable_version: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_version: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update_kind: Option<UpdateKind>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    risk_score: Option<u8>,
+}
+
+impl<'a> DependencyRecord<'a> {
+    fn new(report: &'a Report, dependency: &'a DeclaredDependency) -> Self {
+        DependencyRecord {
+            name: &dependency.name,
+            section: dependency.section,
+            constraint: &dependency.constraint,
+            status: report.status(dependency),
+            satisfiable_version: report.satisfiable_version(dependency),
+            latest_version: report.latest_version(dependency),
+            update_kind: report
+                .mismatch(dependency)
+                .and_then(|mismatch| mismatch.update_kind()),
+            risk_score: dependency.risk_score,
+        }
+    }
+}
+
+impl<'a> MismatchRecord<'a> {
+    fn new(mismatch: &'a VersionMismatch, section: Section) -> Self {
+        let (name, constraint, version) = mismatch.destruct();
+
+        MismatchRecord {
+            name,
+            section,
+            constraint,
+            latest_version: version,
+            satisfiable_version: mismatch.satisfiable_version(),
+            update_kind: mismatch.update_kind(),
+            libyears: mismatch.libyears(),
+            group: mismatch.group(),
+            workspace: mismatch.workspace(),
+            released_at: None,
+            days_behind: None,
+            registry: None,
+            homepage: None,
+            license: None,
+            last_published_at: None,
+            flags: mismatch.flags(),
+            risk_score: None,
+        }
+    }
+
+    /// Adds the fields of the chosen columns that are not always listed.
+    fn with_columns(
+        mut self,
+        report: &Report,
+        mismatch: &VersionMismatch,
+        columns: &[Column],
+    ) -> Self {
+        let value = |column: Column| {
+            Some(column.value(report, mismatch, false))
+                .filter(|value| columns.contains(&column) && !value.is_empty())
+        };
+
+        self.released_at = value(Column::Released);
+        self.days_behind = mismatch
+            .days_behind(report.finished_at)
+            .filter(|_| columns.contains(&Column::Behind));
+        self.registry = value(Column::Registry);
+        self.homepage = value(Column::Homepage);
+        self.license = value(Column::License);
+        self.last_published_at = value(Column::LastPublished);
+        self.risk_score = report
+            .declared_dependency(mismatch.name())
+            .and_then(|dependency| dependency.risk_score);
+        self
+    }
+}
+
+impl<'a> DriftRecord<'a> {
+    fn new(report: &'a Report, drift: &'a Drift, columns: &[Column]) -> Self {
+        let mut record = DriftRecord {
+            new: vec![],
+            changed: vec![],
+            resolved: drift
+                .resolved
+                .iter()
+                .map(|(section, mismatch)| MismatchRecord::new(mismatch, *section))
+                .collect(),
+        };
+
+        for section in [Section::Dependencies, Section::DevDependencies] {
+            for mismatch in report.mismatches(section) {
+                match drift.change(section, mismatch) {
+                    Some(Change::New) => record.new.push(
+                        MismatchRecord::new(mismatch, section)
+                            .with_columns(report, mismatch, columns),
+                    ),
+                    Some(Change::Changed) => {
+                        let previous = drift.changed.iter().find(|(s, previous)| {
+                            *s == section && previous.name() == mismatch.name()
+                        });
+
+                        if let Some((_, previous)) = previous {
+                            record.changed.push(ChangeRecord {
+                                previous: MismatchRecord::new(previous, section),
+                                current: MismatchRecord::new(mismatch, section)
+                                    .with_columns(report, mismatch, columns),
+                            });
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        record
+    }
+}
+
+impl<'a> JsonOutput<'a> {
+    pub fn new(reports: &'a [Report], options: PrintOptions) -> Self {
+        match reports {
+            [report] => JsonOutput::Report(Box::new(JsonReport::new(report, options))),
+            _ => JsonOutput::Reports(JsonReports::new(
+                reports
+                    .iter()
+                    .map(|report| (report.manifest.as_str(), JsonReport::new(report, options))),
+            )),
+        }
+    }
+}
+
+impl<'a, T> JsonReports<'a, T> {
+    pub fn new(reports: impl IntoIterator<Item = (&'a str, T)>) -> Self {
+        JsonReports {
+            schema_version: SCHEMA_VERSION,
+            manifests: reports.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> JsonReport<'a> {
+    /// Builds the JSON report, which also lists every checked dependency if `all` is set.
+    /// Mismatches are always listed in full, so that the report can serve as a baseline.
+    pub fn new(report: &'a Report, options: PrintOptions) -> Self {
+        let mismatches = [Section::Dependencies, Section::DevDependencies]
+            .into_iter()
+            .flat_map(|section| {
+                report.mismatches(section).iter().map(move |mismatch| {
+                    MismatchRecord::new(mismatch, section).with_columns(
+                        report,
+                        mismatch,
+                        options.columns,
+                    )
+                })
+            })
+            .collect();
+
+        JsonReport {
+            schema_version: SCHEMA_VERSION,
+            manifest: &report.manifest,
+            package_manager: report.dependency_type,
+            mismatches,
+            errors: &report.errors,
+            unknown: report.mismatches.unknown.as_deref().unwrap_or(&[]),
+            version_skew: report.mismatches.version_skew.as_deref().unwrap_or(&[]),
+            dependencies: options.all.then(|| {
+                report
+                    .dependencies
+                    .iter()
+                    .map(|dependency| DependencyRecord::new(report, dependency))
+                    .collect()
+            }),
+            drift: report
+                .drift
+                .as_ref()
+                .map(|drift| DriftRecord::new(report, drift, options.columns)),
+            vulnerabilities: report.vulnerabilities.as_deref(),
+            unavailable: report.unavailable.as_deref(),
+            license_violations: report.license_violations.as_deref(),
+            missing_provenance: report.missing_provenance.as_deref(),
+            denied: report.denied.as_deref(),
+            transitive: report.transitive.as_deref(),
+            unmaintained: report
+                .unmaintained()
+                .map(|dependency| UnmaintainedRecord {
+                    name: &dependency.name,
+                    section: dependency.section,
+                    last_published_at: dependency
+                        .last_published_at
+                        .map(|published_at| format_date(published_at, false)),
+                    flags: &dependency.flags,
+                })
+                .collect(),
+            risk_score: report.risk_score(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use depchk_core::Mismatches;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    #[test]
+    fn test_json_report() {
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("react", "^17.0.0", "18.2.0")
+                    .with_satisfiable_version("17.0.2")
+                    .with_update_kind(UpdateKind::Major)],
+                dev_dependencies: Some(vec![VersionMismatch::new("jest", "^28.0.0", "29.5.0")]),
+                version_skew: None,
+                unknown: Some(vec!["left-pad".to_string()]),
+            },
+            dependencies: vec![],
+            errors: vec![FailedCheck {
+                name: "lodash".to_string(),
+                section: Section::Dependencies,
+                message: "timed out".to_string(),
+            }],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        assert_eq!(
+            serde_json::to_value(JsonReport::new(&report, PrintOptions::default())).unwrap(),
+            json!({
+                "schema_version": 1,
+                "manifest": "package.json",
+                "package_manager": "npm",
+                "mismatches": [
+                    {
+                        "name": "react",
+                        "section": "dependencies",
+                        "constraint": "^17.0.0",
+                        "latest_version": "18.2.0",
+                        "satisfiable_version": "17.0.2",
+                        "update_kind": "major",
+                    },
+                    {
+                        "name": "jest",
+                        "section": "dev_dependencies",
+                        "constraint": "^28.0.0",
+                        "latest_version": "29.5.0",
+                    },
+                ],
+                "errors": [{ "name": "lodash", "section": "dependencies", "message": "timed out" }],
+                "unknown": ["left-pad"],
+                "version_skew": [],
+            })
+        );
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema: Value = serde_json::from_str(SCHEMA).unwrap();
+
+        for report in ["report", "reports"] {
+            assert_eq!(
+                schema["$defs"][report]["properties"]["schema_version"]["const"],
+                SCHEMA_VERSION
+            );
+        }
+    }
+
+    #[test]
+    fn test_json_reports() {
+        let report = |manifest: &str, dependency_type| Report {
+            manifest: manifest.to_string(),
+            dependency_type,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("serde", "0.9", "1.0.0")],
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let reports = [
+            report("package.json", DependencyType::Npm),
+            report("crates/cli/Cargo.toml", DependencyType::Cargo),
+        ];
+
+        let single =
+            serde_json::to_value(JsonOutput::new(&reports[..1], PrintOptions::default())).unwrap();
+
+        assert_eq!(single["manifest"], "package.json");
+
+        let several =
+            serde_json::to_value(JsonOutput::new(&reports, PrintOptions::default())).unwrap();
+
+        assert_eq!(several["schema_version"], SCHEMA_VERSION);
+        assert_eq!(
+            several["manifests"]["crates/cli/Cargo.toml"]["manifest"],
+            "crates/cli/Cargo.toml"
+        );
+        assert_eq!(
+            several["manifests"]["package.json"]["mismatches"][0]["name"],
+            "serde"
+        );
+    }
+}
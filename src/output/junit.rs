@@ -0,0 +1,220 @@
+use std::fmt::Write;
+
+use super::{escape_xml as escape, DeclaredDependency, Report, Section};
+
+/// Renders the reports as a JUnit XML document, with one test suite per section of
+/// each dependency file and one test case per dependency. Outdated dependencies are
+/// failures, dependencies that could not be checked are errors, and dependencies
+/// whose latest version is unknown are skipped.
+pub fn render_junit(reports: &[Report]) -> String {
+    let mut totals = Counts::default();
+    let mut suites = String::new();
+
+    for report in reports {
+        for section in [Section::Dependencies, Section::DevDependencies] {
+            let dependencies: Vec<_> = report
+                .dependencies
+                .iter()
+                .filter(|dependency| dependency.section == section)
+                .collect();
+
+            if dependencies.is_empty() {
+                continue;
+            }
+
+            let counts = Counts::of(report, &dependencies);
+
+            let _ = writeln!(
+                suites,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" skipped="{}">"#,
+                escape(&format!("{}: {}", report.manifest, section.name())),
+                counts.tests,
+                counts.failures,
+                counts.errors,
+                counts.skipped,
+            );
+
+            for dependency in dependencies {
+                write_test_case(&mut suites, report, dependency);
+            }
+
+            suites.push_str("  </testsuite>\n");
+
+            totals.add(&counts);
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="depchk" tests="{}" failures="{}" errors="{}" skipped="{}">"#,
+        totals.tests, totals.failures, totals.errors, totals.skipped,
+    );
+
+    xml.push_str(&suites);
+    xml.push_str("</testsuites>");
+
+    xml
+}
+
+#[derive(Default)]
+struct Counts {
+    tests: usize,
+    failures: usize,
+    errors: usize,
+    skipped: usize,
+}
+
+impl Counts {
+    fn of(report: &Report, dependencies: &[&DeclaredDependency]) -> Self {
+        dependencies
+            .iter()
+            .fold(Counts::default(), |mut counts, dependency| {
+                counts.tests += 1;
+
+                if report.mismatch(dependency).is_some() {
+                    counts.failures += 1;
+                } else if report.is_unknown(dependency) {
+                    counts.skipped += 1;
+                } else if report.error(dependency).is_some() {
+                    counts.errors += 1;
+                }
+
+                counts
+            })
+    }
+
+    fn add(&mut self, other: &Counts) {
+        self.tests += other.tests;
+        self.failures += other.failures;
+        self.errors += other.errors;
+        self.skipped += other.skipped;
+    }
+}
+
+fn write_test_case(xml: &mut String, report: &Report, dependency: &DeclaredDependency) {
+    let _ = write!(
+        xml,
+        r#"    <testcase name="{}" classname="{}""#,
+        escape(&dependency.name),
+        escape(&format!(
+            "{}.{}",
+            report.manifest,
+            dependency.section.name()
+        )),
+    );
+
+    if let Some(mismatch) = report.mismatch(dependency) {
+        let (name, constraint, version) = mismatch.destruct();
+        let message = match mismatch.update_kind() {
+            Some(update_kind) => format!(
+                "{} is outdated: latest version {} ({} update) does not satisfy {}",
+                name, version, update_kind, constraint
+            ),
+            None => format!(
+                "{} is outdated: latest version {} does not satisfy {}",
+                name, version, constraint
+            ),
+        };
+
+        let _ = writeln!(
+            xml,
+            ">\n      <failure type=\"outdated\" message=\"{}\">constraint: {}\nlatest: {}</failure>\n    </testcase>",
+            escape(&message),
+            escape(constraint),
+            escape(version),
+        );
+    } else if report.is_unknown(dependency) {
+        xml.push_str(
+            ">\n      <skipped message=\"latest version unknown, not in the offline cache\"/>\n    </testcase>\n",
+        );
+    } else if let Some(err) = report.error(dependency) {
+        let _ = writeln!(
+            xml,
+            ">\n      <error type=\"lookup\" message=\"{}\">constraint: {}</error>\n    </testcase>",
+            escape(&err.message),
+            escape(&dependency.constraint),
+        );
+    } else {
+        xml.push_str("/>\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use depchk_core::{Mismatches, VersionMismatch};
+
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::output::FailedCheck;
+    use crate::DependencyType;
+
+    fn declared(name: &str, constraint: &str, section: Section) -> DeclaredDependency {
+        DeclaredDependency {
+            name: name.to_string(),
+            constraint: constraint.to_string(),
+            section,
+            latest_version: None,
+            registry: "https://registry.npmjs.org".to_string(),
+            homepage: None,
+            license: None,
+            last_published_at: None,
+            flags: Vec::new(),
+            risk_score: None,
+        }
+    }
+
+    #[test]
+    fn test_render_junit_reports_one_case_per_dependency() {
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("react", "^17.0.0", "18.2.0")],
+                dev_dependencies: Some(vec![]),
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![
+                declared("react", "^17.0.0", Section::Dependencies),
+                declared("lodash", "^4.17.0", Section::Dependencies),
+                declared("@types/node", "<20", Section::DevDependencies),
+            ],
+            errors: vec![FailedCheck {
+                name: "@types/node".to_string(),
+                section: Section::DevDependencies,
+                message: "connection reset".to_string(),
+            }],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let xml = render_junit(&[report]);
+
+        assert!(xml.contains(
+            r#"<testsuites name="depchk" tests="3" failures="1" errors="1" skipped="0">"#
+        ));
+        assert!(
+            xml.contains(r#"<testsuite name="package.json: dependencies" tests="2" failures="1""#)
+        );
+        assert!(xml.contains(r#"<testcase name="lodash" classname="package.json.dependencies"/>"#));
+        assert!(xml.contains(
+            r#"message="react is outdated: latest version 18.2.0 does not satisfy ^17.0.0""#
+        ));
+        assert!(xml.contains(
+            r#"<testcase name="@types/node" classname="package.json.dev_dependencies">"#
+        ));
+        assert!(xml.contains(
+            r#"<error type="lookup" message="connection reset">constraint: &lt;20</error>"#
+        ));
+    }
+}
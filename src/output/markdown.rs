@@ -0,0 +1,364 @@
+use std::fmt::Write;
+
+use depchk_core::audit::advisory_url;
+use depchk_core::VersionMismatch;
+
+use super::{audit, flag_names, format_date, PrintOptions, Report};
+
+/// Renders the report as GitHub-flavored Markdown, with a table of mismatches for
+/// each group, e.g. each section of the dependency file, or a single table of every
+/// dependency when they are all reported.
+pub fn render_markdown(report: &Report, options: PrintOptions) -> String {
+    let mismatches = &report.mismatches;
+    let mut markdown = String::new();
+
+    if options.all {
+        let _ = writeln!(markdown, "## All Dependencies\n");
+        write_inventory_table(&mut markdown, report);
+    } else {
+        let groups = report.groups(options.grouping);
+
+        if groups.is_empty() {
+            let _ = match report.drift {
+                Some(_) => writeln!(markdown, "Nothing changed since the baseline."),
+                None => writeln!(markdown, "All dependencies are up to date."),
+            };
+        }
+
+        for (index, group) in groups.into_iter().enumerate() {
+            if index > 0 {
+                let _ = writeln!(markdown);
+            }
+
+            let _ = writeln!(markdown, "## {}\n", group.title);
+            write_mismatch_table(&mut markdown, &group.mismatches);
+        }
+    }
+
+    // Unknown dependencies are already listed as skipped along with all the others
+    if let Some(unknown) = mismatches.unknown.as_ref().filter(|_| !options.all) {
+        let _ = writeln!(markdown, "\n## Unknown\n");
+        let _ = writeln!(
+            markdown,
+            "These dependencies are not in the offline cache, so their latest version is unknown.\n"
+        );
+
+        for name in unknown {
+            let _ = writeln!(markdown, "- `{}`", name);
+        }
+    }
+
+    match &mismatches.version_skew {
+        Some(version_skew) if !version_skew.is_empty() => {
+            let _ = writeln!(markdown, "\n## Version Skew\n");
+            let _ = writeln!(
+                markdown,
+                "| Package Name | Workspace Package | Version Constraint |"
+            );
+            let _ = writeln!(markdown, "| --- | --- | --- |");
+
+            for skew in version_skew {
+                for constraint in skew.constraints() {
+                    let _ = writeln!(
+                        markdown,
+                        "| {} | {} | {} |",
+                        escape(skew.name()),
+                        escape(constraint.package()),
+                        escape(constraint.constraint()),
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(vulnerabilities) = &report.vulnerabilities {
+        let _ = writeln!(markdown, "\n## Vulnerabilities\n");
+
+        if vulnerabilities.is_empty() {
+            let _ = writeln!(markdown, "No known vulnerabilities.");
+        } else {
+            let _ = writeln!(
+                markdown,
+                "| Package Name | Version | Advisory | Severity | Summary | Fixed In |"
+            );
+            let _ = writeln!(markdown, "| --- | --- | --- | --- | --- | --- |");
+
+            for vulnerability in vulnerabilities {
+                let mut advisory = std::iter::once(vulnerability.id())
+                    .chain(vulnerability.aliases().iter().map(String::as_str))
+                    .map(advisory_link)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                if let Some(informational) = vulnerability.informational() {
+                    let _ = write!(advisory, " ({})", escape(informational));
+                }
+
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} | {} | {} | {} | {} |",
+                    escape(vulnerability.package()),
+                    escape(vulnerability.version()),
+                    advisory,
+                    audit::severity(vulnerability),
+                    escape(vulnerability.summary().unwrap_or_default()),
+                    escape(&vulnerability.fixed_versions().join(", ")),
+                );
+            }
+        }
+    }
+
+    match &report.unavailable {
+        Some(unavailable) if !unavailable.is_empty() => {
+            let _ = writeln!(markdown, "\n## Unavailable Versions\n");
+            let _ = writeln!(markdown, "| Package Name | Locked Version | Status |");
+            let _ = writeln!(markdown, "| --- | --- | --- |");
+
+            for locked in unavailable {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} | {} |",
+                    escape(locked.name()),
+                    escape(locked.version()),
+                    locked.reason().name(),
+                );
+            }
+        }
+        _ => {}
+    }
+
+    match &report.license_violations {
+        Some(violations) if !violations.is_empty() => {
+            let _ = writeln!(markdown, "\n## License Violations\n");
+            let _ = writeln!(markdown, "| Package Name | License |");
+            let _ = writeln!(markdown, "| --- | --- |");
+
+            for violation in violations {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} |",
+                    escape(violation.name()),
+                    escape(violation.license().unwrap_or("none")),
+                );
+            }
+        }
+        _ => {}
+    }
+
+    match &report.denied {
+        Some(denied) if !denied.is_empty() => {
+            let _ = writeln!(markdown, "\n## Denied Dependencies\n");
+            let _ = writeln!(markdown, "| Package Name | Version | Reason |");
+            let _ = writeln!(markdown, "| --- | --- | --- |");
+
+            for dependency in denied {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} | {} |",
+                    escape(dependency.name()),
+                    escape(dependency.version().unwrap_or("any")),
+                    escape(dependency.reason()),
+                );
+            }
+        }
+        _ => {}
+    }
+
+    match &report.transitive {
+        Some(transitive) if !transitive.is_empty() => {
+            let _ = writeln!(markdown, "\n## Transitive Dependencies\n");
+            let _ = writeln!(
+                markdown,
+                "| Package Name | Locked Version | Latest Version | Update | Advisories | Via |"
+            );
+            let _ = writeln!(markdown, "| --- | --- | --- | --- | --- | --- |");
+
+            for finding in transitive {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} | {} | {} | {} | {} |",
+                    escape(&finding.name),
+                    escape(&finding.version),
+                    escape(finding.latest_version.as_deref().unwrap_or_default()),
+                    finding.update_kind.map_or("", |kind| kind.name()),
+                    escape(&finding.advisories.join(", ")),
+                    escape(&finding.via.join(", ")),
+                );
+            }
+        }
+        _ => {}
+    }
+
+    match &report.missing_provenance {
+        Some(missing) if !missing.is_empty() => {
+            let _ = writeln!(markdown, "\n## Missing Provenance\n");
+            let _ = writeln!(
+                markdown,
+                "| Package Name | Latest Version | Status | Attested Version |"
+            );
+            let _ = writeln!(markdown, "| --- | --- | --- | --- |");
+
+            for package in missing {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} | {} | {} |",
+                    escape(package.name()),
+                    escape(package.version()),
+                    package.status(),
+                    escape(package.attested_version().unwrap_or_default()),
+                );
+            }
+        }
+        _ => {}
+    }
+
+    let mut unmaintained = report.unmaintained().peekable();
+
+    if unmaintained.peek().is_some() {
+        let _ = writeln!(markdown, "\n## Unmaintained Dependencies\n");
+        let _ = writeln!(markdown, "| Package Name | Last Published | Flags |");
+        let _ = writeln!(markdown, "| --- | --- | --- |");
+
+        for dependency in unmaintained {
+            let last_published = dependency
+                .last_published_at
+                .map_or(String::new(), |published_at| {
+                    format_date(published_at, true)
+                });
+
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} |",
+                escape(&dependency.name),
+                last_published,
+                flag_names(&dependency.flags),
+            );
+        }
+    }
+
+    markdown
+}
+
+fn write_mismatch_table(markdown: &mut String, mismatches: &[&VersionMismatch]) {
+    if mismatches.is_empty() {
+        let _ = writeln!(markdown, "All dependencies are up to date.");
+        return;
+    }
+
+    let _ = writeln!(
+        markdown,
+        "| Package Name | Version Constraint | Latest Version | Update |"
+    );
+    let _ = writeln!(markdown, "| --- | --- | --- | --- |");
+
+    for mismatch in mismatches {
+        let (name, constraint, version) = mismatch.destruct();
+
+        let _ = writeln!(
+            markdown,
+            "| {} | {} | {} | {} |",
+            escape(name),
+            escape(constraint),
+            escape(version),
+            mismatch.update_kind().map_or("", |kind| kind.name()),
+        );
+    }
+}
+
+fn write_inventory_table(markdown: &mut String, report: &Report) {
+    let _ = writeln!(
+        markdown,
+        "| Package Name | Section | Version Constraint | Satisfiable Version | Latest Version | Status |"
+    );
+    let _ = writeln!(markdown, "| --- | --- | --- | --- | --- | --- |");
+
+    for dependency in &report.dependencies {
+        let _ = writeln!(
+            markdown,
+            "| {} | {} | {} | {} | {} | {} |",
+            escape(&dependency.name),
+            escape(dependency.section.name()),
+            escape(&dependency.constraint),
+            escape(report.satisfiable_version(dependency).unwrap_or_default()),
+            escape(report.latest_version(dependency).unwrap_or_default()),
+            escape(report.status(dependency).name()),
+        );
+    }
+}
+
+/// The identifier of an advisory, linked to where it can be read if it is published.
+fn advisory_link(id: &str) -> String {
+    match advisory_url(id) {
+        Some(url) => format!("[{}]({})", escape(id), url),
+        None => escape(id),
+    }
+}
+
+/// Escapes the characters that would otherwise end a table cell or be
+/// interpreted as inline markup.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if matches!(c, '|' | '\\' | '*' | '_' | '`' | '<' | '>') {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use depchk_core::audit::{AuditedPackage, Vulnerability};
+    use depchk_core::Mismatches;
+
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::DependencyType;
+
+    #[test]
+    fn test_render_markdown() {
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("react", "^16 || ^17", "18.2.0")],
+                dev_dependencies: Some(vec![]),
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: Some(vec![Vulnerability::new(
+                &AuditedPackage::new("react", "17.0.2", "npm"),
+                "GHSA-35jh-r3h4-6jhm",
+            )
+            .with_aliases(vec!["CVE-2021-23337".to_string()])
+            .with_cvss_score(7.2)]),
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let markdown = render_markdown(&report, PrintOptions::default());
+
+        assert!(markdown.starts_with("## Dependencies\n\n| Package Name |"));
+        assert!(markdown.contains("| react | ^16 \\|\\| ^17 | 18.2.0 |  |"));
+        assert!(markdown.contains("## Dev Dependencies\n\nAll dependencies are up to date."));
+        assert!(!markdown.contains("## Version Skew"));
+        assert!(markdown.contains(
+            "| [GHSA-35jh-r3h4-6jhm](https://github.com/advisories/GHSA-35jh-r3h4-6jhm), \
+             [CVE-2021-23337](https://nvd.nist.gov/vuln/detail/CVE-2021-23337) | high (7.2) |"
+        ));
+    }
+}
@@ -0,0 +1,923 @@
+pub mod audit;
+mod badge;
+mod baseline;
+mod columns;
+mod csv;
+mod cyclonedx;
+pub mod explain;
+mod github;
+mod gitlab;
+mod html;
+mod json;
+mod junit;
+mod markdown;
+mod ndjson;
+mod order;
+pub mod prometheus;
+mod spdx;
+pub mod stream;
+mod summary;
+mod table;
+pub mod verify;
+
+use std::collections::hash_map::RandomState;
+use std::error::Error;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::path::Path;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use depchk_core::audit::Vulnerability;
+use depchk_core::denylist::DeniedDependency;
+use depchk_core::groups::UpdateGroups;
+use depchk_core::license::LicenseViolation;
+use depchk_core::maintenance::MaintenanceFlag;
+use depchk_core::risk::{self, RiskFactors};
+use depchk_core::{Mismatches, MissingProvenance, UnavailableVersion, UpdateKind, VersionMismatch};
+use json::JsonOutput;
+use serde::{Deserialize, Serialize};
+
+use crate::DependencyType;
+
+pub use badge::BadgeFormat;
+pub use baseline::{Baseline, Drift};
+pub use columns::{Column, DEFAULT_COLUMNS, WIDE_COLUMNS};
+pub use json::SCHEMA;
+pub use order::{Grouping, SortOrder};
+pub use summary::{Summary, Thresholds};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum OutputTypes {
+    #[default]
+    Table,
+    /// The report as JSON, as described by `depchk schema`
+    Json,
+    /// The same report as the JSON output, as YAML
+    Yaml,
+    /// The same report as the JSON output, as TOML
+    Toml,
+    Csv,
+    Junit,
+    Markdown,
+    Html,
+    Github,
+    Gitlab,
+    Ndjson,
+    /// A CycloneDX software bill of materials of every checked dependency
+    #[value(alias = "sbom")]
+    Cyclonedx,
+    /// An SPDX 2.3 software bill of materials of every checked dependency
+    Spdx,
+    /// Gauges of the outdated dependencies in the Prometheus text format
+    Prometheus,
+    /// The SVG badge written by `depchk badge`
+    #[value(skip)]
+    BadgeSvg,
+    /// The shields.io endpoint payload written by `depchk badge --format json`
+    #[value(skip)]
+    BadgeJson,
+}
+
+/// The part of the dependency file a dependency was declared in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Section {
+    Dependencies,
+    DevDependencies,
+}
+
+/// A dependency that was checked, whether it turned out to be outdated or not.
+pub struct DeclaredDependency {
+    pub name: String,
+    pub constraint: String,
+    pub section: Section,
+    /// The latest version of the dependency, if it could be resolved.
+    pub latest_version: Option<String>,
+    /// The registry the dependency is resolved from.
+    pub registry: String,
+    /// The homepage of the dependency, only looked up when it is reported.
+    pub homepage: Option<String>,
+    /// The license of the dependency, only looked up when it is reported or checked
+    /// against a license policy.
+    pub license: Option<String>,
+    /// When the dependency last published a version, only looked up when its
+    /// maintenance is checked.
+    pub last_published_at: Option<SystemTime>,
+    /// The signs that the dependency is no longer maintained, if its maintenance was checked.
+    pub flags: Vec<MaintenanceFlag>,
+    /// The risk score of the dependency from 0 to 100, only worked out when asked for.
+    pub risk_score: Option<u8>,
+}
+
+/// The outcome of checking a single dependency.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    UpToDate,
+    Outdated,
+    /// Not checked, because the dependency is not in the offline cache.
+    Skipped,
+    Errored,
+}
+
+/// A dependency whose check failed, along with the reason it failed.
+#[derive(Serialize)]
+pub struct FailedCheck {
+    pub name: String,
+    pub section: Section,
+    pub message: String,
+}
+
+/// A package that is only installed because the declared dependencies depend on it,
+/// and that is outdated or has known vulnerabilities.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TransitiveFinding {
+    pub name: String,
+    /// The version the lockfile locks the package to.
+    pub version: String,
+    /// The latest version, if the locked version is outdated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_kind: Option<UpdateKind>,
+    /// The identifiers of the known vulnerabilities of the locked version.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub advisories: Vec<String>,
+    /// The declared dependencies that pull in the package.
+    pub via: Vec<String>,
+}
+
+/// Everything that is known about a finished check, which
+/// every output format is rendered from.
+pub struct Report {
+    /// The path of the dependency file, as it was given on the command line.
+    pub manifest: String,
+    pub dependency_type: DependencyType,
+    pub started_at: SystemTime,
+    pub finished_at: SystemTime,
+    pub mismatches: Mismatches,
+    pub dependencies: Vec<DeclaredDependency>,
+    pub errors: Vec<FailedCheck>,
+    /// How the mismatches changed since the baseline, if compared against one.
+    pub drift: Option<Drift>,
+    /// The known vulnerabilities of the dependencies, if they were audited.
+    pub vulnerabilities: Option<Vec<Vulnerability>>,
+    /// The dependencies whose locked version was yanked or unpublished, if the
+    /// lockfile was checked.
+    pub unavailable: Option<Vec<UnavailableVersion>>,
+    /// The dependencies whose license is not permitted, if checked against a policy.
+    pub license_violations: Option<Vec<LicenseViolation>>,
+    /// The dependencies whose latest version has no provenance attestation, if
+    /// provenance was checked.
+    pub missing_provenance: Option<Vec<MissingProvenance>>,
+    /// The dependencies that the deny list does not permit, if checked against one.
+    pub denied: Option<Vec<DeniedDependency>>,
+    /// The outdated or vulnerable packages that are only installed because the declared
+    /// dependencies depend on them, if the lockfile was checked for them.
+    pub transitive: Option<Vec<TransitiveFinding>>,
+}
+
+/// Options of how a report is printed, which apply to every output format.
+#[derive(Copy, Clone, Debug)]
+pub struct PrintOptions<'a> {
+    pub grouping: Grouping,
+    /// If true, only the report itself is printed, without the summary footer.
+    pub quiet: bool,
+    /// If true, every checked dependency is reported, not only the outdated ones.
+    pub all: bool,
+    /// The columns mismatches are listed with, by the outputs that have columns.
+    pub columns: &'a [Column],
+    /// If true, the JSON output is indented over several lines.
+    pub pretty: bool,
+}
+
+impl Default for PrintOptions<'_> {
+    fn default() -> Self {
+        PrintOptions {
+            grouping: Grouping::default(),
+            quiet: false,
+            all: false,
+            columns: DEFAULT_COLUMNS,
+            pretty: false,
+        }
+    }
+}
+
+impl OutputTypes {
+    /// Whether mismatches can be written one at a time as they are found, rather
+    /// than only once the whole check has finished.
+    pub fn is_streamable(&self) -> bool {
+        matches!(
+            self,
+            OutputTypes::Table
+                | OutputTypes::Json
+                | OutputTypes::Yaml
+                | OutputTypes::Csv
+                | OutputTypes::Ndjson
+        )
+    }
+
+    /// Whether the output can report the changes since a baseline, rather than
+    /// every mismatch.
+    pub fn supports_baseline(&self) -> bool {
+        matches!(
+            self,
+            OutputTypes::Table
+                | OutputTypes::Json
+                | OutputTypes::Yaml
+                | OutputTypes::Toml
+                | OutputTypes::Markdown
+        )
+    }
+}
+
+impl Section {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Section::Dependencies => "dependencies",
+            Section::DevDependencies => "dev_dependencies",
+        }
+    }
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::UpToDate => "up_to_date",
+            Status::Outdated => "outdated",
+            Status::Skipped => "skipped",
+            Status::Errored => "errored",
+        }
+    }
+}
+
+impl Report {
+    /// The mismatches found in the given section of the dependency file.
+    pub fn mismatches(&self, section: Section) -> &[VersionMismatch] {
+        match section {
+            Section::Dependencies => &self.mismatches.dependencies,
+            Section::DevDependencies => self.mismatches.dev_dependencies.as_deref().unwrap_or(&[]),
+        }
+    }
+
+    /// Marks the mismatches of the packages that belong to a group with its name.
+    pub fn assign_groups(&mut self, groups: &UpdateGroups) {
+        let mismatches = self
+            .mismatches
+            .dependencies
+            .iter_mut()
+            .chain(self.mismatches.dev_dependencies.iter_mut().flatten());
+
+        for mismatch in mismatches {
+            if let Some(group) = groups.group_of(mismatch.name()) {
+                *mismatch = mismatch.clone().with_group(group);
+            }
+        }
+    }
+
+    /// Marks every mismatch with the name of the workspace package that declares it.
+    pub fn assign_workspace(&mut self, workspace: &str) {
+        let mismatches = self
+            .mismatches
+            .dependencies
+            .iter_mut()
+            .chain(self.mismatches.dev_dependencies.iter_mut().flatten());
+
+        for mismatch in mismatches {
+            *mismatch = mismatch.clone().with_workspace(workspace);
+        }
+    }
+
+    /// Adds the mismatches, dependencies and errors of the report of another package
+    /// of the workspace to this one.
+    pub fn append(&mut self, mut other: Report) {
+        let mismatches = &mut self.mismatches;
+
+        mismatches
+            .dependencies
+            .append(&mut other.mismatches.dependencies);
+
+        if let Some(mut dev_dependencies) = other.mismatches.dev_dependencies {
+            mismatches
+                .dev_dependencies
+                .get_or_insert_with(Vec::new)
+                .append(&mut dev_dependencies);
+        }
+
+        // Packages declared by several workspace packages are only listed once
+        if let Some(unknown) = other.mismatches.unknown {
+            let known = mismatches.unknown.get_or_insert_with(Vec::new);
+
+            for name in unknown {
+                if !known.contains(&name) {
+                    known.push(name);
+                }
+            }
+        }
+
+        self.dependencies.append(&mut other.dependencies);
+        self.errors.append(&mut other.errors);
+        self.finished_at = other.finished_at;
+    }
+
+    /// Only keeps the mismatches that are at most as far behind as the kind of update
+    /// each package is limited to, leaving out the ones whose kind of update could not
+    /// be worked out as well. Packages that are not limited are all kept.
+    pub fn retain_updates_up_to(&mut self, update_kind: impl Fn(&str) -> Option<UpdateKind>) {
+        let keep = |mismatch: &VersionMismatch| match update_kind(mismatch.name()) {
+            Some(update_kind) => mismatch
+                .update_kind()
+                .is_some_and(|kind| kind <= update_kind),
+            None => true,
+        };
+
+        self.mismatches.dependencies.retain(keep);
+
+        if let Some(dev_dependencies) = &mut self.mismatches.dev_dependencies {
+            dev_dependencies.retain(keep);
+        }
+    }
+
+    /// Summarizes the mismatches, or only the new and changed ones when compared
+    /// against a baseline.
+    pub fn summary(&self) -> Summary {
+        let mismatches = || {
+            [Section::Dependencies, Section::DevDependencies]
+                .into_iter()
+                .flat_map(|section| self.drifted_mismatches(section))
+        };
+
+        let days_behind = mismatches()
+            .filter_map(|mismatch| mismatch.days_behind(self.finished_at))
+            .max();
+
+        Summary::new(self.dependencies.len(), mismatches())
+            .with_risk_score(self.risk_score())
+            .with_days_behind(days_behind)
+    }
+
+    pub fn mismatch(&self, dependency: &DeclaredDependency) -> Option<&VersionMismatch> {
+        self.mismatches(dependency.section)
+            .iter()
+            .find(|mismatch| mismatch.name() == dependency.name)
+    }
+
+    pub fn error(&self, dependency: &DeclaredDependency) -> Option<&FailedCheck> {
+        self.errors
+            .iter()
+            .find(|err| err.section == dependency.section && err.name == dependency.name)
+    }
+
+    pub fn status(&self, dependency: &DeclaredDependency) -> Status {
+        if self.mismatch(dependency).is_some() {
+            Status::Outdated
+        } else if self.is_unknown(dependency) {
+            Status::Skipped
+        } else if self.error(dependency).is_some() {
+            Status::Errored
+        } else {
+            Status::UpToDate
+        }
+    }
+
+    /// The latest version of the dependency, if it could be resolved.
+    pub fn latest_version<'a>(&'a self, dependency: &'a DeclaredDependency) -> Option<&'a str> {
+        match self.mismatch(dependency) {
+            Some(mismatch) => Some(mismatch.latest_version()),
+            None => dependency.latest_version.as_deref(),
+        }
+    }
+
+    /// The highest version the constraint of the dependency allows, which for up to
+    /// date dependencies is the latest version.
+    pub fn satisfiable_version<'a>(
+        &'a self,
+        dependency: &'a DeclaredDependency,
+    ) -> Option<&'a str> {
+        match self.mismatch(dependency) {
+            Some(mismatch) => mismatch.satisfiable_version(),
+            None => dependency.latest_version.as_deref(),
+        }
+    }
+
+    /// Works out the risk score of every checked dependency, out of whatever the report
+    /// knows about it: how outdated it is, its known vulnerabilities if the dependencies
+    /// were audited, and its maintenance flags if their maintenance was checked.
+    pub fn score_risks(&mut self) {
+        let scores: Vec<_> = self
+            .dependencies
+            .iter()
+            .map(|dependency| {
+                let mut factors = RiskFactors::default().with_flags(&dependency.flags);
+
+                if let Some(mismatch) = self.mismatch(dependency) {
+                    factors = factors.with_update(mismatch.update_kind(), mismatch.libyears());
+                }
+
+                let (informational, vulnerabilities): (Vec<_>, Vec<_>) = self
+                    .vulnerabilities
+                    .iter()
+                    .flatten()
+                    .filter(|vulnerability| vulnerability.package() == dependency.name)
+                    .partition(|vulnerability| vulnerability.informational().is_some());
+
+                factors
+                    .with_advisories(vulnerabilities.len(), informational.len())
+                    .score()
+            })
+            .collect();
+
+        for (dependency, score) in self.dependencies.iter_mut().zip(scores) {
+            dependency.risk_score = Some(score);
+        }
+    }
+
+    /// The risk score of the project, if the risks of its dependencies were scored.
+    pub fn risk_score(&self) -> Option<u8> {
+        risk::project_score(
+            self.dependencies
+                .iter()
+                .filter_map(|dependency| dependency.risk_score),
+        )
+    }
+
+    /// The checked dependencies that show signs of no longer being maintained.
+    pub fn unmaintained(&self) -> impl Iterator<Item = &DeclaredDependency> {
+        self.dependencies
+            .iter()
+            .filter(|dependency| !dependency.flags.is_empty())
+    }
+
+    /// The checked dependency with the given name, from whichever section it is in.
+    pub fn declared_dependency(&self, name: &str) -> Option<&DeclaredDependency> {
+        self.dependencies
+            .iter()
+            .find(|dependency| dependency.name == name)
+    }
+
+    pub fn is_unknown(&self, dependency: &DeclaredDependency) -> bool {
+        self.mismatches
+            .unknown
+            .as_ref()
+            .is_some_and(|unknown| unknown.contains(&dependency.name))
+    }
+}
+
+/// The summary of the reports of every dependency file that was checked.
+pub fn combined_summary(reports: &[Report]) -> Summary {
+    reports
+        .iter()
+        .map(Report::summary)
+        .reduce(Summary::merge)
+        .unwrap_or_default()
+}
+
+/// When the last of the reports was finished, which dates the documents that cover
+/// all of them.
+fn finished_at(reports: &[Report]) -> SystemTime {
+    reports
+        .iter()
+        .map(|report| report.finished_at)
+        .max()
+        .unwrap_or_else(SystemTime::now)
+}
+
+/// The line that heads the table of a dependency file when several are checked, apart
+/// from the table of the one before it.
+fn table_heading(index: usize, manifest: &str) -> String {
+    match index {
+        0 => manifest.to_string(),
+        _ => format!("\n{}", manifest),
+    }
+}
+
+/// Prints a document in the JSON, YAML or TOML output.
+fn print_document(
+    document: &impl Serialize,
+    output_type: OutputTypes,
+) -> Result<(), Box<dyn Error>> {
+    match output_type {
+        OutputTypes::Yaml => println!("{}", serde_yaml::to_string(document)?),
+        OutputTypes::Toml => print!("{}", toml::to_string(document)?),
+        _ => println!("{}", serde_json::to_string(document)?),
+    }
+
+    Ok(())
+}
+
+/// Prints the reports to stdout. The reports of several dependency files are printed
+/// as a single report, in which each of them is keyed or headed by the path of its
+/// dependency file.
+pub fn print_reports(
+    reports: &[Report],
+    output_type: OutputTypes,
+    options: PrintOptions,
+) -> Result<(), Box<dyn Error>> {
+    match output_type {
+        // Tables are printed by prettytable itself, which colors them on a terminal
+        OutputTypes::Table => {
+            for (index, report) in reports.iter().enumerate() {
+                if reports.len() > 1 {
+                    println!("{}", table_heading(index, &report.manifest));
+                }
+
+                table::print_table_mismatches(report, options);
+            }
+
+            if !options.quiet {
+                println!("{}", combined_summary(reports));
+            }
+
+            Ok(())
+        }
+        _ => write_reports(&mut io::stdout().lock(), reports, output_type, options),
+    }
+}
+
+/// Writes the reports to a file, as a single report like [`print_reports`]. The report
+/// is written to a temporary file next to it first, which then replaces it, so that
+/// the file never holds a partial report.
+pub fn save_reports(
+    path: &Path,
+    reports: &[Report],
+    output_type: OutputTypes,
+    options: PrintOptions,
+) -> Result<(), Box<dyn Error>> {
+    let mut output = Vec::new();
+
+    write_reports(&mut output, reports, output_type, options)?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("{} is not a file path", path.display()))?;
+    let temp_path = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        process::id()
+    ));
+
+    fs::write(&temp_path, output)?;
+
+    if let Err(err) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err.into());
+    }
+
+    Ok(())
+}
+
+fn write_reports(
+    out: &mut dyn io::Write,
+    reports: &[Report],
+    output_type: OutputTypes,
+    options: PrintOptions,
+) -> Result<(), Box<dyn Error>> {
+    let several = reports.len() > 1;
+
+    match output_type {
+        OutputTypes::Table => {
+            for (index, report) in reports.iter().enumerate() {
+                if several {
+                    writeln!(out, "{}", table_heading(index, &report.manifest))?;
+                }
+
+                table::write_table_mismatches(out, report, options)?;
+            }
+
+            if !options.quiet {
+                writeln!(out, "{}", combined_summary(reports))?;
+            }
+        }
+        OutputTypes::Json => {
+            let output = JsonOutput::new(reports, options);
+
+            match options.pretty {
+                true => writeln!(out, "{}", serde_json::to_string_pretty(&output)?)?,
+                false => writeln!(out, "{}", serde_json::to_string(&output)?)?,
+            }
+        }
+        OutputTypes::Yaml => writeln!(
+            out,
+            "{}",
+            serde_yaml::to_string(&JsonOutput::new(reports, options))?
+        )?,
+        OutputTypes::Toml => write!(
+            out,
+            "{}",
+            toml::to_string(&JsonOutput::new(reports, options))?
+        )?,
+        OutputTypes::Csv => csv::write_csv_mismatches(out, reports, options)?,
+        OutputTypes::Junit => writeln!(out, "{}", junit::render_junit(reports))?,
+        OutputTypes::Markdown => {
+            for (index, report) in reports.iter().enumerate() {
+                if several {
+                    match index {
+                        0 => writeln!(out, "# {}\n", report.manifest)?,
+                        _ => writeln!(out, "\n# {}\n", report.manifest)?,
+                    }
+                }
+
+                write!(out, "{}", markdown::render_markdown(report, options))?;
+            }
+
+            if !options.quiet {
+                writeln!(out, "\n{}", combined_summary(reports))?;
+            }
+        }
+        OutputTypes::Html => writeln!(out, "{}", html::render_html(reports))?,
+        OutputTypes::Github => {
+            for report in reports {
+                let source = fs::read_to_string(&report.manifest).ok();
+
+                write!(
+                    out,
+                    "{}",
+                    github::render_annotations(report, source.as_deref())
+                )?;
+                github::write_step_summary(report, options)?;
+            }
+        }
+        OutputTypes::Cyclonedx => writeln!(
+            out,
+            "{}",
+            serde_json::to_string_pretty(&cyclonedx::render_cyclonedx(reports))?
+        )?,
+        OutputTypes::Spdx => writeln!(
+            out,
+            "{}",
+            serde_json::to_string_pretty(&spdx::render_spdx(reports))?
+        )?,
+        OutputTypes::Ndjson => {
+            for report in reports {
+                // Records do not say which dependency file they are about on their own
+                if several {
+                    writeln!(
+                        out,
+                        "{}",
+                        serde_json::to_string(&ndjson::Record::Manifest {
+                            path: &report.manifest
+                        })?
+                    )?;
+                }
+
+                write!(out, "{}", ndjson::render_ndjson(report)?)?;
+            }
+        }
+        OutputTypes::Prometheus => write!(out, "{}", prometheus::render_prometheus(reports))?,
+        OutputTypes::BadgeSvg => writeln!(out, "{}", badge::render_badge_svg(reports))?,
+        OutputTypes::BadgeJson => writeln!(
+            out,
+            "{}",
+            serde_json::to_string(&badge::render_badge_json(reports))?
+        )?,
+        OutputTypes::Gitlab => writeln!(
+            out,
+            "{}",
+            serde_json::to_string_pretty(&gitlab::render_gitlab(reports))?
+        )?,
+    }
+
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Builds the package URL (purl) identifying a package of the given type.
+fn package_url(dependency_type: DependencyType, name: &str) -> String {
+    match dependency_type {
+        DependencyType::Npm => format!("pkg:npm/{}", name.replace('@', "%40")),
+        DependencyType::Cargo => format!("pkg:cargo/{}", name),
+    }
+}
+
+/// Generates a random (version 4) UUID, used to identify generated documents.
+fn random_uuid() -> String {
+    let mut bytes = [0u8; 16];
+
+    for chunk in bytes.chunks_mut(8) {
+        chunk.copy_from_slice(&RandomState::new().build_hasher().finish().to_le_bytes());
+    }
+
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Lists the maintenance flags of a dependency, e.g. `stale, archived`.
+fn flag_names(flags: &[MaintenanceFlag]) -> String {
+    flags
+        .iter()
+        .map(MaintenanceFlag::name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Writes the date in full, or only its day if `short_dates` is set.
+fn format_date(time: SystemTime, short_dates: bool) -> String {
+    match short_dates {
+        true => format_timestamp(time)[..10].to_string(),
+        false => format!("{}Z", format_timestamp(time)),
+    }
+}
+
+/// Formats a point in time as an ISO 8601 UTC date and time, without
+/// fractional seconds or an offset, e.g. `2023-04-01T12:30:00`.
+fn format_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+
+    // Converts days since the epoch into a civil date, from Howard Hinnant's
+    // `civil_from_days` algorithm.
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+/// Escapes text for use in XML and HTML content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_package_url() {
+        assert_eq!(
+            package_url(DependencyType::Npm, "@babel/core"),
+            "pkg:npm/%40babel/core"
+        );
+        assert_eq!(
+            package_url(DependencyType::Cargo, "serde"),
+            "pkg:cargo/serde"
+        );
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00");
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_secs(1_709_210_096)),
+            "2024-02-29T12:34:56"
+        );
+    }
+
+    #[test]
+    fn test_random_uuid() {
+        let uuid = random_uuid();
+
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert_ne!(uuid, random_uuid());
+    }
+
+    #[test]
+    fn test_save_report() {
+        let dir = std::env::temp_dir().join(format!("depchk-save-report-{}", random_uuid()));
+        let path = dir.join("report.json");
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "stale").unwrap();
+
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: UNIX_EPOCH,
+            finished_at: UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("react", "^17.0.0", "18.2.0")],
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        save_reports(&path, &[report], OutputTypes::Json, PrintOptions::default()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            concat!(
+                "{\"schema_version\":1,\"manifest\":\"package.json\",\"package_manager\":\"npm\",",
+                "\"mismatches\":[{\"name\":\"react\",\"section\":\"dependencies\",",
+                "\"constraint\":\"^17.0.0\",\"latest_version\":\"18.2.0\"}],",
+                "\"errors\":[],\"unknown\":[],\"version_skew\":[]}\n"
+            )
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_retain_updates_up_to() {
+        let mut report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: UNIX_EPOCH,
+            finished_at: UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![
+                    VersionMismatch::new("react", "^17.0.0", "18.2.0")
+                        .with_update_kind(UpdateKind::Major),
+                    VersionMismatch::new("axios", "~1.5.0", "1.6.2")
+                        .with_update_kind(UpdateKind::Minor),
+                    VersionMismatch::new("left-pad", "1.0.0", "latest"),
+                ],
+                dev_dependencies: Some(vec![VersionMismatch::new("jest", "29.7.0", "29.7.1")
+                    .with_update_kind(UpdateKind::Patch)]),
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        report.retain_updates_up_to(|name| match name {
+            "react" => None,
+            "axios" => Some(UpdateKind::Patch),
+            _ => Some(UpdateKind::Minor),
+        });
+
+        assert_eq!(
+            report
+                .mismatches(Section::Dependencies)
+                .iter()
+                .map(VersionMismatch::name)
+                .collect::<Vec<_>>(),
+            ["react"]
+        );
+        assert_eq!(report.mismatches(Section::DevDependencies).len(), 1);
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml(r#"<a & 'b'> "c""#),
+            "&lt;a &amp; &apos;b&apos;&gt; &quot;c&quot;"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::error::Error;
+use std::fmt::Write as _;
+use std::io::{self, Write as _};
+
+use depchk_core::workspace::VersionSkew;
+use depchk_core::VersionMismatch;
+use serde::Serialize;
+
+use super::{Report, Section};
+
+/// A single line of NDJSON output. Every line is a standalone JSON object whose
+/// `type` tells which kind of record it is.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record<'a> {
+    /// Starts the records of a dependency file, when several are checked.
+    Manifest {
+        path: &'a str,
+    },
+    Mismatch {
+        #[serde(flatten)]
+        mismatch: &'a VersionMismatch,
+        section: Section,
+    },
+    Error {
+        name: &'a str,
+        section: Section,
+        message: &'a str,
+    },
+    Unknown {
+        name: &'a str,
+    },
+    VersionSkew {
+        #[serde(flatten)]
+        skew: &'a VersionSkew,
+    },
+}
+
+impl Record<'_> {
+    /// Writes the record as a line to stdout and flushes it right away, so that
+    /// consumers see it as soon as it is known.
+    pub fn print(&self) -> Result<(), Box<dyn Error>> {
+        let mut stdout = io::stdout().lock();
+
+        writeln!(stdout, "{}", serde_json::to_string(self)?)?;
+        stdout.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Renders the whole report as NDJSON, one record per line.
+pub fn render_ndjson(report: &Report) -> Result<String, Box<dyn Error>> {
+    let mut records = Vec::new();
+
+    for section in [Section::Dependencies, Section::DevDependencies] {
+        records.extend(
+            report
+                .mismatches(section)
+                .iter()
+                .map(|mismatch| Record::Mismatch { mismatch, section }),
+        );
+    }
+
+    records.extend(report.errors.iter().map(|err| Record::Error {
+        name: &err.name,
+        section: err.section,
+        message: &err.message,
+    }));
+
+    if let Some(unknown) = &report.mismatches.unknown {
+        records.extend(unknown.iter().map(|name| Record::Unknown { name }));
+    }
+
+    if let Some(version_skew) = &report.mismatches.version_skew {
+        records.extend(version_skew.iter().map(|skew| Record::VersionSkew { skew }));
+    }
+
+    let mut ndjson = String::new();
+
+    for record in records {
+        let _ = writeln!(ndjson, "{}", serde_json::to_string(&record)?);
+    }
+
+    Ok(ndjson)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use depchk_core::Mismatches;
+
+    use super::*;
+    use crate::output::FailedCheck;
+    use crate::DependencyType;
+
+    #[test]
+    fn test_render_ndjson() {
+        let report = Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![],
+                dev_dependencies: Some(vec![VersionMismatch::new("jest", "^28.0.0", "29.5.0")]),
+                version_skew: None,
+                unknown: Some(vec!["lodash".to_string()]),
+            },
+            dependencies: vec![],
+            errors: vec![FailedCheck {
+                name: "react".to_string(),
+                section: Section::Dependencies,
+                message: "connection reset".to_string(),
+            }],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        assert_eq!(
+            render_ndjson(&report).unwrap(),
+            concat!(
+                r#"{"type":"mismatch","name":"jest","constraint":"^28.0.0","version":"29.5.0","section":"dev_dependencies"}"#,
+                "\n",
+                r#"{"type":"error","name":"react","section":"dependencies","message":"connection reset"}"#,
+                "\n",
+                r#"{"type":"unknown","name":"lodash"}"#,
+                "\n",
+            )
+        );
+    }
+}
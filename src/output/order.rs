@@ -0,0 +1,402 @@
+use std::cmp::{Ordering, Reverse};
+
+use clap::ValueEnum;
+use depchk_core::{UpdateKind, VersionMismatch};
+
+use super::baseline::Change;
+use super::{Report, Section};
+
+/// The order mismatches are reported in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortOrder {
+    /// Alphabetically by package name
+    #[default]
+    Name,
+    /// Major updates first, then minor and patch updates
+    UpdateType,
+    /// The dependencies that are the most libyears behind first
+    Age,
+}
+
+/// How mismatches are grouped together in the report.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Grouping {
+    /// By the section of the dependency file they are declared in
+    #[default]
+    Section,
+    /// By whether they are major, minor or patch updates
+    UpdateType,
+    /// By the groups of related packages of `--groups`, with the packages of no
+    /// group last
+    Group,
+    /// By the workspace package that declares them, checking every package of the
+    /// workspace of `--workspaces`
+    Workspace,
+}
+
+/// A titled group of mismatches, in the order they are reported in.
+pub struct Group<'a> {
+    pub title: &'a str,
+    pub mismatches: Vec<&'a VersionMismatch>,
+}
+
+impl SortOrder {
+    fn compare(&self, a: &VersionMismatch, b: &VersionMismatch) -> Ordering {
+        let by_name = a.name().cmp(b.name());
+
+        match self {
+            SortOrder::Name => by_name,
+            SortOrder::UpdateType => update_kind_order(a)
+                .cmp(&update_kind_order(b))
+                .then(by_name),
+            SortOrder::Age => b
+                .libyears()
+                .unwrap_or_default()
+                .total_cmp(&a.libyears().unwrap_or_default())
+                .then(by_name),
+        }
+    }
+}
+
+/// Orders major updates first and mismatches of unknown kind last.
+fn update_kind_order(mismatch: &VersionMismatch) -> (bool, Reverse<Option<UpdateKind>>) {
+    (
+        mismatch.update_kind().is_none(),
+        Reverse(mismatch.update_kind()),
+    )
+}
+
+/// Orders groups by name and the mismatches of no group last.
+fn group_order(mismatch: &VersionMismatch) -> (bool, Option<&str>) {
+    (mismatch.group().is_none(), mismatch.group())
+}
+
+/// Orders workspace packages by name and the mismatches of no package last.
+fn workspace_order(mismatch: &VersionMismatch) -> (bool, Option<&str>) {
+    (mismatch.workspace().is_none(), mismatch.workspace())
+}
+
+/// Groups the mismatches by their label, in the order the labels first appear in,
+/// with the mismatches that have none last.
+fn labelled_groups<'a>(
+    mismatches: impl Iterator<Item = &'a VersionMismatch>,
+    label: impl Fn(&'a VersionMismatch) -> Option<&'a str>,
+) -> Vec<Group<'a>> {
+    let mut groups: Vec<Group> = Vec::new();
+    let mut unlabelled = Vec::new();
+
+    for mismatch in mismatches {
+        let Some(title) = label(mismatch) else {
+            unlabelled.push(mismatch);
+            continue;
+        };
+
+        match groups.iter_mut().find(|group| group.title == title) {
+            Some(group) => group.mismatches.push(mismatch),
+            None => groups.push(Group {
+                title,
+                mismatches: vec![mismatch],
+            }),
+        }
+    }
+
+    if !unlabelled.is_empty() {
+        groups.push(Group {
+            title: "Other Updates",
+            mismatches: unlabelled,
+        });
+    }
+
+    groups
+}
+
+impl Report {
+    /// Sorts everything in the report, so that the output is the same from one
+    /// run to the next no matter in which order the lookups resolved. Grouping
+    /// by update type orders mismatches by their update type first, for the
+    /// formats that do not render groups of their own.
+    pub fn sort(&mut self, order: SortOrder, grouping: Grouping) {
+        let compare = |a: &VersionMismatch, b: &VersionMismatch| match grouping {
+            Grouping::Section => order.compare(a, b),
+            Grouping::UpdateType => update_kind_order(a)
+                .cmp(&update_kind_order(b))
+                .then_with(|| order.compare(a, b)),
+            Grouping::Group => group_order(a)
+                .cmp(&group_order(b))
+                .then_with(|| order.compare(a, b)),
+            Grouping::Workspace => workspace_order(a)
+                .cmp(&workspace_order(b))
+                .then_with(|| order.compare(a, b)),
+        };
+
+        self.mismatches.dependencies.sort_by(compare);
+
+        if let Some(dev_dependencies) = &mut self.mismatches.dev_dependencies {
+            dev_dependencies.sort_by(compare);
+        }
+
+        if let Some(unknown) = &mut self.mismatches.unknown {
+            unknown.sort();
+        }
+
+        if let Some(version_skew) = &mut self.mismatches.version_skew {
+            version_skew.sort_by(|a, b| a.name().cmp(b.name()));
+        }
+
+        if let Some(drift) = &mut self.drift {
+            drift.resolved.sort_by(|(a_section, a), (b_section, b)| {
+                a_section.cmp(b_section).then_with(|| compare(a, b))
+            });
+        }
+
+        self.dependencies
+            .sort_by(|a, b| (a.section, &a.name).cmp(&(b.section, &b.name)));
+        self.errors
+            .sort_by(|a, b| (a.section, &a.name).cmp(&(b.section, &b.name)));
+    }
+
+    /// Splits the mismatches into the groups they are rendered in. Sections are
+    /// always listed, even when empty, while update type groups, groups of related
+    /// packages and workspace packages only when they have mismatches. When compared
+    /// against a baseline, the mismatches are grouped by how they changed since
+    /// instead, and only the changes are listed.
+    pub fn groups(&self, grouping: Grouping) -> Vec<Group<'_>> {
+        if let Some(drift) = &self.drift {
+            let changed = |change: Change| {
+                [Section::Dependencies, Section::DevDependencies]
+                    .into_iter()
+                    .flat_map(move |section| {
+                        self.mismatches(section)
+                            .iter()
+                            .filter(move |mismatch| drift.change(section, mismatch) == Some(change))
+                    })
+                    .collect()
+            };
+
+            return [
+                ("New Since the Baseline", changed(Change::New)),
+                ("Changed Since the Baseline", changed(Change::Changed)),
+                (
+                    "Resolved Since the Baseline",
+                    drift
+                        .resolved
+                        .iter()
+                        .map(|(_, mismatch)| mismatch)
+                        .collect(),
+                ),
+            ]
+            .into_iter()
+            .map(|(title, mismatches)| Group { title, mismatches })
+            .filter(|group| !group.mismatches.is_empty())
+            .collect();
+        }
+
+        match grouping {
+            Grouping::Section => {
+                let mut groups = vec![Group {
+                    title: "Dependencies",
+                    mismatches: self.mismatches.dependencies.iter().collect(),
+                }];
+
+                if let Some(dev_dependencies) = &self.mismatches.dev_dependencies {
+                    groups.push(Group {
+                        title: "Dev Dependencies",
+                        mismatches: dev_dependencies.iter().collect(),
+                    });
+                }
+
+                groups
+            }
+            Grouping::UpdateType => {
+                let mismatches: Vec<_> = self
+                    .mismatches
+                    .dependencies
+                    .iter()
+                    .chain(self.mismatches.dev_dependencies.iter().flatten())
+                    .collect();
+
+                [
+                    ("Major Updates", Some(UpdateKind::Major)),
+                    ("Minor Updates", Some(UpdateKind::Minor)),
+                    ("Patch Updates", Some(UpdateKind::Patch)),
+                    ("Other Updates", None),
+                ]
+                .into_iter()
+                .map(|(title, update_kind)| Group {
+                    title,
+                    mismatches: mismatches
+                        .iter()
+                        .filter(|mismatch| mismatch.update_kind() == update_kind)
+                        .copied()
+                        .collect(),
+                })
+                .filter(|group| !group.mismatches.is_empty())
+                .collect()
+            }
+            Grouping::Group => {
+                let mismatches = self
+                    .mismatches
+                    .dependencies
+                    .iter()
+                    .chain(self.mismatches.dev_dependencies.iter().flatten());
+
+                labelled_groups(mismatches, VersionMismatch::group)
+            }
+            Grouping::Workspace => {
+                let mismatches = self
+                    .mismatches
+                    .dependencies
+                    .iter()
+                    .chain(self.mismatches.dev_dependencies.iter().flatten());
+
+                labelled_groups(mismatches, VersionMismatch::workspace)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use depchk_core::Mismatches;
+
+    use super::*;
+    use crate::DependencyType;
+
+    fn report() -> Report {
+        Report {
+            manifest: "package.json".to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![
+                    VersionMismatch::new("react", "^17.0.0", "18.2.0")
+                        .with_update_kind(UpdateKind::Major)
+                        .with_libyears(0.5),
+                    VersionMismatch::new("axios", "~0.27.0", "0.27.2"),
+                    VersionMismatch::new("lodash", "~4.16.0", "4.17.21")
+                        .with_update_kind(UpdateKind::Minor)
+                        .with_libyears(4.0),
+                ],
+                dev_dependencies: Some(vec![VersionMismatch::new("jest", "^28.0.0", "29.5.0")
+                    .with_update_kind(UpdateKind::Major)]),
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        }
+    }
+
+    fn names(mismatches: &[VersionMismatch]) -> Vec<&str> {
+        mismatches.iter().map(VersionMismatch::name).collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut report = report();
+
+        report.sort(SortOrder::Name, Grouping::Section);
+        assert_eq!(
+            names(&report.mismatches.dependencies),
+            ["axios", "lodash", "react"]
+        );
+
+        report.sort(SortOrder::UpdateType, Grouping::Section);
+        assert_eq!(
+            names(&report.mismatches.dependencies),
+            ["react", "lodash", "axios"]
+        );
+
+        report.sort(SortOrder::Age, Grouping::Section);
+        assert_eq!(
+            names(&report.mismatches.dependencies),
+            ["lodash", "react", "axios"]
+        );
+
+        report.sort(SortOrder::Age, Grouping::UpdateType);
+        assert_eq!(
+            names(&report.mismatches.dependencies),
+            ["react", "lodash", "axios"]
+        );
+    }
+
+    #[test]
+    fn test_groups_by_update_type() {
+        let mut report = report();
+
+        report.sort(SortOrder::Name, Grouping::UpdateType);
+
+        let groups = report.groups(Grouping::UpdateType);
+        let titles: Vec<_> = groups.iter().map(|group| group.title).collect();
+
+        assert_eq!(titles, ["Major Updates", "Minor Updates", "Other Updates"]);
+        assert_eq!(
+            groups[0]
+                .mismatches
+                .iter()
+                .map(|mismatch| mismatch.name())
+                .collect::<Vec<_>>(),
+            ["react", "jest"]
+        );
+    }
+
+    #[test]
+    fn test_groups_by_group() {
+        let mut report = report();
+
+        for mismatch in &mut report.mismatches.dependencies {
+            if mismatch.name() != "axios" {
+                *mismatch = mismatch.clone().with_group("frontend");
+            }
+        }
+
+        report.sort(SortOrder::Name, Grouping::Group);
+
+        assert_eq!(
+            names(&report.mismatches.dependencies),
+            ["lodash", "react", "axios"]
+        );
+
+        let groups = report.groups(Grouping::Group);
+        let titles: Vec<_> = groups.iter().map(|group| group.title).collect();
+
+        assert_eq!(titles, ["frontend", "Other Updates"]);
+        assert_eq!(groups[1].mismatches.len(), 2);
+    }
+
+    #[test]
+    fn test_groups_by_workspace() {
+        let mut package = report();
+        let mut report = report();
+
+        report.assign_workspace("web");
+        package.mismatches.dependencies.truncate(1);
+        package.mismatches.dev_dependencies = None;
+        package.assign_workspace("api");
+        report.append(package);
+
+        report.sort(SortOrder::Name, Grouping::Workspace);
+
+        assert_eq!(
+            names(&report.mismatches.dependencies),
+            ["react", "axios", "lodash", "react"]
+        );
+
+        let groups = report.groups(Grouping::Workspace);
+        let titles: Vec<_> = groups.iter().map(|group| group.title).collect();
+
+        assert_eq!(titles, ["api", "web"]);
+        assert_eq!(groups[0].mismatches.len(), 1);
+        assert_eq!(groups[1].mismatches.len(), 4);
+    }
+}
@@ -0,0 +1,305 @@
+use std::error::Error;
+use std::fmt::Write;
+use std::time::UNIX_EPOCH;
+
+use depchk_core::UpdateKind;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+
+use super::{finished_at, Report, Section};
+
+/// The content type of the Prometheus text exposition format.
+const CONTENT_TYPE_TEXT: &str = "text/plain; version=0.0.4";
+
+/// The update types mismatches are counted by, as they are labelled.
+const UPDATE_TYPES: [(&str, Option<UpdateKind>); 4] = [
+    ("major", Some(UpdateKind::Major)),
+    ("minor", Some(UpdateKind::Minor)),
+    ("patch", Some(UpdateKind::Patch)),
+    ("other", None),
+];
+
+/// Renders the reports as metrics in the Prometheus text exposition format. Every
+/// series is written even when it is zero, so that it does not disappear from
+/// dashboards once the last outdated dependency is updated. When several dependency
+/// files are checked, every series is labelled with the one it is about.
+pub fn render_prometheus(reports: &[Report]) -> String {
+    let manifest = |report: &'_ Report| match reports.len() {
+        1 => None,
+        _ => Some(report.manifest.clone()),
+    };
+
+    let sections = |report: &Report| match report.mismatches.dev_dependencies {
+        Some(_) => vec![Section::Dependencies, Section::DevDependencies],
+        None => vec![Section::Dependencies],
+    };
+
+    let mut metrics = String::new();
+
+    write_header(
+        &mut metrics,
+        "depchk_dependencies_total",
+        "The number of checked dependencies.",
+    );
+
+    for report in reports {
+        for section in sections(report) {
+            let _ = writeln!(
+                metrics,
+                "{} {}",
+                series(
+                    "depchk_dependencies_total",
+                    manifest(report).as_deref(),
+                    &[("section", section.name())]
+                ),
+                report
+                    .dependencies
+                    .iter()
+                    .filter(|dependency| dependency.section == section)
+                    .count()
+            );
+        }
+    }
+
+    write_header(
+        &mut metrics,
+        "depchk_outdated_total",
+        "The number of dependencies whose latest version does not satisfy their constraint.",
+    );
+
+    for report in reports {
+        for section in sections(report) {
+            for (update_type, update_kind) in UPDATE_TYPES {
+                let _ = writeln!(
+                    metrics,
+                    "{} {}",
+                    series(
+                        "depchk_outdated_total",
+                        manifest(report).as_deref(),
+                        &[("section", section.name()), ("update_type", update_type)]
+                    ),
+                    report
+                        .mismatches(section)
+                        .iter()
+                        .filter(|mismatch| mismatch.update_kind() == update_kind)
+                        .count()
+                );
+            }
+        }
+    }
+
+    write_header(
+        &mut metrics,
+        "depchk_errors_total",
+        "The number of dependencies that could not be checked.",
+    );
+
+    for report in reports {
+        let _ = writeln!(
+            metrics,
+            "{} {}",
+            series("depchk_errors_total", manifest(report).as_deref(), &[]),
+            report.errors.len()
+        );
+    }
+
+    // Risk scores are only written when they were worked out, as a score of zero
+    // would claim that there is no risk
+    let risk_scores: Vec<_> = reports
+        .iter()
+        .filter_map(|report| Some((report, report.risk_score()?)))
+        .collect();
+
+    if !risk_scores.is_empty() {
+        write_header(
+            &mut metrics,
+            "depchk_risk_score",
+            "The risk score of each dependency, from 0 to 100.",
+        );
+
+        for (report, _) in &risk_scores {
+            for dependency in &report.dependencies {
+                if let Some(score) = dependency.risk_score {
+                    let _ = writeln!(
+                        metrics,
+                        "{} {}",
+                        series(
+                            "depchk_risk_score",
+                            manifest(report).as_deref(),
+                            &[
+                                ("section", dependency.section.name()),
+                                ("package", &dependency.name)
+                            ]
+                        ),
+                        score
+                    );
+                }
+            }
+        }
+
+        write_header(
+            &mut metrics,
+            "depchk_project_risk_score",
+            "The risk score of the project, which is the highest score of its dependencies.",
+        );
+
+        for (report, risk_score) in &risk_scores {
+            let _ = writeln!(
+                metrics,
+                "{} {}",
+                series(
+                    "depchk_project_risk_score",
+                    manifest(report).as_deref(),
+                    &[]
+                ),
+                risk_score
+            );
+        }
+    }
+
+    write_header(
+        &mut metrics,
+        "depchk_last_run_timestamp_seconds",
+        "When the check finished, in seconds since the Unix epoch.",
+    );
+    let _ = writeln!(
+        metrics,
+        "depchk_last_run_timestamp_seconds {}",
+        finished_at(reports)
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs())
+    );
+
+    metrics
+}
+
+/// The name of a series along with its labels, which start with the dependency file
+/// it is about if there is one.
+fn series(name: &str, manifest: Option<&str>, labels: &[(&str, &str)]) -> String {
+    let labels: Vec<_> = manifest
+        .map(|manifest| ("manifest", manifest))
+        .into_iter()
+        .chain(labels.iter().copied())
+        .map(|(label, value)| format!("{}=\"{}\"", label, value))
+        .collect();
+
+    match labels.is_empty() {
+        true => name.to_string(),
+        false => format!("{}{{{}}}", name, labels.join(",")),
+    }
+}
+
+fn write_header(metrics: &mut String, name: &str, help: &str) {
+    let _ = writeln!(metrics, "# HELP {} {}", name, help);
+    let _ = writeln!(metrics, "# TYPE {} gauge", name);
+}
+
+/// Pushes the metrics of the reports to a Prometheus Pushgateway, replacing the ones
+/// previously pushed by depchk.
+pub async fn push_metrics(
+    http: &Client,
+    pushgateway: &str,
+    reports: &[Report],
+) -> Result<(), Box<dyn Error>> {
+    http.put(format!(
+        "{}/metrics/job/depchk",
+        pushgateway.trim_end_matches('/')
+    ))
+    .header(CONTENT_TYPE, CONTENT_TYPE_TEXT)
+    .body(render_prometheus(reports))
+    .send()
+    .await?
+    .error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use depchk_core::{Mismatches, VersionMismatch};
+
+    use super::*;
+    use crate::output::{DeclaredDependency, FailedCheck};
+    use crate::DependencyType;
+
+    #[test]
+    fn test_render_prometheus() {
+        let dependency = |name: &str, section| DeclaredDependency {
+            name: name.to_string(),
+            constraint: "^1.0.0".to_string(),
+            section,
+            latest_version: None,
+            registry: "https://registry.npmjs.org".to_string(),
+            homepage: None,
+            license: None,
+            last_published_at: None,
+            flags: Vec::new(),
+            risk_score: None,
+        };
+
+        let report = |manifest: &str| Report {
+            manifest: manifest.to_string(),
+            dependency_type: DependencyType::Npm,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_680_000_000),
+            mismatches: Mismatches {
+                dependencies: vec![
+                    VersionMismatch::new("react", "^17.0.0", "18.2.0")
+                        .with_update_kind(UpdateKind::Major),
+                    VersionMismatch::new("axios", "~1.3.0", "1.4.0")
+                        .with_update_kind(UpdateKind::Minor),
+                ],
+                dev_dependencies: None,
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![
+                dependency("react", Section::Dependencies),
+                dependency("axios", Section::Dependencies),
+                dependency("lodash", Section::Dependencies),
+            ],
+            errors: vec![FailedCheck {
+                name: "lodash".to_string(),
+                section: Section::Dependencies,
+                message: "timed out".to_string(),
+            }],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let metrics = render_prometheus(&[report("package.json")]);
+
+        assert!(metrics.contains("# TYPE depchk_outdated_total gauge\n"));
+        assert!(metrics.contains("depchk_dependencies_total{section=\"dependencies\"} 3\n"));
+        assert!(metrics
+            .contains("depchk_outdated_total{section=\"dependencies\",update_type=\"major\"} 1\n"));
+        assert!(metrics
+            .contains("depchk_outdated_total{section=\"dependencies\",update_type=\"patch\"} 0\n"));
+        assert!(metrics.contains("depchk_errors_total 1\n"));
+        assert!(metrics.contains("depchk_last_run_timestamp_seconds 1680000000\n"));
+        assert!(!metrics.contains("dev_dependencies"));
+        assert!(!metrics.contains("depchk_risk_score"));
+
+        let metrics =
+            render_prometheus(&[report("package.json"), report("packages/app/package.json")]);
+
+        assert_eq!(
+            metrics
+                .matches("# TYPE depchk_errors_total gauge\n")
+                .count(),
+            1
+        );
+        assert!(metrics.contains("depchk_errors_total{manifest=\"package.json\"} 1\n"));
+        assert!(metrics.contains("depchk_errors_total{manifest=\"packages/app/package.json\"} 1\n"));
+        assert!(metrics.contains(
+            "depchk_dependencies_total{manifest=\"package.json\",section=\"dependencies\"} 3\n"
+        ));
+    }
+}
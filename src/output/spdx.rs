@@ -0,0 +1,202 @@
+use serde_json::{json, Value};
+
+use super::{finished_at, format_timestamp, package_url, random_uuid, Report, Section};
+
+const ROOT_ID: &str = "SPDXRef-Package-root";
+
+/// Renders every checked dependency as an SPDX 2.3 JSON document. Each dependency
+/// file is described as a root package, which its dependencies are related to.
+/// Version constraints and latest versions are recorded in the package comments.
+pub fn render_spdx(reports: &[Report]) -> Value {
+    let mut packages = Vec::new();
+    let mut relationships = Vec::new();
+    let mut index = 0;
+
+    for (root, report) in reports.iter().enumerate() {
+        let root_id = match reports.len() {
+            1 => ROOT_ID.to_string(),
+            _ => format!("{}-{}", ROOT_ID, root),
+        };
+
+        packages.push(json!({
+            "name": report.manifest,
+            "SPDXID": root_id,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": "NOASSERTION",
+            "copyrightText": "NOASSERTION",
+            "primaryPackagePurpose": "SOURCE",
+        }));
+
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": root_id,
+        }));
+
+        for dependency in &report.dependencies {
+            let id = format!("SPDXRef-Package-{}-{}", spdx_id(&dependency.name), index);
+            index += 1;
+
+            let comment = match report.mismatch(dependency) {
+                Some(mismatch) => format!(
+                    "Declared as {} in {}, latest version is {}",
+                    dependency.constraint,
+                    dependency.section.name(),
+                    mismatch.latest_version()
+                ),
+                None => format!(
+                    "Declared as {} in {}",
+                    dependency.constraint,
+                    dependency.section.name()
+                ),
+            };
+
+            packages.push(json!({
+                "name": dependency.name,
+                "SPDXID": id,
+                "downloadLocation": "NOASSERTION",
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": "NOASSERTION",
+                "copyrightText": "NOASSERTION",
+                "primaryPackagePurpose": "LIBRARY",
+                "comment": comment,
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": package_url(report.dependency_type, &dependency.name),
+                }],
+            }));
+
+            relationships.push(match dependency.section {
+                Section::Dependencies => json!({
+                    "spdxElementId": root_id,
+                    "relationshipType": "DEPENDS_ON",
+                    "relatedSpdxElement": id,
+                }),
+                Section::DevDependencies => json!({
+                    "spdxElementId": id,
+                    "relationshipType": "DEV_DEPENDENCY_OF",
+                    "relatedSpdxElement": root_id,
+                }),
+            });
+        }
+    }
+
+    let name = match reports {
+        [report] => format!("depchk-{}", report.manifest),
+        _ => "depchk".to_string(),
+    };
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": name,
+        "documentNamespace": format!("https://spdx.org/spdxdocs/depchk-{}", random_uuid()),
+        "creationInfo": {
+            "created": format!("{}Z", format_timestamp(finished_at(reports))),
+            "creators": [format!("Tool: depchk-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+/// Replaces the characters that are not allowed in SPDX identifiers.
+fn spdx_id(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use depchk_core::{Mismatches, VersionMismatch};
+
+    use super::*;
+    use crate::output::DeclaredDependency;
+    use crate::DependencyType;
+
+    #[test]
+    fn test_render_spdx() {
+        let report = Report {
+            manifest: "Cargo.toml".to_string(),
+            dependency_type: DependencyType::Cargo,
+            started_at: SystemTime::UNIX_EPOCH,
+            finished_at: SystemTime::UNIX_EPOCH,
+            mismatches: Mismatches {
+                dependencies: vec![VersionMismatch::new("serde_json", "0.9", "1.0.96")],
+                dev_dependencies: Some(vec![]),
+                version_skew: None,
+                unknown: None,
+            },
+            dependencies: vec![
+                DeclaredDependency {
+                    name: "serde_json".to_string(),
+                    constraint: "0.9".to_string(),
+                    section: Section::Dependencies,
+                    latest_version: None,
+                    registry: "https://index.crates.io".to_string(),
+                    homepage: None,
+                    license: None,
+                    last_published_at: None,
+                    flags: Vec::new(),
+                    risk_score: None,
+                },
+                DeclaredDependency {
+                    name: "tempfile".to_string(),
+                    constraint: "3".to_string(),
+                    section: Section::DevDependencies,
+                    latest_version: None,
+                    registry: "https://index.crates.io".to_string(),
+                    homepage: None,
+                    license: None,
+                    last_published_at: None,
+                    flags: Vec::new(),
+                    risk_score: None,
+                },
+            ],
+            errors: vec![],
+            drift: None,
+            vulnerabilities: None,
+            unavailable: None,
+            license_violations: None,
+            missing_provenance: None,
+            denied: None,
+            transitive: None,
+        };
+
+        let spdx = render_spdx(&[report]);
+
+        assert_eq!(spdx["spdxVersion"], "SPDX-2.3");
+        assert_eq!(spdx["packages"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            spdx["packages"][1]["SPDXID"],
+            "SPDXRef-Package-serde-json-0"
+        );
+        assert_eq!(
+            spdx["packages"][1]["comment"],
+            "Declared as 0.9 in dependencies, latest version is 1.0.96"
+        );
+        assert_eq!(
+            spdx["packages"][2]["externalRefs"][0]["referenceLocator"],
+            "pkg:cargo/tempfile"
+        );
+        assert_eq!(
+            spdx["relationships"][2],
+            json!({
+                "spdxElementId": "SPDXRef-Package-tempfile-1",
+                "relationshipType": "DEV_DEPENDENCY_OF",
+                "relatedSpdxElement": ROOT_ID,
+            })
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::error::Error;
+
+use depchk_core::client::RegistryClient;
+use depchk_core::workspace::VersionSkew;
+use depchk_core::{CheckOutcome, DepchkError, Dependency, ProjectDependencies, VersionMismatch};
+
+use futures::{Stream, StreamExt};
+
+use super::csv::{self, Row};
+use super::ndjson::Record;
+use super::summary::Summary;
+use super::{OutputTypes, Section};
+use crate::DependencyCheckErrors;
+
+/// Column widths used to keep streamed table rows aligned without knowing
+/// the latest versions up front.
+struct StreamWidths {
+    name: usize,
+    constraint: usize,
+}
+
+impl StreamWidths {
+    fn new<T: Dependency>(dependencies: &ProjectDependencies<T>) -> Self {
+        dependencies.iter().fold(
+            StreamWidths {
+                name: "Package Name".len(),
+                constraint: "Version Constraint".len(),
+            },
+            |widths, dependency| StreamWidths {
+                name: widths.name.max(dependency.get_name().len()),
+                constraint: widths.constraint.max(dependency.get_constraint().len()),
+            },
+        )
+    }
+
+    fn print_row(&self, name: &str, constraint: &str, version: &str, update_kind: &str) {
+        println!(
+            "{:<name_width$}  {:<constraint_width$}  {:<version_width$}  {}",
+            name,
+            constraint,
+            version,
+            update_kind,
+            name_width = self.name,
+            constraint_width = self.constraint,
+            version_width = "Latest Version".len(),
+        );
+    }
+}
+
+fn print_streamed_mismatch(
+    manifest: &str,
+    mismatch: &VersionMismatch,
+    section: Section,
+    output_type: OutputTypes,
+    widths: &StreamWidths,
+) -> Result<(), Box<dyn Error>> {
+    let (name, constraint, version) = mismatch.destruct();
+
+    match output_type {
+        OutputTypes::Table => widths.print_row(
+            name,
+            constraint,
+            version,
+            mismatch.update_kind().map_or("", |kind| kind.name()),
+        ),
+        OutputTypes::Json => println!("{}", serde_json::to_string(mismatch)?),
+        OutputTypes::Yaml => print!("---\n{}", serde_yaml::to_string(mismatch)?),
+        OutputTypes::Csv => Row::mismatch(manifest, section, mismatch).print()?,
+        OutputTypes::Ndjson => Record::Mismatch { mismatch, section }.print()?,
+        _ => unreachable!("{:?} output cannot be streamed", output_type),
+    }
+
+    Ok(())
+}
+
+/// Writes an NDJSON record for a skipped or failed check as soon as it is known.
+/// The other output types report them once every check has finished.
+fn print_streamed_error(outcome: &CheckOutcome, section: Section) -> Result<(), Box<dyn Error>> {
+    match outcome {
+        CheckOutcome::Skipped(err) => Record::Unknown { name: err.name() }.print(),
+        CheckOutcome::Errored(err) => Record::Error {
+            name: err.name(),
+            section,
+            message: &err.error().to_string(),
+        }
+        .print(),
+        CheckOutcome::UpToDate(_) | CheckOutcome::Outdated(_) => Ok(()),
+    }
+}
+
+async fn print_stream(
+    manifest: &str,
+    outcomes: impl Stream<Item = CheckOutcome>,
+    section: Section,
+    output_type: OutputTypes,
+    widths: &StreamWidths,
+    summary: &mut Summary,
+) -> Result<DependencyCheckErrors, Box<dyn Error>> {
+    futures::pin_mut!(outcomes);
+
+    let mut errors = Vec::new();
+
+    while let Some(outcome) = outcomes.next().await {
+        if output_type == OutputTypes::Ndjson {
+            print_streamed_error(&outcome, section)?;
+        }
+
+        match outcome {
+            CheckOutcome::UpToDate(_) => {}
+            CheckOutcome::Outdated(mismatch) => {
+                summary.add(&mismatch);
+                print_streamed_mismatch(manifest, &mismatch, section, output_type, widths)?
+            }
+            CheckOutcome::Skipped(err) | CheckOutcome::Errored(err) => {
+                errors.push(DepchkError::from(err))
+            }
+        }
+    }
+
+    Ok(DependencyCheckErrors::new(errors))
+}
+
+pub async fn stream_mismatches<T: Dependency>(
+    client: &RegistryClient,
+    manifest: &str,
+    dependencies: &ProjectDependencies<T>,
+    include_dev_dependencies: bool,
+    concurrency: usize,
+    output_type: OutputTypes,
+    quiet: bool,
+) -> Result<(DependencyCheckErrors, Summary), Box<dyn Error>> {
+    let widths = StreamWidths::new(dependencies);
+
+    let total = dependencies.dependencies().len()
+        + match include_dev_dependencies {
+            true => dependencies.dev_dependencies().len(),
+            false => 0,
+        };
+    let mut summary = Summary::new(total, []);
+
+    match output_type {
+        OutputTypes::Table => widths.print_row(
+            "Package Name",
+            "Version Constraint",
+            "Latest Version",
+            "Update",
+        ),
+        OutputTypes::Csv => csv::print_header()?,
+        _ => {}
+    }
+
+    let mut err = print_stream(
+        manifest,
+        dependencies.check_stream(client, concurrency),
+        Section::Dependencies,
+        output_type,
+        &widths,
+        &mut summary,
+    )
+    .await?;
+
+    if include_dev_dependencies {
+        if output_type == OutputTypes::Table {
+            println!("\nDev Dependencies");
+        }
+
+        err.join(
+            print_stream(
+                manifest,
+                dependencies.check_dev_stream(client, concurrency),
+                Section::DevDependencies,
+                output_type,
+                &widths,
+                &mut summary,
+            )
+            .await?,
+        );
+    }
+
+    if output_type == OutputTypes::Table && !quiet {
+        println!("\n{}", summary);
+    }
+
+    Ok((err, summary))
+}
+
+pub fn print_streamed_skew(
+    version_skew: &[VersionSkew],
+    output_type: OutputTypes,
+) -> Result<(), Box<dyn Error>> {
+    for skew in version_skew {
+        match output_type {
+            OutputTypes::Table => {
+                for constraint in skew.constraints() {
+                    println!(
+                        "{}  {}  {}",
+                        skew.name(),
+                        constraint.package(),
+                        constraint.constraint()
+                    );
+                }
+            }
+            OutputTypes::Csv => {
+                for row in Row::version_skew(skew) {
+                    row.print()?;
+                }
+            }
+            OutputTypes::Json => println!("{}", serde_json::to_string(skew)?),
+            OutputTypes::Yaml => print!("---\n{}", serde_yaml::to_string(skew)?),
+            OutputTypes::Ndjson => Record::VersionSkew { skew }.print()?,
+            _ => unreachable!("{:?} output cannot be streamed", output_type),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use std::fmt::Display;
+
+use depchk_core::{UpdateKind, VersionMismatch};
+
+/// Aggregate counts of a check, printed as a single line after the report.
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    total: usize,
+    outdated: usize,
+    major: usize,
+    minor: usize,
+    patch: usize,
+    libyears: f64,
+    /// How many days ago the oldest latest version that is not used yet was released.
+    days_behind: Option<u64>,
+    /// The risk score of the project, if the risks of its dependencies were scored.
+    risk_score: Option<u8>,
+}
+
+/// Limits on how far behind the dependencies may fall, which fail the check when they
+/// are exceeded, so that CI runs can hold projects to a level of dependency hygiene.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Thresholds {
+    pub max_libyears: Option<f64>,
+    pub max_major_behind: Option<usize>,
+    pub max_outdated_count: Option<usize>,
+}
+
+impl Summary {
+    pub fn new<'a>(
+        total: usize,
+        mismatches: impl IntoIterator<Item = &'a VersionMismatch>,
+    ) -> Self {
+        mismatches.into_iter().fold(
+            Summary {
+                total,
+                ..Summary::default()
+            },
+            |mut summary, mismatch| {
+                summary.add(mismatch);
+                summary
+            },
+        )
+    }
+
+    /// The number of outdated dependencies.
+    pub fn outdated(&self) -> usize {
+        self.outdated
+    }
+
+    /// The number of outdated dependencies whose update is at least of the given kind,
+    /// e.g. both major and minor updates for [`UpdateKind::Minor`].
+    pub fn with_risk_score(mut self, risk_score: Option<u8>) -> Self {
+        self.risk_score = risk_score;
+        self
+    }
+
+    pub fn with_days_behind(mut self, days_behind: Option<u64>) -> Self {
+        self.days_behind = days_behind;
+        self
+    }
+
+    pub fn outdated_by_at_least(&self, update_kind: UpdateKind) -> usize {
+        match update_kind {
+            UpdateKind::Major => self.major,
+            UpdateKind::Minor => self.major + self.minor,
+            UpdateKind::Patch => self.major + self.minor + self.patch,
+        }
+    }
+
+    /// Describes each of the thresholds that the summary exceeds.
+    pub fn exceeded(&self, thresholds: &Thresholds) -> Vec<String> {
+        let mut exceeded = Vec::new();
+
+        if let Some(max) = thresholds.max_libyears {
+            if self.libyears > max {
+                exceeded.push(format!(
+                    "the dependencies are {:.1} libyears behind, more than the maximum of {}",
+                    self.libyears, max
+                ));
+            }
+        }
+
+        if let Some(max) = thresholds.max_major_behind {
+            if self.major > max {
+                exceeded.push(format!(
+                    "{} dependencies are a major update behind, more than the maximum of {}",
+                    self.major, max
+                ));
+            }
+        }
+
+        if let Some(max) = thresholds.max_outdated_count {
+            if self.outdated > max {
+                exceeded.push(format!(
+                    "{} dependencies are outdated, more than the maximum of {}",
+                    self.outdated, max
+                ));
+            }
+        }
+
+        exceeded
+    }
+
+    /// Adds up the summaries of several checks, such as the ones of every dependency
+    /// file that is checked. The oldest update and the highest risk score are kept.
+    pub fn merge(self, other: Summary) -> Summary {
+        Summary {
+            total: self.total + other.total,
+            outdated: self.outdated + other.outdated,
+            major: self.major + other.major,
+            minor: self.minor + other.minor,
+            patch: self.patch + other.patch,
+            libyears: self.libyears + other.libyears,
+            days_behind: self.days_behind.max(other.days_behind),
+            risk_score: self.risk_score.max(other.risk_score),
+        }
+    }
+
+    pub fn add(&mut self, mismatch: &VersionMismatch) {
+        self.outdated += 1;
+        self.libyears += mismatch.libyears().unwrap_or_default();
+
+        match mismatch.update_kind() {
+            Some(UpdateKind::Major) => self.major += 1,
+            Some(UpdateKind::Minor) => self.minor += 1,
+            Some(UpdateKind::Patch) => self.patch += 1,
+            None => {}
+        }
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} dependencies outdated",
+            self.outdated, self.total
+        )?;
+
+        if self.outdated > 0 {
+            write!(
+                f,
+                " ({} major, {} minor, {} patch)",
+                self.major, self.minor, self.patch
+            )?;
+        }
+
+        if self.libyears > 0.0 {
+            write!(f, ", {:.1} libyears behind", self.libyears)?;
+        }
+
+        if let Some(days_behind) = self.days_behind {
+            write!(f, ", oldest update released {} days ago", days_behind)?;
+        }
+
+        if let Some(risk_score) = self.risk_score {
+            write!(f, ", risk score {}", risk_score)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mismatches = [
+            VersionMismatch::new("react", "^17.0.0", "18.2.0")
+                .with_update_kind(UpdateKind::Major)
+                .with_libyears(1.5),
+            VersionMismatch::new("lodash", "~4.16.0", "4.17.21")
+                .with_update_kind(UpdateKind::Minor)
+                .with_libyears(4.0),
+            VersionMismatch::new("left-pad", "1.0.0", "1.3.0"),
+        ];
+
+        assert_eq!(
+            Summary::new(12, &mismatches).to_string(),
+            "3 of 12 dependencies outdated (1 major, 1 minor, 0 patch), 5.5 libyears behind"
+        );
+        assert_eq!(
+            Summary::new(12, &mismatches)
+                .with_days_behind(Some(412))
+                .to_string(),
+            "3 of 12 dependencies outdated (1 major, 1 minor, 0 patch), 5.5 libyears behind, oldest update released 412 days ago"
+        );
+        assert_eq!(
+            Summary::new(12, &mismatches).outdated_by_at_least(UpdateKind::Minor),
+            2
+        );
+        assert_eq!(
+            Summary::new(4, []).to_string(),
+            "0 of 4 dependencies outdated"
+        );
+        assert_eq!(
+            Summary::new(4, []).with_risk_score(Some(15)).to_string(),
+            "0 of 4 dependencies outdated, risk score 15"
+        );
+        assert_eq!(
+            Summary::new(12, &mismatches[..1])
+                .merge(Summary::new(4, &mismatches[1..]).with_days_behind(Some(30)))
+                .to_string(),
+            "3 of 16 dependencies outdated (1 major, 1 minor, 0 patch), 5.5 libyears behind, oldest update released 30 days ago"
+        );
+    }
+
+    #[test]
+    fn test_exceeded() {
+        let mismatches = [
+            VersionMismatch::new("react", "^17.0.0", "18.2.0")
+                .with_update_kind(UpdateKind::Major)
+                .with_libyears(1.5),
+            VersionMismatch::new("lodash", "~4.16.0", "4.17.21")
+                .with_update_kind(UpdateKind::Minor)
+                .with_libyears(4.0),
+        ];
+        let summary = Summary::new(12, &mismatches);
+
+        assert!(summary.exceeded(&Thresholds::default()).is_empty());
+        assert!(summary
+            .exceeded(&Thresholds {
+                max_libyears: Some(5.5),
+                max_major_behind: Some(1),
+                max_outdated_count: Some(2),
+            })
+            .is_empty());
+        assert_eq!(
+            summary.exceeded(&Thresholds {
+                max_libyears: Some(5.0),
+                max_major_behind: Some(0),
+                max_outdated_count: None,
+            }),
+            [
+                "the dependencies are 5.5 libyears behind, more than the maximum of 5",
+                "1 dependencies are a major update behind, more than the maximum of 0",
+            ]
+        );
+    }
+}
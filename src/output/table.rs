@@ -0,0 +1,398 @@
+use std::io::{self, IsTerminal, Write};
+
+use depchk_core::{UpdateKind, VersionMismatch};
+
+use prettytable::{Cell, Row, Table};
+
+use super::{audit, flag_names, format_date, Column, Grouping, PrintOptions, Report, Status};
+
+/// The color of an update of the given kind, as a prettytable style spec.
+pub fn update_kind_style(update_kind: Option<UpdateKind>) -> &'static str {
+    match update_kind {
+        Some(UpdateKind::Major) => "Fr",
+        Some(UpdateKind::Minor) => "Fy",
+        Some(UpdateKind::Patch) => "Fg",
+        None => "FR",
+    }
+}
+
+/// The ANSI escape code of the color of an update of the given kind, matching
+/// [`update_kind_style`].
+fn update_kind_color(update_kind: Option<UpdateKind>) -> &'static str {
+    match update_kind {
+        Some(UpdateKind::Major) => "\x1b[31m",
+        Some(UpdateKind::Minor) => "\x1b[33m",
+        Some(UpdateKind::Patch) => "\x1b[32m",
+        None => "\x1b[91m",
+    }
+}
+
+/// The byte offset of the first dot-separated segment of the latest version that
+/// differs from the satisfiable version, if any does.
+fn changed_segment_start(version: &str, satisfiable_version: &str) -> Option<usize> {
+    let mut satisfiable_segments = satisfiable_version.split('.');
+    let mut start = 0;
+
+    for segment in version.split('.') {
+        if satisfiable_segments.next() != Some(segment) {
+            return Some(start);
+        }
+
+        start += segment.len() + 1;
+    }
+
+    None
+}
+
+/// Builds the latest version cell. On a terminal only the segments that changed
+/// since the satisfiable version are colored, e.g. `2.0.0` of `2.0.0` but only
+/// `4.1` of `1.4.1`, so that the size of the jump stands out.
+fn version_cell(mismatch: &VersionMismatch, colorize_segments: bool) -> Cell {
+    let version = mismatch.latest_version();
+
+    let start = mismatch
+        .satisfiable_version()
+        .filter(|_| colorize_segments)
+        .and_then(|satisfiable_version| changed_segment_start(version, satisfiable_version));
+
+    match start {
+        Some(start) => Cell::new(&format!(
+            "{}{}{}\x1b[0m",
+            &version[..start],
+            update_kind_color(mismatch.update_kind()),
+            &version[start..]
+        )),
+        None => Cell::new(version).style_spec(update_kind_style(mismatch.update_kind())),
+    }
+}
+
+fn mismatch_row(
+    report: &Report,
+    mismatch: &VersionMismatch,
+    columns: &[Column],
+    colorize_segments: bool,
+) -> Row {
+    let style = update_kind_style(mismatch.update_kind());
+
+    Row::new(
+        columns
+            .iter()
+            .map(|column| match column {
+                Column::Latest => version_cell(mismatch, colorize_segments),
+                Column::Name => Cell::new(mismatch.name()).style_spec("FG"),
+                Column::Constraint => {
+                    Cell::new(&column.value(report, mismatch, true)).style_spec("FB")
+                }
+                Column::Type => Cell::new(&column.value(report, mismatch, true)).style_spec(style),
+                _ => Cell::new(&column.value(report, mismatch, true)),
+            })
+            .collect(),
+    )
+}
+
+/// A row that spans the whole table, such as the heading of a group.
+fn heading_row(title: &str, columns: &[Column]) -> Row {
+    Row::new(vec![Cell::new(title)
+        .style_spec("b")
+        .with_hspan(columns.len())])
+}
+
+fn mismatch_table(report: &Report, options: PrintOptions, colorize_segments: bool) -> Table {
+    let columns = options.columns;
+    let mut table = Table::new();
+
+    table.set_titles(Row::new(
+        columns
+            .iter()
+            .map(|column| Cell::new(column.title()).style_spec("b"))
+            .collect(),
+    ));
+
+    for (index, group) in report.groups(options.grouping).into_iter().enumerate() {
+        // Dependencies are listed right under the titles, without a heading of their own
+        if index > 0 || options.grouping != Grouping::Section || report.drift.is_some() {
+            table.add_row(heading_row(group.title, columns));
+        }
+
+        for mismatch in group.mismatches {
+            table.add_row(mismatch_row(report, mismatch, columns, colorize_segments));
+        }
+    }
+
+    if let Some(unknown) = &report.mismatches.unknown {
+        table.add_row(heading_row("Unknown (not in the offline cache)", columns));
+
+        for name in unknown {
+            table.add_row(Row::new(
+                columns
+                    .iter()
+                    .map(|column| match column {
+                        Column::Name => Cell::new(name).style_spec("FG"),
+                        _ => Cell::new(""),
+                    })
+                    .collect(),
+            ));
+        }
+    }
+
+    table
+}
+
+/// Builds a table of every checked dependency, along with its status.
+fn inventory_table(report: &Report) -> Table {
+    let mut table = Table::new();
+
+    table.set_titles(row![
+        b->"Package Name",
+        b->"Section",
+        b->"Version Constraint",
+        b->"Satisfiable Version",
+        b->"Latest Version",
+        b->"Status"
+    ]);
+
+    for dependency in &report.dependencies {
+        let status = report.status(dependency);
+        let mismatch = report.mismatch(dependency);
+
+        let style = match status {
+            Status::UpToDate => "Fg",
+            Status::Outdated => update_kind_style(mismatch.and_then(|m| m.update_kind())),
+            Status::Skipped => "Fy",
+            Status::Errored => "FR",
+        };
+
+        table.add_row(Row::new(vec![
+            Cell::new(&dependency.name).style_spec("FG"),
+            Cell::new(dependency.section.name()),
+            Cell::new(&dependency.constraint).style_spec("FB"),
+            Cell::new(report.satisfiable_version(dependency).unwrap_or_default()),
+            Cell::new(report.latest_version(dependency).unwrap_or_default()),
+            Cell::new(status.name()).style_spec(style),
+        ]));
+    }
+
+    table
+}
+
+/// Prints the mismatches table, and the version skew table if there is any skew, to stdout.
+pub fn print_table_mismatches(report: &Report, options: PrintOptions) {
+    // prettytable only colors cells on a terminal, escape codes in the cell
+    // contents have to follow suit so that they do not end up in redirected output
+    for table in tables(report, options, io::stdout().is_terminal()) {
+        table.printstd();
+    }
+}
+
+/// Writes the same tables as [`print_table_mismatches`], without any colors.
+pub fn write_table_mismatches(
+    out: &mut dyn Write,
+    report: &Report,
+    options: PrintOptions,
+) -> io::Result<()> {
+    for table in tables(report, options, false) {
+        table.print(out)?;
+    }
+
+    Ok(())
+}
+
+fn tables(report: &Report, options: PrintOptions, colorize_segments: bool) -> Vec<Table> {
+    let mismatches = &report.mismatches;
+
+    let table = match options.all {
+        true => inventory_table(report),
+        false => mismatch_table(report, options, colorize_segments),
+    };
+
+    let mut tables = vec![table];
+
+    match &mismatches.version_skew {
+        Some(version_skew) if !version_skew.is_empty() => {
+            let mut table = Table::new();
+
+            table.set_titles(
+                row![b->"Package Name", b->"Workspace Package", b->"Version Constraint"],
+            );
+
+            for skew in version_skew {
+                for constraint in skew.constraints() {
+                    table.add_row(
+                        row![FG->skew.name(), constraint.package(), FB->constraint.constraint()],
+                    );
+                }
+            }
+
+            tables.push(table);
+        }
+        _ => {}
+    }
+
+    match &report.vulnerabilities {
+        Some(vulnerabilities) if !vulnerabilities.is_empty() => {
+            tables.push(audit::vulnerability_table(vulnerabilities));
+        }
+        _ => {}
+    }
+
+    match &report.unavailable {
+        Some(unavailable) if !unavailable.is_empty() => {
+            let mut table = Table::new();
+
+            table.set_titles(row![b->"Package Name", b->"Locked Version", b->"Status"]);
+
+            for locked in unavailable {
+                table.add_row(
+                    row![FG->locked.name(), Fr->locked.version(), Fr->locked.reason().name()],
+                );
+            }
+
+            tables.push(table);
+        }
+        _ => {}
+    }
+
+    match &report.license_violations {
+        Some(violations) if !violations.is_empty() => {
+            let mut table = Table::new();
+
+            table.set_titles(row![b->"Package Name", b->"License"]);
+
+            for violation in violations {
+                table
+                    .add_row(row![FG->violation.name(), Fr->violation.license().unwrap_or("none")]);
+            }
+
+            tables.push(table);
+        }
+        _ => {}
+    }
+
+    match &report.denied {
+        Some(denied) if !denied.is_empty() => {
+            let mut table = Table::new();
+
+            table.set_titles(row![b->"Package Name", b->"Version", b->"Reason"]);
+
+            for dependency in denied {
+                let version = dependency.version().unwrap_or("any");
+
+                table.add_row(row![FG->dependency.name(), Fr->version, dependency.reason()]);
+            }
+
+            tables.push(table);
+        }
+        _ => {}
+    }
+
+    match &report.transitive {
+        Some(transitive) if !transitive.is_empty() => {
+            let mut table = Table::new();
+
+            table.set_titles(row![
+                b->"Transitive Dependency",
+                b->"Locked Version",
+                b->"Latest Version",
+                b->"Update",
+                b->"Advisories",
+                b->"Via"
+            ]);
+
+            for finding in transitive {
+                table.add_row(Row::new(vec![
+                    Cell::new(&finding.name).style_spec("FG"),
+                    Cell::new(&finding.version).style_spec("Fr"),
+                    Cell::new(finding.latest_version.as_deref().unwrap_or_default())
+                        .style_spec("Fg"),
+                    Cell::new(finding.update_kind.map_or("", |kind| kind.name())),
+                    Cell::new(&finding.advisories.join("\n")).style_spec("Fr"),
+                    Cell::new(&finding.via.join(", ")),
+                ]));
+            }
+
+            tables.push(table);
+        }
+        _ => {}
+    }
+
+    match &report.missing_provenance {
+        Some(missing) if !missing.is_empty() => {
+            let mut table = Table::new();
+
+            table.set_titles(row![
+                b->"Package Name",
+                b->"Latest Version",
+                b->"Status",
+                b->"Attested Version"
+            ]);
+
+            for package in missing {
+                let style = match package.is_lost() {
+                    true => "Fr",
+                    false => "Fy",
+                };
+
+                table.add_row(Row::new(vec![
+                    Cell::new(package.name()).style_spec("FG"),
+                    Cell::new(package.version()),
+                    Cell::new(package.status()).style_spec(style),
+                    Cell::new(package.attested_version().unwrap_or_default()),
+                ]));
+            }
+
+            tables.push(table);
+        }
+        _ => {}
+    }
+
+    let mut unmaintained = report.unmaintained().peekable();
+
+    if unmaintained.peek().is_some() {
+        let mut table = Table::new();
+
+        table.set_titles(row![b->"Package Name", b->"Last Published", b->"Flags"]);
+
+        for dependency in unmaintained {
+            let last_published = dependency
+                .last_published_at
+                .map_or(String::new(), |published_at| {
+                    format_date(published_at, true)
+                });
+
+            table.add_row(
+                row![FG->dependency.name, last_published, Fy->flag_names(&dependency.flags)],
+            );
+        }
+
+        tables.push(table);
+    }
+
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_segment_start() {
+        assert_eq!(changed_segment_start("2.0.0", "1.4.1"), Some(0));
+        assert_eq!(changed_segment_start("1.5.0", "1.4.1"), Some(2));
+        assert_eq!(changed_segment_start("1.4.2", "1.4.1"), Some(4));
+        assert_eq!(changed_segment_start("1.4.1-rc.2", "1.4.1-rc.1"), Some(9));
+        assert_eq!(changed_segment_start("1.4.1", "1.4.1"), None);
+    }
+
+    #[test]
+    fn test_version_cell() {
+        let mismatch = VersionMismatch::new("lodash", "~4.16.0", "4.17.21")
+            .with_satisfiable_version("4.16.6")
+            .with_update_kind(UpdateKind::Minor);
+
+        assert_eq!(
+            version_cell(&mismatch, true).get_content(),
+            "4.\x1b[33m17.21\x1b[0m"
+        );
+        assert_eq!(version_cell(&mismatch, false).get_content(), "4.17.21");
+    }
+}
@@ -0,0 +1,125 @@
+use std::error::Error;
+
+use depchk_core::IntegrityMismatch;
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+
+use super::json::{JsonReports, SCHEMA_VERSION};
+use super::{print_document, table_heading, OutputTypes};
+use crate::DependencyType;
+
+/// The outcome of `depchk verify`, which compares the checksums of the lockfile
+/// against the ones the registry publishes.
+#[derive(Serialize)]
+pub struct VerifyReport {
+    pub schema_version: u32,
+    pub manifest: String,
+    pub package_manager: DependencyType,
+    pub verified: usize,
+    pub mismatches: Vec<IntegrityMismatch>,
+}
+
+impl VerifyReport {
+    pub fn new(
+        manifest: &str,
+        package_manager: DependencyType,
+        verified: usize,
+        mismatches: Vec<IntegrityMismatch>,
+    ) -> Self {
+        VerifyReport {
+            schema_version: SCHEMA_VERSION,
+            manifest: manifest.to_string(),
+            package_manager,
+            verified,
+            mismatches,
+        }
+    }
+}
+
+fn integrity_table(mismatches: &[IntegrityMismatch]) -> Table {
+    let mut table = Table::new();
+
+    table.set_titles(row![
+        b->"Package Name",
+        b->"Version",
+        b->"Lockfile Checksum",
+        b->"Registry Checksum"
+    ]);
+
+    for mismatch in mismatches {
+        table.add_row(Row::new(vec![
+            Cell::new(mismatch.name()).style_spec("FG"),
+            Cell::new(mismatch.version()),
+            Cell::new(mismatch.locked()).style_spec("Fr"),
+            Cell::new(mismatch.published()),
+        ]));
+    }
+
+    table
+}
+
+/// Prints the outcome of the verifications in the given output type, of which only
+/// the table and the JSON, YAML and TOML outputs are supported. The verifications of
+/// several dependency files are printed as one, headed or keyed by their dependency
+/// file.
+pub fn print_verifications(
+    reports: &[VerifyReport],
+    output_type: OutputTypes,
+    quiet: bool,
+) -> Result<(), Box<dyn Error>> {
+    match output_type {
+        OutputTypes::Table => {
+            for (index, report) in reports.iter().enumerate() {
+                if reports.len() > 1 {
+                    println!("{}", table_heading(index, &report.manifest));
+                }
+
+                if !report.mismatches.is_empty() {
+                    integrity_table(&report.mismatches).printstd();
+                }
+            }
+
+            if !quiet {
+                println!(
+                    "{}",
+                    verify_summary(
+                        reports.iter().map(|report| report.verified).sum(),
+                        reports.iter().map(|report| report.mismatches.len()).sum()
+                    )
+                );
+            }
+        }
+        OutputTypes::Json | OutputTypes::Yaml | OutputTypes::Toml => match reports {
+            [report] => print_document(report, output_type)?,
+            _ => print_document(
+                &JsonReports::new(
+                    reports
+                        .iter()
+                        .map(|report| (report.manifest.as_str(), report)),
+                ),
+                output_type,
+            )?,
+        },
+        _ => {
+            return Err("depchk verify only supports the table, json, yaml and toml outputs".into())
+        }
+    }
+
+    Ok(())
+}
+
+/// A one line summary of the verification, e.g. "1 of 120 locked packages does not
+/// match the registry".
+fn verify_summary(verified: usize, mismatched: usize) -> String {
+    match mismatched {
+        0 => format!("All {} locked packages match the registry", verified),
+        1 => format!(
+            "1 of {} locked packages does not match the registry",
+            verified
+        ),
+        count => format!(
+            "{} of {} locked packages do not match the registry",
+            count, verified
+        ),
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use clap::{Args, ValueEnum};
+use depchk_core::changelog::ReleaseNotes;
+use depchk_core::fix::ConstraintUpdate;
+use depchk_core::pull_request::PullRequest;
+
+use crate::commit::commit_message;
+use crate::{DependencyType, UpdateChoice};
+
+/// The options of `depchk pr`, on top of the ones of the check.
+#[derive(Args, Clone, Debug)]
+pub struct PrArgs {
+    /// The GitHub repository to open the pull requests in, as owner/name. The token in
+    /// the GITHUB_TOKEN environment variable must be able to push to it.
+    #[arg(long, value_name = "OWNER/NAME")]
+    pub repo: String,
+
+    /// The branch the pull requests are opened against. Defaults to the default branch
+    /// of the repository.
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// The path of the dependency file in the repository. Defaults to the path of the
+    /// checked file, which should then be relative to the root of the repository.
+    #[arg(long)]
+    pub path: Option<String>,
+
+    /// How the updates are split into pull requests
+    #[arg(value_enum, long, default_value_t)]
+    pub group: PrGroup,
+}
+
+/// How the updates `depchk pr` proposes are split into pull requests.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum PrGroup {
+    /// A single pull request with every update
+    #[default]
+    All,
+    /// One pull request for each kind of update: major, minor and patch
+    UpdateType,
+    /// One pull request for each dependency
+    Package,
+}
+
+/// How long the release notes of a version may get in a pull request description,
+/// which GitHub limits to 65536 characters.
+const MAX_NOTES_LENGTH: usize = 2000;
+
+/// The pull requests that propose the updates, one for each group of updates,
+/// along with the updates each of them makes. Their descriptions include the
+/// release notes found for the updated dependencies, by name.
+pub fn pull_requests(
+    choices: Vec<UpdateChoice>,
+    group: PrGroup,
+    manifest: &str,
+    dependency_type: DependencyType,
+    changelogs: &HashMap<String, Vec<ReleaseNotes>>,
+) -> Vec<(PullRequest, Vec<ConstraintUpdate>)> {
+    // Packages of the same group of related packages always share a pull request,
+    // whose key is prefixed so that it never clashes with the name of a package
+    let mut groups: BTreeMap<String, (Option<String>, Vec<UpdateChoice>)> = BTreeMap::new();
+
+    for choice in choices {
+        let (key, name) = match (group, &choice.group) {
+            (PrGroup::All, _) => ("all".to_string(), None),
+            (_, Some(name)) => (format!("group-{}", name), Some(name.clone())),
+            (PrGroup::UpdateType, None) => (
+                choice
+                    .update_kind
+                    .map_or("other", |kind| kind.name())
+                    .to_string(),
+                None,
+            ),
+            (PrGroup::Package, None) => (choice.update.name().to_string(), None),
+        };
+
+        groups
+            .entry(key)
+            .or_insert_with(|| (name, Vec::new()))
+            .1
+            .push(choice);
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, (name, choices))| {
+            let title = match (choices.as_slice(), &name, group) {
+                ([choice], _, _) => format!(
+                    "Update {} from {} to {}",
+                    choice.update.name(),
+                    choice.declared,
+                    choice.update.constraint()
+                ),
+                (_, Some(name), _) => format!("Update the {} group", name),
+                (_, None, PrGroup::UpdateType) => {
+                    format!("Update {} {} dependencies", choices.len(), key)
+                }
+                _ => format!("Update {} dependencies", choices.len()),
+            };
+
+            let request = PullRequest::new(
+                &format!("depchk/{}", branch_name(&key)),
+                &title,
+                &description(&choices, manifest, dependency_type, changelogs),
+            )
+            .with_commit_message(&commit_message(&choices));
+
+            let updates = choices.into_iter().map(|choice| choice.update).collect();
+
+            (request, updates)
+        })
+        .collect()
+}
+
+/// The description of a pull request, with a table of the updates it makes and the
+/// release notes of each update, folded away.
+fn description(
+    choices: &[UpdateChoice],
+    manifest: &str,
+    dependency_type: DependencyType,
+    changelogs: &HashMap<String, Vec<ReleaseNotes>>,
+) -> String {
+    let mut body = format!(
+        "Updates the dependencies of `{}` that are behind their latest version, as found by depchk.\n\n",
+        manifest
+    );
+
+    let _ = writeln!(body, "| Package | From | To | Update |");
+    let _ = writeln!(body, "| --- | --- | --- | --- |");
+
+    for choice in choices {
+        let name = choice.update.name();
+
+        let _ = writeln!(
+            body,
+            "| [{}]({}){} | `{}` | `{}` | {} |",
+            name,
+            registry_page(dependency_type, name),
+            if choice.update.is_dev() { " (dev)" } else { "" },
+            choice.declared,
+            choice.update.constraint(),
+            choice.update_kind.map_or("", |kind| kind.name()),
+        );
+    }
+
+    for choice in choices {
+        let name = choice.update.name();
+        let Some(notes) = changelogs.get(name).filter(|notes| !notes.is_empty()) else {
+            continue;
+        };
+
+        let _ = writeln!(
+            body,
+            "\n<details>\n<summary>Release notes of {}</summary>\n",
+            name
+        );
+
+        for release in notes {
+            let _ = match release.url() {
+                Some(url) => writeln!(body, "#### [{}]({})\n", release.version(), url),
+                None => writeln!(body, "#### {}\n", release.version()),
+            };
+
+            let _ = writeln!(body, "{}\n", truncated(release.notes(), MAX_NOTES_LENGTH));
+        }
+
+        body.push_str("</details>\n");
+    }
+
+    body
+}
+
+/// The text cut down to at most `length` characters, marking where it was cut.
+fn truncated(text: &str, length: usize) -> String {
+    match text.char_indices().nth(length) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// The page of the package on the website of its registry.
+fn registry_page(dependency_type: DependencyType, name: &str) -> String {
+    match dependency_type {
+        DependencyType::Npm => format!("https://www.npmjs.com/package/{}", name),
+        DependencyType::Cargo => format!("https://crates.io/crates/{}", name),
+    }
+}
+
+/// A git branch name out of a package name, which may have characters git does
+/// not allow or that would nest the branch. The scope of a package is kept apart
+/// from its name, so that `@babel/core` is `babel--core` rather than the branch of
+/// `babel-core`.
+fn branch_name(key: &str) -> String {
+    key.trim_start_matches('@')
+        .replacen('/', "--", 1)
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                true => c,
+                false => '-',
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use depchk_core::UpdateKind;
+
+    use super::*;
+
+    fn choice(name: &str, declared: &str, updated: &str, kind: UpdateKind) -> UpdateChoice {
+        UpdateChoice {
+            update: ConstraintUpdate::new(name, false, updated),
+            declared: declared.to_string(),
+            current: declared.trim_start_matches('^').to_string(),
+            latest: updated.trim_start_matches('^').to_string(),
+            update_kind: Some(kind),
+            group: None,
+        }
+    }
+
+    #[test]
+    fn test_pull_requests() {
+        let choices = || {
+            vec![
+                choice("axios", "^0.21.0", "^1.6.2", UpdateKind::Major),
+                choice("@babel/core", "^7.22.0", "^7.23.5", UpdateKind::Minor),
+                choice("react", "^17.0.2", "^18.2.0", UpdateKind::Major),
+            ]
+        };
+
+        let changelogs = HashMap::from([(
+            "react".to_string(),
+            vec![ReleaseNotes::new("18.0.0", "- Concurrent rendering")
+                .with_url("https://github.com/facebook/react/releases/tag/v18.0.0")],
+        )]);
+
+        let all = pull_requests(
+            choices(),
+            PrGroup::All,
+            "package.json",
+            DependencyType::Npm,
+            &changelogs,
+        );
+
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].0.branch(), "depchk/all");
+        assert_eq!(all[0].0.title(), "Update 3 dependencies");
+        assert_eq!(all[0].1.len(), 3);
+        assert!(all[0].0.body().contains(
+            "| [axios](https://www.npmjs.com/package/axios) | `^0.21.0` | `^1.6.2` | major |"
+        ));
+        assert!(all[0].0.body().ends_with(concat!(
+            "<details>\n<summary>Release notes of react</summary>\n\n",
+            "#### [18.0.0](https://github.com/facebook/react/releases/tag/v18.0.0)\n\n",
+            "- Concurrent rendering\n\n",
+            "</details>\n",
+        )));
+
+        let by_type = pull_requests(
+            choices(),
+            PrGroup::UpdateType,
+            "package.json",
+            DependencyType::Npm,
+            &HashMap::new(),
+        );
+
+        assert_eq!(by_type[0].0.title(), "Update 2 major dependencies");
+        assert_eq!(
+            by_type[1].0.title(),
+            "Update @babel/core from ^7.22.0 to ^7.23.5"
+        );
+
+        let by_package = pull_requests(
+            choices(),
+            PrGroup::Package,
+            "package.json",
+            DependencyType::Npm,
+            &HashMap::new(),
+        );
+
+        assert_eq!(by_package[0].0.branch(), "depchk/babel--core");
+        assert_eq!(branch_name("babel-core"), "babel-core");
+
+        let mut grouped = choices();
+        grouped[0].group = Some("http".to_string());
+        grouped[2].group = Some("http".to_string());
+
+        let by_group = pull_requests(
+            grouped,
+            PrGroup::Package,
+            "package.json",
+            DependencyType::Npm,
+            &HashMap::new(),
+        );
+
+        assert_eq!(by_group.len(), 2);
+        assert_eq!(by_group[1].0.branch(), "depchk/group-http");
+        assert_eq!(by_group[1].0.title(), "Update the http group");
+        assert_eq!(by_group[1].1.len(), 2);
+        assert_eq!(
+            by_group[0].0.commit_message(),
+            "chore(deps): bump @babel/core from 7.22.0 to 7.23.5"
+        );
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert_eq!(truncated("Fixes", 10), "Fixes");
+        assert_eq!(truncated("Fixes ünicode", 7), "Fixes ü…");
+    }
+}
@@ -0,0 +1,52 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use depchk_core::progress::ProgressReporter;
+use depchk_core::{CheckOutcome, DependencyError};
+
+/// How many characters wide the bar itself is.
+const WIDTH: usize = 30;
+
+/// Shows how many of the dependencies have been checked on a single line of stderr,
+/// which is cleared again once all of them have been.
+#[derive(Default)]
+pub struct ProgressBar {
+    total: AtomicUsize,
+    done: AtomicUsize,
+}
+
+impl ProgressBar {
+    fn advance(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        let total = self.total.load(Ordering::Relaxed).max(done);
+        let mut stderr = io::stderr().lock();
+
+        let _ = match done == total {
+            true => write!(stderr, "\r\x1b[2K"),
+            false => write!(
+                stderr,
+                "\r[{:<width$}] {}/{} dependencies checked",
+                "#".repeat(done * WIDTH / total),
+                done,
+                total,
+                width = WIDTH
+            ),
+        };
+
+        let _ = stderr.flush();
+    }
+}
+
+impl ProgressReporter for ProgressBar {
+    fn checking(&self, total: usize) {
+        self.total.fetch_add(total, Ordering::Relaxed);
+    }
+
+    fn completed(&self, _outcome: &CheckOutcome) {
+        self.advance();
+    }
+
+    fn failed(&self, _err: &DependencyError) {
+        self.advance();
+    }
+}